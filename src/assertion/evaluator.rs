@@ -1,13 +1,29 @@
-use crate::assertion::extractor::extract_value;
-use crate::assertion::types::{AssertExpr, AssertionResult};
+use crate::assertion::extractor::{extract_value, extract_variable};
+use crate::assertion::parser::parse_assert_value;
+use crate::assertion::types::{
+    AssertError, AssertExpr, AssertValue, AssertionResult, CompareOp, FailureContext, Tolerance,
+    ValuePath,
+};
 use crate::http::Response;
+use crate::utils::json_path;
+use crate::variable::{VariableContext, VariableResolver};
 
 /// 执行断言求值
+///
+/// 变量替换后仍残留 `{{name}}` 的断言直接报错，而不是把占位符当作字符串字面量比较。
 pub fn evaluate_assertion(assertion: &AssertExpr, response: &Response) -> AssertionResult {
     let raw = format_assertion(assertion);
+    if let Some(name) = VariableResolver::unresolved(&raw).into_iter().next() {
+        return AssertionResult::error(raw, AssertError::UnresolvedVariable(name));
+    }
 
     match assertion {
-        AssertExpr::Compare { left, op, right } => {
+        AssertExpr::Compare {
+            left,
+            op,
+            right,
+            tolerance,
+        } => {
             // 提取实际值
             let actual_value = match extract_value(response, left) {
                 Ok(v) => v,
@@ -16,24 +32,45 @@ pub fn evaluate_assertion(assertion: &AssertExpr, response: &Response) -> Assert
                 }
             };
 
-            // 比较值
-            match actual_value.compare(*op, right) {
-                Ok(passed) => {
-                    let actual_str = actual_value.to_string();
-                    let expected_str = format!("{} {}", op, right);
-
-                    if passed {
-                        AssertionResult::success(raw, actual_str, expected_str)
-                    } else {
-                        let message = format!(
-                            "Expected {} to be {}, but got {}",
-                            left, expected_str, actual_str
-                        );
-                        AssertionResult::failure(raw, actual_str, expected_str, message)
-                    }
+            let result = compare(
+                raw,
+                left,
+                actual_value.clone(),
+                *op,
+                right,
+                right.to_string(),
+                *tolerance,
+            );
+            with_failure_context(result, response, left, *op, &actual_value, right)
+        }
+
+        AssertExpr::ComparePaths {
+            left,
+            op,
+            right,
+            tolerance,
+        } => {
+            // 两侧都从响应中提取
+            let values = extract_value(response, left)
+                .and_then(|actual| Ok((actual, extract_value(response, right)?)));
+            let (actual_value, expected_value) = match values {
+                Ok((actual, expected)) => coerce_pair(actual, expected),
+                Err(e) => {
+                    return AssertionResult::error(raw, e);
                 }
-                Err(e) => AssertionResult::error(raw, e),
-            }
+            };
+
+            let right_str = format!("{} ({})", right, expected_value);
+            let result = compare(
+                raw,
+                left,
+                actual_value.clone(),
+                *op,
+                &expected_value,
+                right_str,
+                *tolerance,
+            );
+            with_failure_context(result, response, left, *op, &actual_value, &expected_value)
         }
 
         AssertExpr::Exists { path } => {
@@ -52,30 +89,192 @@ pub fn evaluate_assertion(assertion: &AssertExpr, response: &Response) -> Assert
                         "exists".to_string(),
                         message,
                     )
+                    .with_context(json_context(response, path))
                 }
             }
         }
+
+        AssertExpr::NotExists { path } => match extract_value(response, path) {
+            Ok(value) => {
+                let actual_str = value.to_string();
+                let message = format!("Expected {} not to exist, but found {}", path, actual_str);
+                AssertionResult::failure(raw, actual_str, "not exists".to_string(), message)
+            }
+            Err(_) => {
+                AssertionResult::success(raw, "not found".to_string(), "not exists".to_string())
+            }
+        },
+    }
+}
+
+/// 比较实际值与期望值，right_str 为期望值在结果中的显示
+fn compare(
+    raw: String,
+    left: &ValuePath,
+    actual_value: AssertValue,
+    op: CompareOp,
+    right: &AssertValue,
+    right_str: String,
+    tolerance: Option<Tolerance>,
+) -> AssertionResult {
+    match actual_value.compare_with_tolerance(op, right, tolerance) {
+        Ok(passed) => {
+            let actual_str = actual_value.to_string();
+            let expected_str = match tolerance {
+                Some(tolerance) => format!("{} {} {}", op, right_str, tolerance),
+                None => format!("{} {}", op, right_str),
+            };
+
+            if passed {
+                AssertionResult::success(raw, actual_str, expected_str)
+            } else {
+                let message = format!(
+                    "Expected {} to be {}, but got {}",
+                    left, expected_str, actual_str
+                );
+                AssertionResult::failure(raw, actual_str, expected_str, message)
+            }
+        }
+        Err(e) => AssertionResult::error(raw, e),
+    }
+}
+
+/// 比较失败（不是求值错误）时附加响应片段：字符串 contains 失败时为实际的字符串，
+/// 其他 JSON body 路径为父节点
+fn with_failure_context(
+    result: AssertionResult,
+    response: &Response,
+    left: &ValuePath,
+    op: CompareOp,
+    actual: &AssertValue,
+    expected: &AssertValue,
+) -> AssertionResult {
+    if result.passed || result.actual.is_none() {
+        return result;
+    }
+    let context = match (op, actual, expected) {
+        (CompareOp::Contains, AssertValue::String(actual), AssertValue::String(expected)) => {
+            Some(FailureContext::Text {
+                actual: actual.clone(),
+                expected: expected.clone(),
+            })
+        }
+        _ => json_context(response, left),
+    };
+    result.with_context(context)
+}
+
+/// JSON body 路径的父节点，路径不是 JSON body 路径或父节点不存在时为 None
+fn json_context(response: &Response, path: &ValuePath) -> Option<FailureContext> {
+    let ValuePath::Body(segments) = path else {
+        return None;
+    };
+    let (key, parent) = segments.split_last()?;
+    let parent = json_path::lookup(response.json_value()?, parent).ok()?;
+    Some(FailureContext::Json {
+        parent: parent.clone(),
+        key: key.clone(),
+    })
+}
+
+/// 两侧类型不同且一侧是字符串时，按字面量的规则推断字符串的类型
+///
+/// header 的值总是字符串，这样 `headers.x-total == body.items.length` 可以按数字比较。
+fn coerce_pair(left: AssertValue, right: AssertValue) -> (AssertValue, AssertValue) {
+    let coerce = |value: AssertValue| match value {
+        AssertValue::String(s) => parse_assert_value(&s).unwrap_or(AssertValue::String(s)),
+        other => other,
+    };
+    match (&left, &right) {
+        (AssertValue::String(_), AssertValue::String(_)) => (left, right),
+        (AssertValue::String(_), _) => (coerce(left), right),
+        (_, AssertValue::String(_)) => (left, coerce(right)),
+        _ => (left, right),
+    }
+}
+
+/// 基于变量上下文求值条件表达式（用于 @skip-if）
+///
+/// 未定义的变量在比较时视为 `null`，`exists` / `not exists` 检查变量是否已定义。
+pub fn evaluate_condition(
+    condition: &AssertExpr,
+    context: &VariableContext,
+) -> Result<bool, AssertError> {
+    let lookup = |path: &ValuePath| match path {
+        ValuePath::Variable(name) => Ok(extract_variable(context, name)),
+        other => Err(AssertError::InvalidSyntax(format!(
+            "Conditions can only reference variables, got: {}",
+            other
+        ))),
+    };
+
+    match condition {
+        AssertExpr::Compare {
+            left,
+            op,
+            right,
+            tolerance,
+        } => lookup(left)?
+            .unwrap_or(AssertValue::Null)
+            .compare_with_tolerance(*op, right, *tolerance),
+        AssertExpr::ComparePaths { right, .. } => Err(AssertError::InvalidSyntax(format!(
+            "Conditions can only reference variables, got: {}",
+            right
+        ))),
+        AssertExpr::Exists { path } => Ok(lookup(path)?.is_some()),
+        AssertExpr::NotExists { path } => Ok(lookup(path)?.is_none()),
     }
 }
 
 /// 格式化断言表达式为字符串
 fn format_assertion(assertion: &AssertExpr) -> String {
     match assertion {
-        AssertExpr::Compare { left, op, right } => {
+        AssertExpr::Compare {
+            left,
+            op,
+            right,
+            tolerance: None,
+        } => {
+            format!("{} {} {}", left, op, right)
+        }
+        AssertExpr::Compare {
+            left,
+            op,
+            right,
+            tolerance: Some(tolerance),
+        } => {
+            format!("{} {} {} {}", left, op, right, tolerance)
+        }
+        AssertExpr::ComparePaths {
+            left,
+            op,
+            right,
+            tolerance: None,
+        } => {
             format!("{} {} {}", left, op, right)
         }
+        AssertExpr::ComparePaths {
+            left,
+            op,
+            right,
+            tolerance: Some(tolerance),
+        } => {
+            format!("{} {} {} {}", left, op, right, tolerance)
+        }
         AssertExpr::Exists { path } => {
             format!("{} exists", path)
         }
+        AssertExpr::NotExists { path } => {
+            format!("{} not exists", path)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::assertion::parser::parse_assertion;
+    use crate::assertion::parser::{parse_assertion, parse_condition};
     use crate::http::Response;
-    use crate::http::types::Status;
     use reqwest::header::HeaderMap;
     use std::time::Duration;
 
@@ -83,12 +282,13 @@ mod tests {
         let mut headers = HeaderMap::new();
         headers.insert("content-type", "application/json".parse().unwrap());
 
-        Response {
-            status: Status::new(status).unwrap(),
+        Response::new(
+            status,
             headers,
-            body: body.to_string(),
-            duration: Duration::from_millis(duration_ms),
-        }
+            body.to_string(),
+            Duration::from_millis(duration_ms),
+        )
+        .unwrap()
     }
 
     #[test]
@@ -112,6 +312,23 @@ mod tests {
         assert!(result.message.is_some());
     }
 
+    #[test]
+    fn test_evaluate_raw_body() {
+        let response = create_test_response(200, "pong", 10);
+
+        let result = evaluate_assertion(&parse_assertion(r#"body == "pong""#).unwrap(), &response);
+        assert!(result.passed);
+
+        let result = evaluate_assertion(
+            &parse_assertion(r#"body contains "on""#).unwrap(),
+            &response,
+        );
+        assert!(result.passed);
+
+        let result = evaluate_assertion(&parse_assertion(r#"body == "ping""#).unwrap(), &response);
+        assert!(!result.passed);
+    }
+
     #[test]
     fn test_evaluate_header_contains() {
         let assertion = parse_assertion("headers.content-type contains \"json\"").unwrap();
@@ -169,6 +386,86 @@ mod tests {
         assert!(result.message.is_some());
     }
 
+    #[test]
+    fn test_evaluate_not_exists_header() {
+        let assertion = parse_assertion("headers.server not exists").unwrap();
+        let mut response = create_test_response(200, "{}", 100);
+        let result = evaluate_assertion(&assertion, &response);
+        assert!(result.passed);
+        assert_eq!(result.raw, "headers.server not exists");
+
+        response.headers.insert("server", "nginx".parse().unwrap());
+        let result = evaluate_assertion(&assertion, &response);
+        assert!(!result.passed);
+        assert_eq!(result.actual, Some("\"nginx\"".to_string()));
+        assert!(result.message.unwrap().contains("not to exist"));
+    }
+
+    #[test]
+    fn test_evaluate_not_exists_body() {
+        let assertion = parse_assertion("body.user.password not exists").unwrap();
+        let response = create_test_response(200, r#"{"user": {"name": "a"}}"#, 100);
+        assert!(evaluate_assertion(&assertion, &response).passed);
+
+        let response = create_test_response(200, r#"{"user": {"password": "x"}}"#, 100);
+        assert!(!evaluate_assertion(&assertion, &response).passed);
+    }
+
+    #[test]
+    fn test_evaluate_tolerance() {
+        let response = create_test_response(
+            200,
+            r#"{"price": 19.994, "total": 0.30000000000000004}"#,
+            100,
+        );
+
+        let result = evaluate_assertion(
+            &parse_assertion("body.price == 19.99 ± 0.01").unwrap(),
+            &response,
+        );
+        assert!(result.passed);
+        assert_eq!(result.raw, "body.price == 19.99 ± 0.01");
+        assert_eq!(result.expected, "== 19.99 ± 0.01");
+
+        let result = evaluate_assertion(
+            &parse_assertion("body.price == 19.99 within 0.001").unwrap(),
+            &response,
+        );
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("± 0.001"));
+
+        // 百分比容差相对于期望值
+        assert!(
+            evaluate_assertion(
+                &parse_assertion("body.price == 20 ± 1%").unwrap(),
+                &response
+            )
+            .passed
+        );
+        assert!(
+            !evaluate_assertion(
+                &parse_assertion("body.price == 20 ± 0.01%").unwrap(),
+                &response
+            )
+            .passed
+        );
+        assert!(
+            evaluate_assertion(
+                &parse_assertion("body.price != 20 within 0.01%").unwrap(),
+                &response
+            )
+            .passed
+        );
+
+        // 未指定容差时吸收浮点表示误差
+        assert!(
+            evaluate_assertion(&parse_assertion("body.total == 0.3").unwrap(), &response).passed
+        );
+        assert!(
+            !evaluate_assertion(&parse_assertion("body.price == 19.99").unwrap(), &response).passed
+        );
+    }
+
     #[test]
     fn test_evaluate_nested_body() {
         let assertion = parse_assertion("body.user.id == 123").unwrap();
@@ -178,6 +475,92 @@ mod tests {
         assert!(result.passed);
     }
 
+    #[test]
+    fn test_evaluate_condition() {
+        let mut context = VariableContext::new();
+        context.insert("env", "prod");
+
+        let condition = parse_condition("env == prod").unwrap();
+        assert!(evaluate_condition(&condition, &context).unwrap());
+
+        let condition = parse_condition("env != prod").unwrap();
+        assert!(!evaluate_condition(&condition, &context).unwrap());
+
+        // 未定义的变量视为 null
+        let condition = parse_condition("region == eu").unwrap();
+        assert!(!evaluate_condition(&condition, &context).unwrap());
+
+        let condition = parse_condition("env exists").unwrap();
+        assert!(evaluate_condition(&condition, &context).unwrap());
+        let condition = parse_condition("region exists").unwrap();
+        assert!(!evaluate_condition(&condition, &context).unwrap());
+        let condition = parse_condition("region not exists").unwrap();
+        assert!(evaluate_condition(&condition, &context).unwrap());
+    }
+
+    #[test]
+    fn test_evaluate_unresolved_variable() {
+        let response = create_test_response(200, r#"{"id": "{{expected_id}}"}"#, 100);
+
+        // 未定义的变量不会被当作字符串字面量，即使响应中恰好有相同的文本
+        for input in [
+            "body.id == {{expected_id}}",
+            r#"body.id == "{{expected_id}}""#,
+            "body.{{field}} exists",
+        ] {
+            let result = evaluate_assertion(&parse_assertion(input).unwrap(), &response);
+            assert!(!result.passed, "{}", input);
+            let message = result.message.unwrap();
+            assert!(
+                message.starts_with("Unresolved variable in assertion: {{"),
+                "{}",
+                message
+            );
+        }
+
+        let result = evaluate_assertion(
+            &parse_assertion("body.id == {{expected_id}}").unwrap(),
+            &response,
+        );
+        assert_eq!(
+            result.message.as_deref(),
+            Some("Unresolved variable in assertion: {{expected_id}} is not defined")
+        );
+    }
+
+    #[test]
+    fn test_failure_context() {
+        let response = create_test_response(
+            200,
+            r#"{"user": {"name": "alice", "roles": ["viewer"]}, "message": "user created"}"#,
+            100,
+        );
+        let context =
+            |input: &str| evaluate_assertion(&parse_assertion(input).unwrap(), &response).context;
+
+        assert_eq!(
+            context(r#"body.user.name == "bob""#),
+            Some(FailureContext::Json {
+                parent: serde_json::json!({"name": "alice", "roles": ["viewer"]}),
+                key: "name".to_string(),
+            })
+        );
+        assert_eq!(
+            context(r#"body.message contains "deleted""#),
+            Some(FailureContext::Text {
+                actual: "user created".to_string(),
+                expected: "deleted".to_string(),
+            })
+        );
+        // 缺失的键显示父节点中已有的内容
+        assert!(matches!(
+            context("body.user.email exists"),
+            Some(FailureContext::Json { ref key, .. }) if key == "email"
+        ));
+        assert_eq!(context(r#"body.user.name == "alice""#), None);
+        assert_eq!(context("status == 201"), None);
+    }
+
     #[test]
     fn test_evaluate_path_not_found() {
         let assertion = parse_assertion("body.missing == 123").unwrap();
@@ -187,4 +570,43 @@ mod tests {
         assert!(!result.passed);
         assert!(result.message.is_some());
     }
+
+    #[test]
+    fn test_evaluate_compare_paths() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-total-count", "3".parse().unwrap());
+        headers.insert("x-request-id", "abc".parse().unwrap());
+        let response = Response::new(
+            200,
+            headers,
+            r#"{"total": 3, "items": [1, 2, 3], "id": "abc", "min": 1, "max": 5, "active": true}"#
+                .to_string(),
+            Duration::from_millis(10),
+        )
+        .unwrap();
+        let evaluate =
+            |input: &str| evaluate_assertion(&parse_assertion(input).unwrap(), &response);
+
+        let result = evaluate("body.total == body.items.length");
+        assert!(result.passed);
+        assert_eq!(result.expected, "== body.items.length (3)");
+        assert!(evaluate("body.max > body.min").passed);
+        assert!(!evaluate("body.min > body.max").passed);
+
+        // header 的值是字符串，与数字比较时按数字解析
+        assert!(evaluate("headers.x-total-count == body.total").passed);
+        assert!(evaluate("body.items.length <= headers.x-total-count").passed);
+        assert!(evaluate("headers.x-request-id == body.id").passed);
+
+        // 字符串之间不做转换，无法转换的字符串仍是类型不匹配
+        let result = evaluate("body.id == body.total");
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("Type mismatch"));
+        assert!(!evaluate("body.active == body.total").passed);
+
+        // 任一侧不存在时断言失败
+        let result = evaluate("body.total == body.missing");
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("missing"));
+    }
 }
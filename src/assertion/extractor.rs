@@ -1,48 +1,252 @@
+use crate::assertion::parser::parse_assert_value;
 use crate::assertion::types::{AssertError, AssertValue, ValuePath};
 use crate::http::Response;
+use crate::utils::json_path;
+use crate::variable::VariableContext;
 
 /// 从响应中提取值
 pub fn extract_value(response: &Response, path: &ValuePath) -> Result<AssertValue, AssertError> {
+    // 截断的 body 无法可靠地断言
+    if matches!(
+        path,
+        ValuePath::Body(_) | ValuePath::XmlBody(_) | ValuePath::RawBody
+    ) && let Some(message) = response.truncation_error()
+    {
+        return Err(AssertError::ExtractionError(message));
+    }
+
     match path {
         ValuePath::Status => Ok(AssertValue::Number(response.status.code() as f64)),
 
-        ValuePath::Header(name) => {
-            let value = response
+        ValuePath::Header(name) => extract_header(response, name, 0),
+
+        ValuePath::HeaderAt(name, index) => extract_header(response, name, *index),
+
+        ValuePath::HeaderCount(name) => Ok(AssertValue::Number(
+            response.headers.get_all(name).iter().count() as f64,
+        )),
+
+        ValuePath::Body(segments) => extract_from_json_body(response, segments),
+
+        ValuePath::XmlBody(xml_path) => {
+            let content_type = response
                 .headers
-                .get(name)
-                .ok_or_else(|| AssertError::PathNotFound(format!("Header '{}' not found", name)))?;
-            // HeaderValue 转换为 String
-            Ok(AssertValue::String(
-                value
-                    .to_str()
-                    .map_err(|e| {
-                        AssertError::ExtractionError(format!(
-                            "Failed to convert header value to string: {}",
-                            e
-                        ))
-                    })?
-                    .to_string(),
-            ))
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            if !is_xml_content_type(content_type) {
+                return Err(AssertError::ExtractionError(format!(
+                    "'{}' requires an XML response, but Content-Type is '{}'",
+                    path, content_type
+                )));
+            }
+            extract_from_xml_body(&response.body, xml_path)
         }
 
-        ValuePath::Body(segments) => extract_from_json_body(&response.body, segments),
+        ValuePath::RawBody => Ok(AssertValue::String(response.body.clone())),
 
         ValuePath::ResponseTime => Ok(AssertValue::Number(response.duration.as_millis() as f64)),
+
+        ValuePath::FinalUrl => response
+            .final_url
+            .clone()
+            .map(AssertValue::String)
+            .ok_or_else(|| AssertError::PathNotFound("Final URL not available".to_string())),
+
+        ValuePath::Redirects => Ok(AssertValue::Number(response.redirects as f64)),
+
+        ValuePath::Variable(name) => Err(AssertError::ExtractionError(format!(
+            "Variable '{}' cannot be extracted from a response",
+            name
+        ))),
     }
 }
 
+/// 提取同名 Header 中的第 index 个值
+fn extract_header(
+    response: &Response,
+    name: &str,
+    index: usize,
+) -> Result<AssertValue, AssertError> {
+    let value = response
+        .headers
+        .get_all(name)
+        .iter()
+        .nth(index)
+        .ok_or_else(|| match index {
+            0 => AssertError::PathNotFound(format!("Header '{}' not found", name)),
+            _ => AssertError::PathNotFound(format!(
+                "Header '{}' has {} values, index {} is out of bounds",
+                name,
+                response.headers.get_all(name).iter().count(),
+                index
+            )),
+        })?;
+    // HeaderValue 转换为 String
+    Ok(AssertValue::String(
+        value
+            .to_str()
+            .map_err(|e| {
+                AssertError::ExtractionError(format!(
+                    "Failed to convert header value to string: {}",
+                    e
+                ))
+            })?
+            .to_string(),
+    ))
+}
+
+/// 从变量上下文中提取值
+///
+/// 变量值按与断言右值相同的规则推断类型（数字、布尔、null、字符串），
+/// 变量未定义时返回 `None`。
+pub fn extract_variable(context: &VariableContext, name: &str) -> Option<AssertValue> {
+    context
+        .get(name)
+        .map(|value| parse_assert_value(value).unwrap_or(AssertValue::String(value.to_string())))
+}
+
+/// 长度伪路径段，如 `body.items.length`
+const LENGTH_SEGMENT: &str = "length";
+
 /// 从 JSON body 中提取值
-fn extract_from_json_body(body: &str, segments: &[String]) -> Result<AssertValue, AssertError> {
-    let json_value: serde_json::Value = serde_json::from_str(body)?;
+///
+/// 最后一段为 `length` 时返回前一个值的长度（数组/对象的元素数，字符串的字符数），
+/// 对象中真实存在的 `length` 字段优先。
+fn extract_from_json_body(
+    response: &Response,
+    segments: &[String],
+) -> Result<AssertValue, AssertError> {
+    let json_value = response.json_value().ok_or_else(|| {
+        AssertError::ExtractionError("Response body is not valid JSON".to_string())
+    })?;
 
-    let mut current = &json_value;
-    for segment in segments {
-        current = current.get(segment).ok_or_else(|| {
-            AssertError::PathNotFound(format!("Path 'body.{}' not found", segments.join(".")))
-        })?;
+    match json_path::lookup(json_value, segments) {
+        Ok(value) => json_value_to_assert_value(value),
+        Err(e) => match segments.split_last() {
+            Some((last, parent)) if last == LENGTH_SEGMENT => {
+                let parent = json_path::lookup(json_value, parent)
+                    .map_err(|_| AssertError::PathNotFound(e.to_string()))?;
+                json_value_length(parent)
+            }
+            _ => Err(AssertError::PathNotFound(e.to_string())),
+        },
+    }
+}
+
+/// application/xml、text/xml 以及 application/soap+xml 等 `+xml` 类型
+fn is_xml_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence.ends_with("/xml") || essence.ends_with("+xml")
+}
+
+/// 从 XML body 中提取元素文本或属性值
+///
+/// 支持简化的 XPath：`/a/b` 从根元素开始，`//b/c` 从任意位置的 `b` 开始，
+/// `*` 匹配任意元素，`name[n]` 取第 n 个（从 1 开始）同名子元素，`@attr` 只能作为
+/// 最后一步。元素名按本地名匹配（忽略命名空间）。多个节点匹配时取第一个，
+/// 元素的值为其所有文本内容（去掉首尾空白）。
+#[cfg(feature = "xml")]
+fn extract_from_xml_body(body: &str, path: &str) -> Result<AssertValue, AssertError> {
+    let document = roxmltree::Document::parse(body)
+        .map_err(|e| AssertError::ExtractionError(format!("Invalid XML body: {}", e)))?;
+    let invalid = || AssertError::InvalidSyntax(format!("Invalid XML path: {}", path));
+    let not_found = || AssertError::PathNotFound(format!("Path 'body.xpath({})' not found", path));
+
+    let (anywhere, rest) = match path.strip_prefix("//") {
+        Some(rest) => (true, rest),
+        None => (false, path.strip_prefix('/').ok_or_else(invalid)?),
+    };
+    let steps: Vec<&str> = rest.split('/').collect();
+    if steps.iter().any(|step| step.is_empty()) {
+        return Err(invalid());
+    }
+
+    let mut nodes = vec![document.root()];
+    for (index, step) in steps.iter().enumerate() {
+        if let Some(attribute) = step.strip_prefix('@') {
+            if index != steps.len() - 1 {
+                return Err(invalid());
+            }
+            return nodes
+                .iter()
+                .find_map(|node| node.attribute(attribute))
+                .map(|value| AssertValue::String(value.to_string()))
+                .ok_or_else(not_found);
+        }
+
+        let (name, position) = parse_xml_step(step).ok_or_else(invalid)?;
+        let matches = |node: &roxmltree::Node| {
+            node.is_element() && (name == "*" || node.tag_name().name() == name)
+        };
+        nodes = nodes
+            .iter()
+            .flat_map(|node| {
+                let candidates: Vec<roxmltree::Node> = if anywhere && index == 0 {
+                    node.descendants().filter(matches).collect()
+                } else {
+                    node.children().filter(matches).collect()
+                };
+                match position {
+                    Some(position) => candidates
+                        .into_iter()
+                        .nth(position - 1)
+                        .into_iter()
+                        .collect(),
+                    None => candidates,
+                }
+            })
+            .collect();
     }
 
-    json_value_to_assert_value(current)
+    let node = nodes.first().ok_or_else(not_found)?;
+    let text: String = node
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    Ok(AssertValue::String(text.trim().to_string()))
+}
+
+/// 解析 `name` 或 `name[n]`（n 从 1 开始）
+#[cfg(feature = "xml")]
+fn parse_xml_step(step: &str) -> Option<(&str, Option<usize>)> {
+    match step.strip_suffix(']').and_then(|s| s.split_once('[')) {
+        Some((name, position)) => {
+            let position = position.trim().parse::<usize>().ok().filter(|p| *p > 0)?;
+            Some((name, Some(position)))
+        }
+        None => Some((step, None)),
+    }
+}
+
+#[cfg(not(feature = "xml"))]
+fn extract_from_xml_body(_body: &str, path: &str) -> Result<AssertValue, AssertError> {
+    Err(AssertError::ExtractionError(format!(
+        "XML path assertions require rupost to be built with the 'xml' feature: body.xpath({})",
+        path
+    )))
+}
+
+/// 计算 JSON 值的长度
+fn json_value_length(value: &serde_json::Value) -> Result<AssertValue, AssertError> {
+    let len = match value {
+        serde_json::Value::Array(items) => items.len(),
+        serde_json::Value::Object(fields) => fields.len(),
+        serde_json::Value::String(s) => s.chars().count(),
+        other => {
+            return Err(AssertError::TypeMismatch {
+                expected: "array, object or string".to_string(),
+                actual: json_path::type_name(other).to_string(),
+            });
+        }
+    };
+    Ok(AssertValue::Number(len as f64))
 }
 
 /// 将 serde_json::Value 转换为 AssertValue
@@ -70,7 +274,6 @@ fn json_value_to_assert_value(value: &serde_json::Value) -> Result<AssertValue,
 mod tests {
     use super::*;
     use crate::http::Response;
-    use crate::http::types::Status;
     use reqwest::header::HeaderMap;
     use std::time::Duration;
 
@@ -78,12 +281,16 @@ mod tests {
         let mut headers = HeaderMap::new();
         headers.insert("content-type", "application/json".parse().unwrap());
 
-        Response {
-            status: Status::new(status).unwrap(),
+        let mut response = Response::new(
+            status,
             headers,
-            body: body.to_string(),
-            duration: Duration::from_millis(123),
-        }
+            body.to_string(),
+            Duration::from_millis(123),
+        )
+        .unwrap()
+        .with_final_url("https://example.com/login");
+        response.redirects = 2;
+        response
     }
 
     #[test]
@@ -108,6 +315,43 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_extract_duplicate_headers() {
+        let mut response = create_test_response(200, "{}");
+        response
+            .headers
+            .append("set-cookie", "session=abc; Path=/".parse().unwrap());
+        response
+            .headers
+            .append("set-cookie", "theme=dark".parse().unwrap());
+        let string = |s: &str| AssertValue::String(s.to_string());
+
+        assert_eq!(
+            extract_value(&response, &ValuePath::Header("set-cookie".to_string())).unwrap(),
+            string("session=abc; Path=/")
+        );
+        assert_eq!(
+            extract_value(&response, &ValuePath::HeaderAt("set-cookie".to_string(), 1)).unwrap(),
+            string("theme=dark")
+        );
+        assert_eq!(
+            extract_value(&response, &ValuePath::HeaderCount("set-cookie".to_string())).unwrap(),
+            AssertValue::Number(2.0)
+        );
+        assert_eq!(
+            extract_value(&response, &ValuePath::HeaderCount("vary".to_string())).unwrap(),
+            AssertValue::Number(0.0)
+        );
+        let err = extract_value(&response, &ValuePath::HeaderAt("set-cookie".to_string(), 2))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("has 2 values, index 2 is out of bounds"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn test_extract_response_time() {
         let response = create_test_response(200, "{}");
@@ -115,6 +359,27 @@ mod tests {
         assert_eq!(value, AssertValue::Number(123.0));
     }
 
+    #[test]
+    fn test_extract_final_url() {
+        let response = create_test_response(200, "{}");
+        let value = extract_value(&response, &ValuePath::FinalUrl).unwrap();
+        assert_eq!(
+            value,
+            AssertValue::String("https://example.com/login".to_string())
+        );
+
+        let response = Response::error("failed".to_string());
+        let result = extract_value(&response, &ValuePath::FinalUrl);
+        assert!(matches!(result, Err(AssertError::PathNotFound(_))));
+    }
+
+    #[test]
+    fn test_extract_redirects() {
+        let response = create_test_response(200, "{}");
+        let value = extract_value(&response, &ValuePath::Redirects).unwrap();
+        assert_eq!(value, AssertValue::Number(2.0));
+    }
+
     #[test]
     fn test_extract_body_number() {
         let response = create_test_response(200, r#"{"id": 42}"#);
@@ -143,6 +408,14 @@ mod tests {
         assert_eq!(value, AssertValue::Null);
     }
 
+    #[test]
+    fn test_extract_raw_body() {
+        // 非 JSON 响应体也可以整体提取
+        let response = create_test_response(200, "pong");
+        let value = extract_value(&response, &ValuePath::RawBody).unwrap();
+        assert_eq!(value, AssertValue::String("pong".to_string()));
+    }
+
     #[test]
     fn test_extract_nested_body() {
         let response = create_test_response(200, r#"{"user": {"id": 123, "name": "test"}}"#);
@@ -154,6 +427,169 @@ mod tests {
         assert_eq!(value, AssertValue::Number(123.0));
     }
 
+    #[test]
+    fn test_extract_body_length() {
+        let response = create_test_response(
+            200,
+            r#"{"items": [1, 2, 3], "meta": {"a": 1, "b": 2}, "name": "héllo", "count": 5}"#,
+        );
+        let path = |p: &str| ValuePath::Body(p.split('.').map(|s| s.to_string()).collect());
+
+        assert_eq!(
+            extract_value(&response, &path("items.length")).unwrap(),
+            AssertValue::Number(3.0)
+        );
+        assert_eq!(
+            extract_value(&response, &path("meta.length")).unwrap(),
+            AssertValue::Number(2.0)
+        );
+        // 字符串按字符计数
+        assert_eq!(
+            extract_value(&response, &path("name.length")).unwrap(),
+            AssertValue::Number(5.0)
+        );
+        assert!(matches!(
+            extract_value(&response, &path("count.length")),
+            Err(AssertError::TypeMismatch { .. })
+        ));
+        // length 只能作为最后一段
+        assert!(matches!(
+            extract_value(&response, &path("items.length.x")),
+            Err(AssertError::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_extract_body_real_length_field() {
+        let response = create_test_response(200, r#"{"file": {"length": 1024, "name": "a"}}"#);
+        let value = extract_value(
+            &response,
+            &ValuePath::Body(vec!["file".to_string(), "length".to_string()]),
+        )
+        .unwrap();
+        assert_eq!(value, AssertValue::Number(1024.0));
+    }
+
+    #[test]
+    fn test_extract_variable() {
+        let mut context = VariableContext::new();
+        context.insert("env", "prod");
+        context.insert("retries", "3");
+
+        assert_eq!(
+            extract_variable(&context, "env"),
+            Some(AssertValue::String("prod".to_string()))
+        );
+        assert_eq!(
+            extract_variable(&context, "retries"),
+            Some(AssertValue::Number(3.0))
+        );
+        assert_eq!(extract_variable(&context, "missing"), None);
+    }
+
+    #[test]
+    fn test_is_xml_content_type() {
+        assert!(is_xml_content_type("application/xml"));
+        assert!(is_xml_content_type("text/xml; charset=utf-8"));
+        assert!(is_xml_content_type("application/soap+xml"));
+        assert!(!is_xml_content_type("application/json"));
+        assert!(!is_xml_content_type(""));
+    }
+
+    #[test]
+    fn test_extract_xml_from_non_xml_response() {
+        let response = create_test_response(200, r#"{"id": 1}"#);
+        let result = extract_value(&response, &ValuePath::XmlBody("//id".to_string()));
+        assert!(
+            matches!(result, Err(AssertError::ExtractionError(ref m)) if m.contains("application/json"))
+        );
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn test_extract_xml_body() {
+        let mut response = create_test_response(
+            200,
+            r#"<?xml version="1.0"?>
+<users xmlns="urn:example" total="2">
+  <user id="1"><name>Alice</name></user>
+  <user id="7">
+    <name> Bob </name>
+    <roles><role>admin</role></roles>
+  </user>
+</users>"#,
+        );
+        response
+            .headers
+            .insert("content-type", "application/xml".parse().unwrap());
+        let xml = |path: &str| extract_value(&response, &ValuePath::XmlBody(path.to_string()));
+        let string = |s: &str| AssertValue::String(s.to_string());
+
+        assert_eq!(xml("/users/@total").unwrap(), string("2"));
+        assert_eq!(xml("/users/user/name").unwrap(), string("Alice"));
+        assert_eq!(xml("/users/user[2]/name").unwrap(), string("Bob"));
+        assert_eq!(xml("/users/user[2]/@id").unwrap(), string("7"));
+        assert_eq!(xml("//role").unwrap(), string("admin"));
+        assert_eq!(xml("//user/roles/*").unwrap(), string("admin"));
+        assert!(matches!(xml("//group"), Err(AssertError::PathNotFound(_))));
+        assert!(matches!(xml("/user"), Err(AssertError::PathNotFound(_))));
+        assert!(matches!(
+            xml("users/user"),
+            Err(AssertError::InvalidSyntax(_))
+        ));
+        assert!(matches!(
+            xml("//@id/name"),
+            Err(AssertError::InvalidSyntax(_))
+        ));
+        assert!(matches!(
+            xml("//user[0]"),
+            Err(AssertError::InvalidSyntax(_))
+        ));
+
+        response.body = "<users>".to_string();
+        let result = extract_value(&response, &ValuePath::XmlBody("/users".to_string()));
+        assert!(matches!(result, Err(AssertError::ExtractionError(_))));
+    }
+
+    #[test]
+    fn test_extract_body_array_index() {
+        let response = create_test_response(200, r#"{"items": [{"id": 7}, {"id": 8}]}"#);
+        let path = |p: &str| ValuePath::Body(json_path::split_path(p));
+
+        assert_eq!(
+            extract_value(&response, &path("items.1.id")).unwrap(),
+            AssertValue::Number(8.0)
+        );
+        assert_eq!(
+            extract_value(&response, &path("items[0].id")).unwrap(),
+            AssertValue::Number(7.0)
+        );
+        assert!(matches!(
+            extract_value(&response, &path("items.2.id")),
+            Err(AssertError::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_extract_truncated_body() {
+        let mut response = create_test_response(200, r#"{"items": [1, 2"#);
+        response.truncated_at = Some(15);
+
+        let err = extract_value(&response, &ValuePath::Body(vec!["items".to_string()]))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("body truncated at 15 bytes, increase [http] max_body_size"),
+            "{}",
+            err
+        );
+        assert!(extract_value(&response, &ValuePath::RawBody).is_err());
+        assert_eq!(
+            extract_value(&response, &ValuePath::Status).unwrap(),
+            AssertValue::Number(200.0)
+        );
+    }
+
     #[test]
     fn test_extract_body_path_not_found() {
         let response = create_test_response(200, r#"{"id": 42}"#);
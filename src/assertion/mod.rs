@@ -1,10 +1,14 @@
 mod evaluator;
 mod extractor;
 mod parser;
+mod schema;
 /// 断言模块 - 提供 API 响应断言能力
 mod types;
 
-pub use evaluator::evaluate_assertion;
-pub use extractor::extract_value;
-pub use parser::parse_assertion;
-pub use types::{AssertError, AssertExpr, AssertValue, AssertionResult, CompareOp, ValuePath};
+pub use evaluator::{evaluate_assertion, evaluate_condition};
+pub use extractor::{extract_value, extract_variable};
+pub use parser::{parse_assertion, parse_condition};
+pub use schema::validate_schema;
+pub use types::{
+    AssertError, AssertExpr, AssertValue, AssertionResult, CompareOp, FailureContext, ValuePath,
+};
@@ -1,4 +1,7 @@
-use crate::assertion::types::{AssertError, AssertExpr, AssertValue, CompareOp, ValuePath};
+use crate::assertion::types::{
+    AssertError, AssertExpr, AssertValue, CompareOp, Tolerance, ValuePath,
+};
+use crate::utils::json_path;
 
 /// 解析断言表达式
 ///
@@ -6,14 +9,50 @@ use crate::assertion::types::{AssertError, AssertExpr, AssertValue, CompareOp, V
 /// - `status == 200`
 /// - `headers.content-type contains "json"`
 /// - `body.user.id > 0`
+/// - `body.price == 19.99 ± 0.01`（也可写作 `within 0.01`，或按百分比 `± 1%`）
+/// - `body == "pong"`（完整的原始响应体）
+/// - `body.//user/id == "1"` 或 `body.xpath(/users/user[2]/@id) == "7"`（XML 响应体，需要 xml feature）
 /// - `response.time < 1000`
+/// - `response.url == "https://example.com/login"`（跟随重定向后的最终 URL）
+/// - `response.redirects == 1`
 /// - `body.token exists`
+/// - `headers.server not exists`
+/// - `headers.set-cookie[1] contains "theme"`、`headers.set-cookie.count == 2`（同名 Header 的第 n 个和个数）
+/// - `body.total == body.items.length`（右值以 `body.`、`headers.`、`response.` 开头或为 `status` 时，
+///   同样从响应中提取）
 pub fn parse_assertion(input: &str) -> Result<AssertExpr, AssertError> {
+    parse_expr(input, parse_value_path, true)
+}
+
+/// 解析条件表达式（左值为变量名）
+///
+/// 支持的格式：
+/// - `env == prod`
+/// - `retries > 3`
+/// - `token exists`
+pub fn parse_condition(input: &str) -> Result<AssertExpr, AssertError> {
+    parse_expr(input, parse_variable_path, false)
+}
+
+/// 通用表达式解析，左值由 `parse_path` 解析；right_paths 为 true 时右值也可以是响应中的值
+fn parse_expr(
+    input: &str,
+    parse_path: fn(&str) -> Result<ValuePath, AssertError>,
+    right_paths: bool,
+) -> Result<AssertExpr, AssertError> {
     let input = input.trim();
 
-    // 检查是否是 exists 断言
+    // 检查是否是 exists / not exists 断言
     if let Some(path_str) = input.strip_suffix("exists") {
-        let path = parse_value_path(path_str.trim())?;
+        let path_str = path_str.trim_end();
+        if let Some(path_str) = path_str
+            .strip_suffix("not")
+            .filter(|rest| rest.ends_with(char::is_whitespace))
+        {
+            let path = parse_path(path_str.trim())?;
+            return Ok(AssertExpr::NotExists { path });
+        }
+        let path = parse_path(path_str)?;
         return Ok(AssertExpr::Exists { path });
     }
 
@@ -53,10 +92,107 @@ pub fn parse_assertion(input: &str) -> Result<AssertExpr, AssertError> {
         ));
     }
 
-    let left = parse_value_path(left_str)?;
-    let right = parse_assert_value(right_str)?;
+    let left = parse_path(left_str)?;
+    let right_path = if right_paths {
+        parse_right_path(right_str)?
+    } else {
+        None
+    };
+
+    let expr = match right_path {
+        Some((right, tolerance)) => AssertExpr::ComparePaths {
+            left,
+            op,
+            right,
+            tolerance,
+        },
+        None => {
+            let (right, tolerance) = parse_right_with_tolerance(right_str)?;
+            AssertExpr::Compare {
+                left,
+                op,
+                right,
+                tolerance,
+            }
+        }
+    };
+
+    if let AssertExpr::Compare {
+        tolerance: Some(_), ..
+    }
+    | AssertExpr::ComparePaths {
+        tolerance: Some(_), ..
+    } = &expr
+        && !matches!(op, CompareOp::Equal | CompareOp::NotEqual)
+    {
+        return Err(AssertError::InvalidSyntax(format!(
+            "Tolerance is only supported with == and !=: {}",
+            input
+        )));
+    }
+
+    Ok(expr)
+}
 
-    Ok(AssertExpr::Compare { left, op, right })
+/// 右值为响应中的值时解析为路径（可带容差），否则返回 None 按字面量处理
+fn parse_right_path(input: &str) -> Result<Option<(ValuePath, Option<Tolerance>)>, AssertError> {
+    let (path_str, tolerance) = match input
+        .rsplit_once('±')
+        .or_else(|| input.rsplit_once(" within "))
+        .and_then(|(path, tolerance)| Some((path.trim(), Some(parse_tolerance(tolerance)?))))
+    {
+        Some(split) => split,
+        None => (input, None),
+    };
+
+    let is_path = path_str == "status"
+        || ["body.", "headers.", "response."]
+            .iter()
+            .any(|prefix| path_str.starts_with(prefix));
+    if !is_path {
+        return Ok(None);
+    }
+    Ok(Some((parse_value_path(path_str)?, tolerance)))
+}
+
+/// 解析右值及可选的容差后缀：`19.99 ± 0.01`、`19.99 within 0.01`、`100 ± 5%`
+///
+/// 后缀不是合法容差时（如字符串中的 "within"）按普通右值处理。
+fn parse_right_with_tolerance(
+    input: &str,
+) -> Result<(AssertValue, Option<Tolerance>), AssertError> {
+    let split = input
+        .rsplit_once('±')
+        .or_else(|| input.rsplit_once(" within "));
+
+    if let Some((value_str, tolerance_str)) = split
+        && let Some(tolerance) = parse_tolerance(tolerance_str)
+    {
+        return match parse_assert_value(value_str)? {
+            value @ AssertValue::Number(_) => Ok((value, Some(tolerance))),
+            _ => Err(AssertError::InvalidSyntax(format!(
+                "Tolerance requires a numeric value: {}",
+                input
+            ))),
+        };
+    }
+
+    Ok((parse_assert_value(input)?, None))
+}
+
+/// 解析容差：`0.01` 或 `1%`，必须为非负数
+fn parse_tolerance(input: &str) -> Option<Tolerance> {
+    let input = input.trim();
+    let (number, percent) = match input.strip_suffix('%') {
+        Some(number) => (number.trim_end(), true),
+        None => (input, false),
+    };
+    let value = number.parse::<f64>().ok().filter(|v| *v >= 0.0)?;
+    Some(if percent {
+        Tolerance::Percent(value)
+    } else {
+        Tolerance::Absolute(value)
+    })
 }
 
 /// 解析值路径
@@ -71,12 +207,43 @@ fn parse_value_path(input: &str) -> Result<ValuePath, AssertError> {
         return Ok(ValuePath::ResponseTime);
     }
 
+    if input == "response.url" {
+        return Ok(ValuePath::FinalUrl);
+    }
+
+    if input == "response.redirects" {
+        return Ok(ValuePath::Redirects);
+    }
+
     if let Some(rest) = input.strip_prefix("headers.") {
+        if let Some(name) = rest.strip_suffix(".count").filter(|name| !name.is_empty()) {
+            return Ok(ValuePath::HeaderCount(name.to_string()));
+        }
+        if let Some((name, index)) = rest.strip_suffix(']').and_then(|r| r.split_once('[')) {
+            let index = index.trim().parse::<usize>().map_err(|_| {
+                AssertError::InvalidSyntax(format!("Invalid header index in '{}'", input))
+            })?;
+            return Ok(ValuePath::HeaderAt(name.to_string(), index));
+        }
         return Ok(ValuePath::Header(rest.to_string()));
     }
 
+    if input == "body" {
+        return Ok(ValuePath::RawBody);
+    }
+
     if let Some(rest) = input.strip_prefix("body.") {
-        let segments: Vec<String> = rest.split('.').map(|s| s.to_string()).collect();
+        if let Some(path) = rest
+            .strip_prefix("xpath(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return Ok(ValuePath::XmlBody(path.trim().to_string()));
+        }
+        if rest.starts_with('/') {
+            return Ok(ValuePath::XmlBody(rest.to_string()));
+        }
+
+        let segments = json_path::split_path(rest);
         if segments.is_empty() {
             return Err(AssertError::InvalidSyntax(
                 "Body path cannot be empty".to_string(),
@@ -86,13 +253,33 @@ fn parse_value_path(input: &str) -> Result<ValuePath, AssertError> {
     }
 
     Err(AssertError::InvalidSyntax(format!(
-        "Invalid value path: {}. Must start with 'status', 'headers.', 'body.', or 'response.time'",
+        "Invalid value path: {}. Must be 'status', 'body', 'response.time', 'response.url', 'response.redirects' or start with 'headers.', 'body.'",
         input
     )))
 }
 
+/// 解析变量路径（条件表达式的左值）
+fn parse_variable_path(input: &str) -> Result<ValuePath, AssertError> {
+    let input = input.trim();
+
+    let is_valid = input
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && input.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !is_valid {
+        return Err(AssertError::InvalidSyntax(format!(
+            "Invalid variable name: {}",
+            input
+        )));
+    }
+
+    Ok(ValuePath::Variable(input.to_string()))
+}
+
 /// 解析断言值（右值）
-fn parse_assert_value(input: &str) -> Result<AssertValue, AssertError> {
+pub(crate) fn parse_assert_value(input: &str) -> Result<AssertValue, AssertError> {
     let input = input.trim();
 
     // Null
@@ -133,7 +320,9 @@ mod tests {
     fn test_parse_status_assertion() {
         let expr = parse_assertion("status == 200").unwrap();
         match expr {
-            AssertExpr::Compare { left, op, right } => {
+            AssertExpr::Compare {
+                left, op, right, ..
+            } => {
                 assert_eq!(left, ValuePath::Status);
                 assert_eq!(op, CompareOp::Equal);
                 assert_eq!(right, AssertValue::Number(200.0));
@@ -146,7 +335,9 @@ mod tests {
     fn test_parse_header_assertion() {
         let expr = parse_assertion("headers.content-type contains \"json\"").unwrap();
         match expr {
-            AssertExpr::Compare { left, op, right } => {
+            AssertExpr::Compare {
+                left, op, right, ..
+            } => {
                 assert_eq!(left, ValuePath::Header("content-type".to_string()));
                 assert_eq!(op, CompareOp::Contains);
                 assert_eq!(right, AssertValue::String("json".to_string()));
@@ -155,11 +346,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_duplicate_header_paths() {
+        assert_eq!(
+            parse_value_path("headers.set-cookie[1]").unwrap(),
+            ValuePath::HeaderAt("set-cookie".to_string(), 1)
+        );
+        assert_eq!(
+            parse_value_path("headers.set-cookie.count").unwrap(),
+            ValuePath::HeaderCount("set-cookie".to_string())
+        );
+        assert_eq!(
+            parse_value_path("headers.count").unwrap(),
+            ValuePath::Header("count".to_string())
+        );
+        assert!(matches!(
+            parse_value_path("headers.set-cookie[x]"),
+            Err(AssertError::InvalidSyntax(_))
+        ));
+        assert_eq!(
+            parse_value_path("headers.vary[0]").unwrap().to_string(),
+            "headers.vary[0]"
+        );
+    }
+
     #[test]
     fn test_parse_body_assertion() {
         let expr = parse_assertion("body.user.id > 0").unwrap();
         match expr {
-            AssertExpr::Compare { left, op, right } => {
+            AssertExpr::Compare {
+                left, op, right, ..
+            } => {
                 assert_eq!(
                     left,
                     ValuePath::Body(vec!["user".to_string(), "id".to_string()])
@@ -171,11 +388,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_raw_body_assertion() {
+        let expr = parse_assertion(r#"body == "pong""#).unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::Compare {
+                left: ValuePath::RawBody,
+                op: CompareOp::Equal,
+                right: AssertValue::String("pong".to_string()),
+                tolerance: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_body_assertion() {
+        let left = |input: &str| match parse_assertion(input).unwrap() {
+            AssertExpr::Compare { left, .. } | AssertExpr::Exists { path: left } => left,
+            other => panic!("Unexpected assertion: {:?}", other),
+        };
+
+        assert_eq!(
+            left(r#"body.//user/id == "1""#),
+            ValuePath::XmlBody("//user/id".to_string())
+        );
+        assert_eq!(
+            left(r#"body.xpath(/users/user[2]/@id) == "7""#),
+            ValuePath::XmlBody("/users/user[2]/@id".to_string())
+        );
+        assert_eq!(
+            left("body.xpath(//error) exists"),
+            ValuePath::XmlBody("//error".to_string())
+        );
+        assert_eq!(
+            ValuePath::XmlBody("//user/id".to_string()).to_string(),
+            "body.xpath(//user/id)"
+        );
+    }
+
     #[test]
     fn test_parse_response_time_assertion() {
         let expr = parse_assertion("response.time < 1000").unwrap();
         match expr {
-            AssertExpr::Compare { left, op, right } => {
+            AssertExpr::Compare {
+                left, op, right, ..
+            } => {
                 assert_eq!(left, ValuePath::ResponseTime);
                 assert_eq!(op, CompareOp::Less);
                 assert_eq!(right, AssertValue::Number(1000.0));
@@ -184,6 +442,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_final_url_assertion() {
+        let expr = parse_assertion("response.url contains \"/login\"").unwrap();
+        match expr {
+            AssertExpr::Compare { left, op, .. } => {
+                assert_eq!(left, ValuePath::FinalUrl);
+                assert_eq!(op, CompareOp::Contains);
+            }
+            _ => panic!("Expected Compare assertion"),
+        }
+
+        let expr = parse_assertion("response.redirects == 0").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::Compare {
+                left: ValuePath::Redirects,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_parse_exists_assertion() {
         let expr = parse_assertion("body.token exists").unwrap();
@@ -195,6 +474,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_tolerance() {
+        let expr = parse_assertion("body.price == 19.99 ± 0.01").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::Compare {
+                left: ValuePath::Body(vec!["price".to_string()]),
+                op: CompareOp::Equal,
+                right: AssertValue::Number(19.99),
+                tolerance: Some(Tolerance::Absolute(0.01)),
+            }
+        );
+
+        let expr = parse_assertion("body.total != 100 within 5 %").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::Compare {
+                right: AssertValue::Number(100.0),
+                tolerance: Some(Tolerance::Percent(5.0)),
+                ..
+            }
+        ));
+
+        // "within" 后面不是容差时按普通字符串处理
+        let expr = parse_assertion("body.msg == \"ships within 2 days\"").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::Compare {
+                right: AssertValue::String(ref s),
+                tolerance: None,
+                ..
+            } if s == "ships within 2 days"
+        ));
+
+        assert!(parse_assertion("body.price > 19.99 ± 0.01").is_err());
+        assert!(parse_assertion("body.name == \"a\" ± 1").is_err());
+        assert!(parse_condition("retries == 3 ± 1").is_ok());
+    }
+
+    #[test]
+    fn test_parse_compare_paths() {
+        let expr = parse_assertion("body.total == body.items.length").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::ComparePaths {
+                left: ValuePath::Body(vec!["total".to_string()]),
+                op: CompareOp::Equal,
+                right: ValuePath::Body(vec!["items".to_string(), "length".to_string()]),
+                tolerance: None,
+            }
+        );
+
+        let expr = parse_assertion("headers.x-total-count >= status").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::ComparePaths {
+                right: ValuePath::Status,
+                op: CompareOp::GreaterOrEqual,
+                ..
+            }
+        ));
+
+        let expr = parse_assertion("body.net == body.gross ± 0.01").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::ComparePaths {
+                tolerance: Some(Tolerance::Absolute(_)),
+                ..
+            }
+        ));
+        assert!(parse_assertion("body.net > body.gross ± 0.01").is_err());
+
+        // 带引号时仍是字符串字面量，条件表达式中不读取响应
+        let expr = parse_assertion("body.next == \"body.items\"").unwrap();
+        assert!(matches!(
+            expr,
+            AssertExpr::Compare {
+                right: AssertValue::String(ref s),
+                ..
+            } if s == "body.items"
+        ));
+        let expr = parse_condition("mode == status").unwrap();
+        assert!(matches!(expr, AssertExpr::Compare { .. }));
+    }
+
+    #[test]
+    fn test_parse_not_exists_assertion() {
+        let expr = parse_assertion("headers.server not exists").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::NotExists {
+                path: ValuePath::Header("server".to_string())
+            }
+        );
+
+        let expr = parse_assertion("body.user.password  not   exists").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::NotExists {
+                path: ValuePath::Body(vec!["user".to_string(), "password".to_string()])
+            }
+        );
+
+        // 以 not 结尾的字段名仍是 exists 断言
+        let expr = parse_assertion("body.not exists").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::Exists {
+                path: ValuePath::Body(vec!["not".to_string()])
+            }
+        );
+    }
+
     #[test]
     fn test_parse_operators() {
         assert!(parse_assertion("status == 200").is_ok());
@@ -252,6 +644,31 @@ mod tests {
         assert!(parse_assertion("status ==").is_err());
     }
 
+    #[test]
+    fn test_parse_condition() {
+        let expr = parse_condition("env == prod").unwrap();
+        match expr {
+            AssertExpr::Compare {
+                left, op, right, ..
+            } => {
+                assert_eq!(left, ValuePath::Variable("env".to_string()));
+                assert_eq!(op, CompareOp::Equal);
+                assert_eq!(right, AssertValue::String("prod".to_string()));
+            }
+            _ => panic!("Expected Compare condition"),
+        }
+
+        let expr = parse_condition("token exists").unwrap();
+        assert_eq!(
+            expr,
+            AssertExpr::Exists {
+                path: ValuePath::Variable("token".to_string())
+            }
+        );
+
+        assert!(parse_condition("body.id == 1").is_err());
+    }
+
     #[test]
     fn test_parse_nested_body_path() {
         let expr = parse_assertion("body.user.profile.email == \"test@example.com\"").unwrap();
@@ -0,0 +1,120 @@
+//! JSON Schema 校验（@schema），需要启用 `schema` feature
+
+use crate::assertion::types::{AssertError, AssertionResult};
+use crate::http::Response;
+use std::path::Path;
+
+/// 校验响应体是否符合 `path` 指向的 JSON Schema
+///
+/// `raw` 为报告中显示的断言文本。响应体不是 JSON 或不符合 schema 时断言失败，
+/// 消息为第一个校验错误；schema 无法读取或编译时返回错误结果。
+pub fn validate_schema(raw: String, path: &Path, response: &Response) -> AssertionResult {
+    if let Some(message) = response.truncation_error() {
+        return AssertionResult::error(raw, AssertError::ExtractionError(message));
+    }
+    let Some(instance) = response.json_value() else {
+        return AssertionResult::failure(
+            raw,
+            "non-JSON body".to_string(),
+            "JSON body".to_string(),
+            "Response body is not valid JSON, cannot validate against the schema".to_string(),
+        );
+    };
+
+    match first_error(path, instance) {
+        Ok(None) => AssertionResult::success(raw, "valid".to_string(), "valid".to_string()),
+        Ok(Some(message)) => {
+            AssertionResult::failure(raw, "invalid".to_string(), "valid".to_string(), message)
+        }
+        Err(message) => AssertionResult::error(raw, AssertError::ExtractionError(message)),
+    }
+}
+
+/// 读取并编译 schema，返回第一个校验错误（`/path: message`）
+#[cfg(feature = "schema")]
+fn first_error(path: &Path, instance: &serde_json::Value) -> Result<Option<String>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read schema {}: {}", path.display(), e))?;
+    let schema: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid JSON in schema {}: {}", path.display(), e))?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| format!("Invalid schema {}: {}", path.display(), e))?;
+
+    Ok(validator.validate(instance).err().map(|error| {
+        let location = error.instance_path().to_string();
+        if location.is_empty() {
+            error.to_string()
+        } else {
+            format!("{}: {}", location, error)
+        }
+    }))
+}
+
+#[cfg(not(feature = "schema"))]
+fn first_error(_path: &Path, _instance: &serde_json::Value) -> Result<Option<String>, String> {
+    Err("@schema requires rupost to be built with the 'schema' feature".to_string())
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    const USER_SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": {
+            "id": {"type": "integer"},
+            "name": {"type": "string"}
+        }
+    }"#;
+
+    fn response(body: &str) -> Response {
+        Response::new(200, HeaderMap::new(), body.to_string(), Duration::ZERO).unwrap()
+    }
+
+    fn validate(schema: &str, body: &str) -> AssertionResult {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("user.schema.json");
+        std::fs::write(&path, schema).unwrap();
+        validate_schema(
+            "schema ./user.schema.json".to_string(),
+            &path,
+            &response(body),
+        )
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let result = validate(USER_SCHEMA, r#"{"id": 1, "name": "Ada"}"#);
+        assert!(result.passed, "{:?}", result.message);
+
+        let result = validate(USER_SCHEMA, r#"{"id": "1", "name": "Ada"}"#);
+        assert!(!result.passed);
+        assert_eq!(
+            result.message.as_deref(),
+            Some(r#"/id: "1" is not of type "integer""#)
+        );
+
+        let result = validate(USER_SCHEMA, "<html></html>");
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("not valid JSON"));
+    }
+
+    #[test]
+    fn test_validate_schema_errors() {
+        let result = validate("{not json", "{}");
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("Invalid JSON in schema"));
+
+        let result = validate_schema(
+            "schema ./missing.json".to_string(),
+            Path::new("/nonexistent/missing.json"),
+            &response("{}"),
+        );
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("Failed to read schema"));
+    }
+}
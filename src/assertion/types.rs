@@ -23,19 +23,33 @@ pub enum AssertError {
 
     #[error("Value extraction failed: {0}")]
     ExtractionError(String),
+
+    #[error("Unresolved variable in assertion: {{{{{0}}}}} is not defined")]
+    UnresolvedVariable(String),
 }
 
 /// 断言表达式
 #[derive(Debug, Clone, PartialEq)]
 pub enum AssertExpr {
-    /// 比较断言: left op right
+    /// 比较断言: left op right [± tolerance]
     Compare {
         left: ValuePath,
         op: CompareOp,
         right: AssertValue,
+        /// 数字相等比较的容差（`± 0.01`、`within 1%`）
+        tolerance: Option<Tolerance>,
+    },
+    /// 比较响应中的两个值: left op right [± tolerance]，如 `body.total == body.items.length`
+    ComparePaths {
+        left: ValuePath,
+        op: CompareOp,
+        right: ValuePath,
+        tolerance: Option<Tolerance>,
     },
     /// 存在性断言: path exists
     Exists { path: ValuePath },
+    /// 不存在断言: path not exists
+    NotExists { path: ValuePath },
 }
 
 /// 值路径 - 用于从响应中提取值
@@ -43,12 +57,26 @@ pub enum AssertExpr {
 pub enum ValuePath {
     /// HTTP 状态码
     Status,
-    /// 响应 Header
+    /// 响应 Header（同名 Header 有多个时取第一个）
     Header(String),
+    /// 同名 Header 中的第 n 个（从 0 开始），如 `headers.set-cookie[1]`
+    HeaderAt(String, usize),
+    /// 同名 Header 的个数，如 `headers.set-cookie.count`
+    HeaderCount(String),
     /// JSON Body 路径（点号分隔的路径段）
     Body(Vec<String>),
+    /// XML Body 路径（`/a/b`、`//b/@id` 形式的简化 XPath）
+    XmlBody(String),
+    /// 完整的原始响应体（不做 JSON 解析，适用于纯文本响应）
+    RawBody,
     /// 响应时间（毫秒）
     ResponseTime,
+    /// 跟随重定向后的最终 URL
+    FinalUrl,
+    /// 跟随的重定向次数
+    Redirects,
+    /// 上下文变量（用于 @skip-if 条件）
+    Variable(String),
 }
 
 impl fmt::Display for ValuePath {
@@ -56,8 +84,15 @@ impl fmt::Display for ValuePath {
         match self {
             ValuePath::Status => write!(f, "status"),
             ValuePath::Header(name) => write!(f, "headers.{}", name),
+            ValuePath::HeaderAt(name, index) => write!(f, "headers.{}[{}]", name, index),
+            ValuePath::HeaderCount(name) => write!(f, "headers.{}.count", name),
             ValuePath::Body(segments) => write!(f, "body.{}", segments.join(".")),
+            ValuePath::XmlBody(path) => write!(f, "body.xpath({})", path),
+            ValuePath::RawBody => write!(f, "body"),
             ValuePath::ResponseTime => write!(f, "response.time"),
+            ValuePath::FinalUrl => write!(f, "response.url"),
+            ValuePath::Redirects => write!(f, "response.redirects"),
+            ValuePath::Variable(name) => write!(f, "{}", name),
         }
     }
 }
@@ -109,6 +144,41 @@ impl fmt::Display for CompareOp {
     }
 }
 
+/// 数字相等比较的容差
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// 绝对误差：`± 0.01`
+    Absolute(f64),
+    /// 相对于期望值的百分比：`± 1%`
+    Percent(f64),
+}
+
+impl Tolerance {
+    /// 未指定容差时的相对误差（几个 ULP）：吸收浮点运算的表示误差，
+    /// 又不会让大整数（如 ID）的真实差异被忽略
+    pub const DEFAULT_RELATIVE: f64 = 4.0 * f64::EPSILON;
+
+    /// 按容差判断两个数字是否相等，未指定时使用 [`Tolerance::DEFAULT_RELATIVE`]
+    fn approx_eq(tolerance: Option<Self>, actual: f64, expected: f64) -> bool {
+        let diff = (actual - expected).abs();
+        match tolerance {
+            Some(Self::Absolute(delta)) => diff <= delta,
+            Some(Self::Percent(percent)) => diff <= expected.abs() * percent / 100.0,
+            // 接近 0 时退化为绝对误差
+            None => diff <= Self::DEFAULT_RELATIVE * actual.abs().max(expected.abs()).max(1.0),
+        }
+    }
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tolerance::Absolute(delta) => write!(f, "± {}", delta),
+            Tolerance::Percent(percent) => write!(f, "± {}%", percent),
+        }
+    }
+}
+
 /// 断言值
 #[derive(Debug, Clone, PartialEq)]
 pub enum AssertValue {
@@ -132,11 +202,21 @@ impl fmt::Display for AssertValue {
 impl AssertValue {
     /// 比较两个值
     pub fn compare(&self, op: CompareOp, other: &AssertValue) -> Result<bool, AssertError> {
+        self.compare_with_tolerance(op, other, None)
+    }
+
+    /// 比较两个值，数字的 == / != 按容差判断
+    pub fn compare_with_tolerance(
+        &self,
+        op: CompareOp,
+        other: &AssertValue,
+        tolerance: Option<Tolerance>,
+    ) -> Result<bool, AssertError> {
         match (self, other) {
             // 数字比较
             (AssertValue::Number(a), AssertValue::Number(b)) => Ok(match op {
-                CompareOp::Equal => (a - b).abs() < f64::EPSILON,
-                CompareOp::NotEqual => (a - b).abs() >= f64::EPSILON,
+                CompareOp::Equal => Tolerance::approx_eq(tolerance, *a, *b),
+                CompareOp::NotEqual => !Tolerance::approx_eq(tolerance, *a, *b),
                 CompareOp::Greater => a > b,
                 CompareOp::Less => a < b,
                 CompareOp::GreaterOrEqual => a >= b,
@@ -224,6 +304,21 @@ pub struct AssertionResult {
 
     /// 失败消息
     pub message: Option<String>,
+
+    /// 失败时的响应片段，用于 -v / --show-context 展示
+    pub context: Option<FailureContext>,
+}
+
+/// 断言失败时的响应片段
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailureContext {
+    /// JSON body 路径的父节点，key 为路径的最后一段（对象的键或数组下标）
+    Json {
+        parent: serde_json::Value,
+        key: String,
+    },
+    /// 字符串 contains 失败时的实际字符串和期望包含的文本
+    Text { actual: String, expected: String },
 }
 
 impl AssertionResult {
@@ -235,6 +330,7 @@ impl AssertionResult {
             actual: Some(actual),
             expected,
             message: None,
+            context: None,
         }
     }
 
@@ -246,6 +342,7 @@ impl AssertionResult {
             actual: Some(actual),
             expected,
             message: Some(message),
+            context: None,
         }
     }
 
@@ -257,8 +354,15 @@ impl AssertionResult {
             actual: None,
             expected: String::new(),
             message: Some(error.to_string()),
+            context: None,
         }
     }
+
+    /// 附加失败时的响应片段
+    pub fn with_context(mut self, context: Option<FailureContext>) -> Self {
+        self.context = context;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +397,52 @@ mod tests {
         assert!(!a.compare(CompareOp::Equal, &b).unwrap());
     }
 
+    #[test]
+    fn test_assert_value_compare_tolerance() {
+        let price = AssertValue::Number(19.994);
+        let expected = AssertValue::Number(19.99);
+
+        assert!(
+            price
+                .compare_with_tolerance(
+                    CompareOp::Equal,
+                    &expected,
+                    Some(Tolerance::Absolute(0.01))
+                )
+                .unwrap()
+        );
+        assert!(
+            price
+                .compare_with_tolerance(
+                    CompareOp::NotEqual,
+                    &expected,
+                    Some(Tolerance::Percent(0.01))
+                )
+                .unwrap()
+        );
+
+        // 默认相对误差：大数的最低位差异视为相等，小数的真实差异不会被忽略
+        let big = AssertValue::Number(1e12);
+        assert!(
+            big.compare(CompareOp::Equal, &AssertValue::Number(1e12 + 1e-4))
+                .unwrap()
+        );
+        assert!(
+            !big.compare(CompareOp::Equal, &AssertValue::Number(1e12 + 1.0))
+                .unwrap()
+        );
+        assert!(
+            !AssertValue::Number(1e-6)
+                .compare(CompareOp::Equal, &AssertValue::Number(2e-6))
+                .unwrap()
+        );
+        assert!(
+            AssertValue::Number(0.1 + 0.2)
+                .compare(CompareOp::Equal, &AssertValue::Number(0.3))
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_assert_value_compare_strings() {
         let a = AssertValue::String("hello world".to_string());
@@ -320,6 +470,7 @@ mod tests {
             ValuePath::Body(vec!["user".to_string(), "id".to_string()]).to_string(),
             "body.user.id"
         );
+        assert_eq!(ValuePath::RawBody.to_string(), "body");
         assert_eq!(ValuePath::ResponseTime.to_string(), "response.time");
     }
 }
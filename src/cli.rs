@@ -1,11 +1,18 @@
-use clap::{Parser, Subcommand};
-use rupost::http::Response;
-use rupost::parser::types::ParsedRequest;
-use rupost::runner::TestExecutor;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use rupost::generator::http::HttpGenerator;
+use rupost::history::exchange::ExchangeFormat;
+use rupost::http::download::{DownloadTarget, format_size};
+use rupost::http::types::{FormPart, Method, Url};
+use rupost::http::{Client, Response};
+use rupost::parser::converter::is_json_like;
+use rupost::parser::types::{Auth, ParsedRequest};
+use rupost::runner::{RepeatSummary, TestExecutor, TestExecutorBuilder, TestReporter};
+use rupost::utils::cookie::CookieJar;
 use rupost::utils::{ResponseFormat, ResponseFormatter};
 use rupost::variable::VariableContext;
 use rupost::{Result, RupostError};
-use tracing::{debug, error, info};
+use std::path::{Path, PathBuf};
+use tracing::{debug, error, info, warn};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -13,6 +20,18 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
+    /// Also append logs to this file as JSON lines (level still follows RUST_LOG)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Use this config file instead of merging ~/.config/rupost/rupost.toml with the nearest rupost.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Disable colored output and the progress bar (also the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
     /// 可选参数用于默认运行(curl/httpie 风格)
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub args: Vec<String>,
@@ -21,12 +40,23 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Run requests from a file
-    #[command(alias = "t")]
+    #[command(
+        alias = "t",
+        after_help = "Exit codes:\n  \
+            0  all requests passed\n  \
+            1  assertion or status failures (or some requests got no response)\n  \
+            2  the file could not be parsed, or the config/CLI options are invalid\n  \
+            3  every request failed at the transport level (connection refused, timeout, ...)\n\n\
+            --exit-zero turns 1 and 3 into 0; 2 is still reported."
+    )]
     Test {
-        /// Path to the .http file
-        path: String,
+        /// Paths to the .http / .md files, run in order with a combined summary
+        /// (directories are accepted with --list)
+        #[arg(required = true)]
+        paths: Vec<String>,
 
-        /// Environment name (e.g., dev, staging, prod)
+        /// Environment name (e.g., dev, staging, prod); defaults to $RUPOST_ENV, then
+        /// default_environment in rupost.toml; "none" disables it
         #[arg(short, long)]
         env: Option<String>,
 
@@ -34,9 +64,97 @@ pub enum Commands {
         #[arg(long, value_name = "KEY=VALUE")]
         var: Vec<String>,
 
-        /// Show detailed request/response information
-        #[arg(short, long)]
-        verbose: bool,
+        /// Load variable overrides from a file of key=value lines (--var takes precedence)
+        #[arg(long, value_name = "PATH")]
+        var_file: Option<String>,
+
+        /// Increase output detail: -v shows responses, -vv also the sent requests
+        #[arg(short, long, action = ArgAction::Count)]
+        verbose: u8,
+
+        /// Fail requests that still contain unresolved {{variables}}
+        #[arg(long)]
+        strict_vars: bool,
+
+        /// Treat validation warnings (e.g. duplicate @name) as errors
+        #[arg(long)]
+        strict: bool,
+
+        /// Prefix relative request URLs (e.g. `GET /api/users`) with this URL
+        #[arg(long, value_name = "URL")]
+        base_url: Option<String>,
+
+        /// Allow `@pre-run` directives to execute shell commands
+        #[arg(long)]
+        allow_exec: bool,
+
+        /// Run the whole file N times, sharing variables (`@once` requests run only once)
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+
+        /// List the requests (number, name, method, URL) without running them; URL variables
+        /// are resolved when --env / --var is given
+        #[arg(long)]
+        list: bool,
+
+        /// Do not follow redirects; 3xx responses are returned as-is (per request: `@no-redirect`)
+        #[arg(long, visible_alias = "no-follow")]
+        no_redirect: bool,
+
+        /// Follow at most N redirects (default 10); a longer chain fails the request
+        #[arg(long, value_name = "N", conflicts_with = "no_redirect", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_redirects: Option<usize>,
+
+        /// Only run requests tagged with this `@group` (repeatable; others are skipped)
+        #[arg(long, value_name = "NAME")]
+        group: Vec<String>,
+
+        /// Skip TLS certificate verification (also `[http] insecure` in rupost.toml)
+        #[arg(short = 'k', long)]
+        insecure: bool,
+
+        /// Trust the CA certificate(s) in this PEM file (also `[http] ca_cert` in rupost.toml)
+        #[arg(long, value_name = "PATH")]
+        cacert: Option<String>,
+
+        /// Send requests through this proxy (http://, https:// or socks5://; also `[http] proxy`).
+        /// Without it HTTP_PROXY / HTTPS_PROXY / NO_PROXY are used
+        #[arg(short = 'x', long, value_name = "URL")]
+        proxy: Option<String>,
+
+        /// Do not use any proxy, including HTTP_PROXY / HTTPS_PROXY
+        #[arg(long, conflicts_with = "proxy")]
+        no_proxy: bool,
+
+        /// Connect to ADDR for HOST while keeping the Host header and TLS SNI, like curl
+        /// (repeatable; also `[http] resolve`). Applies to every port of HOST
+        #[arg(long, value_name = "HOST:PORT:ADDR")]
+        resolve: Vec<String>,
+
+        /// Retry connection errors and timeouts up to N times with exponential backoff
+        /// (also `[http.retry]`, which can add status codes such as 503)
+        #[arg(long, value_name = "N")]
+        retry: Option<u32>,
+
+        /// Truncate response bodies longer than N bytes (0 = no limit; default 200, or 10240 with -v)
+        #[arg(long, value_name = "N")]
+        max_body_bytes: Option<usize>,
+
+        /// Write every captured variable to rupost.captured.toml for the next run (not just @persist ones)
+        #[arg(long)]
+        persist_captures: bool,
+
+        /// Exit with 0 even when requests fail, for reporting-only runs (parse/config errors still exit 2)
+        #[arg(long)]
+        exit_zero: bool,
+
+        /// Show the part of the response around each failed assertion (always shown with -v)
+        #[arg(long)]
+        show_context: bool,
+
+        /// Seed for {{$uuid}} and {{$randomInt}} so repeated runs send identical values
+        #[arg(long)]
+        seed: Option<u64>,
     },
 
     /// Manage request history
@@ -49,6 +167,96 @@ pub enum Commands {
     /// Generate test file from history
     #[command(alias = "g")]
     Generate(GenerateArgs),
+
+    /// Convert a curl command into a .http request block
+    Convert {
+        /// The curl command (read from stdin when omitted)
+        command: Option<String>,
+
+        /// Write the block to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// Append to the output file instead of overwriting it
+        #[arg(long, requires = "out")]
+        append: bool,
+    },
+
+    /// Export request files to other tools
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+
+    /// Check .http/.md files for syntax errors without sending requests
+    Validate {
+        /// Files or directories (searched recursively for .http and .md files)
+        #[arg(required = true)]
+        paths: Vec<String>,
+
+        /// Treat warnings (e.g. duplicate @name) as errors
+        #[arg(long)]
+        strict: bool,
+
+        /// Environment whose variables must resolve every {{variable}} in the files
+        #[arg(short, long)]
+        env: Option<String>,
+    },
+
+    /// Rewrite .http files in the canonical layout
+    Fmt {
+        /// Files or directories (searched recursively for .http files)
+        #[arg(required = true)]
+        paths: Vec<String>,
+
+        /// Only report files that would change; exit non-zero if any would
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Export a .http/.md file as a Postman Collection v2.1
+    Postman {
+        /// Path to the .http or .md file
+        path: String,
+
+        /// Output collection file; environments from rupost.toml are written next to it
+        #[arg(short, long)]
+        out: String,
+    },
+
+    /// Print curl commands for a history entry or the requests in a .http/.md file
+    Curl {
+        /// Path to the .http or .md file
+        #[arg(required_unless_present = "history")]
+        path: Option<String>,
+
+        /// History entry id (or unique prefix) to export instead of a file
+        #[arg(long, conflicts_with = "path")]
+        history: Option<String>,
+
+        /// Only export the request with this @name
+        #[arg(long, conflicts_with = "index")]
+        name: Option<String>,
+
+        /// Only export the N-th request (1-based)
+        #[arg(long)]
+        index: Option<usize>,
+
+        /// Environment name (e.g., dev, staging, prod)
+        #[arg(short, long)]
+        env: Option<String>,
+
+        /// Variable overrides (key=value)
+        #[arg(long, value_name = "KEY=VALUE")]
+        var: Vec<String>,
+
+        /// Prefix relative request URLs (e.g. `GET /api/users`) with this URL
+        #[arg(long, value_name = "URL", conflicts_with = "history")]
+        base_url: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -64,6 +272,53 @@ pub enum HistoryCommands {
         #[arg(short, long)]
         reverse: bool,
     },
+
+    /// Show aggregated request statistics
+    #[command(alias = "s")]
+    Stats {
+        /// Only include entries newer than this (e.g. 30m, 24h, 7d, 2024-01-31)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Emit statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export history to an interchange format
+    Export {
+        /// Output format (inferred from --out when omitted, defaults to har)
+        #[arg(long, value_enum)]
+        format: Option<ExchangeFormat>,
+
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    /// Import history entries from a HAR file
+    Import {
+        /// Path to the .har file
+        path: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum GenerateFormat {
+    /// Runnable .http file
+    Http,
+    /// Markdown documentation with runnable ```http blocks
+    Md,
+    /// Shell script of httpie commands
+    Httpie,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum NameStyle {
+    /// Method and URL path, e.g. get_api_users
+    Path,
+    /// Request timestamp, e.g. req_1717000000
+    Timestamp,
 }
 
 #[derive(Parser, Debug)]
@@ -71,46 +326,180 @@ pub struct GenerateArgs {
     /// Output file path
     pub output_file: String,
 
-    /// Number of recent requests to include
-    #[arg(short, long, default_value = "1")]
-    pub last: usize,
+    /// Number of recent requests to include (default 1), or the number of
+    /// candidates offered with --interactive (default 50)
+    #[arg(short, long)]
+    pub last: Option<usize>,
 
     /// Interactive selection mode
     #[arg(short, long)]
     pub interactive: bool,
+
+    /// Generate from a HAR capture instead of history
+    #[arg(long, value_name = "HAR_FILE")]
+    pub from_har: Option<String>,
+
+    /// Keep static assets (images, css, fonts) when generating from HAR
+    #[arg(long, requires = "from_har")]
+    pub include_assets: bool,
+
+    /// Generate from an OpenAPI 3 spec (YAML or JSON) instead of history
+    #[arg(long, value_name = "SPEC_FILE", conflicts_with = "from_har")]
+    pub from_openapi: Option<String>,
+
+    /// Collapse repeated requests (same method, URL and body), keeping the most recent
+    #[arg(long)]
+    pub dedupe: bool,
+
+    /// How request names are derived
+    #[arg(long, value_enum, default_value = "path")]
+    pub name_style: NameStyle,
+
+    /// Keep full origins in request lines instead of {{base_url}} variables
+    #[arg(long)]
+    pub no_extract_base_url: bool,
+
+    /// Output format for history based generation
+    #[arg(
+        long,
+        value_enum,
+        default_value = "http",
+        conflicts_with_all = ["from_har", "from_openapi"]
+    )]
+    pub format: GenerateFormat,
+}
+
+/// 命令行解析结果
+struct CliRequest {
+    request: ParsedRequest,
+    /// curl -c：执行后将响应的 Set-Cookie 写入该文件
+    cookie_jar: Option<PathBuf>,
 }
 
 struct CliRunner {
     formatter: ResponseFormatter,
-    executor: TestExecutor,
+    /// 执行时按 --download 等设置构建 TestExecutor
+    executor: TestExecutorBuilder,
+    /// httpie 风格未指定 body 时，是否读取管道输入的 stdin
+    read_stdin: bool,
+    /// -o / --download：将响应 body 写入文件而不是打印
+    download: Option<DownloadTarget>,
+    /// --save：执行后将请求追加到该 .http 文件
+    save: Option<PathBuf>,
+    /// --repeat / --concurrency：重复执行并只打印统计
+    repeat: Option<RepeatSettings>,
+}
+
+/// 重复执行的次数和并发数
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RepeatSettings {
+    times: usize,
+    concurrency: usize,
 }
 
 impl CliRunner {
     fn new() -> Self {
         Self {
             formatter: ResponseFormatter::new(ResponseFormat::Verbose),
-            executor: TestExecutor::new(),
+            executor: TestExecutor::builder(),
+            read_stdin: false,
+            download: None,
+            save: None,
+            repeat: None,
         }
     }
 
-    async fn run(&self, args: Vec<String>) -> Result<()> {
-        info!("Parsing command line arguments");
-        let parsed_request = self.parse_args(args)?;
+    /// --max-body-bytes：截断过长的响应 body
+    fn with_max_body_bytes(mut self, max_body_bytes: Option<usize>) -> Self {
+        if let Some(max_body_bytes) = max_body_bytes {
+            self.formatter = self.formatter.with_max_body_bytes(max_body_bytes);
+        }
+        self
+    }
 
-        // Setup empty context for CLI run
-        let mut context = VariableContext::new();
+    fn with_repeat(mut self, repeat: Option<RepeatSettings>) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    fn with_save(mut self, save: Option<PathBuf>) -> Self {
+        self.save = save;
+        self
+    }
+
+    /// body 由客户端直接写入文件，这里只保留用于发送前检查和输出摘要
+    fn with_download(mut self, download: Option<DownloadTarget>) -> Self {
+        self.download = download;
+        self
+    }
+
+    fn with_stdin(mut self, read_stdin: bool) -> Self {
+        self.read_stdin = read_stdin;
+        self
+    }
+
+    /// 启用严格变量模式（--strict-vars）
+    fn with_strict_vars(mut self, strict_vars: bool) -> Self {
+        self.executor = self.executor.strict_vars(strict_vars);
+        self
+    }
+
+    /// 使用按 --no-redirect / --insecure / --cacert 配置的客户端
+    fn with_client(mut self, client: Client) -> Self {
+        self.executor = self.executor.client(client);
+        self
+    }
+
+    fn executor(&self) -> TestExecutor {
+        self.executor
+            .clone()
+            .download(self.download.clone())
+            .build()
+    }
+
+    async fn run(&self, args: Vec<String>, mut context: VariableContext) -> Result<()> {
+        // 指定的输出文件已存在时，在发送请求前报错
+        if let Some(target) = &self.download {
+            target.check()?;
+        }
+
+        info!("Parsing command line arguments");
+        let CliRequest {
+            request: parsed_request,
+            cookie_jar,
+        } = self.parse_args(args)?;
 
         info!(url = %parsed_request.url, method = ?parsed_request.method_or_default(), "Executing HTTP request");
 
+        if let Some(repeat) = self.repeat {
+            return self.run_repeated(parsed_request, &context, repeat).await;
+        }
+
+        // 保存的是变量替换前的请求
+        let unresolved_request = self.save.as_ref().map(|_| parsed_request.clone());
+
         // Execute with source="cli"
         let result = self
-            .executor
+            .executor()
             .execute_one(parsed_request, 1, &mut context, Some("cli".to_string()))
             .await;
 
+        if let (Some(path), Some(response)) = (&cookie_jar, &result.response) {
+            save_cookie_jar(path, &result.url, response)?;
+        }
+
+        if let (Some(path), Some(request), Some(response)) =
+            (&self.save, &unresolved_request, &result.response)
+        {
+            save_request(path, request, response)?;
+        }
+
         if result.success {
             if let Some(response) = result.response {
-                self.format_response(response);
+                match &self.download {
+                    Some(_) => print_download(&response),
+                    None => self.format_response(response),
+                }
             }
         } else {
             error!("Request failed: {}", result.error.unwrap_or_default());
@@ -118,6 +507,32 @@ impl CliRunner {
         Ok(())
     }
 
+    /// 重复执行请求，打印各状态码次数和耗时统计而不是每个响应
+    async fn run_repeated(
+        &self,
+        request: ParsedRequest,
+        context: &VariableContext,
+        repeat: RepeatSettings,
+    ) -> Result<()> {
+        let results = self
+            .executor()
+            .execute_repeated(
+                request,
+                context,
+                repeat.times,
+                repeat.concurrency,
+                Some("cli".to_string()),
+            )
+            .await;
+
+        // 失败原因通常相同，只报告第一个
+        if let Some(error) = results.iter().find_map(|r| r.error.as_deref()) {
+            error!("Request failed: {}", error);
+        }
+        TestReporter::new(0).print_repeat_summary(&RepeatSummary::from_results(&results));
+        Ok(())
+    }
+
     fn format_response(&self, response: Response) {
         match self.formatter.format(&response) {
             Ok(output) => println!("{}", output),
@@ -125,7 +540,7 @@ impl CliRunner {
         }
     }
 
-    fn parse_args(&self, args: Vec<String>) -> Result<ParsedRequest> {
+    fn parse_args(&self, args: Vec<String>) -> Result<CliRequest> {
         let args = if args.first().map(|s| s == "curl").unwrap_or(false) {
             debug!("Detected curl-style command");
             args[1..].to_vec()
@@ -141,18 +556,45 @@ impl CliRunner {
 
         if is_curl {
             debug!("Using curl parser");
-            self.parse_curl(args)
+            self.parse_curl_command(args)
         } else {
             debug!("Using httpie parser");
-            self.parse_httpie(args)
+            let mut request = self.parse_httpie(args)?;
+            // 参数中没有 body 时才读取 stdin，stdin 为终端时不读取
+            if self.read_stdin
+                && request.body.is_none()
+                && request.form.is_empty()
+                && let Some(body) = read_piped_stdin()?
+            {
+                debug!("Using stdin as request body");
+                set_stdin_body(&mut request, body);
+            }
+            Ok(CliRequest {
+                request,
+                cookie_jar: None,
+            })
         }
     }
+
     fn parse_curl(&self, args: Vec<String>) -> Result<ParsedRequest> {
+        Ok(self.parse_curl_command(args)?.request)
+    }
+
+    fn parse_curl_command(&self, args: Vec<String>) -> Result<CliRequest> {
         let mut method = String::from("GET");
         let mut url = String::new();
         let mut headers: Vec<(String, String)> = Vec::new(); // Explicit type annotation
         let mut data_parts = Vec::new();
+        let mut form = Vec::new();
         let mut force_get = false;
+        // -u / --oauth2-bearer 与 @auth 相同，发送时显式的 -H Authorization 优先
+        let mut auth: Option<Auth> = None;
+        let mut parsed_timeout = None;
+        // -b 的参数，含 `=` 的是 cookie 字符串，否则是 cookie 文件
+        let mut cookie_args: Vec<String> = Vec::new();
+        let mut cookie_jar = None;
+        // -d @- 从 stdin 读取的 body，按内容推断 Content-Type
+        let mut stdin_data = false;
 
         let mut args_iter = args.into_iter().peekable();
 
@@ -173,26 +615,114 @@ impl CliRunner {
                     }
                 }
                 // Data (body or query)
-                "-d" | "--data" | "--data-raw" => {
+                "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+                | "--data-urlencode" => {
+                    if let Some(data) = args_iter.next() {
+                        stdin_data |= reads_stdin(&arg, &data);
+                        data_parts.push(curl_data(&arg, &data)?);
+                    }
+                }
+                // JSON body (curl 7.82+)，同时设置 JSON 相关 headers
+                "--json" => {
                     if let Some(data) = args_iter.next() {
                         data_parts.push(data);
+                        for (key, value) in [
+                            ("Content-Type", "application/json"),
+                            ("Accept", "application/json"),
+                        ] {
+                            if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                                headers.push((key.to_string(), value.to_string()));
+                            }
+                        }
+                    }
+                }
+                // multipart 表单字段
+                "-F" | "--form" | "--form-string" => {
+                    if let Some(field) = args_iter.next() {
+                        form.push(curl_form_part(&field, arg == "--form-string")?);
+                    }
+                }
+                // 映射为 header 的常用选项
+                "-A" | "--user-agent" | "-e" | "--referer" => {
+                    if let Some(value) = args_iter.next() {
+                        let key = match arg.as_str() {
+                            "-A" | "--user-agent" => "User-Agent",
+                            _ => "Referer",
+                        };
+                        headers.push((key.to_string(), value));
+                    }
+                }
+                "-b" | "--cookie" => {
+                    if let Some(value) = args_iter.next() {
+                        cookie_args.push(value);
                     }
                 }
+                "-c" | "--cookie-jar" => {
+                    if let Some(path) = args_iter.next() {
+                        cookie_jar = Some(PathBuf::from(path));
+                    }
+                }
+                "--url" => {
+                    if let Some(u) = args_iter.next() {
+                        url = u;
+                    }
+                }
+                // Basic 认证
+                "-u" | "--user" => {
+                    if let Some(credentials) = args_iter.next() {
+                        auth = Some(basic_auth(&credentials)?);
+                    }
+                }
+                "--oauth2-bearer" => {
+                    if let Some(token) = args_iter.next() {
+                        auth = Some(Auth::Bearer(token));
+                    }
+                }
+                "-I" | "--head" => {
+                    method = String::from("HEAD");
+                }
                 // Force GET even with data
                 "-G" | "--get" => {
                     force_get = true;
                 }
+                // 整个请求的超时时间（秒，可为小数）
+                "-m" | "--max-time" => {
+                    if let Some(seconds) = args_iter.next() {
+                        parsed_timeout = Some(curl_timeout(&seconds)?);
+                    }
+                }
+                // 带参数但暂不支持的选项，消费其参数以免被当作 URL
+                s if CURL_IGNORED_VALUE_OPTIONS.contains(&s) => {
+                    let value = args_iter.next().unwrap_or_default();
+                    debug!("Ignoring unsupported curl option: {} {}", s, value);
+                }
                 // 其他未知选项暂时忽略
                 s if s.starts_with('-') => {
                     // 如果是 -X=POST 这种形式
                     if let Some((opt, val)) = s.split_once('=') {
                         match opt {
                             "-X" | "--request" => method = val.to_uppercase(),
-                            "-d" | "--data" | "--data-raw" => data_parts.push(val.to_string()),
-                            _ => {} // 忽略其他选项
+                            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+                            | "--data-urlencode" => {
+                                stdin_data |= reads_stdin(opt, val);
+                                data_parts.push(curl_data(opt, val)?)
+                            }
+                            "--form" => form.push(curl_form_part(val, false)?),
+                            "--form-string" => form.push(curl_form_part(val, true)?),
+                            "--user" => auth = Some(basic_auth(val)?),
+                            "--oauth2-bearer" => auth = Some(Auth::Bearer(val.to_string())),
+                            "--max-time" => parsed_timeout = Some(curl_timeout(val)?),
+                            "--user-agent" => {
+                                headers.push(("User-Agent".to_string(), val.to_string()))
+                            }
+                            "--referer" => headers.push(("Referer".to_string(), val.to_string())),
+                            "--url" => url = val.to_string(),
+                            "--cookie" => cookie_args.push(val.to_string()),
+                            "--cookie-jar" => cookie_jar = Some(PathBuf::from(val)),
+                            _ => debug!("Ignoring unsupported curl option: {}", s),
                         }
                     }
-                    // 其他带参数的选项，跳过下一个参数
+                    // 不含 `=` 的未知选项直接忽略，不消费下一个参数
                 }
                 // URL (位置参数)
                 _ => {
@@ -203,13 +733,27 @@ impl CliRunner {
             }
         }
 
-        // 如果有 data 且没有强制 GET，默认使用 POST
-        if !data_parts.is_empty() && method == "GET" && !force_get {
+        // 如果有 data/form 且没有强制 GET，默认使用 POST
+        if (!data_parts.is_empty() || !form.is_empty()) && method == "GET" && !force_get {
             method = String::from("POST");
         }
 
         if url.is_empty() {
-            return Err(RupostError::ParseError("URL is required".to_string()));
+            return Err(RupostError::InvalidCliArgs("URL is required".to_string()));
+        }
+
+        let mut cookies = Vec::new();
+        for value in cookie_args {
+            if value.contains('=') {
+                cookies.push(value);
+            } else if let Some(header) =
+                CookieJar::load(Path::new(&value))?.cookie_header(&Url::parse(&url)?)
+            {
+                cookies.push(header);
+            }
+        }
+        if !cookies.is_empty() {
+            headers.push(("Cookie".to_string(), cookies.join("; ")));
         }
 
         // Construct ParsedRequest
@@ -217,6 +761,9 @@ impl CliRunner {
         parsed.method = Some(method);
         parsed.url = url;
         parsed.headers = headers;
+        parsed.metadata.timeout = parsed_timeout;
+        parsed.form = form;
+        parsed.metadata.auth = auth;
 
         // 处理 data
         if force_get && !data_parts.is_empty() {
@@ -236,19 +783,26 @@ impl CliRunner {
         }
 
         // 默认 content type 如果有 body
-        if parsed.body.is_some()
+        if let Some(body) = &parsed.body
             && !parsed
                 .headers
                 .iter()
                 .any(|(k, _)| k.eq_ignore_ascii_case("Content-Type"))
         {
-            parsed.headers.push((
-                "Content-Type".to_string(),
-                "application/x-www-form-urlencoded".to_string(),
-            ));
+            let content_type = if stdin_data && is_json_like(body) {
+                "application/json"
+            } else {
+                "application/x-www-form-urlencoded"
+            };
+            parsed
+                .headers
+                .push(("Content-Type".to_string(), content_type.to_string()));
         }
 
-        Ok(parsed)
+        Ok(CliRequest {
+            request: parsed,
+            cookie_jar,
+        })
     }
     /// 判断参数是否为键值对参数（headers, query, body）
     /// URL 格式不算键值对：http://, https://, :/, :port
@@ -262,9 +816,12 @@ impl CliRunner {
         if arg.starts_with(":/") {
             return false;
         }
-        // 3. :port 格式 (如 :3000 -> localhost:3000)
-        if arg.starts_with(':') && arg[1..].chars().all(|c| c.is_ascii_digit()) {
-            return false;
+        // 3. :port 格式 (如 :3000 或 :3000/api -> localhost:3000)
+        if let Some(rest) = arg.strip_prefix(':') {
+            let port = rest.split('/').next().unwrap_or_default();
+            if port.chars().all(|c| c.is_ascii_digit()) {
+                return false;
+            }
         }
         // 4. 包含 :// 的 URL（其他协议）
         if arg.contains("://") {
@@ -289,13 +846,26 @@ impl CliRunner {
         let mut headers: Vec<(String, String)> = Vec::new();
         let mut query_params: Vec<(String, String)> = Vec::new();
         let mut body_parts = serde_json::Map::new();
+        // multipart 表单（按参数顺序），仅在出现文件字段时使用
+        let mut form = Vec::new();
+        let mut has_files = false;
+        let mut raw_json_fields = Vec::new();
 
+        let has_url_after_first = args.len() > 1;
         let mut args_iter = args.into_iter().peekable();
 
         // Step 1: 检查第一个参数是否为 HTTP Method
         if let Some(first) = args_iter.peek() {
-            let is_method = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"]
-                .contains(&first.to_uppercase().as_str());
+            if let Some(reason) = Method::unsupported_reason(first) {
+                return Err(RupostError::InvalidCliArgs(format!(
+                    "Unsupported HTTP method {}: {}",
+                    first.to_uppercase(),
+                    reason
+                )));
+            }
+            // 扩展方法（全大写的 token）后面必须还有 URL，避免把大写的主机名当作方法
+            let is_method = Method::NAMES.contains(&first.to_uppercase().as_str())
+                || (Method::is_extension(first) && has_url_after_first);
             if is_method {
                 method = args_iter.next().unwrap().to_uppercase();
             }
@@ -310,36 +880,84 @@ impl CliRunner {
 
         // Step 3: 处理剩余的键值对参数
         for arg in args_iter {
-            if let Some((key, value)) = arg.split_once("==") {
+            // 非键值对参数在 Step 2 之后应该不存在，忽略
+            let Some((key, separator, value)) = split_httpie_item(&arg) else {
+                continue;
+            };
+            match separator {
                 // Query parameter
-                query_params.push((key.to_string(), value.to_string()));
-            } else if let Some((key, value)) = arg.split_once(":=") {
-                // Raw JSON field
-                body_parts.insert(
-                    key.to_string(),
-                    serde_json::from_str::<serde_json::Value>(value)
-                        .unwrap_or(serde_json::Value::String(value.to_string())),
-                );
-            } else if let Some((key, value)) = arg.split_once('=') {
-                // String data field
-                body_parts.insert(
-                    key.to_string(),
-                    serde_json::Value::String(value.to_string()),
-                );
-            } else if let Some((key, value)) = arg.split_once(':') {
+                "==" => query_params.push((key.to_string(), value.to_string())),
+                // Raw JSON field，:=@ 从文件读取
+                ":=" | ":=@" => {
+                    let raw = if separator == ":=@" {
+                        let content = read_httpie_file(value)?;
+                        let json = serde_json::from_str(&content).map_err(|e| {
+                            RupostError::ParseError(format!("Invalid JSON in {}: {}", value, e))
+                        })?;
+                        Some(json)
+                    } else {
+                        serde_json::from_str::<serde_json::Value>(value).ok()
+                    };
+                    insert_json_path(
+                        &mut body_parts,
+                        &arg,
+                        key,
+                        raw.unwrap_or(serde_json::Value::String(value.to_string())),
+                    )?;
+                    raw_json_fields.push(key.to_string());
+                }
+                // String data field，=@ 从文件读取
+                "=" | "=@" => {
+                    let value = if separator == "=@" {
+                        read_httpie_file(value)?
+                    } else {
+                        value.to_string()
+                    };
+                    insert_json_path(
+                        &mut body_parts,
+                        &arg,
+                        key,
+                        serde_json::Value::String(value.clone()),
+                    )?;
+                    form.push(FormPart::Text {
+                        name: key.to_string(),
+                        value,
+                    });
+                }
+                // File upload field
+                "@" => {
+                    let part = form_file_part(key, value);
+                    if let FormPart::File { path, .. } = &part
+                        && !path.is_file()
+                    {
+                        return Err(RupostError::InvalidCliArgs(format!(
+                            "File not found: {}",
+                            path.display()
+                        )));
+                    }
+                    form.push(part);
+                    has_files = true;
+                }
                 // Header
-                headers.push((key.to_string(), value.to_string()));
+                _ => headers.push((key.to_string(), value.to_string())),
             }
-            // 非键值对参数在 Step 2 之后应该不存在，忽略
+        }
+
+        // 有文件字段时以 multipart 发送，数据字段作为文本字段
+        if has_files && !raw_json_fields.is_empty() {
+            return Err(RupostError::InvalidCliArgs(format!(
+                "Raw JSON fields cannot be combined with file uploads: {}",
+                raw_json_fields.join(", ")
+            )));
         }
 
         // If body_parts is not empty, method implicitly becomes POST if it was GET
-        if !body_parts.is_empty() && method == "GET" {
+        if (!body_parts.is_empty() || has_files) && method == "GET" {
             method = String::from("POST");
         }
 
         if url.is_empty() {
-            return Err(RupostError::ParseError("URL is required".to_string()));
+            return Err(RupostError::InvalidCliArgs("URL is required".to_string()));
         }
 
         // Append query params to URL manually if needed
@@ -364,8 +982,10 @@ impl CliRunner {
         parsed.url = url;
         parsed.headers = headers;
 
-        // 添加 body (JSON)
-        if !body_parts.is_empty() {
+        // 添加 body (multipart 或 JSON)
+        if has_files {
+            parsed.form = form;
+        } else if !body_parts.is_empty() {
             let json_body = serde_json::to_string(&body_parts)
                 .map_err(|e| RupostError::ParseError(e.to_string()))?;
             parsed.body = Some(json_body);
@@ -384,18 +1004,648 @@ impl CliRunner {
     }
 }
 
+/// 带参数但暂不支持的 curl 选项，解析时跳过其参数
+const CURL_IGNORED_VALUE_OPTIONS: &[&str] = &[
+    "-o",
+    "--output",
+    "--connect-timeout",
+    "-w",
+    "--write-out",
+    "-x",
+    "--proxy",
+    "-U",
+    "--proxy-user",
+    "--cacert",
+    "--capath",
+    "-E",
+    "--cert",
+    "--key",
+    "--retry",
+    "--retry-delay",
+    "--retry-max-time",
+    "--limit-rate",
+    "--max-redirs",
+    "-r",
+    "--range",
+    "--resolve",
+    "--interface",
+    "-D",
+    "--dump-header",
+    "-K",
+    "--config",
+];
+
+/// 将响应中的 Set-Cookie 合并写入 cookie 文件（curl -c）
+fn save_cookie_jar(path: &Path, url: &str, response: &Response) -> Result<()> {
+    let url = Url::parse(url)?;
+    let mut jar = CookieJar::load(path)?;
+    for value in response
+        .headers
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+    {
+        jar.store(value, &url);
+    }
+    jar.save(path)?;
+    debug!(
+        "Saved {} cookies to {}",
+        jar.cookies().len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// 解析 curl 的秒数参数（如 `5`、`2.5`）
+fn curl_timeout(seconds: &str) -> Result<std::time::Duration> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .map(std::time::Duration::from_secs_f64)
+        .ok_or_else(|| {
+            RupostError::InvalidCliArgs(format!("Invalid --max-time value: {}", seconds))
+        })
+}
+
+/// 按 curl 规则处理 `-d` 系列参数的值
+///
+/// - `-d` / `--data` / `--data-ascii`：`@file` 读取文件内容并去掉换行
+/// - `--data-binary`：`@file` 原样读取文件内容
+/// - `--data-raw`：不识别 `@`
+/// - `--data-urlencode`：见 [`curl_data_urlencode`]
+fn curl_data(option: &str, value: &str) -> Result<String> {
+    match option {
+        "--data-raw" => Ok(value.to_string()),
+        "--data-urlencode" => curl_data_urlencode(value),
+        _ => match value.strip_prefix('@') {
+            Some(path) if option == "--data-binary" => read_data_file(path),
+            Some(path) => Ok(read_data_file(path)?.replace(['\r', '\n'], "")),
+            None => Ok(value.to_string()),
+        },
+    }
+}
+
+/// 处理 `--data-urlencode` 的值，支持 curl 的五种写法：
+/// `content`、`=content`、`name=content`、`@file`、`name@file`
+fn curl_data_urlencode(value: &str) -> Result<String> {
+    let encode = |content: &str| {
+        url::form_urlencoded::byte_serialize(content.as_bytes()).collect::<String>()
+    };
+    let with_name = |name: &str, content: &str| {
+        if name.is_empty() {
+            encode(content)
+        } else {
+            format!("{}={}", name, encode(content))
+        }
+    };
+
+    if let Some((name, content)) = value.split_once('=') {
+        Ok(with_name(name, content))
+    } else if let Some((name, path)) = value.split_once('@') {
+        Ok(with_name(name, &read_data_file(path)?))
+    } else {
+        Ok(encode(value))
+    }
+}
+
+/// --save：将请求连同响应状态断言追加到 .http 文件，相同的块已存在时跳过
+fn save_request(path: &Path, request: &ParsedRequest, response: &Response) -> Result<()> {
+    if !request.form.is_empty() {
+        warn!("Multipart forms cannot be saved to .http files, skipping --save");
+        return Ok(());
+    }
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    match HttpGenerator::append_saved_request(&content, request, response.status.code()) {
+        Some(content) => {
+            std::fs::write(path, content)?;
+            println!("Saved request to {}", path.display());
+        }
+        None => println!("Request already saved in {}, skipping", path.display()),
+    }
+    Ok(())
+}
+
+/// 输出已写入文件的响应摘要
+fn print_download(response: &Response) {
+    if let Some(saved) = &response.saved_to {
+        println!(
+            "HTTP {} {}  {}  {}ms  -> {}",
+            response.status.code(),
+            response.status.reason_phrase(),
+            format_size(saved.size),
+            response.duration.as_millis(),
+            saved.path.display()
+        );
+    }
+}
+
+/// 读取 `@file` 引用的文件，`@-` 读取 stdin
+fn read_data_file(path: &str) -> Result<String> {
+    if path == "-" {
+        return read_stdin();
+    }
+    std::fs::read_to_string(path)
+        .map_err(|e| RupostError::ParseError(format!("Failed to read data file {}: {}", path, e)))
+}
+
+/// curl data 参数是否引用 stdin（`@-` 或 `--data-urlencode name@-`）
+fn reads_stdin(option: &str, value: &str) -> bool {
+    match option {
+        "--data-raw" => false,
+        "--data-urlencode" => !value.contains('=') && value.ends_with("@-"),
+        _ => value == "@-",
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    use std::io::Read;
+
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| RupostError::ParseError(format!("Failed to read stdin: {}", e)))?;
+    Ok(content)
+}
+
+/// 读取管道输入的 stdin，stdin 是终端或内容为空时返回 None
+fn read_piped_stdin() -> Result<Option<String>> {
+    use std::io::IsTerminal;
+
+    if std::io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    let content = read_stdin()?;
+    Ok((!content.is_empty()).then_some(content))
+}
+
+/// 将 stdin 内容作为 body：像 JSON 时补充 Content-Type，GET 隐式改为 POST
+fn set_stdin_body(request: &mut ParsedRequest, body: String) {
+    if is_json_like(&body)
+        && !request
+            .headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("Content-Type"))
+    {
+        request
+            .headers
+            .push(("Content-Type".to_string(), "application/json".to_string()));
+    }
+    if request.method_or_default() == "GET" {
+        request.method = Some("POST".to_string());
+    }
+    request.body = Some(body);
+}
+
+/// 解析 curl 的 `-F` 参数：`name=value` 或 `name=@path[;type=mime]`
+///
+/// `--form-string` 的值按原样作为文本，不识别 `@`。
+fn curl_form_part(field: &str, literal: bool) -> Result<FormPart> {
+    let (name, value) = field.split_once('=').ok_or_else(|| {
+        RupostError::InvalidCliArgs(format!(
+            "Invalid form field: {}, expected name=value or name=@file",
+            field
+        ))
+    })?;
+
+    let Some(file) = value.strip_prefix('@').filter(|_| !literal) else {
+        return Ok(FormPart::Text {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    };
+
+    Ok(form_file_part(name, file))
+}
+
+/// 解析文件字段 `path[;type=mime]`（curl `-F` 与 httpie `field@` 共用）
+fn form_file_part(name: &str, file: &str) -> FormPart {
+    let mut options = file.split(';');
+    let path = options.next().unwrap_or_default();
+    let mut content_type = None;
+    for option in options {
+        match option.split_once('=') {
+            Some(("type", mime)) => content_type = Some(mime.to_string()),
+            _ => debug!("Ignoring unsupported form option: {}", option),
+        }
+    }
+
+    FormPart::File {
+        name: name.to_string(),
+        path: path.into(),
+        content_type,
+    }
+}
+
+/// httpie 请求项的分隔符，同一位置上较长的优先
+const HTTPIE_SEPARATORS: &[&str] = &["==", ":=@", ":=", "=@", "=", "@", ":"];
+
+/// 按最先出现的分隔符拆分 httpie 请求项，返回 (key, 分隔符, value)
+///
+/// 因此 `X-Email:a@b.com` 是 header，`file@/tmp/a:b.png` 是文件字段。
+fn split_httpie_item(arg: &str) -> Option<(&str, &'static str, &str)> {
+    arg.char_indices().find_map(|(index, _)| {
+        HTTPIE_SEPARATORS
+            .iter()
+            .find(|separator| arg[index..].starts_with(**separator))
+            .map(|separator| (&arg[..index], *separator, &arg[index + separator.len()..]))
+    })
+}
+
+/// httpie 嵌套 JSON 路径中的一段，如 `user[tags][]` 为 user、tags、追加
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+    Append,
+}
+
+/// 解析 `name[key][0][]` 形式的字段名，格式不正确时返回 None
+fn parse_json_path(key: &str) -> Option<Vec<JsonPathSegment>> {
+    let (base, mut rest) = match key.find('[') {
+        Some(start) => (&key[..start], &key[start..]),
+        None => (key, ""),
+    };
+    if base.is_empty() {
+        return None;
+    }
+
+    let mut segments = vec![JsonPathSegment::Key(base.to_string())];
+    while !rest.is_empty() {
+        let end = rest.strip_prefix('[')?.find(']')? + 1;
+        let inner = &rest[1..end];
+        segments.push(if inner.is_empty() {
+            JsonPathSegment::Append
+        } else if let Ok(index) = inner.parse::<usize>() {
+            JsonPathSegment::Index(index)
+        } else {
+            JsonPathSegment::Key(inner.to_string())
+        });
+        rest = &rest[end + 1..];
+    }
+    Some(segments)
+}
+
+/// 按 httpie 的嵌套路径把值写入 JSON body，`[]` 追加到数组
+///
+/// 同一路径既作为对象/数组又作为普通值使用时返回错误。
+fn insert_json_path(
+    body: &mut serde_json::Map<String, serde_json::Value>,
+    arg: &str,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<()> {
+    let segments = parse_json_path(key)
+        .ok_or_else(|| RupostError::InvalidCliArgs(format!("Invalid JSON path in '{}'", arg)))?;
+
+    let mut root = serde_json::Value::Object(std::mem::take(body));
+    let result = set_json_path(&mut root, &segments, value, arg);
+    if let serde_json::Value::Object(map) = root {
+        *body = map;
+    }
+    result
+}
+
+fn set_json_path(
+    target: &mut serde_json::Value,
+    segments: &[JsonPathSegment],
+    value: serde_json::Value,
+    arg: &str,
+) -> Result<()> {
+    use serde_json::Value;
+
+    let conflict = || {
+        RupostError::InvalidCliArgs(format!(
+            "Conflicting JSON path in '{}': a value is used both as an object/array and as a field",
+            arg
+        ))
+    };
+
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(());
+    };
+
+    // 空位按下一段的类型创建容器
+    let empty_container = || match rest.first() {
+        Some(JsonPathSegment::Key(_)) => Value::Object(Default::default()),
+        _ => Value::Array(Vec::new()),
+    };
+
+    let slot = match segment {
+        JsonPathSegment::Key(key) => {
+            if target.is_null() {
+                *target = Value::Object(Default::default());
+            }
+            let object = target.as_object_mut().ok_or_else(conflict)?;
+            object.entry(key.clone()).or_insert(Value::Null)
+        }
+        JsonPathSegment::Index(index) => {
+            if target.is_null() {
+                *target = Value::Array(Vec::new());
+            }
+            let array = target.as_array_mut().ok_or_else(conflict)?;
+            if array.len() <= *index {
+                array.resize(index + 1, Value::Null);
+            }
+            &mut array[*index]
+        }
+        JsonPathSegment::Append => {
+            if target.is_null() {
+                *target = Value::Array(Vec::new());
+            }
+            let array = target.as_array_mut().ok_or_else(conflict)?;
+            array.push(Value::Null);
+            array.last_mut().unwrap()
+        }
+    };
+
+    if rest.is_empty() {
+        // 叶子：可覆盖普通值，不能覆盖对象或数组
+        if slot.is_object() || slot.is_array() {
+            return Err(conflict());
+        }
+        *slot = value;
+        return Ok(());
+    }
+
+    if slot.is_null() {
+        *slot = empty_container();
+    }
+    if !(slot.is_object() || slot.is_array()) {
+        return Err(conflict());
+    }
+    set_json_path(slot, rest, value, arg)
+}
+
+/// 读取 httpie `=@` / `:=@` 引用的文件
+fn read_httpie_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| RupostError::ParseError(format!("Failed to read file {}: {}", path, e)))
+}
+
+/// 将 curl 的 `user[:password]` 转换为 Basic 认证
+///
+/// 省略密码时在终端中提示输入（与 curl 一致），非交互环境下返回错误。
+fn basic_auth(credentials: &str) -> Result<Auth> {
+    let (user, password) = match credentials.split_once(':') {
+        Some((user, password)) => (user.to_string(), password.to_string()),
+        None => (credentials.to_string(), prompt_password(credentials)?),
+    };
+    Ok(Auth::Basic { user, password })
+}
+
+fn prompt_password(user: &str) -> Result<String> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() {
+        return Err(RupostError::InvalidCliArgs(format!(
+            "Password for user '{}' is required, use -u {}:<password>",
+            user, user
+        )));
+    }
+
+    inquire::Password::new(&format!("Enter host password for user '{}':", user))
+        .without_confirmation()
+        .prompt()
+        .map_err(|e| RupostError::Other(format!("Failed to read password: {}", e)))
+}
+
+/// 将一条 curl 命令字符串转换为 ParsedRequest
+pub fn convert_curl(command: &str) -> Result<ParsedRequest> {
+    let mut args = rupost::utils::shell::split_words(command)?;
+    if args.first().is_some_and(|s| s == "curl") {
+        args.remove(0);
+    }
+    CliRunner::new().parse_curl(args)
+}
+
+/// 临时请求中由 rupost 自身处理的参数（不交给 curl/httpie 解析）
+#[derive(Debug, Default, PartialEq)]
+struct AdhocArgs {
+    env: Option<String>,
+    vars: Vec<String>,
+    strict_vars: bool,
+    ignore_stdin: bool,
+    no_redirect: bool,
+    max_redirects: Option<usize>,
+    insecure: bool,
+    cacert: Option<String>,
+    proxy: Option<String>,
+    no_proxy: bool,
+    resolve: Vec<String>,
+    output: Option<PathBuf>,
+    download: bool,
+    force: bool,
+    save: Option<PathBuf>,
+    repeat: Option<usize>,
+    concurrency: Option<usize>,
+    max_body_bytes: Option<usize>,
+    seed: Option<u64>,
+}
+
+impl AdhocArgs {
+    /// --repeat 时返回重复执行设置，--concurrency 默认为 1
+    fn repeat_settings(&self) -> Option<RepeatSettings> {
+        self.repeat.map(|times| RepeatSettings {
+            times,
+            concurrency: self.concurrency.unwrap_or(1),
+        })
+    }
+
+    /// -o 或 --download 时返回下载设置
+    fn download_target(&self) -> Option<DownloadTarget> {
+        (self.output.is_some() || self.download).then(|| DownloadTarget {
+            path: self.output.clone(),
+            force: self.force,
+        })
+    }
+}
+
+/// 取出 `--env` / `--var` / `--strict-vars` / `--ignore-stdin` / `--no-redirect` / `--max-redirects` / `--save` / `--repeat` /
+/// `--concurrency` / `--max-body-bytes` / `--seed`、TLS 相关的 `-k` / `--insecure` / `--cacert`、代理相关的
+/// `-x` / `--proxy` / `--no-proxy`、`--resolve` 和下载相关的 `-o` / `--output` / `--download` / `--force`，
+/// 其余参数保持顺序交给 curl/httpie 解析
+///
+/// 除 `-o`、`-k`、`-x`（与 curl 含义相同）外只识别长选项：`-e` 仍是 curl 的 --referer，
+/// `-d` 是 curl 的 --data，`-f` 是 curl 的 --fail。
+fn extract_adhoc_args(args: Vec<String>) -> Result<(Vec<String>, AdhocArgs)> {
+    let mut rest = Vec::new();
+    let mut adhoc_args = AdhocArgs::default();
+    let mut args_iter = args.into_iter();
+
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--env" | "--var" | "-o" | "--output" | "--save" | "--repeat" | "--concurrency"
+            | "--max-body-bytes" | "--seed" | "--cacert" | "-x" | "--proxy" | "--resolve"
+            | "--max-redirects" => {
+                let value = args_iter.next().ok_or_else(|| {
+                    RupostError::InvalidCliArgs(format!("{} requires a value", arg))
+                })?;
+                match arg.as_str() {
+                    "--env" => adhoc_args.env = Some(value),
+                    "--var" => adhoc_args.vars.push(value),
+                    "--save" => adhoc_args.save = Some(PathBuf::from(value)),
+                    "--repeat" => adhoc_args.repeat = Some(parse_count(&arg, &value)?),
+                    "--concurrency" => adhoc_args.concurrency = Some(parse_count(&arg, &value)?),
+                    "--max-redirects" => {
+                        adhoc_args.max_redirects = Some(parse_count(&arg, &value)?)
+                    }
+                    "--max-body-bytes" => {
+                        adhoc_args.max_body_bytes = Some(parse_byte_limit(&value)?)
+                    }
+                    "--seed" => adhoc_args.seed = Some(parse_seed(&value)?),
+                    "--cacert" => adhoc_args.cacert = Some(value),
+                    "-x" | "--proxy" => adhoc_args.proxy = Some(value),
+                    "--resolve" => adhoc_args.resolve.push(value),
+                    _ => adhoc_args.output = Some(PathBuf::from(value)),
+                }
+            }
+            "--strict-vars" => adhoc_args.strict_vars = true,
+            "--ignore-stdin" => adhoc_args.ignore_stdin = true,
+            "--no-redirect" | "--no-follow" => adhoc_args.no_redirect = true,
+            "-k" | "--insecure" => adhoc_args.insecure = true,
+            "--no-proxy" => adhoc_args.no_proxy = true,
+            "--download" => adhoc_args.download = true,
+            "--force" => adhoc_args.force = true,
+            _ => {
+                if let Some(env) = arg.strip_prefix("--env=") {
+                    adhoc_args.env = Some(env.to_string());
+                } else if let Some(var) = arg.strip_prefix("--var=") {
+                    adhoc_args.vars.push(var.to_string());
+                } else if let Some(output) = arg.strip_prefix("--output=") {
+                    adhoc_args.output = Some(PathBuf::from(output));
+                } else if let Some(save) = arg.strip_prefix("--save=") {
+                    adhoc_args.save = Some(PathBuf::from(save));
+                } else if let Some(repeat) = arg.strip_prefix("--repeat=") {
+                    adhoc_args.repeat = Some(parse_count("--repeat", repeat)?);
+                } else if let Some(concurrency) = arg.strip_prefix("--concurrency=") {
+                    adhoc_args.concurrency = Some(parse_count("--concurrency", concurrency)?);
+                } else if let Some(max) = arg.strip_prefix("--max-redirects=") {
+                    adhoc_args.max_redirects = Some(parse_count("--max-redirects", max)?);
+                } else if let Some(proxy) = arg.strip_prefix("--proxy=") {
+                    adhoc_args.proxy = Some(proxy.to_string());
+                } else if let Some(resolve) = arg.strip_prefix("--resolve=") {
+                    adhoc_args.resolve.push(resolve.to_string());
+                } else if let Some(cacert) = arg.strip_prefix("--cacert=") {
+                    adhoc_args.cacert = Some(cacert.to_string());
+                } else if let Some(limit) = arg.strip_prefix("--max-body-bytes=") {
+                    adhoc_args.max_body_bytes = Some(parse_byte_limit(limit)?);
+                } else if let Some(seed) = arg.strip_prefix("--seed=") {
+                    adhoc_args.seed = Some(parse_seed(seed)?);
+                } else {
+                    rest.push(arg);
+                }
+            }
+        }
+    }
+
+    if adhoc_args.repeat.is_some()
+        && (adhoc_args.download_target().is_some() || adhoc_args.save.is_some())
+    {
+        return Err(RupostError::InvalidCliArgs(
+            "--repeat cannot be combined with -o/--output, --download or --save".to_string(),
+        ));
+    }
+    if adhoc_args.proxy.is_some() && adhoc_args.no_proxy {
+        return Err(RupostError::InvalidCliArgs(
+            "--proxy cannot be combined with --no-proxy".to_string(),
+        ));
+    }
+    if adhoc_args.concurrency.is_some() && adhoc_args.repeat.is_none() {
+        return Err(RupostError::InvalidCliArgs(
+            "--concurrency requires --repeat".to_string(),
+        ));
+    }
+
+    Ok((rest, adhoc_args))
+}
+
+/// 解析 --repeat / --concurrency 的值，必须为正整数
+fn parse_count(option: &str, value: &str) -> Result<usize> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| {
+            RupostError::InvalidCliArgs(format!(
+                "{} expects a positive integer, got '{}'",
+                option, value
+            ))
+        })
+}
+
+/// 解析 --seed 的值
+fn parse_seed(value: &str) -> Result<u64> {
+    value.parse::<u64>().map_err(|_| {
+        RupostError::InvalidCliArgs(format!(
+            "--seed expects a non-negative integer, got '{}'",
+            value
+        ))
+    })
+}
+
+/// 解析 --max-body-bytes 的值，0 表示不限制
+fn parse_byte_limit(value: &str) -> Result<usize> {
+    value.parse::<usize>().map_err(|_| {
+        RupostError::InvalidCliArgs(format!(
+            "--max-body-bytes expects a non-negative integer, got '{}'",
+            value
+        ))
+    })
+}
+
 pub async fn run(args: Vec<String>) -> Result<()> {
-    let runner = CliRunner::new();
-    runner.run(args).await
+    // 需在判断 curl/httpie 风格之前取出，否则 --env 会让 httpie 命令被当作 curl
+    let (args, adhoc_args) = extract_adhoc_args(args)?;
+    let env = crate::select_env(adhoc_args.env.as_deref())?;
+    let mut context = crate::build_var_context(env.as_deref(), None, &adhoc_args.vars)?;
+    if let Some(seed) = adhoc_args.seed {
+        context.set_seed(seed);
+    }
+    let client = crate::build_client(
+        crate::redirect_limit(adhoc_args.no_redirect, adhoc_args.max_redirects),
+        adhoc_args.insecure,
+        adhoc_args.cacert.as_deref(),
+        adhoc_args.proxy.as_deref(),
+        adhoc_args.no_proxy,
+        &adhoc_args.resolve,
+        None,
+    )?;
+    if client.is_insecure() {
+        TestReporter::default().print_tls_warning(&[]);
+    }
+
+    let runner = CliRunner::new()
+        .with_strict_vars(adhoc_args.strict_vars)
+        .with_client(client)
+        .with_stdin(!adhoc_args.ignore_stdin)
+        .with_download(adhoc_args.download_target())
+        .with_repeat(adhoc_args.repeat_settings())
+        .with_max_body_bytes(adhoc_args.max_body_bytes)
+        .with_save(adhoc_args.save);
+    runner.run(args, context).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 测试中执行的请求不写入工作目录下的历史记录
+    fn runner() -> CliRunner {
+        let mut runner = CliRunner::new();
+        runner.executor = runner.executor.record_history(false);
+        runner
+    }
+
     #[test]
     fn test_parse_httpie() {
-        let runner = CliRunner::new();
+        let runner = runner();
         // Test case: POST example.com id:=1 name=foo token:123 q==search
         let args = vec![
             "POST".to_string(),
@@ -414,11 +1664,245 @@ mod tests {
         // Test case: GET
         let args3 = vec!["example.com".to_string()];
         runner.parse_httpie(args3).unwrap();
+
+        // TRACE 和扩展方法按方法解析，CONNECT 说明不支持的原因
+        let request = runner
+            .parse_httpie(vec!["trace".to_string(), "example.com".to_string()])
+            .unwrap();
+        assert_eq!(request.method.as_deref(), Some("TRACE"));
+        let request = runner
+            .parse_httpie(vec!["PURGE".to_string(), "example.com".to_string()])
+            .unwrap();
+        assert_eq!(request.method.as_deref(), Some("PURGE"));
+        let request = runner
+            .parse_httpie(vec!["EXAMPLE.COM".to_string()])
+            .unwrap();
+        assert_eq!(request.url, "EXAMPLE.COM");
+        let err = runner
+            .parse_httpie(vec!["CONNECT".to_string(), "example.com:443".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported HTTP method CONNECT"));
+    }
+
+    #[test]
+    fn test_split_httpie_item() {
+        assert_eq!(split_httpie_item("q==a=b"), Some(("q", "==", "a=b")));
+        assert_eq!(
+            split_httpie_item("data:=@a.json"),
+            Some(("data", ":=@", "a.json"))
+        );
+        assert_eq!(split_httpie_item("id:=1"), Some(("id", ":=", "1")));
+        assert_eq!(
+            split_httpie_item("bio=@bio.txt"),
+            Some(("bio", "=@", "bio.txt"))
+        );
+        assert_eq!(
+            split_httpie_item("email=a@b.com"),
+            Some(("email", "=", "a@b.com"))
+        );
+        assert_eq!(
+            split_httpie_item("file@/tmp/a:b.png"),
+            Some(("file", "@", "/tmp/a:b.png"))
+        );
+        assert_eq!(
+            split_httpie_item("X-Email:a@b.com"),
+            Some(("X-Email", ":", "a@b.com"))
+        );
+        assert_eq!(split_httpie_item("plain"), None);
+    }
+
+    fn httpie_body(items: &[&str]) -> Result<serde_json::Value> {
+        let mut args = vec!["example.com".to_string()];
+        args.extend(items.iter().map(|s| s.to_string()));
+        let request = runner().parse_httpie(args)?;
+        Ok(serde_json::from_str(request.body.as_deref().unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_parse_json_path() {
+        use JsonPathSegment::*;
+        assert_eq!(parse_json_path("name"), Some(vec![Key("name".to_string())]));
+        assert_eq!(
+            parse_json_path("user[tags][0][]"),
+            Some(vec![
+                Key("user".to_string()),
+                Key("tags".to_string()),
+                Index(0),
+                Append
+            ])
+        );
+        assert_eq!(parse_json_path("[a]"), None);
+        assert_eq!(parse_json_path("a[b"), None);
+        assert_eq!(parse_json_path("a[b]c"), None);
+    }
+
+    #[test]
+    fn test_parse_httpie_nested_json() {
+        use serde_json::json;
+
+        let cases: Vec<(Vec<&str>, serde_json::Value)> = vec![
+            // 对象嵌套
+            (
+                vec!["user[name]=Alice", "user[address][city]=Paris"],
+                json!({"user": {"name": "Alice", "address": {"city": "Paris"}}}),
+            ),
+            // [] 追加
+            (
+                vec!["user[tags][]=a", "user[tags][]=b"],
+                json!({"user": {"tags": ["a", "b"]}}),
+            ),
+            // 顶层数组与 := 值
+            (
+                vec!["ids[]:=1", "ids[]:=2", "flag:=true"],
+                json!({"ids": [1, 2], "flag": true}),
+            ),
+            // 下标，缺失位置补 null
+            (
+                vec!["arr[2]=c", "arr[0]=a"],
+                json!({"arr": ["a", null, "c"]}),
+            ),
+            // 数组中的对象：[] 每次追加新对象，下标可回到已有对象
+            (
+                vec!["items[][id]:=1", "items[][id]:=2", "items[0][name]=first"],
+                json!({"items": [{"id": 1, "name": "first"}, {"id": 2}]}),
+            ),
+            // 嵌套数组
+            (
+                vec!["matrix[0][]:=1", "matrix[0][]:=2", "matrix[1][]:=3"],
+                json!({"matrix": [[1, 2], [3]]}),
+            ),
+            // 合并到 := 写入的对象，重复的普通值被覆盖
+            (
+                vec!["user:={\"id\": 7}", "user[name]=Bob", "user[name]=Alice"],
+                json!({"user": {"id": 7, "name": "Alice"}}),
+            ),
+        ];
+
+        for (items, expected) in cases {
+            assert_eq!(httpie_body(&items).unwrap(), expected, "items: {:?}", items);
+        }
+    }
+
+    #[test]
+    fn test_parse_httpie_nested_json_conflicts() {
+        let cases: Vec<Vec<&str>> = vec![
+            // 普通值后作为对象
+            vec!["user=Alice", "user[name]=Bob"],
+            // 对象后作为普通值
+            vec!["user[name]=Bob", "user=Alice"],
+            // 数组后作为对象
+            vec!["tags[]=a", "tags[name]=b"],
+            // 对象后按数组追加
+            vec!["user[name]=Bob", "user[]=x"],
+            // 数组元素为普通值时继续嵌套
+            vec!["arr[0]=a", "arr[0][x]=b"],
+            // 格式不正确的路径
+            vec!["user[name=Bob"],
+        ];
+
+        for items in cases {
+            let err = httpie_body(&items).unwrap_err();
+            let offending = items.last().unwrap();
+            assert!(
+                err.to_string().contains(offending),
+                "items: {:?}, error: {}",
+                items,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_httpie_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bio = dir.path().join("bio.txt");
+        std::fs::write(&bio, "line1\nline2\n").unwrap();
+        let data = dir.path().join("data.json");
+        std::fs::write(&data, r#"{"tags": ["a", "b"]}"#).unwrap();
+        let photo = dir.path().join("photo.png");
+        std::fs::write(&photo, [0u8, 1, 2]).unwrap();
+        let runner = runner();
+
+        // =@ 读取字符串，:=@ 读取 JSON
+        let request = runner
+            .parse_httpie(vec![
+                "example.com/users".to_string(),
+                format!("bio=@{}", bio.display()),
+                format!("meta:=@{}", data.display()),
+                "X-Email:a@b.com".to_string(),
+            ])
+            .unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"bio": "line1\nline2\n", "meta": {"tags": ["a", "b"]}})
+        );
+        assert!(
+            request
+                .headers
+                .contains(&("X-Email".to_string(), "a@b.com".to_string()))
+        );
+
+        // field@ 切换为 multipart，数据字段按顺序成为文本字段
+        let request = runner
+            .parse_httpie(vec![
+                "example.com/upload".to_string(),
+                "title=hello".to_string(),
+                format!("photo@{};type=image/png", photo.display()),
+            ])
+            .unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.body, None);
+        assert_eq!(
+            request.form,
+            vec![
+                FormPart::Text {
+                    name: "title".to_string(),
+                    value: "hello".to_string(),
+                },
+                FormPart::File {
+                    name: "photo".to_string(),
+                    path: photo.clone(),
+                    content_type: Some("image/png".to_string()),
+                },
+            ]
+        );
+
+        // 文件缺失或格式错误时报错并包含路径
+        let missing = dir.path().join("missing.json");
+        for item in [
+            format!("a=@{}", missing.display()),
+            format!("a:=@{}", missing.display()),
+            format!("a@{}", missing.display()),
+        ] {
+            let err = runner
+                .parse_httpie(vec!["example.com".to_string(), item])
+                .unwrap_err();
+            assert!(err.to_string().contains("missing.json"), "{}", err);
+        }
+        let err = runner
+            .parse_httpie(vec![
+                "example.com".to_string(),
+                format!("a:=@{}", bio.display()),
+            ])
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid JSON"));
+        assert!(
+            runner
+                .parse_httpie(vec![
+                    "example.com".to_string(),
+                    "id:=1".to_string(),
+                    format!("photo@{}", photo.display()),
+                ])
+                .is_err()
+        );
     }
 
     #[test]
     fn test_parse_curl() {
-        let runner = CliRunner::new();
+        let runner = runner();
 
         // Test case: curl -X POST -H "Content-Type: application/json" -d '{"name":"foo"}' example.com
         let args = vec![
@@ -481,12 +1965,747 @@ mod tests {
         assert!(request6.url.contains("page=1"));
     }
 
+    #[test]
+    fn test_convert_curl() {
+        let request = convert_curl(
+            "curl -X POST https://api.example.com/users \\\n  -H 'Content-Type: application/json' \\\n  -d '{\"name\": \"O'\\''Brien\"}' --compressed -s",
+        )
+        .unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert_eq!(
+            request.headers,
+            vec![("Content-Type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"name": "O'Brien"}"#));
+
+        let request = convert_curl(r#"curl --json '{"a":1}' --url https://a.com"#).unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.url, "https://a.com");
+        assert!(
+            request
+                .headers
+                .contains(&("Content-Type".to_string(), "application/json".to_string()))
+        );
+
+        assert!(convert_curl("curl -d 'unterminated https://a.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_curl_auth() {
+        // -u 与 @auth 走相同的转换，发送时生成 Authorization header
+        let authorization = |command: &str| {
+            let request: rupost::http::Request = convert_curl(command).unwrap().try_into().unwrap();
+            request.headers["authorization"]
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+
+        let request = convert_curl("curl -u admin:secret https://example.com/api").unwrap();
+        assert_eq!(request.url, "https://example.com/api");
+        assert!(request.headers.is_empty());
+        assert_eq!(
+            request.metadata.auth,
+            Some(Auth::Basic {
+                user: "admin".to_string(),
+                password: "secret".to_string()
+            })
+        );
+        assert_eq!(
+            authorization("curl -u admin:secret https://example.com/api"),
+            "Basic YWRtaW46c2VjcmV0"
+        );
+
+        // 空密码与 --user= 形式
+        assert_eq!(
+            authorization("curl --user=admin: https://example.com"),
+            "Basic YWRtaW46"
+        );
+
+        let request = convert_curl("curl --oauth2-bearer abc.def https://example.com/api").unwrap();
+        assert_eq!(request.url, "https://example.com/api");
+        assert_eq!(
+            request.metadata.auth,
+            Some(Auth::Bearer("abc.def".to_string()))
+        );
+
+        // 显式 -H Authorization 优先
+        assert_eq!(
+            authorization("curl -u admin:secret -H 'authorization: Token xyz' https://example.com"),
+            "Token xyz"
+        );
+    }
+
+    #[test]
+    fn test_parse_curl_value_options() {
+        // 暂不支持的选项：参数被消费，不会被当作 URL
+        let request = convert_curl(
+            "curl --connect-timeout 5 -o out.json --cacert ca.pem -x http://proxy:8080 https://example.com/api",
+        )
+        .unwrap();
+        assert_eq!(request.url, "https://example.com/api");
+        assert!(request.headers.is_empty());
+        assert_eq!(request.metadata.timeout, None);
+
+        // 已支持的选项会被应用
+        let request = convert_curl(
+            "curl -m 2.5 -A rupost/1.0 -e https://ref.example.com https://example.com/api",
+        )
+        .unwrap();
+        assert_eq!(request.url, "https://example.com/api");
+        assert_eq!(
+            request.metadata.timeout,
+            Some(std::time::Duration::from_millis(2500))
+        );
+        assert_eq!(
+            request.headers,
+            vec![
+                ("User-Agent".to_string(), "rupost/1.0".to_string()),
+                ("Referer".to_string(), "https://ref.example.com".to_string()),
+            ]
+        );
+
+        let request =
+            convert_curl("curl --max-time=3 --connect-timeout=1 --user-agent=x https://a.com")
+                .unwrap();
+        assert_eq!(request.url, "https://a.com");
+        assert_eq!(
+            request.metadata.timeout,
+            Some(std::time::Duration::from_secs(3))
+        );
+        assert_eq!(request.headers[0].1, "x");
+
+        assert!(convert_curl("curl --max-time soon https://a.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_curl_data_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let payload = dir.path().join("payload.json");
+        std::fs::write(&payload, "{\"name\": \"alice\",\r\n \"age\": 3}\n").unwrap();
+        let payload = payload.display();
+
+        // -d 去掉换行，--data-binary 原样保留
+        let request = convert_curl(&format!(
+            "curl -H 'Content-Type: application/json' -d @{} https://a.com",
+            payload
+        ))
+        .unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(
+            request.body.as_deref(),
+            Some("{\"name\": \"alice\", \"age\": 3}")
+        );
+
+        let request =
+            convert_curl(&format!("curl --data-binary @{} https://a.com", payload)).unwrap();
+        assert_eq!(
+            request.body.as_deref(),
+            Some("{\"name\": \"alice\",\r\n \"age\": 3}\n")
+        );
+
+        // --data-raw 不识别 @
+        let request = convert_curl("curl --data-raw @literal https://a.com").unwrap();
+        assert_eq!(request.body.as_deref(), Some("@literal"));
+
+        // -G 时文件内容拼接到 query
+        let query = dir.path().join("query.txt");
+        std::fs::write(&query, "page=2\n").unwrap();
+        let request = convert_curl(&format!(
+            "curl -G -d @{} https://a.com/users?sort=name",
+            query.display()
+        ))
+        .unwrap();
+        assert_eq!(request.method.as_deref(), Some("GET"));
+        assert_eq!(request.url, "https://a.com/users?sort=name&page=2");
+        assert_eq!(request.body, None);
+
+        let err = convert_curl("curl -d @/nonexistent/payload.json https://a.com").unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/payload.json"));
+    }
+
+    #[test]
+    fn test_parse_curl_data_urlencode() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let message = dir.path().join("message.txt");
+        std::fs::write(&message, "hello world & more").unwrap();
+
+        let request = convert_curl(&format!(
+            "curl --data-urlencode 'q=a b&c' --data-urlencode '=x/y' --data-urlencode 'msg@{}' --data-urlencode=raw https://a.com",
+            message.display()
+        ))
+        .unwrap();
+        assert_eq!(
+            request.body.as_deref(),
+            Some("q=a+b%26c&x%2Fy&msg=hello+world+%26+more&raw")
+        );
+
+        let request =
+            convert_curl("curl -G --data-urlencode 'name=O Brien' https://a.com/search").unwrap();
+        assert_eq!(request.url, "https://a.com/search?name=O+Brien");
+    }
+
+    #[test]
+    fn test_parse_curl_cookies() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let jar = dir.path().join("cookies.txt");
+        std::fs::write(
+            &jar,
+            ".example.com\tTRUE\t/\tFALSE\t0\tsid\tabc\nother.com\tFALSE\t/\tFALSE\t0\tx\t1\n",
+        )
+        .unwrap();
+
+        let request = convert_curl(&format!(
+            "curl -b 'theme=dark; lang=en' -b {} https://api.example.com/users",
+            jar.display()
+        ))
+        .unwrap();
+        assert_eq!(
+            request.headers,
+            vec![(
+                "Cookie".to_string(),
+                "theme=dark; lang=en; sid=abc".to_string()
+            )]
+        );
+
+        // 不存在的 cookie 文件被忽略，-c 不会被当作 URL
+        let request =
+            convert_curl("curl -b missing.txt -c jar.txt https://api.example.com").unwrap();
+        assert!(request.headers.is_empty());
+        assert_eq!(request.url, "https://api.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_curl_cookie_jar_round_trip() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("set-cookie", "session=abc123; Path=/; HttpOnly")
+                    .append_header("set-cookie", "theme=dark"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .and(header("cookie", "session=abc123; theme=dark"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let jar = dir.path().join("cookies.txt").display().to_string();
+        let runner = runner();
+
+        runner
+            .run(
+                vec![
+                    "curl".to_string(),
+                    "-X".to_string(),
+                    "POST".to_string(),
+                    "-c".to_string(),
+                    jar.clone(),
+                    format!("{}/login", mock_server.uri()),
+                ],
+                VariableContext::new(),
+            )
+            .await
+            .unwrap();
+        let content = std::fs::read_to_string(&jar).unwrap();
+        assert!(content.contains("#HttpOnly_127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc123"));
+
+        runner
+            .run(
+                vec![
+                    "curl".to_string(),
+                    "-b".to_string(),
+                    jar,
+                    format!("{}/me", mock_server.uri()),
+                ],
+                VariableContext::new(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_extract_adhoc_args() {
+        let args = vec![
+            "--env",
+            "dev",
+            "GET",
+            "--var=id=1",
+            "{{base_url}}/users/{{id}}",
+        ]
+        .into_iter()
+        .chain([
+            "--var",
+            "token=abc",
+            "--strict-vars",
+            "--no-follow",
+            "--max-redirects=3",
+            "-k",
+            "--cacert=ca.pem",
+            "-x",
+            "socks5://127.0.0.1:1080",
+            "--resolve",
+            "api.example.com:443:10.0.0.5",
+            "--seed=7",
+            "-e",
+            "ref",
+        ])
+        .map(String::from)
+        .collect();
+        let (rest, adhoc_args) = extract_adhoc_args(args).unwrap();
+
+        assert_eq!(rest, vec!["GET", "{{base_url}}/users/{{id}}", "-e", "ref"]);
+        assert_eq!(
+            adhoc_args,
+            AdhocArgs {
+                env: Some("dev".to_string()),
+                vars: vec!["id=1".to_string(), "token=abc".to_string()],
+                strict_vars: true,
+                no_redirect: true,
+                max_redirects: Some(3),
+                insecure: true,
+                cacert: Some("ca.pem".to_string()),
+                proxy: Some("socks5://127.0.0.1:1080".to_string()),
+                resolve: vec!["api.example.com:443:10.0.0.5".to_string()],
+                seed: Some(7),
+                ..Default::default()
+            }
+        );
+
+        assert!(extract_adhoc_args(vec!["GET".to_string(), "--var".to_string()]).is_err());
+
+        let args = ["GET", "example.com/a.json", "-o", "a.json", "--force"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let (rest, adhoc_args) = extract_adhoc_args(args).unwrap();
+        assert_eq!(rest, vec!["GET", "example.com/a.json"]);
+        assert_eq!(
+            adhoc_args.download_target(),
+            Some(DownloadTarget {
+                path: Some(PathBuf::from("a.json")),
+                force: true,
+            })
+        );
+
+        let (_, adhoc_args) =
+            extract_adhoc_args(vec!["--download".to_string(), "example.com".to_string()]).unwrap();
+        assert_eq!(
+            adhoc_args.download_target(),
+            Some(DownloadTarget {
+                path: None,
+                force: false,
+            })
+        );
+
+        let args = ["GET", ":3000/health", "--repeat", "20", "--concurrency=5"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let (rest, adhoc_args) = extract_adhoc_args(args).unwrap();
+        assert_eq!(rest, vec!["GET", ":3000/health"]);
+        assert_eq!(
+            adhoc_args.repeat_settings(),
+            Some(RepeatSettings {
+                times: 20,
+                concurrency: 5,
+            })
+        );
+
+        let extract =
+            |args: &[&str]| extract_adhoc_args(args.iter().map(|s| s.to_string()).collect());
+        assert_eq!(
+            extract(&["--repeat=3", "example.com"])
+                .unwrap()
+                .1
+                .repeat_settings(),
+            Some(RepeatSettings {
+                times: 3,
+                concurrency: 1,
+            })
+        );
+        assert!(extract(&["--repeat", "0", "example.com"]).is_err());
+        assert!(extract(&["--repeat", "x", "example.com"]).is_err());
+        assert!(extract(&["--concurrency", "2", "example.com"]).is_err());
+        assert!(extract(&["--repeat", "2", "--download", "example.com"]).is_err());
+        assert!(extract(&["--repeat", "2", "--save", "a.http", "example.com"]).is_err());
+
+        assert_eq!(
+            extract(&["example.com", "--max-body-bytes", "0"])
+                .unwrap()
+                .1
+                .max_body_bytes,
+            Some(0)
+        );
+        assert_eq!(
+            extract(&["--max-body-bytes=512", "example.com"])
+                .unwrap()
+                .1
+                .max_body_bytes,
+            Some(512)
+        );
+        assert!(extract(&["--max-body-bytes", "-1", "example.com"]).is_err());
+        assert!(extract(&["--proxy=http://p:8080", "--no-proxy", "example.com"]).is_err());
+    }
+
+    #[test]
+    fn test_max_redirects_bounds_match() {
+        // rupost test 与临时请求的 --max-redirects 同为 usize，且都要求至少为 1
+        let max_redirects = |args: &[&str]| match Cli::try_parse_from(args).map(|cli| cli.command) {
+            Ok(Some(Commands::Test { max_redirects, .. })) => Ok(max_redirects),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e),
+        };
+        assert_eq!(
+            max_redirects(&["rupost", "test", "a.http", "--max-redirects", "3"]).unwrap(),
+            Some(3usize)
+        );
+        assert!(max_redirects(&["rupost", "test", "a.http", "--max-redirects", "0"]).is_err());
+
+        let extract =
+            |args: &[&str]| extract_adhoc_args(args.iter().map(|s| s.to_string()).collect());
+        assert_eq!(
+            extract(&["--max-redirects", "3", "example.com"])
+                .unwrap()
+                .1
+                .max_redirects,
+            Some(3usize)
+        );
+        assert!(extract(&["--max-redirects", "0", "example.com"]).is_err());
+        assert!(extract(&["--max-redirects=0", "example.com"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_repeat() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(7)
+            .mount(&mock_server)
+            .await;
+
+        let runner = runner().with_repeat(Some(RepeatSettings {
+            times: 7,
+            concurrency: 3,
+        }));
+        let args = vec!["GET".to_string(), format!("{}/health", mock_server.uri())];
+        runner.run(args, VariableContext::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_save() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("api.http");
+        std::fs::write(&file, "GET https://example.com/health\n").unwrap();
+
+        let mut context = VariableContext::new();
+        context.set("base_url", mock_server.uri());
+        let args: Vec<String> = vec!["{{base_url}}/login", "user=admin", "pass=x"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let runner = runner().with_save(Some(file.clone()));
+
+        runner.run(args.clone(), context.clone()).await.unwrap();
+        let saved = std::fs::read_to_string(&file).unwrap();
+        runner.run(args, context).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), saved);
+
+        // 保存未替换变量的请求，可直接用 rupost test 执行
+        let parsed = rupost::parser::HttpFileParser::parse_content(&saved).unwrap();
+        assert_eq!(parsed.requests.len(), 2);
+        let request = &parsed.requests[1];
+        assert_eq!(request.name(), Some("post_login"));
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.url, "{{base_url}}/login");
+        assert_eq!(request.metadata.assertions, vec!["status == 201"]);
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({"user": "admin", "pass": "x"}));
+    }
+
+    #[tokio::test]
+    async fn test_run_download() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let bytes = vec![0x89, b'P', b'N', b'G', 0x00, 0xff];
+        Mock::given(method("GET"))
+            .and(path("/logo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(bytes.clone()))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let output = dir.path().join("logo.png");
+        let args = vec![format!("{}/logo.png", mock_server.uri())];
+        let runner = |force| {
+            runner().with_download(Some(DownloadTarget {
+                path: Some(output.clone()),
+                force,
+            }))
+        };
+
+        runner(false)
+            .run(args.clone(), VariableContext::new())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), bytes);
+
+        // 已存在的文件需要 --force，且不会发送请求
+        let err = runner(false)
+            .run(args.clone(), VariableContext::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("--force"));
+
+        std::fs::write(&output, "old").unwrap();
+        runner(true)
+            .run(args, VariableContext::new())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), bytes);
+    }
+
+    #[tokio::test]
+    async fn test_run_resolves_variables() {
+        use wiremock::matchers::{body_json, header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/users/42"))
+            .and(query_param("lang", "zh"))
+            .and(header("authorization", "Bearer abc"))
+            .and(body_json(serde_json::json!({"owner": "42"})))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut context = VariableContext::new();
+        context.set("base_url", mock_server.uri());
+        context.set("id", "42");
+        context.set("token", "abc");
+        let runner = runner().with_strict_vars(true);
+
+        // httpie 风格
+        runner
+            .run(
+                vec![
+                    "POST",
+                    "{{base_url}}/users/{{id}}",
+                    "lang==zh",
+                    "Authorization:Bearer {{token}}",
+                    "owner={{id}}",
+                ]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+                context.clone(),
+            )
+            .await
+            .unwrap();
+
+        // curl 风格
+        runner
+            .run(
+                vec![
+                    "curl",
+                    "-X",
+                    "POST",
+                    "{{base_url}}/users/{{id}}?lang=zh",
+                    "-H",
+                    "Authorization: Bearer {{token}}",
+                    "-H",
+                    "Content-Type: application/json",
+                    "-d",
+                    r#"{"owner": "{{id}}"}"#,
+                ]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+                context,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_strict_vars_skips_request() {
+        use wiremock::matchers::any;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let args: Vec<String> = vec![
+            "GET".to_string(),
+            format!("{}/users/{{{{missing}}}}", mock_server.uri()),
+        ];
+        runner()
+            .with_strict_vars(true)
+            .run(args, VariableContext::new())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parse_body_keeps_method() {
+        // 带 body 时只有 GET 会隐式改为 POST
+        let request = runner()
+            .parse_httpie(
+                vec!["DELETE", "example.com/items/1", "reason=dup"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            )
+            .unwrap();
+        assert_eq!(request.method.as_deref(), Some("DELETE"));
+        assert_eq!(request.body.as_deref(), Some(r#"{"reason":"dup"}"#));
+
+        let request =
+            convert_curl(r#"curl -X PATCH https://example.com/items/1 --json '{"a":1}'"#).unwrap();
+        assert_eq!(request.method.as_deref(), Some("PATCH"));
+        assert_eq!(request.body.as_deref(), Some(r#"{"a":1}"#));
+    }
+
+    #[test]
+    fn test_set_stdin_body() {
+        let runner = runner();
+
+        // 像 JSON 的 body：补充 Content-Type，GET 隐式改为 POST
+        let mut request = runner
+            .parse_httpie(vec![":3000/api/items".to_string()])
+            .unwrap();
+        set_stdin_body(&mut request, "{\"name\": \"a\"}\n".to_string());
+        assert_eq!(request.url, ":3000/api/items");
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.body.as_deref(), Some("{\"name\": \"a\"}\n"));
+        assert_eq!(
+            request.headers,
+            vec![("Content-Type".to_string(), "application/json".to_string())]
+        );
+
+        // 显式的方法和 Content-Type 保持不变
+        let mut request = runner
+            .parse_httpie(
+                vec!["PUT", "example.com", "Content-Type:text/csv"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            )
+            .unwrap();
+        set_stdin_body(&mut request, "[1,2]".to_string());
+        assert_eq!(request.method.as_deref(), Some("PUT"));
+        assert_eq!(request.headers.len(), 1);
+
+        let mut request = runner
+            .parse_httpie(vec!["example.com".to_string()])
+            .unwrap();
+        set_stdin_body(&mut request, "plain text".to_string());
+        assert!(request.headers.is_empty());
+    }
+
+    #[test]
+    fn test_reads_stdin() {
+        assert!(reads_stdin("-d", "@-"));
+        assert!(reads_stdin("--data-binary", "@-"));
+        assert!(reads_stdin("--data-urlencode", "name@-"));
+        assert!(!reads_stdin("--data-raw", "@-"));
+        assert!(!reads_stdin("--data-urlencode", "name=a@-"));
+        assert!(!reads_stdin("-d", "@file.json"));
+    }
+
+    #[test]
+    fn test_parse_curl_form() {
+        let request = convert_curl(
+            "curl -F file=@./photo.png -F 'doc=@/tmp/a b.txt;type=text/plain' -F title=hello --form-string 'raw=@literal' https://example.com/upload",
+        )
+        .unwrap();
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.url, "https://example.com/upload");
+        assert_eq!(request.body, None);
+        assert_eq!(
+            request.form,
+            vec![
+                FormPart::File {
+                    name: "file".to_string(),
+                    path: "./photo.png".into(),
+                    content_type: None,
+                },
+                FormPart::File {
+                    name: "doc".to_string(),
+                    path: "/tmp/a b.txt".into(),
+                    content_type: Some("text/plain".to_string()),
+                },
+                FormPart::Text {
+                    name: "title".to_string(),
+                    value: "hello".to_string(),
+                },
+                FormPart::Text {
+                    name: "raw".to_string(),
+                    value: "@literal".to_string(),
+                },
+            ]
+        );
+
+        let request = convert_curl("curl -X PUT --form=a=1 https://a.com").unwrap();
+        assert_eq!(request.method.as_deref(), Some("PUT"));
+        assert_eq!(request.form.len(), 1);
+
+        assert!(convert_curl("curl -F novalue https://a.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_curl_user_without_password() {
+        // 测试中 stdin 不是终端，无法提示输入密码
+        if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            return;
+        }
+        let err = convert_curl("curl -u admin https://example.com").unwrap_err();
+        assert!(err.to_string().contains("Password for user 'admin'"));
+    }
+
     #[test]
     fn test_is_key_value_param() {
         // URL 格式不应被识别为键值对
         assert!(!CliRunner::is_key_value_param("http://example.com"));
         assert!(!CliRunner::is_key_value_param("https://example.com/api"));
         assert!(!CliRunner::is_key_value_param(":/api/users")); // localhost 简写
+        assert!(!CliRunner::is_key_value_param(":3000/api/items"));
         assert!(!CliRunner::is_key_value_param(":3000")); // 端口简写
         assert!(!CliRunner::is_key_value_param(":8080"));
         assert!(!CliRunner::is_key_value_param("localhost:3000"));
@@ -504,7 +2723,7 @@ mod tests {
 
     #[test]
     fn test_parse_httpie_with_urls() {
-        let runner = CliRunner::new();
+        let runner = runner();
 
         // Test: http:// URL
         let args = vec!["http://example.com".to_string()];
@@ -1,6 +1,11 @@
 use thiserror::Error;
 
+/// rupost 的错误类型
+///
+/// 带上下文的变体（URL、变量名等）可以直接匹配，底层错误通过 `source()` 获取。
+/// 标记为 `#[non_exhaustive]`，以后增加变体不会破坏下游的 match。
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum RupostError {
     #[error("解析错误: {0}")]
     ParseError(String),
@@ -8,8 +13,21 @@ pub enum RupostError {
     #[error("无效的 URL: {0}")]
     InvalidUrl(String),
 
-    #[error("HTTP 请求失败: {0}")]
-    HttpError(#[from] reqwest::Error),
+    /// URL 无法解析，input 为原始输入
+    #[error("无效的 URL '{input}': {source}")]
+    UrlError {
+        input: String,
+        #[source]
+        source: url::ParseError,
+    },
+
+    /// HTTP 请求失败，url 为请求的目标（与具体请求无关的错误，如构建客户端失败时为 None）
+    #[error("HTTP 请求失败{}: {source}", url.as_deref().map(|url| format!(" ({})", url)).unwrap_or_default())]
+    HttpError {
+        url: Option<String>,
+        #[source]
+        source: reqwest::Error,
+    },
 
     #[error("网络错误: {0}")]
     NetworkError(String),
@@ -20,13 +38,56 @@ pub enum RupostError {
     #[error("JSON 解析错误: {0}")]
     JsonError(#[from] serde_json::Error),
 
-    #[error("URL 解析错误: {0}")]
-    UrlParseError(#[from] url::ParseError),
+    #[error("配置错误: {0}")]
+    ConfigError(#[from] crate::variable::ConfigError),
+
+    /// 无法从响应中捕获变量（@capture）
+    #[error("无法捕获变量 '{variable}': {reason}")]
+    CaptureError { variable: String, reason: String },
+
+    /// 命令行参数无效或相互冲突
+    #[error("无效的参数: {0}")]
+    InvalidCliArgs(String),
 
     #[error("{0}")]
     Other(String),
 }
 
+impl RupostError {
+    /// 由 URL 解析错误构建，保留原始输入
+    pub fn url(input: impl Into<String>, source: url::ParseError) -> Self {
+        RupostError::UrlError {
+            input: input.into(),
+            source,
+        }
+    }
+
+    /// 错误消息加上 `source()` 链中尚未包含在消息里的原因，如连接失败的具体原因
+    pub fn full_message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source {
+            let cause_message = cause.to_string();
+            if !message.contains(&cause_message) {
+                message.push_str(": ");
+                message.push_str(&cause_message);
+            }
+            source = cause.source();
+        }
+        message
+    }
+}
+
+/// reqwest 错误自带的 URL 移到 `url` 字段，避免消息中重复
+impl From<reqwest::Error> for RupostError {
+    fn from(err: reqwest::Error) -> Self {
+        RupostError::HttpError {
+            url: err.url().map(|url| url.to_string()),
+            source: err.without_url(),
+        }
+    }
+}
+
 // Add conversion from anyhow::Error
 impl From<anyhow::Error> for RupostError {
     fn from(err: anyhow::Error) -> Self {
@@ -0,0 +1,140 @@
+use crate::history::model::HistoryEntry;
+use crate::parser::ParsedRequest;
+
+pub struct CurlGenerator;
+
+impl CurlGenerator {
+    /// Build a curl command replaying a recorded request
+    pub fn from_entry(entry: &HistoryEntry) -> String {
+        let headers: Vec<(&str, &str)> = entry
+            .request
+            .headers
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.as_str(), v)))
+            .collect();
+
+        Self::format_command(
+            &entry.request.method,
+            &entry.request.url,
+            &headers,
+            entry.request.body.as_deref(),
+        )
+    }
+
+    /// Build a curl command for a parsed (and already resolved) request
+    ///
+    /// `@auth` becomes an Authorization header unless one is set explicitly.
+    pub fn from_request(request: &ParsedRequest) -> String {
+        let authorization = request
+            .metadata
+            .auth
+            .as_ref()
+            .filter(|_| !request.has_authorization_header())
+            .map(|auth| auth.header_value());
+        let headers: Vec<(&str, &str)> = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .chain(authorization.as_deref().map(|v| ("Authorization", v)))
+            .collect();
+
+        Self::format_command(
+            request.method_or_default(),
+            &request.url_with_query(),
+            &headers,
+            request.body.as_deref(),
+        )
+    }
+
+    fn format_command(
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> String {
+        let mut parts = vec![format!("curl -X {} {}", method, shell_quote(url))];
+
+        for (key, value) in headers {
+            parts.push(format!(
+                "-H {}",
+                shell_quote(&format!("{}: {}", key, value))
+            ));
+        }
+
+        if let Some(body) = body.filter(|b| !b.is_empty()) {
+            parts.push(format!("--data-raw {}", shell_quote(body)));
+        }
+
+        parts.join(" \\\n  ")
+    }
+}
+
+/// Quote a string for POSIX shells
+///
+/// Everything is wrapped in single quotes, which keep newlines and `$`
+/// literal; embedded single quotes are written as `'\''`.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HttpFileParser;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("a\nb"), "'a\nb'");
+        assert_eq!(shell_quote("$HOME `x`"), "'$HOME `x`'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_quote_round_trip() {
+        let input = "it's a \"test\"\n$HOME \\ `whoami` ''";
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf %s {}", shell_quote(input)))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), input);
+    }
+
+    #[test]
+    fn test_from_request() {
+        let content = "POST https://example.com/users?a=1&b=2\nContent-Type: application/json\nX-Note: it's fine\n\n{\"name\": \"O'Brien\",\n \"bio\": \"line1\\nline2\"}";
+        let parsed = HttpFileParser::parse_content(content).unwrap();
+        let command = CurlGenerator::from_request(&parsed.requests[0]);
+
+        assert_eq!(
+            command,
+            "curl -X POST 'https://example.com/users?a=1&b=2' \\\n  \
+             -H 'Content-Type: application/json' \\\n  \
+             -H 'X-Note: it'\\''s fine' \\\n  \
+             --data-raw '{\"name\": \"O'\\''Brien\",\n \"bio\": \"line1\\nline2\"}'"
+        );
+    }
+
+    #[test]
+    fn test_from_request_with_auth() {
+        let parsed =
+            HttpFileParser::parse_content("@auth basic admin secret\nGET https://example.com/me")
+                .unwrap();
+        assert_eq!(
+            CurlGenerator::from_request(&parsed.requests[0]),
+            "curl -X GET 'https://example.com/me' \\\n  -H 'Authorization: Basic YWRtaW46c2VjcmV0'"
+        );
+    }
+
+    #[test]
+    fn test_from_request_without_body() {
+        let parsed = HttpFileParser::parse_content("GET https://example.com/health").unwrap();
+        assert_eq!(
+            CurlGenerator::from_request(&parsed.requests[0]),
+            "curl -X GET 'https://example.com/health'"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::Result;
+use crate::generator::http::{GenerateOptions, HttpGenerator};
+use crate::history::model::HistoryEntry;
+use crate::history::serialization::har::{Har, HarEntry};
+use url::Url;
+
+/// Mime type prefixes treated as static assets
+const ASSET_MIME_PREFIXES: &[&str] = &[
+    "image/",
+    "font/",
+    "text/css",
+    "application/font-",
+    "application/x-font-",
+    "application/vnd.ms-fontobject",
+];
+
+/// File extensions treated as static assets
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp", "avif", "css", "woff", "woff2",
+    "ttf", "otf", "eot",
+];
+
+pub struct HarGenerator;
+
+impl HarGenerator {
+    /// Convert a HAR capture into .http file content
+    ///
+    /// Static assets are dropped unless `include_assets` is set. Requests
+    /// against the most common origin are rewritten to use `{{base_url}}`.
+    /// Returns the content and the number of generated requests.
+    pub fn generate(
+        har: Har,
+        include_assets: bool,
+        options: &GenerateOptions,
+    ) -> Result<(String, usize)> {
+        let mut entries: Vec<HistoryEntry> = har
+            .log
+            .entries
+            .into_iter()
+            .filter(|e| include_assets || !Self::is_static_asset(e))
+            .map(HistoryEntry::from)
+            .collect();
+
+        if entries.is_empty() {
+            return Ok((String::new(), 0));
+        }
+
+        // Only the most common origin is replaced, other hosts stay as captured
+        let mut output = String::new();
+        let origin = HttpGenerator::origins_by_frequency(&entries)
+            .into_iter()
+            .next()
+            .filter(|_| options.extract_base_url);
+        if let Some(origin) = origin {
+            for entry in &mut entries {
+                if let Some(rewritten) =
+                    HttpGenerator::rewrite_url(&entry.request.url, &origin, "base_url")
+                {
+                    entry.request.url = rewritten;
+                }
+            }
+            output.push_str(&HttpGenerator::base_url_header(&[(
+                "base_url".to_string(),
+                origin,
+            )]));
+        }
+
+        let options = GenerateOptions {
+            extract_base_url: false,
+            ..*options
+        };
+        let (content, count) = HttpGenerator::generate(&entries, &options)?;
+        output.push_str(&content);
+        Ok((output, count))
+    }
+
+    fn is_static_asset(entry: &HarEntry) -> bool {
+        let mime = entry.response.content.mime_type.to_lowercase();
+        if ASSET_MIME_PREFIXES.iter().any(|p| mime.starts_with(p)) {
+            return true;
+        }
+
+        Url::parse(&entry.request.url)
+            .ok()
+            .and_then(|u| {
+                u.path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .and_then(|last| last.rsplit_once('.'))
+                    .map(|(_, ext)| ext.to_lowercase())
+            })
+            .is_some_and(|ext| ASSET_EXTENSIONS.contains(&ext.as_str()))
+    }
+}
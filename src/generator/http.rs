@@ -1,85 +1,442 @@
 use crate::Result;
 use crate::history::model::HistoryEntry;
+use crate::parser::{HttpFileParser, ParsedRequest};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use url::{Position, Url};
+
+/// How `@name` values are derived for generated requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameStyle {
+    /// Method plus URL path, e.g. `get_api_users`
+    #[default]
+    Path,
+    /// Request time, e.g. `req_1717000000`
+    Timestamp,
+}
+
+/// Options controlling .http generation from history entries
+#[derive(Debug, Clone, Copy)]
+pub struct GenerateOptions {
+    /// Collapse entries with the same method, URL and body, keeping the most recent
+    pub dedupe: bool,
+    pub name_style: NameStyle,
+    /// Replace each origin with a `{{base_url}}` style variable
+    pub extract_base_url: bool,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            dedupe: false,
+            name_style: NameStyle::default(),
+            extract_base_url: true,
+        }
+    }
+}
+
+/// A history entry paired with its generated request name
+pub(crate) type NamedEntry = (String, HistoryEntry);
 
 pub struct HttpGenerator;
 
 impl HttpGenerator {
     /// Convert a list of history entries to .http file content
-    pub fn generate(entries: &[HistoryEntry]) -> Result<String> {
-        let mut output = String::new();
+    ///
+    /// Returns the content and the number of generated requests.
+    pub fn generate(
+        entries: &[HistoryEntry],
+        options: &GenerateOptions,
+    ) -> Result<(String, usize)> {
+        let (variables, entries) = Self::prepare(entries, options);
+        let mut output = Self::base_url_header(&variables);
 
-        for (i, entry) in entries.iter().enumerate() {
+        for (i, (name, entry)) in entries.iter().enumerate() {
             if i > 0 {
                 output.push_str("\n\n");
             }
-            output.push_str(&Self::format_entry(entry));
+            output.push_str(&Self::format_entry(entry, name));
         }
 
         if !entries.is_empty() {
             output.push('\n');
         }
 
-        Ok(output)
+        Ok((output, entries.len()))
     }
 
-    fn format_entry(entry: &HistoryEntry) -> String {
-        let mut block = String::new();
+    /// Apply dedupe / base_url extraction and assign unique names
+    ///
+    /// Returns the extracted `(variable, origin)` pairs and the named entries.
+    /// Shared by the .http and Markdown generators.
+    pub(crate) fn prepare(
+        entries: &[HistoryEntry],
+        options: &GenerateOptions,
+    ) -> (Vec<(String, String)>, Vec<NamedEntry>) {
+        let mut entries = if options.dedupe {
+            Self::dedupe(entries)
+        } else {
+            entries.to_vec()
+        };
+
+        let variables = if options.extract_base_url {
+            Self::extract_base_urls(&mut entries)
+        } else {
+            Vec::new()
+        };
+
+        // Names already handed out, so collisions get a numeric suffix
+        let mut used_names = HashSet::new();
+        let named = entries
+            .into_iter()
+            .map(|entry| {
+                let name = Self::unique_name(
+                    Self::entry_name(&entry, options.name_style),
+                    &mut used_names,
+                );
+                (name, entry)
+            })
+            .collect();
+
+        (variables, named)
+    }
+
+    /// Drop repeated requests (same method, URL and body), keeping the most
+    /// recent occurrence of each. Entries are expected in chronological order.
+    pub fn dedupe(entries: &[HistoryEntry]) -> Vec<HistoryEntry> {
+        let mut seen = HashSet::new();
+        let mut kept: Vec<HistoryEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| {
+                let mut hasher = DefaultHasher::new();
+                entry.request.body.hash(&mut hasher);
+                seen.insert((
+                    entry.request.method.to_uppercase(),
+                    entry.request.url.clone(),
+                    hasher.finish(),
+                ))
+            })
+            .cloned()
+            .collect();
+        kept.reverse();
+        kept
+    }
+
+    /// Rewrite request URLs to `{{base_url}}/path?query`, one variable per origin
+    ///
+    /// The most frequent origin becomes `base_url`, the others `base_url_2`,
+    /// `base_url_3`, ... Returns `(variable, origin)` pairs.
+    fn extract_base_urls(entries: &mut [HistoryEntry]) -> Vec<(String, String)> {
+        let variables: Vec<(String, String)> = Self::origins_by_frequency(entries)
+            .into_iter()
+            .enumerate()
+            .map(|(index, origin)| {
+                let variable = match index {
+                    0 => "base_url".to_string(),
+                    n => format!("base_url_{}", n + 1),
+                };
+                (variable, origin)
+            })
+            .collect();
+
+        for entry in entries.iter_mut() {
+            if let Some(rewritten) = variables.iter().find_map(|(variable, origin)| {
+                Self::rewrite_url(&entry.request.url, origin, variable)
+            }) {
+                entry.request.url = rewritten;
+            }
+        }
+
+        variables
+    }
+
+    /// Distinct http(s) origins, most frequent first (first seen wins ties)
+    pub(crate) fn origins_by_frequency(entries: &[HistoryEntry]) -> Vec<String> {
+        let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if let Some(origin) = Self::origin_of(&entry.request.url) {
+                counts.entry(origin).or_insert((0, index)).0 += 1;
+            }
+        }
+
+        let mut origins: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+        origins.sort_by(|(_, (a_count, a_first)), (_, (b_count, b_first))| {
+            b_count.cmp(a_count).then(a_first.cmp(b_first))
+        });
+        origins.into_iter().map(|(origin, _)| origin).collect()
+    }
+
+    fn origin_of(url: &str) -> Option<String> {
+        let origin = Url::parse(url).ok()?.origin();
+        origin.is_tuple().then(|| origin.ascii_serialization())
+    }
+
+    /// Replace `origin` in `url` with `{{variable}}`, None for other origins
+    pub(crate) fn rewrite_url(url: &str, origin: &str, variable: &str) -> Option<String> {
+        let parsed = Url::parse(url).ok()?;
+        if parsed.origin().ascii_serialization() != origin {
+            return None;
+        }
+        Some(format!(
+            "{{{{{}}}}}{}",
+            variable,
+            &parsed[Position::BeforePath..]
+        ))
+    }
+
+    /// Comment block telling which variables replace which origins
+    ///
+    /// .http files have no file-level variables, so they must be defined in rupost.toml.
+    pub(crate) fn base_url_header(variables: &[(String, String)]) -> String {
+        if variables.is_empty() {
+            return String::new();
+        }
 
-        // 1. Comment / Name
-        // Generate a name like: "GET /api/users - 10:23:45"
+        let mut header = String::new();
+        for (variable, origin) in variables {
+            header.push_str(&format!(
+                "# {{{{{}}}}} replaces the original host {}\n",
+                variable, origin
+            ));
+        }
+        let names: Vec<&str> = variables.iter().map(|(v, _)| v.as_str()).collect();
+        header.push_str(&format!(
+            "# Define {} in rupost.toml to run these requests\n\n",
+            names.join(", ")
+        ));
+        header
+    }
+
+    /// The recorded `@name` when the entry came from a request file, otherwise derived by `style`
+    fn entry_name(entry: &HistoryEntry, style: NameStyle) -> String {
+        if let Some(name) = &entry.name {
+            return name.clone();
+        }
+        match style {
+            NameStyle::Path => Self::path_name(&entry.request.method, &entry.request.url),
+            NameStyle::Timestamp => format!("req_{}", entry.timestamp.timestamp()),
+        }
+    }
+
+    /// Derive a readable name from method and URL path (`POST /auth/login` -> `post_auth_login`)
+    ///
+    /// Scheme, host, a leading `{{base_url}}`, query and fragment are ignored.
+    fn path_name(method: &str, url: &str) -> String {
+        let url = url.split(['?', '#']).next().unwrap_or_default();
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or_default(),
+            None if url.starts_with("{{") => {
+                url.find("}}").map(|i| &url[i + 2..]).unwrap_or_default()
+            }
+            None => url,
+        };
+
+        let mut name = method.to_lowercase();
+        for segment in path.split('/') {
+            let segment: String = segment
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            for part in segment.split('_').filter(|p| !p.is_empty()) {
+                name.push('_');
+                name.push_str(part);
+            }
+        }
+
+        if !name.contains('_') {
+            name.push_str("_root");
+        }
+        name
+    }
+
+    /// Append `_2`, `_3`, ... until the name has not been used yet
+    fn unique_name(base: String, used: &mut HashSet<String>) -> String {
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while used.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        used.insert(name.clone());
+        name
+    }
 
-        block.push_str(&format!(
+    fn format_entry(entry: &HistoryEntry, name: &str) -> String {
+        let mut block = format!(
             "### Request {}\n",
             entry.id.chars().take(8).collect::<String>()
+        );
+        block.push_str(&Self::format_entry_request(entry, Some(name)));
+        block
+    }
+
+    /// Metadata, request line, headers and body of a recorded request
+    ///
+    /// Metadata has to precede the request line, anything after the blank
+    /// line would be read back as body.
+    pub(crate) fn format_entry_request(entry: &HistoryEntry, name: Option<&str>) -> String {
+        let mut block = String::new();
+
+        // 1. Name and implicit status assertion
+        if let Some(name) = name {
+            block.push_str(&format!("# @name {}\n", name));
+        }
+        block.push_str(&format!("# @assert status == {}\n", entry.response.status));
+
+        // 2. Request line, headers and body
+        // Skip common auto-headers that shouldn't be hardcoded in tests
+        let headers: Vec<(&str, &str)> = entry
+            .request
+            .headers
+            .iter()
+            .filter(|(key, _)| !Self::should_skip_header(key.as_str()))
+            .filter_map(|(key, value)| value.to_str().ok().map(|v| (key.as_str(), v)))
+            .collect();
+        block.push_str(&Self::format_request_lines(
+            &entry.request.method,
+            &entry.request.url,
+            &headers,
+            entry.request.body.as_deref(),
         ));
-        block.push_str(&format!(
-            "# @name req_{}_{}\n",
-            entry.timestamp.timestamp(),
-            i8::abs(entry.id.as_bytes()[0] as i8)
+
+        block
+    }
+
+    /// Format a parsed request as a standalone `###` block
+    ///
+    /// Used when converting requests from other sources (e.g. curl commands)
+    /// so they can be appended to an existing .http file.
+    pub fn format_request(request: &ParsedRequest) -> String {
+        let headers: Vec<(&str, &str)> = request
+            .headers
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+
+        let mut block = match request.name() {
+            Some(name) => format!("### {}\n", name),
+            None => "###\n".to_string(),
+        };
+        if let Some(auth) = &request.metadata.auth {
+            block.push_str(&format!("# @auth {}\n", auth));
+        }
+        block.push_str(&Self::format_request_lines(
+            request.method_or_default(),
+            &request.url,
+            &headers,
+            request.body.as_deref(),
         ));
+        block
+    }
 
-        // 2. Request Line
-        block.push_str(&format!("{} {}\n", entry.request.method, entry.request.url));
+    /// Append an executed ad-hoc request to .http file content
+    ///
+    /// The block gets a generated `@name` (unique within `content`) and the
+    /// observed status as assertion. Returns None when an identical block is
+    /// already present.
+    pub fn append_saved_request(
+        content: &str,
+        request: &ParsedRequest,
+        status: u16,
+    ) -> Option<String> {
+        let base = match request.name() {
+            Some(name) => name.to_string(),
+            None => Self::path_name(request.method_or_default(), &request.url),
+        };
+        if content.contains(&Self::format_saved_request(request, &base, status)) {
+            return None;
+        }
 
-        // 3. Headers
-        for (key, value) in &entry.request.headers {
-            let key_str = key.as_str();
-            // Skip common auto-headers that shouldn't be hardcoded in tests
-            if Self::should_skip_header(key_str) {
-                continue;
-            }
-            if let Ok(val_str) = value.to_str() {
-                block.push_str(&format!("{}: {}\n", key_str, val_str));
+        let mut used: HashSet<String> = HttpFileParser::parse_content(content)
+            .map(|parsed| {
+                parsed
+                    .requests
+                    .iter()
+                    .filter_map(|r| r.name().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let name = Self::unique_name(base, &mut used);
+        Some(Self::append_block(
+            content,
+            &Self::format_saved_request(request, &name, status),
+        ))
+    }
+
+    fn format_saved_request(request: &ParsedRequest, name: &str, status: u16) -> String {
+        let headers: Vec<(&str, &str)> = request
+            .headers
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+
+        let mut block = format!(
+            "### {}\n# @name {}\n# @assert status == {}\n",
+            name, name, status
+        );
+        if let Some(auth) = &request.metadata.auth {
+            block.push_str(&format!("# @auth {}\n", auth));
+        }
+        block.push_str(&Self::format_request_lines(
+            request.method_or_default(),
+            &request.url,
+            &headers,
+            request.body.as_deref(),
+        ));
+        block
+    }
+
+    /// Append a `###` block to existing file content, separated by a blank line
+    pub fn append_block(content: &str, block: &str) -> String {
+        let mut content = content.to_string();
+        if !content.is_empty() {
+            if !content.ends_with('\n') {
+                content.push('\n');
             }
+            content.push('\n');
+        }
+        content.push_str(block);
+        content
+    }
+
+    /// Request line, headers and (pretty-printed when JSON) body
+    fn format_request_lines(
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> String {
+        let mut lines = format!("{} {}\n", method, url);
+
+        for (key, value) in headers {
+            lines.push_str(&format!("{}: {}\n", key, value));
         }
 
-        // 4. Body
-        if let Some(body) = entry.request.body.as_ref().filter(|b| !b.trim().is_empty()) {
-            block.push('\n');
+        if let Some(body) = body.filter(|b| !b.trim().is_empty()) {
+            lines.push('\n');
             // Try to pretty print JSON
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
-                if let Ok(pretty) = serde_json::to_string_pretty(&json) {
-                    block.push_str(&pretty);
-                } else {
-                    block.push_str(body);
-                }
-            } else {
-                block.push_str(body);
+            match serde_json::from_str::<serde_json::Value>(body)
+                .ok()
+                .and_then(|json| serde_json::to_string_pretty(&json).ok())
+            {
+                Some(pretty) => lines.push_str(&pretty),
+                None => lines.push_str(body),
             }
-            block.push('\n');
+            lines.push('\n');
         }
 
-        // 5. Assertions (Metadata)
-        // Add implicit assertion for status code
-        block.push_str(&format!(
-            "\n# @assert status == {}\n",
-            entry.response.status
-        ));
-
-        block
+        lines
     }
 
-    fn should_skip_header(name: &str) -> bool {
+    pub(crate) fn should_skip_header(name: &str) -> bool {
         let name_lower = name.to_lowercase();
         matches!(
             name_lower.as_str(),
@@ -87,3 +444,363 @@ impl HttpGenerator {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HttpFileParser;
+    use crate::parser::types::Auth;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_rewrite_url() {
+        assert_eq!(
+            HttpGenerator::rewrite_url("https://a.com:443/users?id=1", "https://a.com", "base_url"),
+            Some("{{base_url}}/users?id=1".to_string())
+        );
+        assert_eq!(
+            HttpGenerator::rewrite_url("https://b.com/users", "https://a.com", "base_url"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_base_url_mixed_origins() {
+        let entries = vec![
+            HistoryEntry::test_entry("1", "GET", "https://auth.example.com/login", None),
+            HistoryEntry::test_entry("2", "GET", "https://api.example.com/users?page=2", None),
+            HistoryEntry::test_entry("3", "GET", "https://api.example.com/users/1#top", None),
+            HistoryEntry::test_entry("4", "GET", "http://localhost:8080/health", None),
+            HistoryEntry::test_entry("5", "GET", "{{base_url}}/already", None),
+        ];
+        let (content, count) =
+            HttpGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        assert_eq!(count, 5);
+
+        assert!(content.starts_with(
+            "# {{base_url}} replaces the original host https://api.example.com\n\
+             # {{base_url_2}} replaces the original host https://auth.example.com\n\
+             # {{base_url_3}} replaces the original host http://localhost:8080\n\
+             # Define base_url, base_url_2, base_url_3 in rupost.toml to run these requests\n\n"
+        ));
+
+        let parsed = HttpFileParser::parse_content(&content).unwrap();
+        let urls: Vec<&str> = parsed.requests.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "{{base_url_2}}/login",
+                "{{base_url}}/users?page=2",
+                "{{base_url}}/users/1#top",
+                "{{base_url_3}}/health",
+                "{{base_url}}/already",
+            ]
+        );
+        // Names are still derived from the path
+        assert_eq!(parsed.requests[0].name(), Some("get_login"));
+    }
+
+    #[test]
+    fn test_extract_base_url_disabled() {
+        let entries = vec![HistoryEntry::test_entry(
+            "1",
+            "GET",
+            "https://api.example.com/users",
+            None,
+        )];
+        let options = GenerateOptions {
+            extract_base_url: false,
+            ..Default::default()
+        };
+        let (content, _) = HttpGenerator::generate(&entries, &options).unwrap();
+        assert!(!content.contains("base_url"));
+        assert!(content.contains("GET https://api.example.com/users\n"));
+    }
+
+    #[test]
+    fn test_generate_round_trip() {
+        let mut post = HistoryEntry::test_entry(
+            "1",
+            "POST",
+            "https://api.example.com/users",
+            Some(r#"{"name":"alice"}"#),
+        );
+        post.response.status = 201;
+        let entries = vec![
+            post,
+            HistoryEntry::test_entry("2", "GET", "https://api.example.com/health", None),
+        ];
+        let (content, _) = HttpGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+
+        let parsed = HttpFileParser::parse_content(&content).unwrap();
+        assert_eq!(parsed.requests.len(), 2);
+        assert_eq!(
+            parsed.requests[0].metadata.assertions,
+            vec!["status == 201"]
+        );
+        let body: serde_json::Value =
+            serde_json::from_str(parsed.requests[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["name"], "alice");
+        // The status assertion must not leak into the body
+        assert_eq!(parsed.requests[1].body, None);
+        assert_eq!(
+            parsed.requests[1].metadata.assertions,
+            vec!["status == 200"]
+        );
+    }
+
+    #[test]
+    fn test_path_name() {
+        assert_eq!(
+            HttpGenerator::path_name("GET", "https://a.com/api/users?page=2"),
+            "get_api_users"
+        );
+        assert_eq!(
+            HttpGenerator::path_name("POST", "{{base_url}}/auth/login"),
+            "post_auth_login"
+        );
+        assert_eq!(
+            HttpGenerator::path_name("DELETE", "http://a.com/users/{{id}}/avatar-image"),
+            "delete_users_id_avatar_image"
+        );
+        assert_eq!(HttpGenerator::path_name("GET", "https://a.com"), "get_root");
+        assert_eq!(
+            HttpGenerator::path_name("GET", "https://a.com/"),
+            "get_root"
+        );
+    }
+
+    #[test]
+    fn test_generate_names_with_collisions() {
+        let entries = vec![
+            HistoryEntry::test_entry("a", "GET", "https://a.com/users", None),
+            HistoryEntry::test_entry("b", "GET", "https://a.com/users?page=2", None),
+            HistoryEntry::test_entry("c", "GET", "https://a.com/users_2", None),
+            HistoryEntry::test_entry("d", "POST", "https://a.com/users", Some("{}")),
+            HistoryEntry::test_entry("e", "GET", "https://a.com/users-2", None),
+        ];
+        let (content, count) =
+            HttpGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        assert_eq!(count, 5);
+
+        let parsed = HttpFileParser::parse_content(&content).unwrap();
+        let names: Vec<&str> = parsed.requests.iter().filter_map(|r| r.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "get_users",
+                "get_users_2",
+                "get_users_2_2",
+                "post_users",
+                "get_users_2_3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_names_are_stable() {
+        // Names depend only on method and path, not on when the request was recorded
+        let mut older = HistoryEntry::test_entry("a", "GET", "https://a.com/users", None);
+        older.timestamp = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+        let entries = vec![
+            older,
+            HistoryEntry::test_entry("b", "GET", "https://a.com/users", None),
+        ];
+
+        let (first, _) = HttpGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        let (second, _) = HttpGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        assert_eq!(first, second);
+        assert!(first.contains("### Request a\n# @name get_users\n"));
+        assert!(first.contains("### Request b\n# @name get_users_2\n"));
+    }
+
+    #[test]
+    fn test_generate_names_stable_when_entries_change() {
+        fn names(entries: &[HistoryEntry]) -> HashMap<String, String> {
+            HttpGenerator::prepare(entries, &GenerateOptions::default())
+                .1
+                .into_iter()
+                .map(|(name, entry)| (entry.id, name))
+                .collect()
+        }
+
+        let users = HistoryEntry::test_entry("a", "GET", "https://a.com/users", None);
+        let login = HistoryEntry::test_entry("b", "POST", "https://a.com/auth/login", Some("{}"));
+        let users_page = HistoryEntry::test_entry("c", "GET", "https://a.com/users?page=2", None);
+        let orders = HistoryEntry::test_entry("d", "GET", "https://b.com/orders", None);
+        let base = names(&[
+            users.clone(),
+            login.clone(),
+            users_page.clone(),
+            orders.clone(),
+        ]);
+        assert_eq!(base["a"], "get_users");
+        assert_eq!(base["b"], "post_auth_login");
+        assert_eq!(base["c"], "get_users_2");
+        assert_eq!(base["d"], "get_orders");
+
+        // Re-recording with a newer colliding entry only names the new one
+        let appended = names(&[
+            users.clone(),
+            login.clone(),
+            users_page.clone(),
+            orders.clone(),
+            HistoryEntry::test_entry("e", "GET", "https://a.com/users", None),
+        ]);
+        assert_eq!(appended["e"], "get_users_3");
+        assert!(base.iter().all(|(id, name)| &appended[id] == name));
+
+        // Unrelated entries added or removed anywhere do not rename the others
+        let inserted = names(&[
+            users.clone(),
+            HistoryEntry::test_entry("f", "DELETE", "https://a.com/users/1", None),
+            login.clone(),
+            users_page.clone(),
+            orders.clone(),
+        ]);
+        assert_eq!(inserted["f"], "delete_users_1");
+        assert!(base.iter().all(|(id, name)| &inserted[id] == name));
+
+        let removed = names(&[users.clone(), users_page.clone(), orders.clone()]);
+        assert!(removed.iter().all(|(id, name)| &base[id] == name));
+
+        // Dropping the newest of a colliding group keeps the earlier names
+        let trimmed = names(&[users, login, orders]);
+        assert!(trimmed.iter().all(|(id, name)| &base[id] == name));
+    }
+
+    #[test]
+    fn test_generate_keeps_recorded_names() {
+        let mut login =
+            HistoryEntry::test_entry("a", "POST", "https://a.com/auth/login", Some("{}"));
+        login.name = Some("login".to_string());
+        let mut again =
+            HistoryEntry::test_entry("b", "POST", "https://a.com/auth/login", Some("{}"));
+        again.name = Some("login".to_string());
+        let entries = vec![
+            login,
+            again,
+            HistoryEntry::test_entry("c", "GET", "https://a.com/users", None),
+        ];
+
+        for name_style in [NameStyle::Path, NameStyle::Timestamp] {
+            let options = GenerateOptions {
+                name_style,
+                ..Default::default()
+            };
+            let (content, _) = HttpGenerator::generate(&entries, &options).unwrap();
+            let parsed = HttpFileParser::parse_content(&content).unwrap();
+            let names: Vec<&str> = parsed.requests.iter().filter_map(|r| r.name()).collect();
+            assert_eq!(names[..2], ["login", "login_2"]);
+        }
+    }
+
+    #[test]
+    fn test_generate_timestamp_names() {
+        // Non-ASCII / high-byte ids used to feed an i8 cast
+        let entries = vec![
+            HistoryEntry::test_entry("é1", "GET", "https://a.com/x", None),
+            HistoryEntry::test_entry("\u{80}", "GET", "https://a.com/y", None),
+        ];
+        let options = GenerateOptions {
+            name_style: NameStyle::Timestamp,
+            ..Default::default()
+        };
+        let (content, _) = HttpGenerator::generate(&entries, &options).unwrap();
+        assert!(content.contains("# @name req_1717000000\n"));
+        assert!(content.contains("# @name req_1717000000_2\n"));
+    }
+
+    #[test]
+    fn test_dedupe_keeps_most_recent() {
+        let entries = vec![
+            HistoryEntry::test_entry("1", "GET", "https://a.com/users", None),
+            HistoryEntry::test_entry("2", "POST", "https://a.com/users", Some(r#"{"n":1}"#)),
+            HistoryEntry::test_entry("3", "GET", "https://a.com/users", None),
+            HistoryEntry::test_entry("4", "POST", "https://a.com/users", Some(r#"{"n":2}"#)),
+            HistoryEntry::test_entry("5", "get", "https://a.com/users", None),
+        ];
+        let ids: Vec<String> = HttpGenerator::dedupe(&entries)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["2", "4", "5"]);
+
+        let options = GenerateOptions {
+            dedupe: true,
+            ..Default::default()
+        };
+        let (_, count) = HttpGenerator::generate(&entries, &options).unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_format_request_round_trip() {
+        let mut request = ParsedRequest::new(0);
+        request.method = Some("POST".to_string());
+        request.url = "https://api.example.com/users".to_string();
+        request.headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        request.body = Some(r#"{"name":"alice","tags":["a"]}"#.to_string());
+        request.metadata.auth = Some(Auth::Bearer("abc".to_string()));
+
+        let block = HttpGenerator::format_request(&request);
+        assert!(block.starts_with("###\n# @auth bearer abc\nPOST https://api.example.com/users\n"));
+        assert!(block.contains("  \"name\": \"alice\""));
+
+        let parsed = HttpFileParser::parse_content(&block).unwrap();
+        assert_eq!(parsed.requests.len(), 1);
+        let restored = &parsed.requests[0];
+        assert_eq!(restored.url, request.url);
+        assert_eq!(restored.headers, request.headers);
+        assert_eq!(restored.metadata.auth, request.metadata.auth);
+        let body: serde_json::Value =
+            serde_json::from_str(restored.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["tags"][0], "a");
+    }
+
+    #[test]
+    fn test_append_saved_request() {
+        let mut request = ParsedRequest::new(0);
+        request.method = Some("POST".to_string());
+        request.url = "{{base_url}}/login".to_string();
+        request.headers = vec![("Authorization".to_string(), "Bearer {{token}}".to_string())];
+        request.body = Some(r#"{"user":"admin"}"#.to_string());
+
+        let content = HttpGenerator::append_saved_request("", &request, 200).unwrap();
+        assert!(content.starts_with(
+            "### post_login\n# @name post_login\n# @assert status == 200\nPOST {{base_url}}/login\n"
+        ));
+
+        // 相同的请求不会重复追加
+        assert_eq!(
+            HttpGenerator::append_saved_request(&content, &request, 200),
+            None
+        );
+
+        // 状态不同时追加新的块，名称保持唯一
+        let content = HttpGenerator::append_saved_request(&content, &request, 401).unwrap();
+        let parsed = HttpFileParser::parse_content(&content).unwrap();
+        assert_eq!(parsed.requests.len(), 2);
+        assert_eq!(parsed.requests[1].name(), Some("post_login_2"));
+        assert_eq!(
+            parsed.requests[1].metadata.assertions,
+            vec!["status == 401"]
+        );
+        assert_eq!(parsed.requests[1].url, "{{base_url}}/login");
+        assert_eq!(parsed.requests[1].headers, request.headers);
+        parsed.validate(true).unwrap();
+    }
+
+    #[test]
+    fn test_append_block() {
+        assert_eq!(HttpGenerator::append_block("", "###\n"), "###\n");
+        assert_eq!(
+            HttpGenerator::append_block("GET /a", "###\n"),
+            "GET /a\n\n###\n"
+        );
+        assert_eq!(
+            HttpGenerator::append_block("GET /a\n", "###\n"),
+            "GET /a\n\n###\n"
+        );
+    }
+}
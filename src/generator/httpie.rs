@@ -0,0 +1,222 @@
+//! httpie command lines from history entries
+//!
+//! JSON object bodies become httpie request items (`field=value` for strings,
+//! `field:=json` for everything else); other bodies, including an empty
+//! object, are piped in on stdin. Commands that do not pipe a body pass
+//! `--ignore-stdin` so httpie does not read whatever stdin the script has.
+
+use crate::Result;
+use crate::generator::curl::shell_quote;
+use crate::generator::http::{GenerateOptions, HttpGenerator};
+use crate::history::model::HistoryEntry;
+use serde_json::Value;
+
+pub struct HttpieGenerator;
+
+impl HttpieGenerator {
+    /// Convert a list of history entries to a shell script of httpie commands
+    ///
+    /// Returns the content and the number of generated commands. Origins are
+    /// kept as recorded since the commands run outside rupost.
+    pub fn generate(
+        entries: &[HistoryEntry],
+        options: &GenerateOptions,
+    ) -> Result<(String, usize)> {
+        let options = GenerateOptions {
+            extract_base_url: false,
+            ..*options
+        };
+        let (_, entries) = HttpGenerator::prepare(entries, &options);
+
+        let mut output = String::from("#!/bin/sh\n");
+        for (name, entry) in &entries {
+            output.push_str(&format!("\n# {}\n{}\n", name, Self::from_entry(entry)));
+        }
+        Ok((output, entries.len()))
+    }
+
+    /// Build an httpie command replaying a recorded request
+    pub fn from_entry(entry: &HistoryEntry) -> String {
+        let request = &entry.request;
+        let body = request.body.as_deref().filter(|b| !b.is_empty());
+        let fields = body.and_then(json_fields);
+        let stdin_body = body.filter(|_| fields.is_none());
+
+        let program = match stdin_body {
+            Some(_) => "http",
+            None => "http --ignore-stdin",
+        };
+
+        let mut parts = vec![format!(
+            "{} {} {}",
+            program,
+            request.method,
+            shell_quote(&request.url)
+        )];
+        for (key, value) in &request.headers {
+            if HttpGenerator::should_skip_header(key.as_str()) {
+                continue;
+            }
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            // httpie sends JSON items with this content type already
+            if fields.is_some()
+                && key == reqwest::header::CONTENT_TYPE
+                && value.starts_with("application/json")
+            {
+                continue;
+            }
+            // `Name:` would remove the header, `Name;` sends it empty
+            let item = if value.is_empty() {
+                format!("{};", key)
+            } else {
+                format!("{}:{}", key, value)
+            };
+            parts.push(shell_quote(&item));
+        }
+
+        if let Some(body) = stdin_body {
+            return format!(
+                "printf %s {} | {}",
+                shell_quote(body),
+                parts.join(" \\\n  ")
+            );
+        }
+        if let Some(fields) = fields {
+            parts.extend(fields.iter().map(|field| shell_quote(field)));
+        }
+        parts.join(" \\\n  ")
+    }
+}
+
+/// httpie request items for a JSON object body, None when the body has to be
+/// sent as is (not an object, an empty object which httpie would not send, or
+/// keys httpie would read as options, separators or nested paths)
+fn json_fields(body: &str) -> Option<Vec<String>> {
+    let Ok(Value::Object(map)) = serde_json::from_str::<Value>(body) else {
+        return None;
+    };
+    if map.is_empty() {
+        return None;
+    }
+
+    map.iter()
+        .map(|(key, value)| {
+            if key.is_empty()
+                || key.starts_with('-')
+                || key.contains([':', '=', '@', '[', ']', '\\'])
+            {
+                return None;
+            }
+            Some(match value {
+                // `key=@file` reads a file and `key==value` is a query parameter
+                Value::String(s) if !s.starts_with(['@', '=']) => format!("{}={}", key, s),
+                other => format!("{}:={}", key, other),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_json_body_to_items() {
+        let mut entry = HistoryEntry::test_entry(
+            "1",
+            "POST",
+            "https://example.com/users?team=a",
+            Some(r#"{"name": "O'Brien", "age": 30, "tags": ["a"], "admin": false}"#),
+        );
+        let headers = &mut entry.request.headers;
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("authorization", HeaderValue::from_static("Bearer t"));
+        headers.insert("user-agent", HeaderValue::from_static("rupost"));
+
+        assert_eq!(
+            HttpieGenerator::from_entry(&entry),
+            "http --ignore-stdin POST 'https://example.com/users?team=a' \\\n  \
+             'authorization:Bearer t' \\\n  \
+             'admin:=false' \\\n  \
+             'age:=30' \\\n  \
+             'name=O'\\''Brien' \\\n  \
+             'tags:=[\"a\"]'"
+        );
+    }
+
+    #[test]
+    fn test_raw_body_from_stdin() {
+        let mut entry = HistoryEntry::test_entry(
+            "1",
+            "PUT",
+            "https://example.com/notes/1",
+            Some("hello\nworld"),
+        );
+        let headers = &mut entry.request.headers;
+        headers.insert("content-type", HeaderValue::from_static("text/plain"));
+        headers.insert("x-empty", HeaderValue::from_static(""));
+        assert_eq!(
+            HttpieGenerator::from_entry(&entry),
+            "printf %s 'hello\nworld' | http PUT 'https://example.com/notes/1' \\\n  \
+             'content-type:text/plain' \\\n  \
+             'x-empty;'"
+        );
+
+        // Arrays and keys httpie would parse as separators stay raw
+        assert_eq!(json_fields("[1, 2]"), None);
+        assert_eq!(json_fields(r#"{"a:b": 1}"#), None);
+        assert_eq!(json_fields(r#"{"user[name]": "x"}"#), None);
+        assert_eq!(json_fields(r#"{"-v": true}"#), None);
+    }
+
+    #[test]
+    fn test_empty_object_from_stdin() {
+        // httpie sends no body at all without request items
+        assert_eq!(json_fields("{}"), None);
+
+        let mut entry =
+            HistoryEntry::test_entry("1", "POST", "https://example.com/jobs", Some("{}"));
+        entry
+            .request
+            .headers
+            .insert("content-type", HeaderValue::from_static("application/json"));
+        assert_eq!(
+            HttpieGenerator::from_entry(&entry),
+            "printf %s '{}' | http POST 'https://example.com/jobs' \\\n  \
+             'content-type:application/json'"
+        );
+    }
+
+    #[test]
+    fn test_strings_httpie_would_misread() {
+        let fields = json_fields(r#"{"handle": "@ada", "op": "=1", "name": "a@b"}"#);
+        assert_eq!(
+            fields,
+            Some(vec![
+                r#"handle:="@ada""#.to_string(),
+                "name=a@b".to_string(),
+                r#"op:="=1""#.to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let entries = vec![
+            HistoryEntry::test_entry("1", "GET", "https://example.com/health", None),
+            HistoryEntry::test_entry("2", "GET", "https://example.com/health", None),
+        ];
+        let (content, count) =
+            HttpieGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            content,
+            "#!/bin/sh\n\n\
+             # get_health\nhttp --ignore-stdin GET 'https://example.com/health'\n\n\
+             # get_health_2\nhttp --ignore-stdin GET 'https://example.com/health'\n"
+        );
+    }
+}
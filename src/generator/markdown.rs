@@ -0,0 +1,136 @@
+//! Markdown API documentation from history entries
+//!
+//! Each request gets a heading (its generated name) followed by an ```http
+//! block, so the document can be executed again with `rupost test`. When an
+//! entry carries a recorded response body it is shown as an example response.
+
+use crate::Result;
+use crate::generator::http::{GenerateOptions, HttpGenerator};
+use crate::history::model::HistoryEntry;
+
+pub struct MarkdownGenerator;
+
+impl MarkdownGenerator {
+    /// Convert a list of history entries to Markdown documentation
+    ///
+    /// Returns the content and the number of documented requests.
+    pub fn generate(
+        entries: &[HistoryEntry],
+        options: &GenerateOptions,
+    ) -> Result<(String, usize)> {
+        let (variables, entries) = HttpGenerator::prepare(entries, options);
+
+        let mut output = String::from("# API Requests\n");
+
+        if !variables.is_empty() {
+            output.push('\n');
+            for (variable, origin) in &variables {
+                output.push_str(&format!(
+                    "- `{{{{{}}}}}` replaces the original host {}\n",
+                    variable, origin
+                ));
+            }
+            let names: Vec<String> = variables.iter().map(|(v, _)| format!("`{}`", v)).collect();
+            output.push_str(&format!(
+                "\nDefine {} in rupost.toml to run these requests.\n",
+                names.join(", ")
+            ));
+        }
+
+        for (name, entry) in &entries {
+            output.push_str(&format!("\n## {}\n\n", name));
+
+            // The heading names the request, so no @name inside the block
+            let request = HttpGenerator::format_entry_request(entry, None);
+            output.push_str(&fenced("http", request.trim_end()));
+
+            if let Some(body) = entry
+                .response
+                .body
+                .as_deref()
+                .filter(|b| !b.trim().is_empty())
+            {
+                output.push_str(&format!(
+                    "\nExample response (`{}`):\n\n",
+                    entry.response.status
+                ));
+                match serde_json::from_str::<serde_json::Value>(body)
+                    .ok()
+                    .and_then(|json| serde_json::to_string_pretty(&json).ok())
+                {
+                    Some(pretty) => output.push_str(&fenced("json", &pretty)),
+                    None => output.push_str(&fenced("text", body.trim_end())),
+                }
+            }
+        }
+
+        Ok((output, entries.len()))
+    }
+}
+
+/// Wrap content in a code fence longer than any backtick run inside it
+fn fenced(lang: &str, content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, lang, content, fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::MarkdownFileParser;
+
+    #[test]
+    fn test_generate_round_trip() {
+        let mut login = HistoryEntry::test_entry(
+            "1",
+            "POST",
+            "https://api.example.com/auth/login",
+            Some(r#"{"user":"admin","note":"```"}"#),
+        );
+        login.response.body = Some(r#"{"token":"abc"}"#.to_string());
+        let mut users =
+            HistoryEntry::test_entry("2", "GET", "https://api.example.com/users?page=2", None);
+        users.response.body = Some("plain text".to_string());
+        let entries = vec![
+            login,
+            users,
+            HistoryEntry::test_entry("3", "GET", "https://other.example.com/health", None),
+        ];
+        let (content, count) =
+            MarkdownGenerator::generate(&entries, &GenerateOptions::default()).unwrap();
+        assert_eq!(count, 3);
+
+        assert!(content.contains("## post_auth_login\n"));
+        assert!(
+            content.contains(
+                "Example response (`200`):\n\n```json\n{\n  \"token\": \"abc\"\n}\n```\n"
+            )
+        );
+        assert!(content.contains("```text\nplain text\n```\n"));
+        // A body containing ``` gets a longer fence
+        assert!(content.contains("````http\n"));
+
+        let parsed = MarkdownFileParser::parse_content(&content).unwrap();
+        assert_eq!(parsed.requests.len(), 3);
+
+        let login = &parsed.requests[0];
+        assert_eq!(login.name(), Some("post_auth_login"));
+        assert_eq!(login.method.as_deref(), Some("POST"));
+        assert_eq!(login.url, "{{base_url}}/auth/login");
+        assert_eq!(login.metadata.assertions, vec!["status == 200"]);
+        let body: serde_json::Value = serde_json::from_str(login.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["note"], "```");
+
+        assert_eq!(parsed.requests[1].name(), Some("get_users"));
+        assert_eq!(parsed.requests[1].url, "{{base_url}}/users?page=2");
+        assert_eq!(parsed.requests[1].body, None);
+        assert_eq!(parsed.requests[2].url, "{{base_url_2}}/health");
+    }
+
+    #[test]
+    fn test_fenced() {
+        assert_eq!(fenced("json", "{}"), "```json\n{}\n```\n");
+        assert_eq!(fenced("http", "a ```` b"), "`````http\na ```` b\n`````\n");
+    }
+}
@@ -1 +1,7 @@
+pub mod curl;
+pub mod har;
 pub mod http;
+pub mod httpie;
+pub mod markdown;
+pub mod openapi;
+pub mod postman;
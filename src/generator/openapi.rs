@@ -0,0 +1,401 @@
+//! OpenAPI 3.x to .http generation
+//!
+//! The spec is handled as an untyped JSON tree so that both YAML and JSON
+//! documents are accepted and unknown extensions are ignored. Local `$ref`s
+//! (`#/components/...`) are followed; remote references are rejected.
+
+use crate::{Result, RupostError};
+use serde_json::{Map, Value, json};
+
+/// HTTP methods in the order they are emitted for each path
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Nesting limit when synthesizing examples, guards against recursive schemas
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+pub struct OpenApiGenerator;
+
+impl OpenApiGenerator {
+    /// Parse a spec document, JSON or YAML
+    pub fn parse_spec(content: &str) -> Result<Value> {
+        match serde_json::from_str(content) {
+            Ok(value) => Ok(value),
+            Err(_) => serde_yaml::from_str(content)
+                .map_err(|e| RupostError::ParseError(format!("Invalid OpenAPI document: {}", e))),
+        }
+    }
+
+    /// Convert a spec into .http file content
+    ///
+    /// Returns the content and the number of generated requests.
+    pub fn generate(spec: &Value) -> Result<(String, usize)> {
+        let paths = spec
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| RupostError::ParseError("OpenAPI document has no paths".to_string()))?;
+
+        let mut output = String::new();
+        if let Some(server) = spec.pointer("/servers/0/url").and_then(Value::as_str) {
+            output.push_str(&format!(
+                "# base_url: {}\n# Define base_url in rupost.toml to run these requests\n\n",
+                server
+            ));
+        }
+
+        let mut count = 0;
+        for (path, item) in paths {
+            let item = resolve(spec, item)?;
+            let shared_params = item
+                .get("parameters")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            for method in METHODS {
+                let Some(operation) = item.get(*method) else {
+                    continue;
+                };
+                if count > 0 {
+                    output.push('\n');
+                }
+                output.push_str(&Self::format_operation(
+                    spec,
+                    path,
+                    method,
+                    operation,
+                    shared_params,
+                )?);
+                count += 1;
+            }
+        }
+
+        Ok((output, count))
+    }
+
+    fn format_operation(
+        spec: &Value,
+        path: &str,
+        method: &str,
+        operation: &Value,
+        shared_params: &[Value],
+    ) -> Result<String> {
+        let method = method.to_uppercase();
+        let mut block = String::new();
+
+        // 1. Title and metadata
+        let title = operation
+            .get("summary")
+            .and_then(Value::as_str)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("{} {}", method, path));
+        block.push_str(&format!("### {}\n", title));
+        if let Some(id) = operation.get("operationId").and_then(Value::as_str) {
+            block.push_str(&format!("@name {}\n", id));
+        }
+        if let Some(status) = Self::success_status(operation) {
+            block.push_str(&format!("@assert status == {}\n", status));
+        }
+
+        // 2. Parameters (operation level overrides path level by name + location)
+        let mut params: Vec<&Value> = Vec::new();
+        let operation_params = operation
+            .get("parameters")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for param in operation_params.iter().chain(shared_params) {
+            let param = resolve(spec, param)?;
+            let key = (param.get("name"), param.get("in"));
+            if !params.iter().any(|p| (p.get("name"), p.get("in")) == key) {
+                params.push(param);
+            }
+        }
+
+        // 3. Request line: {id} -> {{id}}, required query params appended
+        let mut url = format!("{{{{base_url}}}}{}", path_template(path));
+        let query: Vec<String> = params
+            .iter()
+            .filter(|p| is_required(p) && location(p) == Some("query"))
+            .filter_map(|p| p.get("name").and_then(Value::as_str))
+            .map(|name| format!("{}={{{{{}}}}}", name, variable_name(name)))
+            .collect();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        block.push_str(&format!("{} {}\n", method, url));
+
+        // 4. Required headers
+        for param in params
+            .iter()
+            .filter(|p| is_required(p) && location(p) == Some("header"))
+        {
+            if let Some(name) = param.get("name").and_then(Value::as_str) {
+                block.push_str(&format!("{}: {{{{{}}}}}\n", name, variable_name(name)));
+            }
+        }
+
+        // 5. JSON body
+        if let Some(body) = Self::example_body(spec, operation)? {
+            block.push_str("Content-Type: application/json\n\n");
+            block.push_str(&serde_json::to_string_pretty(&body)?);
+            block.push('\n');
+        }
+
+        Ok(block)
+    }
+
+    /// The lowest documented 2xx response code
+    fn success_status(operation: &Value) -> Option<u16> {
+        operation
+            .get("responses")?
+            .as_object()?
+            .keys()
+            .filter_map(|code| code.parse::<u16>().ok())
+            .filter(|code| (200..300).contains(code))
+            .min()
+    }
+
+    /// Example JSON body for the operation's request body, if it has a JSON one
+    fn example_body(spec: &Value, operation: &Value) -> Result<Option<Value>> {
+        let Some(request_body) = operation.get("requestBody") else {
+            return Ok(None);
+        };
+        let request_body = resolve(spec, request_body)?;
+        let Some(content) = request_body.get("content").and_then(Value::as_object) else {
+            return Ok(None);
+        };
+
+        let media = content.get("application/json").or_else(|| {
+            content
+                .iter()
+                .find(|(mime, _)| mime.ends_with("+json"))
+                .map(|(_, media)| media)
+        });
+        let Some(media) = media else {
+            return Ok(None);
+        };
+
+        if let Some(example) = media.get("example") {
+            return Ok(Some(example.clone()));
+        }
+        if let Some(example) = media
+            .get("examples")
+            .and_then(Value::as_object)
+            .and_then(|examples| examples.values().next())
+        {
+            let example = resolve(spec, example)?;
+            if let Some(value) = example.get("value") {
+                return Ok(Some(value.clone()));
+            }
+        }
+
+        match media.get("schema") {
+            Some(schema) => Ok(Some(example_from_schema(spec, schema, 0)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Follow a local `$ref` (possibly chained) to its target
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> Result<&'a Value> {
+    let mut current = value;
+    for _ in 0..MAX_SCHEMA_DEPTH {
+        let Some(reference) = current.get("$ref").and_then(Value::as_str) else {
+            return Ok(current);
+        };
+        let pointer = reference.strip_prefix('#').ok_or_else(|| {
+            RupostError::ParseError(format!("Remote $ref is not supported: {}", reference))
+        })?;
+        current = spec
+            .pointer(pointer)
+            .ok_or_else(|| RupostError::ParseError(format!("Unresolved $ref: {}", reference)))?;
+    }
+    Err(RupostError::ParseError(
+        "$ref chain is too deep or circular".to_string(),
+    ))
+}
+
+/// Synthesize an example value from a schema's example/default/type information
+fn example_from_schema(spec: &Value, schema: &Value, depth: usize) -> Result<Value> {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Ok(Value::Null);
+    }
+    let schema = resolve(spec, schema)?;
+
+    if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+        return Ok(example.clone());
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|values| values.first())
+    {
+        return Ok(first.clone());
+    }
+
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            if let Value::Object(fields) = example_from_schema(spec, part, depth + 1)? {
+                merged.extend(fields);
+            }
+        }
+        return Ok(Value::Object(merged));
+    }
+    if let Some(first) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(*key).and_then(Value::as_array)?.first())
+    {
+        return example_from_schema(spec, first, depth + 1);
+    }
+
+    let schema_type = schema.get("type").and_then(Value::as_str).or_else(|| {
+        schema
+            .get("properties")
+            .map(|_| "object")
+            .or_else(|| schema.get("items").map(|_| "array"))
+    });
+
+    let value = match schema_type {
+        Some("object") => {
+            let mut fields = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    fields.insert(
+                        name.clone(),
+                        example_from_schema(spec, property, depth + 1)?,
+                    );
+                }
+            }
+            Value::Object(fields)
+        }
+        Some("array") => match schema.get("items") {
+            Some(items) => json!([example_from_schema(spec, items, depth + 1)?]),
+            None => json!([]),
+        },
+        Some("string") => json!(string_example(schema)),
+        Some("integer") => json!(0),
+        Some("number") => json!(0.0),
+        Some("boolean") => json!(false),
+        _ => Value::Null,
+    };
+    Ok(value)
+}
+
+fn string_example(schema: &Value) -> &'static str {
+    match schema.get("format").and_then(Value::as_str) {
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("email") => "user@example.com",
+        Some("uuid") => "00000000-0000-0000-0000-000000000000",
+        Some("uri") | Some("url") => "https://example.com",
+        _ => "string",
+    }
+}
+
+/// Turn `/pets/{petId}` into `/pets/{{petId}}`
+fn path_template(path: &str) -> String {
+    let mut output = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&format!(
+            "{{{{{}}}}}",
+            variable_name(&rest[start + 1..start + len])
+        ));
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Map a parameter name onto a valid variable name (`X-API-Key` -> `X_API_Key`)
+fn variable_name(name: &str) -> String {
+    let mut variable: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if variable.starts_with(|c: char| c.is_ascii_digit()) {
+        variable.insert(0, '_');
+    }
+    variable
+}
+
+fn is_required(param: &Value) -> bool {
+    param
+        .get("required")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn location(param: &Value) -> Option<&str> {
+    param.get("in").and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_from_schema() {
+        let spec = json!({
+            "components": {
+                "schemas": {
+                    "Tag": {"type": "object", "properties": {"label": {"type": "string"}}},
+                    "Node": {
+                        "type": "object",
+                        "properties": {"child": {"$ref": "#/components/schemas/Node"}}
+                    }
+                }
+            }
+        });
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer"},
+                "email": {"type": "string", "format": "email"},
+                "role": {"type": "string", "enum": ["admin", "user"]},
+                "active": {"type": "boolean", "default": true},
+                "tags": {"type": "array", "items": {"$ref": "#/components/schemas/Tag"}}
+            }
+        });
+
+        assert_eq!(
+            example_from_schema(&spec, &schema, 0).unwrap(),
+            json!({
+                "id": 0,
+                "email": "user@example.com",
+                "role": "admin",
+                "active": true,
+                "tags": [{"label": "string"}]
+            })
+        );
+
+        // Recursive schemas stop at the depth limit
+        let node = json!({"$ref": "#/components/schemas/Node"});
+        assert!(example_from_schema(&spec, &node, 0).is_ok());
+    }
+
+    #[test]
+    fn test_path_template() {
+        assert_eq!(path_template("/pets/{petId}"), "/pets/{{petId}}");
+        assert_eq!(
+            path_template("/orgs/{org-id}/users/{user.id}/avatar"),
+            "/orgs/{{org_id}}/users/{{user_id}}/avatar"
+        );
+        assert_eq!(path_template("/health"), "/health");
+    }
+
+    #[test]
+    fn test_resolve_rejects_remote_ref() {
+        let spec = json!({});
+        let remote = json!({"$ref": "other.yaml#/components/schemas/User"});
+        assert!(resolve(&spec, &remote).is_err());
+        let missing = json!({"$ref": "#/components/schemas/Missing"});
+        assert!(resolve(&spec, &missing).is_err());
+    }
+}
@@ -0,0 +1,326 @@
+//! Postman Collection v2.1 export
+//!
+//! Parsed `.http` / `.md` requests map onto collection items one-to-one.
+//! `{{variable}}` references are kept verbatim since Postman uses the same
+//! syntax, and rupost.toml environments become Postman environment files.
+
+use crate::assertion::{AssertExpr, AssertValue, CompareOp, ValuePath, parse_assertion};
+use crate::parser::{ParsedFile, ParsedRequest};
+use crate::variable::VariableConfig;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const COLLECTION_SCHEMA: &str =
+    "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanCollection {
+    pub info: PostmanInfo,
+    #[serde(default)]
+    pub item: Vec<PostmanItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanInfo {
+    #[serde(rename = "_postman_id")]
+    pub id: String,
+    pub name: String,
+    pub schema: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanItem {
+    pub name: String,
+    pub request: PostmanRequest,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event: Vec<PostmanEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanRequest {
+    pub method: String,
+    #[serde(default)]
+    pub header: Vec<PostmanHeader>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<PostmanBody>,
+    pub url: PostmanUrl,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostmanHeader {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanBody {
+    pub mode: String,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanUrl {
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanEvent {
+    pub listen: String,
+    pub script: PostmanScript,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanScript {
+    #[serde(rename = "type")]
+    pub script_type: String,
+    pub exec: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostmanEnvironment {
+    pub id: String,
+    pub name: String,
+    pub values: Vec<PostmanEnvValue>,
+    #[serde(rename = "_postman_variable_scope")]
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostmanEnvValue {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+}
+
+pub struct PostmanGenerator;
+
+impl PostmanGenerator {
+    /// Build a collection with one item per parsed request
+    pub fn collection(name: &str, parsed: &ParsedFile) -> PostmanCollection {
+        PostmanCollection {
+            info: PostmanInfo {
+                id: Uuid::new_v4().to_string(),
+                name: name.to_string(),
+                schema: COLLECTION_SCHEMA.to_string(),
+            },
+            item: parsed
+                .requests
+                .iter()
+                .enumerate()
+                .map(|(index, request)| Self::item(index + 1, request))
+                .collect(),
+        }
+    }
+
+    /// Build one Postman environment per rupost.toml environment (sorted by name)
+    ///
+    /// Values are copied as written, so `${VAR}` references are not expanded
+    /// and secrets from the shell environment never end up in the export.
+    pub fn environments(config: &VariableConfig) -> Vec<PostmanEnvironment> {
+        let mut names: Vec<&String> = config.environments.keys().collect();
+        names.sort();
+
+        names
+            .into_iter()
+            .map(|name| {
+                let mut values: Vec<PostmanEnvValue> = config.environments[name]
+                    .variables
+                    .iter()
+                    .map(|(key, value)| PostmanEnvValue {
+                        key: key.clone(),
+                        value: value.clone(),
+                        enabled: true,
+                    })
+                    .collect();
+                values.sort_by(|a, b| a.key.cmp(&b.key));
+
+                PostmanEnvironment {
+                    id: Uuid::new_v4().to_string(),
+                    name: name.clone(),
+                    values,
+                    scope: "environment".to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn item(request_number: usize, request: &ParsedRequest) -> PostmanItem {
+        let name = request
+            .name()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("Request #{}", request_number));
+
+        let exec: Vec<String> = request
+            .metadata
+            .assertions
+            .iter()
+            .filter_map(|assertion| Self::status_test(assertion))
+            .collect();
+        let event = if exec.is_empty() {
+            Vec::new()
+        } else {
+            vec![PostmanEvent {
+                listen: "test".to_string(),
+                script: PostmanScript {
+                    script_type: "text/javascript".to_string(),
+                    exec,
+                },
+            }]
+        };
+
+        PostmanItem {
+            name,
+            request: PostmanRequest {
+                method: request.method_or_default().to_string(),
+                header: request
+                    .headers
+                    .iter()
+                    .map(|(key, value)| PostmanHeader {
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+                    .collect(),
+                body: request.body.as_ref().map(|body| PostmanBody {
+                    mode: "raw".to_string(),
+                    raw: body.clone(),
+                }),
+                url: PostmanUrl {
+                    raw: request.url_with_query(),
+                },
+            },
+            event,
+        }
+    }
+
+    /// Convert a `status <op> <number>` assertion into a pm.test line
+    ///
+    /// Other assertion kinds are not translated.
+    fn status_test(assertion: &str) -> Option<String> {
+        let AssertExpr::Compare {
+            left: ValuePath::Status,
+            op,
+            right: AssertValue::Number(expected),
+            tolerance: None,
+        } = parse_assertion(assertion).ok()?
+        else {
+            return None;
+        };
+
+        let expected = expected as u16;
+        let check = match op {
+            CompareOp::Equal => format!("pm.response.to.have.status({})", expected),
+            CompareOp::NotEqual => format!("pm.expect(pm.response.code).to.not.eql({})", expected),
+            CompareOp::Greater => format!("pm.expect(pm.response.code).to.be.above({})", expected),
+            CompareOp::Less => format!("pm.expect(pm.response.code).to.be.below({})", expected),
+            CompareOp::GreaterOrEqual => {
+                format!("pm.expect(pm.response.code).to.be.at.least({})", expected)
+            }
+            CompareOp::LessOrEqual => {
+                format!("pm.expect(pm.response.code).to.be.at.most({})", expected)
+            }
+            CompareOp::Contains => return None,
+        };
+
+        Some(format!(
+            "pm.test(\"{}\", function () {{ {}; }});",
+            assertion.replace('"', "\\\""),
+            check
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HttpFileParser;
+    use crate::variable::Environment;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_collection_round_trip() {
+        let content = r#"
+### Create User
+@name createUser
+@assert status == 201
+@assert body.id exists
+POST {{base_url}}/users
+Content-Type: application/json
+Authorization: Bearer {{token}}
+
+{"name": "alice"}
+
+###
+GET {{base_url}}/health
+"#;
+        let parsed = HttpFileParser::parse_content(content).unwrap();
+        let collection = PostmanGenerator::collection("api", &parsed);
+
+        let json = serde_json::to_string(&collection).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["info"]["schema"], COLLECTION_SCHEMA);
+
+        let restored: PostmanCollection = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.item.len(), parsed.requests.len());
+
+        for (item, request) in restored.item.iter().zip(&parsed.requests) {
+            assert_eq!(item.request.method, request.method_or_default());
+            assert_eq!(item.request.url.raw, request.url);
+            assert_eq!(item.request.header.len(), request.headers.len());
+            assert_eq!(
+                item.request.body.as_ref().map(|b| b.raw.as_str()),
+                request.body.as_deref()
+            );
+        }
+
+        let create = &restored.item[0];
+        assert_eq!(create.name, "createUser");
+        assert_eq!(create.request.header[1].value, "Bearer {{token}}");
+        // Only the status assertion is translated
+        assert_eq!(create.event.len(), 1);
+        assert_eq!(create.event[0].script.exec.len(), 1);
+        assert!(create.event[0].script.exec[0].contains("pm.response.to.have.status(201)"));
+
+        let health = &restored.item[1];
+        assert_eq!(health.name, "Request #2");
+        assert!(health.event.is_empty());
+        assert!(value["item"][1]["request"].get("body").is_none());
+    }
+
+    #[test]
+    fn test_status_test_operators() {
+        assert_eq!(
+            PostmanGenerator::status_test("status < 500").unwrap(),
+            "pm.test(\"status < 500\", function () { pm.expect(pm.response.code).to.be.below(500); });"
+        );
+        assert!(PostmanGenerator::status_test("body.id exists").is_none());
+        assert!(PostmanGenerator::status_test("status ==").is_none());
+    }
+
+    #[test]
+    fn test_environments() {
+        let mut config = VariableConfig::default();
+        let mut variables = HashMap::new();
+        variables.insert("base_url".to_string(), "http://localhost".to_string());
+        variables.insert("api_key".to_string(), "${DEV_API_KEY}".to_string());
+        config.environments.insert(
+            "dev".to_string(),
+            Environment {
+                variables,
+                ..Default::default()
+            },
+        );
+        config
+            .environments
+            .insert("prod".to_string(), Environment::default());
+
+        let environments = PostmanGenerator::environments(&config);
+        assert_eq!(environments.len(), 2);
+        assert_eq!(environments[0].name, "dev");
+        assert_eq!(environments[0].values[0].key, "api_key");
+        // ${VAR} references are kept as written
+        assert_eq!(environments[0].values[0].value, "${DEV_API_KEY}");
+        assert_eq!(environments[0].values[1].key, "base_url");
+        assert_eq!(environments[1].name, "prod");
+        assert!(environments[1].values.is_empty());
+    }
+}
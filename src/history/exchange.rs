@@ -0,0 +1,117 @@
+use crate::Result;
+use crate::history::serialization::har::Har;
+use crate::history::storage::HistoryStorage;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Supported history interchange formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExchangeFormat {
+    Har,
+}
+
+impl ExchangeFormat {
+    /// Infer the format from a file extension (e.g. `session.har`)
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path
+            .as_ref()
+            .extension()?
+            .to_str()?
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "har" => Some(Self::Har),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize all history entries in the given format
+pub fn export_history(storage: &HistoryStorage, format: ExchangeFormat) -> Result<(String, usize)> {
+    let entries = storage.list()?;
+    let content = match format {
+        ExchangeFormat::Har => serde_json::to_string_pretty(&Har::from_entries(&entries))?,
+    };
+    Ok((content, entries.len()))
+}
+
+/// Import entries from a HAR file, skipping entries already present in history
+///
+/// Returns `(imported, skipped)` counts.
+pub fn import_har<P: AsRef<Path>>(storage: &HistoryStorage, path: P) -> Result<(usize, usize)> {
+    let content = fs::read_to_string(path)?;
+    let har: Har = serde_json::from_str(&content)?;
+
+    let mut seen: HashSet<u64> = storage.list()?.iter().map(|e| e.content_hash()).collect();
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for entry in har.into_entries() {
+        if seen.insert(entry.content_hash()) {
+            storage.append(&entry)?;
+            imported += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    Ok((imported, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::model::HistoryEntry;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_then_import_deduplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = HistoryStorage::new_with_path(temp_dir.path().join("source.jsonl"));
+        source
+            .append(&HistoryEntry::test_entry(
+                "1",
+                "GET",
+                "https://a.com/1",
+                None,
+            ))
+            .unwrap();
+        source
+            .append(&HistoryEntry::test_entry(
+                "2",
+                "GET",
+                "https://a.com/2",
+                None,
+            ))
+            .unwrap();
+
+        let (har, count) = export_history(&source, ExchangeFormat::Har).unwrap();
+        assert_eq!(count, 2);
+        let har_path = temp_dir.path().join("session.har");
+        fs::write(&har_path, har).unwrap();
+
+        let target = HistoryStorage::new_with_path(temp_dir.path().join("target.jsonl"));
+        assert_eq!(import_har(&target, &har_path).unwrap(), (2, 0));
+        // Importing the same archive again must not duplicate entries
+        assert_eq!(import_har(&target, &har_path).unwrap(), (0, 2));
+
+        let original = source.list().unwrap();
+        let imported = target.list().unwrap();
+        assert_eq!(imported.len(), 2);
+        for (a, b) in original.iter().zip(&imported) {
+            assert_ne!(a.id, b.id);
+            assert_eq!(a.content_hash(), b.content_hash());
+        }
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ExchangeFormat::from_extension("session.HAR"),
+            Some(ExchangeFormat::Har)
+        );
+        assert_eq!(ExchangeFormat::from_extension("session.json"), None);
+        assert_eq!(ExchangeFormat::from_extension("session"), None);
+    }
+}
@@ -1,6 +1,8 @@
+pub mod exchange;
 pub mod model;
 pub mod printer;
 pub mod recorder;
 pub mod selector;
 pub mod serialization;
+pub mod stats;
 pub mod storage;
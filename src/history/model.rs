@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 use crate::history::serialization;
 
@@ -19,13 +21,60 @@ pub struct HistoryEntry {
     /// 请求快照
     pub request: RequestSnapshot,
 
-    /// 请求来源 (例如 "cli", "file:test.http")
+    /// 请求来源 (例如 "cli", "file:tests/auth.http#login")
+    #[serde(default)]
     pub source: Option<String>,
 
+    /// 请求的 @name，来自请求文件时记录，生成 .http 文件时沿用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     /// 响应元数据
     pub response: ResponseMeta,
 }
 
+impl HistoryEntry {
+    /// 基于请求内容计算的哈希值（不含 ID），用于导入时去重
+    ///
+    /// 时间戳按毫秒精度参与计算，与 HAR 等外部格式的精度保持一致。
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.timestamp.timestamp_millis().hash(&mut hasher);
+        self.request.method.to_uppercase().hash(&mut hasher);
+        self.request.url.hash(&mut hasher);
+        self.request.body.hash(&mut hasher);
+        self.response.status.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+impl HistoryEntry {
+    /// 测试用条目：无 header，响应 200，时间固定为 2024-05-29T16:26:40Z，其余字段按需修改
+    pub(crate) fn test_entry(id: &str, method: &str, url: &str, body: Option<&str>) -> Self {
+        use chrono::TimeZone;
+
+        HistoryEntry {
+            id: id.to_string(),
+            timestamp: Utc.timestamp_opt(1_717_000_000, 0).unwrap(),
+            duration_ms: 10,
+            request: RequestSnapshot {
+                method: method.to_string(),
+                url: url.to_string(),
+                headers: HeaderMap::new(),
+                body: body.map(|b| b.to_string()),
+            },
+            source: None,
+            name: None,
+            response: ResponseMeta {
+                status: 200,
+                headers: HeaderMap::new(),
+                body: None,
+            },
+        }
+    }
+}
+
 /// 请求快照 (用于测试生成)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestSnapshot {
@@ -38,11 +87,15 @@ pub struct RequestSnapshot {
     pub body: Option<String>,
 }
 
-/// 响应元数据 (不包含 Body，节省空间)
+/// 响应元数据 (测试运行不记录 Body，节省空间)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseMeta {
     pub status: u16,
 
     #[serde(with = "serialization::header_map")]
     pub headers: HeaderMap,
+
+    /// 响应体，仅当来源提供时存在（如 HAR 导入）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
 }
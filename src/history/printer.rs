@@ -1,5 +1,7 @@
+use super::stats::{HistoryStats, parse_since};
 use super::storage::get_storage;
 use crate::Result;
+use chrono::Utc;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, Color, Table};
 
@@ -12,9 +14,9 @@ pub fn list_history(limit: usize, reverse: bool) -> Result<()> {
     }
 
     let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec!["ID", "Time", "Method", "URL", "Status", "Duration"]);
+    table.load_preset(UTF8_FULL).set_header(vec![
+        "ID", "Time", "Method", "URL", "Status", "Duration", "Source",
+    ]);
 
     for entry in entries {
         let status_color = if entry.response.status < 400 {
@@ -30,6 +32,7 @@ pub fn list_history(limit: usize, reverse: bool) -> Result<()> {
             Cell::new(&entry.request.url).add_attribute(Attribute::Dim),
             Cell::new(entry.response.status).fg(status_color),
             Cell::new(format!("{}ms", entry.duration_ms)),
+            Cell::new(entry.source.as_deref().unwrap_or("-")).add_attribute(Attribute::Dim),
         ]);
     }
 
@@ -37,3 +40,73 @@ pub fn list_history(limit: usize, reverse: bool) -> Result<()> {
 
     Ok(())
 }
+
+pub fn print_stats(since: Option<&str>, json: bool) -> Result<()> {
+    let storage = get_storage();
+    let mut entries = storage.list()?;
+
+    if let Some(since) = since {
+        let cutoff = parse_since(since, Utc::now())?;
+        entries.retain(|e| e.timestamp >= cutoff);
+    }
+
+    let stats = HistoryStats::from_entries(&entries);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!("Total entries: {}\n", stats.total);
+
+    let mut methods = Table::new();
+    methods
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["Method", "Count"]);
+    for (method, count) in &stats.by_method {
+        methods.add_row(vec![Cell::new(method), Cell::new(count)]);
+    }
+    println!("{}", methods);
+
+    let mut statuses = Table::new();
+    statuses
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["Status", "Count"]);
+    for (class, count) in &stats.by_status_class {
+        let color = match class.as_str() {
+            "2xx" => Color::Green,
+            "3xx" => Color::Cyan,
+            "4xx" => Color::Yellow,
+            _ => Color::Red,
+        };
+        statuses.add_row(vec![Cell::new(class).fg(color), Cell::new(count)]);
+    }
+    println!("{}", statuses);
+
+    let mut hosts = Table::new();
+    hosts
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["Host", "Count"]);
+    for host in &stats.top_hosts {
+        hosts.add_row(vec![Cell::new(&host.host), Cell::new(host.count)]);
+    }
+    println!("{}", hosts);
+
+    if let Some(d) = &stats.duration_ms {
+        let mut durations = Table::new();
+        durations
+            .load_preset(UTF8_FULL)
+            .set_header(vec!["Avg", "Min", "P50", "P90", "P99", "Max"]);
+        durations.add_row(vec![
+            Cell::new(format!("{:.1}ms", d.avg)),
+            Cell::new(format!("{}ms", d.min)),
+            Cell::new(format!("{}ms", d.p50)),
+            Cell::new(format!("{}ms", d.p90)),
+            Cell::new(format!("{}ms", d.p99)),
+            Cell::new(format!("{}ms", d.max)),
+        ]);
+        println!("{}", durations);
+    }
+
+    Ok(())
+}
@@ -8,7 +8,12 @@ use uuid::Uuid;
 /// 记录请求历史
 ///
 /// 这是一个 Best-effort 操作，如果写入失败会打印警告，但不会返回错误。
-pub fn record_history(request: RequestSnapshot, response: &Response, source: Option<String>) {
+pub fn record_history(
+    request: RequestSnapshot,
+    response: &Response,
+    source: Option<String>,
+    name: Option<String>,
+) {
     let history_entry = HistoryEntry {
         id: Uuid::new_v4().to_string(),
         timestamp: Utc::now(),
@@ -16,9 +21,11 @@ pub fn record_history(request: RequestSnapshot, response: &Response, source: Opt
         duration_ms: response.duration.as_millis() as u64,
         request,
         source,
+        name,
         response: ResponseMeta {
             status: response.status.code(),
             headers: response.headers.clone(),
+            body: None,
         },
     };
 
@@ -3,11 +3,16 @@ use crate::Result;
 use crate::RupostError;
 use crate::history::model::HistoryEntry;
 use crate::history::storage::HistoryStorage;
-use inquire::MultiSelect;
+use inquire::{InquireError, MultiSelect};
+
+/// Default number of candidates offered in interactive mode
+pub const DEFAULT_INTERACTIVE_CANDIDATES: usize = 50;
 
 /// Selection strategy for history entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SelectionStrategy {
-    Interactive,
+    /// Pick from the N most recent entries in a TUI prompt
+    Interactive(usize),
     Last(usize),
 }
 
@@ -17,15 +22,17 @@ pub fn select_entries(
     strategy: SelectionStrategy,
 ) -> Result<Vec<HistoryEntry>> {
     match strategy {
-        SelectionStrategy::Interactive => select_interactive(storage),
+        SelectionStrategy::Interactive(candidates) => select_interactive(storage, candidates),
         SelectionStrategy::Last(n) => storage.tail(n),
     }
 }
 
 /// Interactively select history entries using a TUI
-fn select_interactive(storage: &HistoryStorage) -> Result<Vec<HistoryEntry>> {
+///
+/// A cancelled prompt (Esc / Ctrl-C) is treated as an empty selection.
+fn select_interactive(storage: &HistoryStorage, candidates: usize) -> Result<Vec<HistoryEntry>> {
     // 1. Fetch recent history
-    let mut entries = storage.tail(50)?;
+    let mut entries = storage.tail(candidates)?;
     entries.reverse();
 
     if entries.is_empty() {
@@ -57,11 +64,17 @@ fn select_interactive(storage: &HistoryStorage) -> Result<Vec<HistoryEntry>> {
         })
         .collect();
 
-    let selected_wrappers = MultiSelect::new("Select requests to generate:", wrapped_options)
+    let selected_wrappers = match MultiSelect::new("Select requests to generate:", wrapped_options)
         .with_page_size(15)
         .with_help_message("Space to select, Enter to finish, type to filter")
         .prompt()
-        .map_err(|e| RupostError::Other(format!("Interaction canceled or failed: {}", e)))?;
+    {
+        Ok(selected) => selected,
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(RupostError::Other(format!("Interaction failed: {}", e))),
+    };
 
     // 4. Map back to entries
     let selected_entries: Vec<HistoryEntry> = selected_wrappers
@@ -83,32 +96,11 @@ fn format_entry_for_display(e: &HistoryEntry) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::history::model::{RequestSnapshot, ResponseMeta};
-    use reqwest::header::HeaderMap;
     use tempfile::TempDir;
 
-    fn create_dummy_entry(id: &str, url: &str) -> HistoryEntry {
-        HistoryEntry {
-            id: id.to_string(),
-            timestamp: chrono::Utc::now(),
-            duration_ms: 100,
-            request: RequestSnapshot {
-                method: "GET".to_string(),
-                url: url.to_string(),
-                headers: HeaderMap::new(),
-                body: None,
-            },
-            source: None,
-            response: ResponseMeta {
-                status: 200,
-                headers: HeaderMap::new(),
-            },
-        }
-    }
-
     #[test]
     fn test_format_display() {
-        let entry = create_dummy_entry("1", "https://example.com");
+        let entry = HistoryEntry::test_entry("1", "GET", "https://example.com", None);
         let display = format_entry_for_display(&entry);
         // "[200] GET https://example.com (2024-...)"
         assert!(display.contains("[200] GET https://example.com"));
@@ -120,13 +112,30 @@ mod tests {
         let file_path = temp_dir.path().join("history.jsonl");
         let storage = HistoryStorage::new_with_path(file_path);
 
-        storage.append(&create_dummy_entry("1", "u1")).unwrap();
-        storage.append(&create_dummy_entry("2", "u2")).unwrap();
-        storage.append(&create_dummy_entry("3", "u3")).unwrap();
+        storage
+            .append(&HistoryEntry::test_entry("1", "GET", "u1", None))
+            .unwrap();
+        storage
+            .append(&HistoryEntry::test_entry("2", "GET", "u2", None))
+            .unwrap();
+        storage
+            .append(&HistoryEntry::test_entry("3", "GET", "u3", None))
+            .unwrap();
 
         let selected = select_entries(&storage, SelectionStrategy::Last(2)).unwrap();
         assert_eq!(selected.len(), 2);
         assert_eq!(selected[0].id, "2");
         assert_eq!(selected[1].id, "3");
     }
+
+    #[test]
+    fn test_select_interactive_empty_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("history.jsonl");
+        let storage = HistoryStorage::new_with_path(file_path);
+
+        // No candidates: returns before prompting
+        let selected = select_entries(&storage, SelectionStrategy::Interactive(10)).unwrap();
+        assert!(selected.is_empty());
+    }
 }
@@ -0,0 +1,384 @@
+//! HAR 1.2 (HTTP Archive) support
+//!
+//! Only the subset of the format needed to round-trip `HistoryEntry`
+//! records is modelled. Unknown fields are ignored on import so that
+//! archives produced by browsers and proxies can be read directly.
+
+use crate::history::model::{HistoryEntry, RequestSnapshot, ResponseMeta};
+use crate::http::types::Status;
+use chrono::{DateTime, Utc};
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const HAR_VERSION: &str = "1.2";
+const HTTP_VERSION: &str = "HTTP/1.1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: DateTime<Utc>,
+    /// Total elapsed time in milliseconds
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: serde_json::Value,
+    #[serde(default)]
+    pub timings: HarTimings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub content: HarContent,
+    #[serde(default, rename = "redirectURL")]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+impl Default for HarTimings {
+    fn default() -> Self {
+        Self {
+            send: 0.0,
+            wait: -1.0,
+            receive: 0.0,
+        }
+    }
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+impl Har {
+    /// Build an archive from history entries
+    pub fn from_entries(entries: &[HistoryEntry]) -> Self {
+        Self {
+            log: HarLog {
+                version: HAR_VERSION.to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: entries.iter().map(HarEntry::from).collect(),
+            },
+        }
+    }
+
+    /// Convert every archived entry into a fresh `HistoryEntry` (new ids)
+    pub fn into_entries(self) -> Vec<HistoryEntry> {
+        self.log
+            .entries
+            .into_iter()
+            .map(HistoryEntry::from)
+            .collect()
+    }
+}
+
+impl From<&HistoryEntry> for HarEntry {
+    fn from(entry: &HistoryEntry) -> Self {
+        let query_string = url::Url::parse(&entry.request.url)
+            .map(|u| {
+                u.query_pairs()
+                    .map(|(name, value)| HarNameValue {
+                        name: name.into_owned(),
+                        value: value.into_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let post_data = entry.request.body.as_ref().map(|body| HarPostData {
+            mime_type: content_type_of(&entry.request.headers),
+            text: body.clone(),
+        });
+
+        let status_text = Status::new(entry.response.status)
+            .map(|s| s.reason_phrase().to_string())
+            .unwrap_or_default();
+
+        HarEntry {
+            started_date_time: entry.timestamp,
+            time: entry.duration_ms as f64,
+            request: HarRequest {
+                method: entry.request.method.clone(),
+                url: entry.request.url.clone(),
+                http_version: HTTP_VERSION.to_string(),
+                cookies: Vec::new(),
+                headers: to_name_values(&entry.request.headers),
+                query_string,
+                body_size: post_data.as_ref().map_or(0, |p| p.text.len() as i64),
+                post_data,
+                headers_size: -1,
+            },
+            response: HarResponse {
+                status: entry.response.status,
+                status_text,
+                http_version: HTTP_VERSION.to_string(),
+                cookies: Vec::new(),
+                headers: to_name_values(&entry.response.headers),
+                content: HarContent {
+                    size: entry.response.body.as_ref().map_or(0, |b| b.len() as i64),
+                    mime_type: content_type_of(&entry.response.headers),
+                    text: entry.response.body.clone(),
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: -1,
+            },
+            cache: serde_json::json!({}),
+            timings: HarTimings {
+                wait: entry.duration_ms as f64,
+                ..HarTimings::default()
+            },
+        }
+    }
+}
+
+impl From<HarEntry> for HistoryEntry {
+    fn from(entry: HarEntry) -> Self {
+        let body = entry
+            .request
+            .post_data
+            .map(|p| p.text)
+            .filter(|text| !text.is_empty());
+
+        HistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: entry.started_date_time,
+            duration_ms: entry.time.max(0.0).round() as u64,
+            request: RequestSnapshot {
+                method: entry.request.method.to_uppercase(),
+                url: entry.request.url,
+                headers: from_name_values(&entry.request.headers),
+                body,
+            },
+            source: Some("har".to_string()),
+            name: None,
+            response: ResponseMeta {
+                status: entry.response.status,
+                headers: from_name_values(&entry.response.headers),
+                body: entry.response.content.text.filter(|text| !text.is_empty()),
+            },
+        }
+    }
+}
+
+fn content_type_of(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn to_name_values(headers: &HeaderMap) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .map(|(k, v)| HarNameValue {
+            name: k.as_str().to_string(),
+            value: v.to_str().unwrap_or("").to_string(),
+        })
+        .collect()
+}
+
+/// HTTP/2 pseudo-headers (`:authority` etc.) are not valid header names and are dropped
+fn from_name_values(pairs: &[HarNameValue]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for pair in pairs {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(pair.name.as_bytes()),
+            HeaderValue::from_str(&pair.value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut entries = vec![
+            HistoryEntry::test_entry("1", "GET", "https://example.com/users?page=2", None),
+            HistoryEntry::test_entry("2", "POST", "https://example.com/users", Some(r#"{"a":1}"#)),
+        ];
+        for entry in &mut entries {
+            entry.duration_ms = 42;
+            entry.source = Some("cli".to_string());
+            entry
+                .request
+                .headers
+                .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            entry
+                .response
+                .headers
+                .insert("x-request-id", HeaderValue::from_static("abc"));
+        }
+        entries[1].response.status = 201;
+        entries[1].response.body = Some(r#"{"id":7}"#.to_string());
+
+        let har = Har::from_entries(&entries);
+        let json = serde_json::to_string(&har).unwrap();
+        let imported = serde_json::from_str::<Har>(&json).unwrap().into_entries();
+
+        assert_eq!(imported.len(), entries.len());
+        for (original, imported) in entries.iter().zip(&imported) {
+            assert_ne!(original.id, imported.id);
+            assert_eq!(original.timestamp, imported.timestamp);
+            assert_eq!(original.duration_ms, imported.duration_ms);
+            assert_eq!(original.request.method, imported.request.method);
+            assert_eq!(original.request.url, imported.request.url);
+            assert_eq!(original.request.headers, imported.request.headers);
+            assert_eq!(original.request.body, imported.request.body);
+            assert_eq!(original.response.status, imported.response.status);
+            assert_eq!(original.response.headers, imported.response.headers);
+            assert_eq!(original.response.body, imported.response.body);
+        }
+    }
+
+    #[test]
+    fn test_export_fields() {
+        let mut entry =
+            HistoryEntry::test_entry("1", "GET", "https://example.com/search?q=rust", None);
+        entry.response.status = 404;
+        let entries = vec![entry];
+        let har = Har::from_entries(&entries);
+        let value = serde_json::to_value(&har).unwrap();
+
+        assert_eq!(value["log"]["version"], "1.2");
+        let entry = &value["log"]["entries"][0];
+        assert_eq!(entry["request"]["queryString"][0]["name"], "q");
+        assert_eq!(entry["request"]["queryString"][0]["value"], "rust");
+        assert_eq!(entry["response"]["status"], 404);
+        assert_eq!(entry["response"]["statusText"], "Not Found");
+        assert!(entry["request"].get("postData").is_none());
+    }
+
+    #[test]
+    fn test_import_browser_har() {
+        // Trimmed-down archive in the shape produced by browser devtools
+        let json = r#"{
+          "log": {
+            "version": "1.2",
+            "creator": {"name": "WebInspector", "version": "537.36"},
+            "pages": [],
+            "entries": [{
+              "startedDateTime": "2024-05-01T10:00:00.123Z",
+              "time": 12.6,
+              "request": {
+                "method": "post",
+                "url": "https://api.example.com/login",
+                "headers": [
+                  {"name": ":authority", "value": "api.example.com"},
+                  {"name": "content-type", "value": "application/json"}
+                ],
+                "postData": {"mimeType": "application/json", "text": "{\"u\":1}"}
+              },
+              "response": {"status": 200, "headers": [], "content": {"size": 2, "mimeType": "application/json"}},
+              "_priority": "High"
+            }]
+          }
+        }"#;
+
+        let entries = serde_json::from_str::<Har>(json).unwrap().into_entries();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.request.method, "POST");
+        assert_eq!(entry.duration_ms, 13);
+        assert_eq!(entry.request.headers.len(), 1);
+        assert_eq!(entry.request.body.as_deref(), Some(r#"{"u":1}"#));
+        assert_eq!(entry.timestamp.timestamp_millis(), 1714557600123);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::Result;
+use crate::RupostError;
+use crate::history::model::HistoryEntry;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of hosts reported in `top_hosts`
+const TOP_HOSTS: usize = 10;
+
+/// Aggregated statistics over history entries
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistoryStats {
+    /// Total number of entries in the window
+    pub total: usize,
+
+    /// Entry count per HTTP method
+    pub by_method: BTreeMap<String, usize>,
+
+    /// Entry count per status class ("2xx", "4xx", ...)
+    pub by_status_class: BTreeMap<String, usize>,
+
+    /// Most requested hosts, busiest first
+    pub top_hosts: Vec<HostCount>,
+
+    /// Duration distribution (None when there are no entries)
+    pub duration_ms: Option<DurationStats>,
+}
+
+/// Request count for a single host
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostCount {
+    pub host: String,
+    pub count: usize,
+}
+
+/// Duration distribution in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationStats {
+    pub avg: f64,
+    pub min: u64,
+    pub max: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl HistoryStats {
+    /// Aggregate the given entries
+    pub fn from_entries(entries: &[HistoryEntry]) -> Self {
+        let mut by_method = BTreeMap::new();
+        let mut by_status_class = BTreeMap::new();
+        let mut hosts: HashMap<String, usize> = HashMap::new();
+
+        for entry in entries {
+            *by_method
+                .entry(entry.request.method.to_uppercase())
+                .or_insert(0) += 1;
+            *by_status_class
+                .entry(status_class(entry.response.status))
+                .or_insert(0) += 1;
+            *hosts.entry(host_of(&entry.request.url)).or_insert(0) += 1;
+        }
+
+        let mut top_hosts: Vec<HostCount> = hosts
+            .into_iter()
+            .map(|(host, count)| HostCount { host, count })
+            .collect();
+        // Busiest first, ties broken alphabetically for stable output
+        top_hosts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.host.cmp(&b.host)));
+        top_hosts.truncate(TOP_HOSTS);
+
+        let mut durations: Vec<u64> = entries.iter().map(|e| e.duration_ms).collect();
+        durations.sort_unstable();
+
+        Self {
+            total: entries.len(),
+            by_method,
+            by_status_class,
+            top_hosts,
+            duration_ms: DurationStats::from_sorted(&durations),
+        }
+    }
+}
+
+impl DurationStats {
+    fn from_sorted(sorted: &[u64]) -> Option<Self> {
+        let (min, max) = (*sorted.first()?, *sorted.last()?);
+        let sum: u64 = sorted.iter().sum();
+
+        Some(Self {
+            avg: sum as f64 / sorted.len() as f64,
+            min,
+            max,
+            p50: percentile(sorted, 50.0),
+            p90: percentile(sorted, 90.0),
+            p99: percentile(sorted, 99.0),
+        })
+    }
+}
+
+/// Nearest-rank percentile over an ascending, non-empty slice
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Map a status code to its class label, e.g. 404 -> "4xx"
+fn status_class(status: u16) -> String {
+    format!("{}xx", status / 100)
+}
+
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_else(|| "<unknown>".to_string())
+}
+
+/// Parse a `--since` value into an absolute cutoff
+///
+/// Accepts a relative window (`30m`, `24h`, `7d`, `90s`), an RFC 3339
+/// timestamp, or a plain date (`2024-01-31`, interpreted as UTC midnight).
+pub fn parse_since(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+
+    if let Ok(ts) = DateTime::parse_from_rfc3339(input) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let invalid = || {
+        RupostError::ParseError(format!(
+            "Invalid --since value '{}': expected e.g. 30m, 24h, 7d or a date",
+            input
+        ))
+    };
+
+    let unit_start = input
+        .char_indices()
+        .last()
+        .map(|(i, _)| i)
+        .ok_or_else(invalid)?;
+    let (amount, unit) = input.split_at(unit_start);
+    let amount: i64 = amount
+        .parse()
+        .ok()
+        .filter(|amount| *amount >= 0)
+        .ok_or_else(invalid)?;
+    let window = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        _ => None,
+    };
+
+    // Out-of-range windows are rejected rather than panicking
+    window
+        .and_then(|window| now.checked_sub_signed(window))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_aggregation() {
+        let entries: Vec<HistoryEntry> = [
+            ("GET", "https://a.com/x", 200, 10),
+            ("GET", "https://a.com/y", 404, 20),
+            ("POST", "https://b.com/z", 201, 30),
+            ("DELETE", "https://a.com/x", 500, 40),
+        ]
+        .into_iter()
+        .map(|(method, url, status, duration_ms)| {
+            let mut entry = HistoryEntry::test_entry("id", method, url, None);
+            entry.response.status = status;
+            entry.duration_ms = duration_ms;
+            entry
+        })
+        .collect();
+
+        let stats = HistoryStats::from_entries(&entries);
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.by_method.get("GET"), Some(&2));
+        assert_eq!(stats.by_method.get("POST"), Some(&1));
+        assert_eq!(stats.by_status_class.get("2xx"), Some(&2));
+        assert_eq!(stats.by_status_class.get("4xx"), Some(&1));
+        assert_eq!(stats.by_status_class.get("5xx"), Some(&1));
+        assert_eq!(stats.top_hosts[0].host, "a.com");
+        assert_eq!(stats.top_hosts[0].count, 3);
+
+        let duration = stats.duration_ms.unwrap();
+        assert_eq!(duration.avg, 25.0);
+        assert_eq!(duration.min, 10);
+        assert_eq!(duration.max, 40);
+        assert_eq!(duration.p50, 20);
+        assert_eq!(duration.p99, 40);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = HistoryStats::from_entries(&[]);
+        assert_eq!(stats.total, 0);
+        assert!(stats.top_hosts.is_empty());
+        assert!(stats.duration_ms.is_none());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("24h", now).unwrap(), now - Duration::hours(24));
+        assert_eq!(parse_since("7d", now).unwrap(), now - Duration::days(7));
+        assert_eq!(
+            parse_since("2024-01-31", now).unwrap().to_rfc3339(),
+            "2024-01-31T00:00:00+00:00"
+        );
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("", now).is_err());
+    }
+
+    #[test]
+    fn test_parse_since_rejects_negative_and_overflow() {
+        let now = Utc::now();
+        assert!(parse_since("-7d", now).is_err());
+        // Larger than TimeDelta can represent
+        assert!(parse_since("9223372036854775807d", now).is_err());
+        // Representable window, but the cutoff falls before the earliest DateTime
+        assert!(parse_since("106751991167d", now).is_err());
+    }
+}
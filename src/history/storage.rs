@@ -126,6 +126,27 @@ impl HistoryStorage {
         Ok(entries.into_iter().skip(skip).collect())
     }
 
+    /// Find an entry by id or unique id prefix (as shown by `history list`)
+    pub fn find(&self, id: &str) -> Result<HistoryEntry> {
+        let mut matches: Vec<HistoryEntry> = self
+            .list()?
+            .into_iter()
+            .filter(|e| e.id.starts_with(id))
+            .collect();
+
+        match matches.len() {
+            1 => Ok(matches.remove(0)),
+            0 => Err(RupostError::Other(format!(
+                "No history entry matches '{}'",
+                id
+            ))),
+            n => Err(RupostError::Other(format!(
+                "History id '{}' is ambiguous ({} matches)",
+                id, n
+            ))),
+        }
+    }
+
     fn read_all(&self) -> Result<Vec<HistoryEntry>> {
         // Read lock? Append only is atomic, but to be sure we don't read partial line from a writer
         // we can take shared lock. `fs2` supports shared lock.
@@ -231,29 +252,8 @@ pub fn get_storage() -> &'static HistoryStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::history::model::{RequestSnapshot, ResponseMeta};
-    use reqwest::header::HeaderMap;
     use tempfile::TempDir;
 
-    fn create_dummy_entry(id: &str) -> HistoryEntry {
-        HistoryEntry {
-            id: id.to_string(),
-            timestamp: chrono::Utc::now(),
-            duration_ms: 100,
-            request: RequestSnapshot {
-                method: "GET".to_string(),
-                url: "https://example.com".to_string(),
-                headers: HeaderMap::new(),
-                body: None,
-            },
-            source: None,
-            response: ResponseMeta {
-                status: 200,
-                headers: HeaderMap::new(),
-            },
-        }
-    }
-
     #[test]
     fn test_append_and_list() {
         let temp_dir = TempDir::new().unwrap();
@@ -263,8 +263,8 @@ mod tests {
             file_path: file_path.clone(),
         };
 
-        let entry1 = create_dummy_entry("1");
-        let entry2 = create_dummy_entry("2");
+        let entry1 = HistoryEntry::test_entry("1", "GET", "https://example.com", None);
+        let entry2 = HistoryEntry::test_entry("2", "GET", "https://example.com", None);
 
         storage.append(&entry1).unwrap();
         storage.append(&entry2).unwrap();
@@ -275,6 +275,35 @@ mod tests {
         assert_eq!(list[1].id, "2");
     }
 
+    #[test]
+    fn test_list_entries_without_source_or_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("history.jsonl");
+        // 早期版本写入的条目没有 source 和 name 字段
+        std::fs::write(
+            &file_path,
+            r#"{"id":"old","timestamp":"2025-01-01T00:00:00Z","duration_ms":5,"request":{"method":"GET","url":"https://example.com","headers":{},"body":null},"response":{"status":200,"headers":{}}}
+"#,
+        )
+        .unwrap();
+        let storage = HistoryStorage { file_path };
+
+        let mut entry = HistoryEntry::test_entry("new", "GET", "https://example.com", None);
+        entry.source = Some("file:tests/auth.http#login".to_string());
+        entry.name = Some("login".to_string());
+        storage.append(&entry).unwrap();
+
+        let list = storage.list().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].source, None);
+        assert_eq!(list[0].name, None);
+        assert_eq!(
+            list[1].source.as_deref(),
+            Some("file:tests/auth.http#login")
+        );
+        assert_eq!(list[1].name.as_deref(), Some("login"));
+    }
+
     #[test]
     fn test_tail() {
         let temp_dir = TempDir::new().unwrap();
@@ -282,7 +311,9 @@ mod tests {
         let storage = HistoryStorage { file_path };
 
         for i in 0..10 {
-            storage.append(&create_dummy_entry(&i.to_string())).unwrap();
+            let entry =
+                HistoryEntry::test_entry(&i.to_string(), "GET", "https://example.com", None);
+            storage.append(&entry).unwrap();
         }
 
         let tail = storage.tail(3).unwrap();
@@ -290,4 +321,21 @@ mod tests {
         assert_eq!(tail[0].id, "7");
         assert_eq!(tail[2].id, "9");
     }
+
+    #[test]
+    fn test_find_by_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("history.jsonl");
+        let storage = HistoryStorage { file_path };
+
+        for id in ["abc123", "abd456", "xyz789"] {
+            let entry = HistoryEntry::test_entry(id, "GET", "https://example.com", None);
+            storage.append(&entry).unwrap();
+        }
+
+        assert_eq!(storage.find("abc").unwrap().id, "abc123");
+        assert_eq!(storage.find("xyz789").unwrap().id, "xyz789");
+        assert!(storage.find("ab").is_err());
+        assert!(storage.find("nope").is_err());
+    }
 }
@@ -1,13 +1,215 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 
-use crate::Result;
-use crate::http::request::Request;
-use crate::http::response::Response;
-use crate::http::types::Method;
+use crate::http::request::{Request, RequestBody};
+use crate::http::response::{Response, Timing};
+use crate::http::retry::{RetryPolicy, retry_after};
+use crate::http::types::{FormPart, Method, ResolveOverride, StreamLimit};
+use crate::{Result, RupostError};
+
+/// 客户端默认超时时间
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 默认在内存中保留的最大响应 body（10 MB）
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// 默认最多跟随的重定向次数（与 reqwest 默认值一致）
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+tokio::task_local! {
+    /// 当前请求已跟随的重定向次数，由重定向策略在发送请求的任务中写入
+    static REDIRECTS: Cell<usize>;
+    /// 当前请求是否跟随重定向（@no-redirect 时为 false）
+    static FOLLOW_REDIRECTS: bool;
+}
+
+/// 带 @resolve 的请求的客户端缓存键：（是否不校验证书, 解析覆盖）
+type ResolveKey = (bool, Vec<ResolveOverride>);
 
 #[derive(Clone)]
 pub struct Client {
     inner: reqwest::Client,
+    options: ClientBuilder,
+    /// 不校验证书的客户端，首个 @insecure 请求时创建
+    insecure_inner: Arc<OnceLock<reqwest::Client>>,
+    /// 带 @resolve 的请求使用的客户端，按（是否校验证书, 解析覆盖）缓存
+    resolve_inner: Arc<Mutex<HashMap<ResolveKey, reqwest::Client>>>,
+}
+
+/// 客户端选项（重定向、TLS、代理、DNS 覆盖、重试、body 大小）
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    follow_redirects: bool,
+    max_redirects: usize,
+    insecure: bool,
+    ca_cert: Option<PathBuf>,
+    proxy: Option<String>,
+    no_proxy: bool,
+    no_proxy_hosts: Option<String>,
+    resolve: Vec<ResolveOverride>,
+    retry: RetryPolicy,
+    max_body_size: usize,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            follow_redirects: true,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            insecure: false,
+            ca_cert: None,
+            proxy: None,
+            no_proxy: false,
+            no_proxy_hosts: None,
+            resolve: Vec::new(),
+            retry: RetryPolicy::default(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// 是否跟随重定向（默认跟随），关闭时 3xx 响应原样返回（--no-redirect）
+    pub fn follow_redirects(mut self, follow: bool) -> Self {
+        self.follow_redirects = follow;
+        self
+    }
+
+    /// 最多跟随的重定向次数（--max-redirects），超过时请求失败
+    pub fn max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// 不校验服务器证书（--insecure）
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    /// 额外信任的 PEM 格式 CA 证书（--cacert）
+    pub fn ca_cert(mut self, path: Option<PathBuf>) -> Self {
+        self.ca_cert = path;
+        self
+    }
+
+    /// 所有请求通过该代理发送（--proxy，支持 http:// / https:// / socks5://）
+    ///
+    /// 未指定时使用 HTTP_PROXY / HTTPS_PROXY / ALL_PROXY 环境变量。
+    pub fn proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// 不使用任何代理，包括环境变量中的代理（--no-proxy）
+    pub fn no_proxy(mut self, no_proxy: bool) -> Self {
+        self.no_proxy = no_proxy;
+        self
+    }
+
+    /// 不经过 --proxy 的主机列表（逗号分隔，格式同 NO_PROXY），默认读取 NO_PROXY 环境变量
+    pub fn no_proxy_hosts(mut self, hosts: Option<String>) -> Self {
+        self.no_proxy_hosts = hosts;
+        self
+    }
+
+    /// 将主机名解析到指定地址，Host header 和 SNI 不变（--resolve / `[http] resolve`）
+    pub fn resolve(mut self, overrides: Vec<ResolveOverride>) -> Self {
+        self.resolve = overrides;
+        self
+    }
+
+    /// 连接失败、超时和指定状态码的自动重试（--retry / `[http.retry]`，默认不重试）
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// 在内存中保留的最大响应 body 字节数（`[http] max_body_size`，0 表示不限制）
+    ///
+    /// 超过时停止读取并将响应标记为已截断；写入文件的下载不受限制。
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// 创建客户端，CA 证书无法读取或解析、代理地址无效时返回错误
+    pub fn build(self) -> Result<Client> {
+        let inner = self.build_inner(self.insecure, &[])?;
+        Ok(Client {
+            inner,
+            options: self,
+            insecure_inner: Arc::default(),
+            resolve_inner: Arc::default(),
+        })
+    }
+
+    /// extra_resolve 为单个请求的 @resolve，同一主机时覆盖客户端的设置
+    fn build_inner(
+        &self,
+        insecure: bool,
+        extra_resolve: &[ResolveOverride],
+    ) -> Result<reqwest::Client> {
+        let max = self.max_redirects;
+        let policy = if self.follow_redirects {
+            reqwest::redirect::Policy::custom(move |attempt| {
+                // @no-redirect 的请求原样返回 3xx 响应
+                if !FOLLOW_REDIRECTS.try_with(|follow| *follow).unwrap_or(true) {
+                    return attempt.stop();
+                }
+                // previous 包含原始请求和之前跟随过的 URL，即本次是第几次重定向
+                let count = attempt.previous().len();
+                if count > max {
+                    attempt.error(format!("too many redirects (max {})", max))
+                } else {
+                    let _ = REDIRECTS.try_with(|redirects| redirects.set(count));
+                    attempt.follow()
+                }
+            })
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .redirect(policy)
+            .tls_danger_accept_invalid_certs(insecure);
+        if let Some(path) = &self.ca_cert {
+            let pem = std::fs::read(path).map_err(|e| {
+                RupostError::Other(format!(
+                    "Cannot read CA certificate {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                RupostError::Other(format!("Invalid CA certificate {}: {}", path.display(), e))
+            })?;
+            builder = builder.tls_certs_merge(certs);
+        }
+
+        if self.no_proxy {
+            builder = builder.no_proxy();
+        } else if let Some(proxy) = &self.proxy {
+            let no_proxy = match &self.no_proxy_hosts {
+                Some(hosts) => reqwest::NoProxy::from_string(hosts),
+                None => reqwest::NoProxy::from_env(),
+            };
+            let proxy = reqwest::Proxy::all(proxy.as_str())
+                .map_err(|e| RupostError::Other(format!("Invalid proxy '{}': {}", proxy, e)))?
+                .no_proxy(no_proxy);
+            builder = builder.proxy(proxy);
+        }
+
+        for entry in self.resolve.iter().chain(extra_resolve) {
+            builder = builder.resolve(&entry.host, SocketAddr::new(entry.addr, entry.port));
+        }
+        Ok(builder.build()?)
+    }
 }
 
 impl Default for Client {
@@ -18,16 +220,108 @@ impl Default for Client {
 
 impl Client {
     pub fn new() -> Self {
-        Self {
-            inner: reqwest::Client::builder()
-                .timeout(Duration::from_secs(30))
-                .build()
-                .expect("Failed to build HTTP client"),
+        Self::builder()
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// 是否对所有请求关闭证书校验
+    pub fn is_insecure(&self) -> bool {
+        self.options.insecure
+    }
+
+    /// 选择发送请求的客户端：@insecure 的请求使用不校验证书的客户端，
+    /// 带 @resolve 的请求使用包含对应 DNS 覆盖的客户端
+    fn client_for(&self, insecure: bool, resolve: &[ResolveOverride]) -> Result<reqwest::Client> {
+        let insecure = insecure || self.options.insecure;
+        if !resolve.is_empty() {
+            let mut clients = self.resolve_inner.lock().unwrap();
+            let key = (insecure, resolve.to_vec());
+            if let Some(client) = clients.get(&key) {
+                return Ok(client.clone());
+            }
+            let client = self.options.build_inner(insecure, resolve)?;
+            clients.insert(key, client.clone());
+            return Ok(client);
         }
+
+        if !insecure || self.options.insecure {
+            return Ok(self.inner.clone());
+        }
+        if let Some(client) = self.insecure_inner.get() {
+            return Ok(client.clone());
+        }
+        let client = self.options.build_inner(true, &[])?;
+        Ok(self.insecure_inner.get_or_init(|| client).clone())
     }
 
-    pub async fn execute(&self, request: Request) -> Result<Response> {
-        let url = reqwest::Url::parse_with_params(&request.url.to_string(), &request.query_params)?;
+    /// 发送请求，按重试策略重试连接失败、超时和指定的状态码
+    ///
+    /// 返回的响应 duration 为各次尝试的耗时之和（不含重试前的等待），attempts 为尝试次数。
+    pub async fn execute(&self, mut request: Request) -> Result<Response> {
+        let policy = &self.options.retry;
+        let mut elapsed = Duration::ZERO;
+        let mut attempt = 1;
+
+        loop {
+            let retry_request = if attempt < policy.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            let start = std::time::Instant::now();
+            let result = self.execute_once(request).await;
+            let retry = match &result {
+                Err(e) if RetryPolicy::is_transient(e) => {
+                    Some((start.elapsed(), policy.delay(attempt, None)))
+                }
+                // 已写入文件的响应不再重试，避免覆盖或重复写入
+                Ok(response)
+                    if response.saved_to.is_none()
+                        && policy.retries_status(response.status.code()) =>
+                {
+                    Some((
+                        response.duration,
+                        policy.delay(attempt, retry_after(&response.headers)),
+                    ))
+                }
+                _ => None,
+            };
+
+            match (retry, retry_request) {
+                (Some((duration, delay)), Some(next)) => {
+                    tracing::debug!("Attempt {} failed, retrying in {:?}", attempt, delay);
+                    elapsed += duration;
+                    tokio::time::sleep(delay).await;
+                    request = next;
+                    attempt += 1;
+                }
+                _ => {
+                    return result.map(|mut response| {
+                        response.duration += elapsed;
+                        response.attempts = attempt as usize;
+                        response
+                    });
+                }
+            }
+        }
+    }
+
+    async fn execute_once(&self, request: Request) -> Result<Response> {
+        let target = request.url.to_string();
+        let mut url = reqwest::Url::parse(&target).map_err(|e| RupostError::url(&target, e))?;
+        // 没有参数时不调用 query_pairs_mut，否则 URL 末尾会多出一个 `?`
+        if !request.query_params.is_empty() {
+            url.query_pairs_mut().extend_pairs(&request.query_params);
+        }
+        if request.url.is_websocket() {
+            return self.execute_ws(url, request).await;
+        }
         let method = match request.method {
             Method::Get => reqwest::Method::GET,
             Method::Post => reqwest::Method::POST,
@@ -36,21 +330,325 @@ impl Client {
             Method::Patch => reqwest::Method::PATCH,
             Method::Head => reqwest::Method::HEAD,
             Method::Options => reqwest::Method::OPTIONS,
+            Method::Trace => reqwest::Method::TRACE,
+            Method::Other(name) => reqwest::Method::from_bytes(name.as_bytes())
+                .map_err(|_| RupostError::ParseError(format!("Invalid HTTP method: {}", name)))?,
         };
-        let mut req = self.inner.request(method, url).headers(request.headers);
+        let mut headers = request.headers;
+        if matches!(request.body, Some(RequestBody::Multipart(_))) {
+            // Content-Type 由 reqwest 生成（包含 boundary）
+            headers.remove(reqwest::header::CONTENT_TYPE);
+        }
+        let mut req = self
+            .client_for(request.insecure, &request.resolve)?
+            .request(method, url)
+            .headers(headers);
+
+        // 流式读取按时长停止时，默认超时需要覆盖整个读取时间
+        let stream_timeout = request
+            .stream
+            .and_then(|limit| limit.duration)
+            .map(|duration| duration + DEFAULT_TIMEOUT);
+        if let Some(timeout) = request.timeout.or(stream_timeout) {
+            req = req.timeout(timeout);
+        }
 
-        if let Some(body) = request.body {
-            req = req.body(body);
+        match request.body {
+            Some(RequestBody::Raw(body)) => req = req.body(body),
+            Some(RequestBody::Multipart(parts)) => {
+                req = req.multipart(Self::build_form(parts).await?)
+            }
+            None => {}
         }
 
         let start = std::time::Instant::now();
-        let response = req.send().await?;
-        let duration = start.elapsed();
+        let (response, redirects) = FOLLOW_REDIRECTS
+            .scope(
+                !request.no_redirect,
+                REDIRECTS.scope(Cell::new(0), async {
+                    let response = req.send().await;
+                    (response, REDIRECTS.with(Cell::get))
+                }),
+            )
+            .await;
+        let response = response?;
+        let ttfb = start.elapsed();
 
         let status = response.status().as_u16();
         let headers = response.headers().clone();
-        let body = response.text().await?;
+        // 读取 body 会消费 response，需提前取出重定向后的 URL
+        let final_url = response.url().to_string();
+
+        if let Some(target) = &request.download {
+            let saved = target.save(&final_url, response).await?;
+            let duration = start.elapsed();
+            let mut response =
+                Response::new(status, headers, String::new(), duration)?.with_final_url(final_url);
+            response.timing = Some(Timing {
+                ttfb,
+                body: duration - ttfb,
+            });
+            response.redirects = redirects;
+            response.saved_to = Some(saved);
+            return Ok(response);
+        }
+
+        let max = match self.options.max_body_size {
+            0 => usize::MAX,
+            max => max,
+        };
+        let (body, truncated) = match request.stream {
+            Some(limit) => Self::read_stream(response, limit, max).await?,
+            None => Self::read_body(response, max).await?,
+        };
+        let duration = start.elapsed();
+
+        let mut response =
+            Response::from_bytes(status, headers, body, duration)?.with_final_url(final_url);
+        response.timing = Some(Timing {
+            ttfb,
+            body: duration - ttfb,
+        });
+        response.redirects = redirects;
+        if truncated {
+            tracing::warn!(
+                "Response body exceeds {} bytes and was truncated, increase [http] max_body_size",
+                max
+            );
+            response.truncated_at = Some(max);
+        }
+        Ok(response)
+    }
+
+    /// WebSocket 冒烟测试：连接、发送 body（如有）、读取一条消息作为响应 body
+    #[cfg(feature = "websocket")]
+    pub async fn execute_ws(&self, url: reqwest::Url, request: Request) -> Result<Response> {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        let ws_error = |e: tokio_tungstenite::tungstenite::Error| {
+            RupostError::NetworkError(format!("WebSocket error: {}", e))
+        };
+
+        let message = match request.body {
+            Some(RequestBody::Raw(body)) => body
+                .as_bytes()
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+            Some(RequestBody::Multipart(_)) => {
+                return Err(RupostError::Other(
+                    "Multipart bodies are not supported for WebSocket requests".to_string(),
+                ));
+            }
+            None => None,
+        };
+
+        let final_url = url.to_string();
+        let mut ws_request = url.as_str().into_client_request().map_err(ws_error)?;
+        ws_request.headers_mut().extend(request.headers);
+
+        let start = std::time::Instant::now();
+        let exchange = async {
+            let (mut stream, handshake) = tokio_tungstenite::connect_async(ws_request)
+                .await
+                .map_err(ws_error)?;
+            let ttfb = start.elapsed();
+
+            if let Some(message) = message {
+                stream
+                    .send(Message::text(message))
+                    .await
+                    .map_err(ws_error)?;
+            }
+
+            // 跳过 ping/pong 等控制帧，读取第一条数据消息
+            let body = loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => break text.as_bytes().to_vec(),
+                    Some(Ok(Message::Binary(bytes))) => break bytes.to_vec(),
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Err(RupostError::NetworkError(
+                            "WebSocket closed before a message was received".to_string(),
+                        ));
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(ws_error(e)),
+                }
+            };
+
+            // 关闭失败不影响结果
+            let _ = stream.close(None).await;
+            Ok((handshake, body, ttfb))
+        };
+
+        let timeout = request.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let (handshake, body, ttfb) =
+            tokio::time::timeout(timeout, exchange)
+                .await
+                .map_err(|_| {
+                    RupostError::NetworkError(format!("WebSocket timed out after {:?}", timeout))
+                })??;
+
+        let duration = start.elapsed();
+        let mut response = Response::from_bytes(
+            handshake.status().as_u16(),
+            handshake.headers().clone(),
+            body,
+            duration,
+        )?
+        .with_final_url(final_url);
+        response.timing = Some(Timing {
+            ttfb,
+            body: duration - ttfb,
+        });
+        Ok(response)
+    }
+
+    #[cfg(not(feature = "websocket"))]
+    pub async fn execute_ws(&self, url: reqwest::Url, _request: Request) -> Result<Response> {
+        Err(RupostError::Other(format!(
+            "Cannot request {}: rupost was built without the `websocket` feature",
+            url
+        )))
+    }
+
+    /// 构建 multipart 表单，文件不存在时在发送前返回错误
+    async fn build_form(parts: Vec<FormPart>) -> Result<reqwest::multipart::Form> {
+        let mut form = reqwest::multipart::Form::new();
+
+        for part in parts {
+            form = match part {
+                FormPart::Text { name, value } => form.text(name, value),
+                FormPart::File {
+                    name,
+                    path,
+                    content_type,
+                } => {
+                    let content = tokio::fs::read(&path).await.map_err(|e| {
+                        RupostError::Other(format!(
+                            "Failed to read form file {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })?;
+                    let file_name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let mime = content_type.unwrap_or_else(|| {
+                        mime_guess::from_path(&path)
+                            .first_or_octet_stream()
+                            .to_string()
+                    });
+                    let part = reqwest::multipart::Part::bytes(content)
+                        .file_name(file_name)
+                        .mime_str(&mime)?;
+                    form.part(name, part)
+                }
+            };
+        }
+
+        Ok(form)
+    }
+
+    /// 读取响应 body，超过 max 字节时截断并停止读取，返回 body 和是否被截断
+    async fn read_body(mut response: reqwest::Response, max: usize) -> Result<(Vec<u8>, bool)> {
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            if buffer.len() > max {
+                buffer.truncate(max);
+                return Ok((buffer, true));
+            }
+        }
+        Ok((buffer, false))
+    }
+
+    /// 按 @stream 限制读取流式响应（如 SSE），达到行数或时长后停止
+    async fn read_stream(
+        mut response: reqwest::Response,
+        limit: StreamLimit,
+        max: usize,
+    ) -> Result<(Vec<u8>, bool)> {
+        let deadline = limit
+            .duration
+            .map(|duration| tokio::time::Instant::now() + duration);
+        let mut buffer: Vec<u8> = Vec::new();
+
+        loop {
+            if let Some(end) = limit.lines.and_then(|lines| nth_line_end(&buffer, lines)) {
+                buffer.truncate(end);
+                break;
+            }
+
+            let chunk = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, response.chunk()).await {
+                    Ok(chunk) => chunk?,
+                    // 到达时长限制
+                    Err(_) => break,
+                },
+                None => response.chunk().await?,
+            };
+
+            match chunk {
+                Some(bytes) => buffer.extend_from_slice(&bytes),
+                // 流已结束
+                None => break,
+            }
+
+            if buffer.len() > max {
+                buffer.truncate(max);
+                return Ok((buffer, true));
+            }
+        }
+
+        Ok((buffer, false))
+    }
+}
+
+/// 第 n 行（含换行符）结束的位置
+fn nth_line_end(buffer: &[u8], n: usize) -> Option<usize> {
+    buffer
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+        .nth(n.checked_sub(1)?)
+        .map(|(index, _)| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nth_line_end() {
+        let buffer = b"data: 1\n\ndata: 2\n";
+        assert_eq!(nth_line_end(buffer, 1), Some(8));
+        assert_eq!(nth_line_end(buffer, 2), Some(9));
+        assert_eq!(nth_line_end(buffer, 3), Some(17));
+        assert_eq!(nth_line_end(buffer, 4), None);
+        assert_eq!(nth_line_end(buffer, 0), None);
+    }
+
+    #[test]
+    fn test_builder_ca_cert_errors() {
+        let missing = Client::builder()
+            .ca_cert(Some(PathBuf::from("does/not/exist.pem")))
+            .build();
+        assert!(missing.is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid.pem");
+        std::fs::write(
+            &path,
+            "-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+        let invalid = Client::builder().ca_cert(Some(path)).build();
+        assert!(invalid.is_err());
 
-        Response::new(status, headers, body, duration)
+        let insecure = Client::builder().insecure(true).build().unwrap();
+        assert!(insecure.is_insecure());
+        assert!(!Client::new().is_insecure());
     }
 }
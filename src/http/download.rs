@@ -0,0 +1,266 @@
+//! 将响应 body 直接写入文件（-o / --download）
+//!
+//! 下载时 body 按块写入磁盘而不在内存中缓存，因此不受 `[http] max_body_size` 限制。
+
+use crate::{Result, RupostError};
+use reqwest::header::HeaderMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 进度刷新的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 响应 body 的保存位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadTarget {
+    /// 为 None 时根据 Content-Disposition 或 URL 推断文件名
+    pub path: Option<PathBuf>,
+    /// 是否覆盖已存在的文件（--force）
+    pub force: bool,
+}
+
+/// 已写入文件的 body
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedBody {
+    pub path: PathBuf,
+    /// 写入的字节数
+    pub size: u64,
+}
+
+impl DownloadTarget {
+    /// 指定的文件已存在且未 --force 时报错，在发送请求前调用
+    pub fn check(&self) -> Result<()> {
+        match &self.path {
+            Some(path) if !self.force && path.exists() => Err(already_exists(path)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 打开要写入的文件，推断出的文件名只能在收到响应头后确定
+    fn open(&self, url: &str, headers: &HeaderMap) -> Result<(PathBuf, std::fs::File)> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => PathBuf::from(file_name(url, headers)),
+        };
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true);
+        if self.force {
+            options.create(true).truncate(true);
+        } else {
+            // create_new 保证不会覆盖
+            options.create_new(true);
+        }
+        let file = options.open(&path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                already_exists(&path)
+            } else {
+                e.into()
+            }
+        })?;
+        Ok((path, file))
+    }
+
+    /// 按块将响应 body 写入文件，stderr 是终端时显示下载进度
+    pub(crate) async fn save(
+        &self,
+        url: &str,
+        mut response: reqwest::Response,
+    ) -> Result<SavedBody> {
+        use tokio::io::AsyncWriteExt;
+
+        let (path, file) = self.open(url, response.headers())?;
+        let mut file = tokio::fs::File::from_std(file);
+        let total = response.content_length();
+        let mut progress = std::io::stderr()
+            .is_terminal()
+            .then(|| Progress::new(total));
+
+        let mut size = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            size += chunk.len() as u64;
+            if let Some(progress) = &mut progress {
+                progress.update(size);
+            }
+        }
+        file.flush().await?;
+        if let Some(progress) = &progress {
+            progress.finish();
+        }
+
+        Ok(SavedBody { path, size })
+    }
+}
+
+/// 单行刷新的下载进度
+struct Progress {
+    total: Option<u64>,
+    last: Instant,
+}
+
+impl Progress {
+    fn new(total: Option<u64>) -> Self {
+        Self {
+            total,
+            last: Instant::now(),
+        }
+    }
+
+    fn update(&mut self, size: u64) {
+        if self.last.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last = Instant::now();
+        let line = match self.total {
+            Some(total) if total > 0 => format!(
+                "Downloading {} / {} ({}%)",
+                format_size(size),
+                format_size(total),
+                size * 100 / total
+            ),
+            _ => format!("Downloading {}", format_size(size)),
+        };
+        eprint!("\r{}\x1b[K", line);
+    }
+
+    fn finish(&self) {
+        eprint!("\r\x1b[K");
+    }
+}
+
+fn already_exists(path: &Path) -> RupostError {
+    RupostError::Other(format!(
+        "{} already exists, use --force to overwrite",
+        path.display()
+    ))
+}
+
+/// 推断下载文件名：Content-Disposition 的 filename，其次是 URL 路径的最后一段，最后是主机名
+pub fn file_name(url: &str, headers: &HeaderMap) -> String {
+    let from_header = headers
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(content_disposition_file_name);
+    let parsed_url = url::Url::parse(url).ok();
+    let from_path = parsed_url
+        .as_ref()
+        .and_then(|u| u.path_segments())
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map(percent_decode);
+
+    from_header
+        .into_iter()
+        .chain(from_path)
+        .chain(
+            parsed_url
+                .as_ref()
+                .and_then(|u| u.host_str())
+                .map(String::from),
+        )
+        // 只保留文件名部分，防止路径穿越
+        .find_map(|name| {
+            Path::new(&name)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "download".to_string())
+}
+
+/// 解析 `attachment; filename="a.json"` 或 `filename*=UTF-8''a%20b.json`（优先）
+fn content_disposition_file_name(value: &str) -> Option<String> {
+    let mut plain = None;
+    for param in value.split(';') {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                let encoded = value.trim().splitn(3, '\'').nth(2)?;
+                return Some(percent_decode(encoded));
+            }
+            "filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain.filter(|name| !name.is_empty())
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = s.get(i + 1..i + 3).filter(|_| bytes[i] == b'%');
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 以 B / KB / MB / GB 显示字节数
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let size = bytes as f64;
+    if size < KB {
+        format!("{} B", bytes)
+    } else if size < KB * KB {
+        format!("{:.1} KB", size / KB)
+    } else if size < KB * KB * KB {
+        format!("{:.1} MB", size / (KB * KB))
+    } else {
+        format!("{:.1} GB", size / (KB * KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        use reqwest::header::CONTENT_DISPOSITION;
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            file_name("https://example.com/api/export%20all.json?x=1", &headers),
+            "export all.json"
+        );
+        assert_eq!(file_name("https://example.com/files/", &headers), "files");
+        assert_eq!(file_name("https://example.com/", &headers), "example.com");
+
+        headers.insert(
+            CONTENT_DISPOSITION,
+            "attachment; filename=\"../../report.csv\"".parse().unwrap(),
+        );
+        assert_eq!(
+            file_name("https://example.com/download", &headers),
+            "report.csv"
+        );
+
+        assert_eq!(
+            content_disposition_file_name(
+                "attachment; filename=\"fallback.txt\"; filename*=UTF-8''%E6%8A%A5%E5%91%8A.txt"
+            ),
+            Some("报告.txt".to_string())
+        );
+        assert_eq!(content_disposition_file_name("inline"), None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0 GB");
+    }
+}
@@ -1,9 +1,13 @@
 pub mod client;
+pub mod download;
 pub mod request;
 pub mod response;
+pub mod retry;
 pub mod types;
 
 // Re-export commonly used types for convenient access
-pub use client::Client;
-pub use request::Request;
-pub use response::Response;
+pub use client::{Client, ClientBuilder};
+pub use download::DownloadTarget;
+pub use request::{Request, RequestBody};
+pub use response::{Response, Timing};
+pub use retry::RetryPolicy;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::time::Duration;
 
 use reqwest::{
     Body,
@@ -6,15 +6,37 @@ use reqwest::{
 };
 
 use crate::Result;
-use crate::http::types::{Method, Url};
+use crate::http::download::DownloadTarget;
+use crate::http::types::{FormPart, Method, ResolveOverride, StreamLimit, Url};
 use serde::Serialize;
 
+/// 请求体
+pub enum RequestBody {
+    /// 原始内容（文本、JSON 等）
+    Raw(Body),
+    /// multipart/form-data 表单，文件在发送时读取
+    Multipart(Vec<FormPart>),
+}
+
 pub struct Request {
     pub method: Method,
     pub url: Url,
     pub headers: Headers,
-    pub body: Option<Body>,
-    pub query_params: HashMap<String, String>,
+    pub body: Option<RequestBody>,
+    /// Query 参数，按添加顺序追加到 URL，同名参数保留多个值
+    pub query_params: Vec<(String, String)>,
+    /// 单个请求的超时时间，None 时使用客户端默认值
+    pub timeout: Option<Duration>,
+    /// 流式读取响应（@stream），None 时读取完整响应体
+    pub stream: Option<StreamLimit>,
+    /// 不校验服务器证书（@insecure）
+    pub insecure: bool,
+    /// 不跟随重定向，3xx 响应原样返回（@no-redirect）
+    pub no_redirect: bool,
+    /// 该请求的 DNS 覆盖（@resolve）
+    pub resolve: Vec<ResolveOverride>,
+    /// 将响应 body 直接写入文件（-o / --download），None 时读入内存
+    pub download: Option<DownloadTarget>,
 }
 
 impl Request {
@@ -24,7 +46,37 @@ impl Request {
             url: Url::parse(url)?,
             headers: Headers::new(),
             body: None,
-            query_params: HashMap::new(),
+            query_params: Vec::new(),
+            timeout: None,
+            stream: None,
+            insecure: false,
+            no_redirect: false,
+            resolve: Vec::new(),
+            download: None,
+        })
+    }
+
+    /// 复制请求用于重试，body 为流时无法复制，返回 None
+    pub fn try_clone(&self) -> Option<Self> {
+        let body = match &self.body {
+            Some(RequestBody::Raw(body)) => {
+                Some(RequestBody::Raw(Body::from(body.as_bytes()?.to_vec())))
+            }
+            Some(RequestBody::Multipart(parts)) => Some(RequestBody::Multipart(parts.clone())),
+            None => None,
+        };
+        Some(Self {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body,
+            query_params: self.query_params.clone(),
+            timeout: self.timeout,
+            stream: self.stream,
+            insecure: self.insecure,
+            no_redirect: self.no_redirect,
+            resolve: self.resolve.clone(),
+            download: self.download.clone(),
         })
     }
 
@@ -40,23 +92,53 @@ impl Request {
     }
 
     pub fn with_text(mut self, text: &str) -> Self {
-        self.body = Some(Body::from(text.to_owned()));
+        self.body = Some(RequestBody::Raw(Body::from(text.to_owned())));
         self
     }
 
     pub fn with_json<T: Serialize>(mut self, data: &T) -> Result<Self> {
         let json = serde_json::to_string(data)?;
         self.insert_header("Content-Type", "application/json");
-        self.body = Some(Body::from(json));
+        self.body = Some(RequestBody::Raw(Body::from(json)));
         Ok(self)
     }
     pub fn with_body(mut self, body: &str) -> Self {
-        self.body = Some(Body::from(body.to_owned()));
+        self.body = Some(RequestBody::Raw(Body::from(body.to_owned())));
+        self
+    }
+
+    pub fn with_multipart(mut self, parts: Vec<FormPart>) -> Self {
+        self.body = Some(RequestBody::Multipart(parts));
         self
     }
 
     pub fn with_query(mut self, key: &str, value: &str) -> Self {
-        self.query_params.insert(key.to_string(), value.to_string());
+        self.query_params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_stream(mut self, limit: StreamLimit) -> Self {
+        self.stream = Some(limit);
+        self
+    }
+
+    pub fn with_insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    pub fn with_no_redirect(mut self, no_redirect: bool) -> Self {
+        self.no_redirect = no_redirect;
+        self
+    }
+
+    pub fn with_resolve(mut self, resolve: Vec<ResolveOverride>) -> Self {
+        self.resolve = resolve;
         self
     }
 
@@ -64,4 +146,20 @@ impl Request {
         self.insert_header("Authorization", &format!("Bearer {}", token));
         self
     }
+
+    pub fn with_auth_basic(mut self, user: &str, password: &str) -> Self {
+        self.insert_header("Authorization", &basic_auth_value(user, password));
+        self
+    }
+}
+
+/// Basic 认证的 Authorization header 值：`Basic base64(user:password)`
+pub fn basic_auth_value(user: &str, password: &str) -> String {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+
+    format!(
+        "Basic {}",
+        STANDARD.encode(format!("{}:{}", user, password))
+    )
 }
@@ -1,6 +1,9 @@
 use crate::Result;
+use crate::http::download::SavedBody;
 use crate::http::types::Status;
 use reqwest::header::HeaderMap as Headers;
+use serde::de::DeserializeOwned;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -8,7 +11,32 @@ pub struct Response {
     pub status: Status,
     pub headers: Headers,
     pub body: String, // 直接使用 String，不需要 reqwest::Body
+    /// body 不是合法 UTF-8 时保留的原始字节（此时 `body` 为有损转换的文本）
+    pub raw_body: Option<Vec<u8>>,
     pub duration: Duration,
+    /// 收到响应头与读取完 body 的耗时拆分（重试时为最后一次尝试）
+    pub timing: Option<Timing>,
+    /// 跟随重定向后最终请求的 URL（response.url 断言）
+    pub final_url: Option<String>,
+    /// 跟随的重定向次数（response.redirects 断言）
+    pub redirects: usize,
+    /// 发送的次数（启用重试时可能大于 1），duration 为各次耗时之和
+    pub attempts: usize,
+    /// body 超过 `[http] max_body_size` 时只读取了前 N 个字节
+    pub truncated_at: Option<usize>,
+    /// body 已写入文件（-o / --download），此时 `body` 为空
+    pub saved_to: Option<SavedBody>,
+    /// body 解析为 JSON 的结果，首次调用 json() 时填充（不是合法 JSON 时为 None）
+    json: OnceLock<Option<serde_json::Value>>,
+}
+
+/// 单次请求的耗时拆分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// 发送请求到收到响应头（包括 DNS、建立连接和 TLS 握手），WebSocket 为握手耗时
+    pub ttfb: Duration,
+    /// 收到响应头之后读取完整 body 的耗时
+    pub body: Duration,
 }
 
 impl Response {
@@ -17,7 +45,35 @@ impl Response {
             status: Status::new(status)?,
             headers,
             body, // 直接使用，无需 clone
+            raw_body: None,
             duration,
+            timing: None,
+            final_url: None,
+            redirects: 0,
+            attempts: 1,
+            truncated_at: None,
+            saved_to: None,
+            json: OnceLock::new(),
+        })
+    }
+
+    /// 从原始字节创建，UTF-8 内容不重复保存
+    pub fn from_bytes(
+        status: u16,
+        headers: Headers,
+        bytes: Vec<u8>,
+        duration: Duration,
+    ) -> Result<Self> {
+        let (body, raw_body) = match String::from_utf8(bytes) {
+            Ok(body) => (body, None),
+            Err(e) => {
+                let bytes = e.into_bytes();
+                (String::from_utf8_lossy(&bytes).into_owned(), Some(bytes))
+            }
+        };
+        Ok(Self {
+            raw_body,
+            ..Self::new(status, headers, body, duration)?
         })
     }
 
@@ -26,10 +82,24 @@ impl Response {
             status: Status::new(500).unwrap(),
             headers: Headers::new(),
             body: message, // 直接使用，无需 clone
+            raw_body: None,
             duration: Duration::from_millis(0),
+            timing: None,
+            final_url: None,
+            redirects: 0,
+            attempts: 1,
+            truncated_at: None,
+            saved_to: None,
+            json: OnceLock::new(),
         }
     }
 
+    /// 记录最终请求的 URL
+    pub fn with_final_url(mut self, url: impl Into<String>) -> Self {
+        self.final_url = Some(url.into());
+        self
+    }
+
     pub fn is_success(&self) -> bool {
         self.status.is_success()
     }
@@ -49,4 +119,115 @@ impl Response {
     pub fn text(&self) -> Result<&str> {
         Ok(&self.body)
     }
+
+    /// 将 body 解析为 JSON，结果在断言、捕获和格式化之间共享，只解析一次
+    ///
+    /// 不是合法 JSON 时返回 None（同样只尝试一次）。解析后再修改 `body` 不会更新结果。
+    pub fn json_value(&self) -> Option<&serde_json::Value> {
+        self.json
+            .get_or_init(|| serde_json::from_str(&self.body).ok())
+            .as_ref()
+    }
+
+    /// 将 body 反序列化为 T，基于 [`Self::json_value`] 的缓存，不会重新解析 body
+    ///
+    /// body 不是合法 JSON 时返回解析错误。
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        match self.json_value() {
+            Some(value) => Ok(T::deserialize(value)?),
+            None => Ok(serde_json::from_str(&self.body)?),
+        }
+    }
+
+    /// body 被截断时，基于 body 的断言和捕获使用的错误信息
+    pub fn truncation_error(&self) -> Option<String> {
+        self.truncated_at.map(|limit| {
+            format!(
+                "body truncated at {} bytes, increase [http] max_body_size",
+                limit
+            )
+        })
+    }
+
+    /// 原始 body 字节（写入文件等需要二进制安全的场景）
+    pub fn bytes(&self) -> &[u8] {
+        self.raw_body.as_deref().unwrap_or(self.body.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        let response =
+            Response::from_bytes(200, Headers::new(), b"hello".to_vec(), Duration::ZERO).unwrap();
+        assert_eq!(response.body, "hello");
+        assert_eq!(response.raw_body, None);
+        assert_eq!(response.bytes(), b"hello");
+
+        let png = vec![0x89, b'P', b'N', b'G', 0xff, 0x00];
+        let response =
+            Response::from_bytes(200, Headers::new(), png.clone(), Duration::ZERO).unwrap();
+        assert_eq!(response.body, "\u{fffd}PNG\u{fffd}\0");
+        assert_eq!(response.bytes(), png.as_slice());
+    }
+
+    #[test]
+    fn test_json_value_is_cached() {
+        let response = Response::new(
+            200,
+            Headers::new(),
+            r#"{"id": 1}"#.to_string(),
+            Duration::ZERO,
+        )
+        .unwrap();
+        assert!(response.json.get().is_none());
+        assert_eq!(response.json_value().unwrap()["id"], 1);
+        assert!(std::ptr::eq(
+            response.json_value().unwrap(),
+            response.json_value().unwrap()
+        ));
+
+        // 非 JSON 的结果同样缓存，不会重复解析
+        let response =
+            Response::new(200, Headers::new(), "pong".to_string(), Duration::ZERO).unwrap();
+        assert_eq!(response.json_value(), None);
+        assert_eq!(response.json.get(), Some(&None));
+        assert_eq!(response.json_value(), None);
+    }
+
+    #[test]
+    fn test_json_typed() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct User {
+            id: u64,
+            name: String,
+        }
+
+        let response = Response::new(
+            200,
+            Headers::new(),
+            r#"{"id": 1, "name": "alice"}"#.to_string(),
+            Duration::ZERO,
+        )
+        .unwrap();
+        let user: User = response.json().unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 1,
+                name: "alice".to_string()
+            }
+        );
+        // 复用 json_value() 的缓存
+        assert!(response.json.get().is_some());
+        assert!(response.json::<Vec<User>>().is_err());
+
+        let response =
+            Response::new(200, Headers::new(), "pong".to_string(), Duration::ZERO).unwrap();
+        let err = response.json::<User>().unwrap_err();
+        assert!(matches!(err, crate::RupostError::JsonError(_)), "{:?}", err);
+    }
 }
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use crate::RupostError;
+
+/// 两次尝试之间的最长等待时间（包括 Retry-After 指定的时间）
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// 连接级别的自动重试策略（默认关闭）
+///
+/// 连接失败和超时总是重试，`retry_on_status` 中的状态码（如 429、502、503）也会重试。
+/// 等待时间从 `base_delay` 开始每次翻倍，响应带 Retry-After 时以其为准。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多尝试次数（包括第一次请求），1 表示不重试
+    pub max_attempts: u32,
+    /// 第一次重试前的等待时间
+    pub base_delay: Duration,
+    /// 需要重试的响应状态码
+    pub retry_on_status: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(500),
+            retry_on_status: Vec::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 1
+    }
+
+    /// 请求失败是否值得重试（连接失败或超时）
+    pub fn is_transient(error: &RupostError) -> bool {
+        match error {
+            RupostError::HttpError { source, .. } => source.is_connect() || source.is_timeout(),
+            RupostError::NetworkError(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_on_status.contains(&status)
+    }
+
+    /// 第 `attempt` 次尝试（从 1 开始）失败后的等待时间
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| {
+                let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+                self.base_delay.saturating_mul(factor)
+            })
+            .min(MAX_RETRY_DELAY)
+    }
+}
+
+/// 解析 Retry-After header（秒数或 HTTP 日期）
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.timestamp() - chrono::Utc::now().timestamp();
+    Some(Duration::from_secs(wait.max(0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            retry_on_status: vec![503],
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(400));
+        assert_eq!(policy.delay(40, None), MAX_RETRY_DELAY);
+        assert_eq!(
+            policy.delay(3, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(3600))),
+            MAX_RETRY_DELAY
+        );
+        assert!(policy.retries_status(503));
+        assert!(!policy.retries_status(500));
+        assert!(!RetryPolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        // 过去的日期表示立即重试
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+}
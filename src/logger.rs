@@ -1,5 +1,13 @@
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, fmt};
 
+use crate::Result;
+
 /// 初始化日志系统
 ///
 /// 支持通过 RUST_LOG 环境变量控制日志级别
@@ -9,16 +17,43 @@ use tracing_subscriber::{EnvFilter, fmt};
 /// - RUST_LOG=debug cargo run
 /// - RUST_LOG=trace cargo run
 pub fn init_logger() {
+    // 不写文件时不会失败
+    let _ = init_logger_with_file(None);
+}
+
+/// 初始化日志系统，并可额外以 JSON 格式追加写入日志文件（--log-file）
+///
+/// 控制台输出保持不变，两者共用 RUST_LOG 过滤级别。文件不经过缓冲，
+/// 每条日志立即写入，因此 `process::exit` 退出时也不会丢失。
+pub fn init_logger_with_file(log_file: Option<&Path>) -> Result<()> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
-    fmt()
-        .with_env_filter(filter)
+    let console_layer = fmt::layer()
         .with_target(true)
         .with_thread_ids(false)
         .with_file(false)
         .with_line_number(false)
-        .with_writer(std::io::stderr)
+        .with_writer(std::io::stderr);
+
+    let file_layer = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Some(
+                fmt::layer()
+                    .json()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(file)),
+            )
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(console_layer)
+        .with(file_layer)
         .init();
 
     tracing::info!("Logger initialized");
+    Ok(())
 }
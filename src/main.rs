@@ -2,59 +2,325 @@ mod cli;
 
 use clap::Parser;
 use cli::{Cli, Commands};
-use rupost::Result;
+use rupost::runner::TestOutcome;
+use rupost::{Result, RupostError};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 退出码（见 `rupost test --help`）：断言或状态码未通过
+const EXIT_FAILED: i32 = 1;
+/// 文件无法解析、配置或命令行参数无效（与 clap 的用法错误一致）
+const EXIT_INVALID: i32 = 2;
+/// 所有请求都没有收到响应
+const EXIT_ALL_ERRORED: i32 = 3;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    // 打印错误及其原因，配置错误等会带上位置和查找过的路径
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("Error: {}", e.full_message());
+        std::process::exit(error_exit_code(&e));
+    }
+}
+
+/// 网络、IO 和捕获错误为 1，其余（解析、配置、URL、无效的参数）为 2
+fn error_exit_code(error: &RupostError) -> i32 {
+    match error {
+        RupostError::HttpError { .. }
+        | RupostError::NetworkError(_)
+        | RupostError::IoError(_)
+        | RupostError::CaptureError { .. } => EXIT_FAILED,
+        _ => EXIT_INVALID,
+    }
+}
+
+/// 测试结果对应的退出码，--exit-zero 时测试失败也返回 0
+fn outcome_exit_code(outcome: TestOutcome, exit_zero: bool) -> i32 {
+    match outcome {
+        _ if exit_zero => 0,
+        TestOutcome::Passed => 0,
+        TestOutcome::Failed => EXIT_FAILED,
+        TestOutcome::AllErrored => EXIT_ALL_ERRORED,
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
     // 初始化日志系统
-    rupost::logger::init_logger();
+    rupost::logger::init_logger_with_file(cli.log_file.as_deref())?;
+    if let Some(path) = cli.config {
+        let _ = CONFIG_PATH.set(path);
+    }
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
 
-    let cli = Cli::parse();
     match cli.command {
         Some(Commands::Test {
-            path,
+            paths,
             env,
             var,
+            var_file,
             verbose,
+            strict_vars,
+            strict,
+            base_url,
+            allow_exec,
+            repeat,
+            list,
+            no_redirect,
+            max_redirects,
+            group,
+            insecure,
+            cacert,
+            proxy,
+            no_proxy,
+            resolve,
+            retry,
+            max_body_bytes,
+            persist_captures,
+            exit_zero,
+            show_context,
+            seed,
         }) => {
-            run_test(&path, env.as_deref(), &var, verbose).await?;
+            use rupost::runner::{TestExecutor, TestReporter};
+            use rupost::variable::ConfigLoader;
+
+            if list {
+                // 只有指定了环境或变量时才加载配置解析 URL 中的变量
+                let var_context = if env.is_some() || var_file.is_some() || !var.is_empty() {
+                    let env = select_env(env.as_deref())?;
+                    Some(build_var_context(
+                        env.as_deref(),
+                        var_file.as_deref(),
+                        &var,
+                    )?)
+                } else {
+                    None
+                };
+                let mut ok = true;
+                for path in &paths {
+                    ok &= list_requests(path, var_context.as_ref())?;
+                }
+                if !ok {
+                    std::process::exit(EXIT_INVALID);
+                }
+                return Ok(());
+            }
+
+            // 加载配置并构建变量上下文
+            let env = select_env(env.as_deref())?;
+            let mut var_context = build_var_context(env.as_deref(), var_file.as_deref(), &var)?;
+
+            if let Some(seed) = seed {
+                var_context.set_seed(seed);
+            }
+            let config = ConfigLoader::resolve(&load_config()?.into_config(), env.as_deref())?;
+            let executor = TestExecutor::builder()
+                .client(build_client(
+                    redirect_limit(no_redirect, max_redirects),
+                    insecure,
+                    cacert.as_deref(),
+                    proxy.as_deref(),
+                    no_proxy,
+                    &resolve,
+                    retry,
+                )?)
+                .strict_vars(strict_vars)
+                .allow_exec(allow_exec)
+                .groups(group)
+                .environment(env)
+                .persist_captures(persist_captures)
+                .config(config)
+                .build();
+            let mut reporter = TestReporter::new(verbose).with_show_context(show_context);
+            if let Some(max_body_bytes) = max_body_bytes {
+                reporter = reporter.with_max_body_bytes(max_body_bytes);
+            }
+            let outcome = run_tests(
+                &paths,
+                &executor,
+                &reporter,
+                &var_context,
+                strict,
+                base_url.as_deref(),
+                repeat as usize,
+            )
+            .await?;
+            let code = outcome_exit_code(outcome, exit_zero);
+            if code != 0 {
+                std::process::exit(code);
+            }
         }
         Some(Commands::History { command }) => match command {
             cli::HistoryCommands::List { limit, reverse } => {
                 rupost::history::printer::list_history(limit, reverse)?;
             }
+            cli::HistoryCommands::Stats { since, json } => {
+                rupost::history::printer::print_stats(since.as_deref(), json)?;
+            }
+            cli::HistoryCommands::Export { format, out } => {
+                use rupost::history::exchange::{ExchangeFormat, export_history};
+                use rupost::history::storage::get_storage;
+
+                let format = format
+                    .or_else(|| out.as_deref().and_then(ExchangeFormat::from_extension))
+                    .unwrap_or(ExchangeFormat::Har);
+                let (content, count) = export_history(get_storage(), format)?;
+                match out {
+                    Some(out) => {
+                        std::fs::write(&out, content)?;
+                        println!("Exported {} entries to {}", count, out);
+                    }
+                    None => println!("{}", content),
+                }
+            }
+            cli::HistoryCommands::Import { path } => {
+                use rupost::history::exchange::import_har;
+                use rupost::history::storage::get_storage;
+
+                let (imported, skipped) = import_har(get_storage(), &path)?;
+                println!(
+                    "Imported {} entries from {} ({} duplicates skipped)",
+                    imported, path, skipped
+                );
+            }
         },
         Some(Commands::Generate(args)) => {
-            use rupost::generator::http::HttpGenerator;
+            use rupost::generator::har::HarGenerator;
+            use rupost::generator::http::{GenerateOptions, HttpGenerator, NameStyle};
+            use rupost::generator::httpie::HttpieGenerator;
+            use rupost::generator::markdown::MarkdownGenerator;
+            use rupost::generator::openapi::OpenApiGenerator;
             use rupost::history::selector::{self, SelectionStrategy};
+            use rupost::history::serialization::har::Har;
             use rupost::history::storage::get_storage;
             use std::fs;
 
+            let options = GenerateOptions {
+                dedupe: args.dedupe,
+                name_style: match args.name_style {
+                    cli::NameStyle::Path => NameStyle::Path,
+                    cli::NameStyle::Timestamp => NameStyle::Timestamp,
+                },
+                extract_base_url: !args.no_extract_base_url,
+            };
+
+            if let Some(har_path) = &args.from_har {
+                let har: Har = serde_json::from_str(&fs::read_to_string(har_path)?)?;
+                let (content, count) = HarGenerator::generate(har, args.include_assets, &options)?;
+
+                if count == 0 {
+                    tracing::warn!("No requests left to generate from {}.", har_path);
+                    return Ok(());
+                }
+
+                fs::write(&args.output_file, content)?;
+                println!(
+                    "Generated test file: {} ({} requests)",
+                    args.output_file, count
+                );
+                return Ok(());
+            }
+
+            if let Some(spec_path) = &args.from_openapi {
+                let spec = OpenApiGenerator::parse_spec(&fs::read_to_string(spec_path)?)?;
+                let (content, count) = OpenApiGenerator::generate(&spec)?;
+
+                if count == 0 {
+                    tracing::warn!("No operations found in {}.", spec_path);
+                    return Ok(());
+                }
+
+                fs::write(&args.output_file, content)?;
+                println!(
+                    "Generated test file: {} ({} requests)",
+                    args.output_file, count
+                );
+                return Ok(());
+            }
+
             let storage = get_storage();
 
             // Determine strategy
             let strategy = if args.interactive {
-                SelectionStrategy::Interactive
+                SelectionStrategy::Interactive(
+                    args.last
+                        .unwrap_or(selector::DEFAULT_INTERACTIVE_CANDIDATES),
+                )
             } else {
-                SelectionStrategy::Last(args.last)
+                SelectionStrategy::Last(args.last.unwrap_or(1))
             };
 
             // Execute selection
             let entries = selector::select_entries(storage, strategy)?;
 
             if entries.is_empty() {
-                tracing::warn!("No history found or selected to generate.");
+                if args.interactive {
+                    println!("Nothing selected");
+                } else {
+                    tracing::warn!("No history found to generate.");
+                }
                 return Ok(());
             }
 
-            let content = HttpGenerator::generate(&entries)?;
+            let (content, count) = match args.format {
+                cli::GenerateFormat::Http => HttpGenerator::generate(&entries, &options)?,
+                cli::GenerateFormat::Md => MarkdownGenerator::generate(&entries, &options)?,
+                cli::GenerateFormat::Httpie => HttpieGenerator::generate(&entries, &options)?,
+            };
             fs::write(&args.output_file, content)?;
             println!(
                 "Generated test file: {} ({} requests)",
-                args.output_file,
-                entries.len()
+                args.output_file, count
             );
         }
+        Some(Commands::Convert {
+            command,
+            out,
+            append,
+        }) => {
+            convert_curl(command, out.as_deref(), append)?;
+        }
+        Some(Commands::Export { command }) => match command {
+            cli::ExportCommands::Postman { path, out } => {
+                export_postman(&path, &out)?;
+            }
+            cli::ExportCommands::Curl {
+                path,
+                history,
+                name,
+                index,
+                env,
+                var,
+                base_url,
+            } => {
+                use rupost::generator::curl::CurlGenerator;
+
+                if let Some(id) = history {
+                    let entry = rupost::history::storage::get_storage().find(&id)?;
+                    println!("{}", CurlGenerator::from_entry(&entry));
+                } else if let Some(path) = path {
+                    export_curl(
+                        &path,
+                        name.as_deref(),
+                        index,
+                        env.as_deref(),
+                        &var,
+                        base_url.as_deref(),
+                    )?;
+                }
+            }
+        },
+        Some(Commands::Validate { paths, strict, env }) => {
+            if !validate_files(&paths, strict, env.as_deref())? {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Fmt { paths, check }) => {
+            if !format_files(&paths, check)? {
+                std::process::exit(1);
+            }
+        }
         None => {
             if cli.args.is_empty() {
                 tracing::error!("No command provided");
@@ -67,63 +333,587 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 依次运行各文件并汇总为一个摘要，所有文件先解析和校验，有文件无效时不发送任何请求
+///
+/// 每个文件使用 var_context 的副本，文件之间不共享捕获的变量，@once 请求在每个文件中各执行一次。
+async fn run_tests(
+    paths: &[String],
+    executor: &rupost::runner::TestExecutor,
+    reporter: &rupost::runner::TestReporter,
+    var_context: &rupost::variable::VariableContext,
+    strict: bool,
+    base_url: Option<&str>,
+    repeat: usize,
+) -> Result<TestOutcome> {
+    use rupost::runner::TestSummary;
+    use rupost::variable::ConfigLoader;
+    use std::path::Path;
+
+    // 1. 解析所有文件，校验请求（重复的 @name 等），--strict 时视为错误；解析错误带上文件路径
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let parsed_file = parse_request_file(Path::new(path)).and_then(|mut parsed_file| {
+            if let Some(base_url) = base_url {
+                parsed_file.apply_base_url(base_url);
+            }
+            parsed_file.validate(strict)?;
+            Ok(parsed_file)
+        });
+        let parsed_file = parsed_file.map_err(|e| match e {
+            RupostError::ParseError(message) => {
+                RupostError::ParseError(format!("{}: {}", path, message))
+            }
+            e => e,
+        })?;
+        files.push((path.as_str(), parsed_file));
+    }
+
+    // 2. 依次执行各文件
+    let mut results = Vec::new();
+    let mut file_summaries = Vec::with_capacity(files.len());
+    for (path, parsed_file) in files {
+        executor.reset_once();
+        let file_results = run_test(
+            path,
+            parsed_file,
+            executor,
+            reporter,
+            var_context.clone(),
+            repeat,
+        )
+        .await?;
+        file_summaries.push((path, TestSummary::from_results(&file_results)));
+        results.extend(file_results);
+    }
+
+    // 3. 打印摘要，多个文件时先列出各文件的结果
+    if file_summaries.len() > 1 {
+        reporter.print_file_summaries(&file_summaries);
+    }
+    let summary = TestSummary::from_results(&results);
+    reporter.print_summary(&summary);
+
+    // 4. 写入 @persist / --persist-captures 的捕获变量，后执行的请求覆盖先执行的
+    let persisted: Vec<(String, String)> = results
+        .iter()
+        .flat_map(|result| result.persisted.iter().cloned())
+        .collect();
+    if !persisted.is_empty() {
+        let path = captured_path();
+        ConfigLoader::save_captured(&path, executor.environment(), &persisted)?;
+        let mut names: Vec<&str> = persisted.iter().map(|(name, _)| name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        reporter.print_persisted(&path, &names);
+    }
+
+    Ok(TestOutcome::from_summary(&summary))
+}
+
+/// 执行一个已解析的文件，边执行边打印结果，--repeat 时各轮共享变量上下文
 async fn run_test(
     file_path: &str,
+    parsed_file: rupost::parser::ParsedFile,
+    executor: &rupost::runner::TestExecutor,
+    reporter: &rupost::runner::TestReporter,
+    mut var_context: rupost::variable::VariableContext,
+    repeat: usize,
+) -> Result<Vec<rupost::runner::TestResult>> {
+    let total = parsed_file.requests.len() * repeat;
+
+    // 打印开始信息，关闭证书校验时显示警告
+    reporter.print_header(file_path, total);
+    if executor.is_insecure() {
+        reporter.print_tls_warning(&[]);
+    } else {
+        let insecure_requests: Vec<String> = parsed_file
+            .requests
+            .iter()
+            .enumerate()
+            .filter(|(_, request)| request.metadata.insecure)
+            .map(|(index, request)| {
+                request
+                    .metadata
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("#{}", index + 1))
+            })
+            .collect();
+        if !insecure_requests.is_empty() {
+            reporter.print_tls_warning(&insecure_requests);
+        }
+    }
+
+    let progress = reporter.progress(total);
+    let mut results = Vec::with_capacity(total);
+    for iteration in 1..=repeat {
+        if repeat > 1 {
+            progress.suspend(|| reporter.print_iteration(iteration, repeat));
+        }
+        let iteration_results = executor
+            .execute_all_with_progress(parsed_file.clone(), &mut var_context, |result| {
+                progress.report(reporter, result)
+            })
+            .await?;
+        results.extend(iteration_results);
+    }
+    progress.finish();
+
+    Ok(results)
+}
+
+/// 列出文件（或目录下所有 .http / .md 文件）中的请求，不执行；有文件无法解析时返回 false
+///
+/// 传入 var_context（指定了环境或变量）时 URL 中的变量按其替换，未定义的保留原样。
+fn list_requests(
+    path: &str,
+    var_context: Option<&rupost::variable::VariableContext>,
+) -> Result<bool> {
+    use colored::Colorize;
+    use rupost::runner::TestReporter;
+    use rupost::variable::VariableResolver;
+
+    let mut files = Vec::new();
+    collect_request_files(std::path::Path::new(path), &["http", "md"], &mut files)?;
+
+    let reporter = TestReporter::default();
+    let mut ok = true;
+    for file in &files {
+        let display = file.display().to_string();
+        match parse_request_file(file) {
+            Ok(mut parsed_file) => {
+                if let Some(var_context) = var_context {
+                    for request in &mut parsed_file.requests {
+                        request.url = VariableResolver::resolve(&request.url, var_context);
+                    }
+                }
+                reporter.print_request_list(&display, &parsed_file)
+            }
+            Err(e) => {
+                ok = false;
+                println!("\n {} {}: {}", "✗".red(), display, e);
+            }
+        }
+    }
+    Ok(ok)
+}
+
+/// 检查所有请求文件并打印问题，有错误（--strict 时包括警告）时返回 false
+fn validate_files(paths: &[String], strict: bool, env: Option<&str>) -> Result<bool> {
+    use colored::Colorize;
+    use rupost::parser::lint::{Severity, lint_file};
+
+    let mut files = Vec::new();
+    for path in paths {
+        collect_request_files(std::path::Path::new(path), &["http", "md"], &mut files)?;
+    }
+
+    // 指定 --env 时检查变量能否从该环境解析
+    let var_context = env
+        .map(|env| build_var_context(Some(env), None, &[]))
+        .transpose()?;
+
+    let mut failed = 0;
+    for file in &files {
+        let report = lint_file(file, var_context.as_ref());
+        let problems = report.errors() + if strict { report.warnings() } else { 0 };
+        let display = file.display().to_string();
+
+        if problems == 0 {
+            println!(
+                " {} {} ({} requests)",
+                "✓".green(),
+                display,
+                report.requests
+            );
+        } else {
+            failed += 1;
+            println!(" {} {}", "✗".red(), display.bold());
+        }
+
+        for diagnostic in &report.diagnostics {
+            let label = match diagnostic.severity {
+                Severity::Error => "error".red(),
+                Severity::Warning => "warning".yellow(),
+            };
+            println!(
+                "   {}:{}: {}: {}",
+                display, diagnostic.line, label, diagnostic.message
+            );
+        }
+    }
+
+    let failed_label = if failed > 0 {
+        failed.to_string().red()
+    } else {
+        failed.to_string().green()
+    };
+    println!(
+        "\n{} files checked, {} with errors",
+        files.len(),
+        failed_label
+    );
+    Ok(failed == 0)
+}
+
+/// 格式化 .http 文件，--check 时只报告需要格式化的文件；有文件需要格式化（--check）
+/// 或无法解析时返回 false
+fn format_files(paths: &[String], check: bool) -> Result<bool> {
+    use colored::Colorize;
+    use rupost::parser::format::format_http;
+
+    let mut files = Vec::new();
+    for path in paths {
+        collect_request_files(std::path::Path::new(path), &["http"], &mut files)?;
+    }
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for file in &files {
+        let display = file.display();
+        if file.extension().and_then(|s| s.to_str()) != Some("http") {
+            println!(
+                " {} {} (only .http files are formatted)",
+                "-".dimmed(),
+                display
+            );
+            continue;
+        }
+
+        let content = std::fs::read_to_string(file)?;
+        let formatted = match format_http(&content) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                failed += 1;
+                println!(" {} {}: {}", "✗".red(), display, e);
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+
+        changed += 1;
+        if check {
+            println!(" {} {}", "Would reformat".yellow(), display);
+        } else {
+            std::fs::write(file, formatted)?;
+            println!(" {} {}", "Formatted".green(), display);
+        }
+    }
+
+    let verb = if check {
+        "would be reformatted"
+    } else {
+        "reformatted"
+    };
+    println!(
+        "\n{} files checked, {} {}{}",
+        files.len(),
+        changed,
+        verb,
+        if failed > 0 {
+            format!(", {} failed to parse", failed.to_string().red())
+        } else {
+            String::new()
+        }
+    );
+    Ok(failed == 0 && !(check && changed > 0))
+}
+
+/// 展开目录（递归查找指定扩展名的文件，按路径排序），文件直接加入
+fn collect_request_files(
+    path: &std::path::Path,
+    extensions: &[&str],
+    files: &mut Vec<std::path::PathBuf>,
+) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<_> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+    for entry in entries {
+        let is_request_file = entry
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| extensions.contains(&ext));
+        if entry.is_dir() || is_request_file {
+            collect_request_files(&entry, extensions, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// --config 指定的配置文件，设置后不再查找 rupost.toml
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// 加载配置：--config 指定的文件，否则合并用户配置和项目配置（见 `ConfigLoader::find_and_load`）
+fn load_config() -> Result<rupost::variable::ConfigLookup> {
+    use rupost::variable::{ConfigLoader, ConfigLookup};
+
+    match CONFIG_PATH.get() {
+        Some(path) => Ok(ConfigLookup {
+            config: Some(ConfigLoader::load_from_path(path)?),
+            searched: vec![path.clone()],
+        }),
+        None => Ok(ConfigLoader::find_and_load()?),
+    }
+}
+
+/// 持久化捕获变量的文件（与 --config 或最近的 rupost.toml 同目录）
+fn captured_path() -> PathBuf {
+    rupost::variable::ConfigLoader::captured_path(CONFIG_PATH.get().map(PathBuf::as_path))
+}
+
+/// 选择环境：--env > RUPOST_ENV > rupost.toml 的 default_environment，`--env none` 不使用环境
+fn select_env(cli_env: Option<&str>) -> Result<Option<String>> {
+    use rupost::variable::ConfigLoader;
+
+    let config = load_config()?.into_config();
+    Ok(ConfigLoader::env_name(cli_env, &config)?)
+}
+
+/// 加载配置并构建变量上下文（--env / --var-file / --var），
+/// 持久化的捕获变量（rupost.captured.toml）优先级最低
+fn build_var_context(
     env_name: Option<&str>,
+    var_file: Option<&str>,
     var_overrides: &[String],
-    verbose: bool,
-) -> Result<()> {
-    use rupost::parser::{HttpFileParser, MarkdownFileParser};
-    use rupost::runner::{TestExecutor, TestReporter, TestSummary};
+) -> Result<rupost::variable::VariableContext> {
     use rupost::variable::{ConfigLoader, VariableContext};
-    use std::path::Path;
 
-    // 1. 加载配置并构建变量上下文
-    let mut var_context = if env_name.is_some() || !var_overrides.is_empty() {
-        let config = ConfigLoader::find_and_load().unwrap_or_default();
+    let captured = ConfigLoader::load_captured(&captured_path(), env_name)?;
+    if env_name.is_none() && var_file.is_none() && var_overrides.is_empty() {
+        let mut context = VariableContext::new();
+        ConfigLoader::merge_captured(&mut context, captured);
+        return Ok(context);
+    }
+
+    // 指定了环境却没有配置文件时直接报错，而不是让 {{base_url}} 之类的变量在请求时才失败
+    let config = load_config()?.require_env(env_name)?;
 
-        // 解析 CLI 变量覆盖
-        let cli_vars: Vec<(String, String)> = var_overrides
+    // 变量文件在前，之后的 --var 覆盖同名变量
+    let mut cli_vars = match var_file {
+        Some(path) => ConfigLoader::load_var_file(path)?,
+        None => Vec::new(),
+    };
+    cli_vars.extend(
+        var_overrides
             .iter()
-            .filter_map(|s| ConfigLoader::parse_cli_var(s))
-            .collect();
+            .filter_map(|s| ConfigLoader::parse_cli_var(s)),
+    );
 
-        ConfigLoader::build_context(&config, env_name, &cli_vars)
-    } else {
-        VariableContext::new()
+    let mut context = ConfigLoader::build_context(&config, env_name, &cli_vars)?;
+    ConfigLoader::merge_captured(&mut context, captured);
+    Ok(context)
+}
+
+/// --no-redirect / --max-redirects 对应的重定向设置，None 表示不跟随重定向
+fn redirect_limit(no_redirect: bool, max_redirects: Option<usize>) -> Option<usize> {
+    (!no_redirect).then(|| max_redirects.unwrap_or(rupost::http::client::DEFAULT_MAX_REDIRECTS))
+}
+
+/// 按命令行选项和 rupost.toml 的 [http] 配置创建客户端，命令行选项优先
+///
+/// `max_redirects` 为最多跟随的重定向次数，None 时不跟随（--no-redirect）；
+/// `retry` 为 --retry 的重试次数，覆盖 `[http.retry]` 的 max_attempts。
+fn build_client(
+    max_redirects: Option<usize>,
+    insecure: bool,
+    cacert: Option<&str>,
+    proxy: Option<&str>,
+    no_proxy: bool,
+    resolve: &[String],
+    retry: Option<u32>,
+) -> Result<rupost::http::Client> {
+    use rupost::http::{Client, RetryPolicy};
+
+    let http = load_config()?.into_config().http;
+    let ca_cert = cacert.map(String::from).or(http.ca_cert);
+    let proxy = proxy.map(String::from).or(http.proxy);
+    // 命令行的 --resolve 在配置之后应用，同一主机时优先
+    let resolve = http
+        .resolve
+        .iter()
+        .map(String::as_str)
+        .chain(resolve.iter().map(String::as_str))
+        .map(str::parse)
+        .collect::<Result<Vec<rupost::http::types::ResolveOverride>>>()?;
+
+    let mut retry_policy = RetryPolicy {
+        retry_on_status: http.retry.retry_on_status,
+        ..RetryPolicy::default()
     };
+    if let Some(max_attempts) = retry
+        .map(|retries| retries.saturating_add(1))
+        .or(http.retry.max_attempts)
+    {
+        retry_policy.max_attempts = max_attempts.max(1);
+    }
+    if let Some(delay) = http.retry.base_delay_ms {
+        retry_policy.base_delay = std::time::Duration::from_millis(delay);
+    }
 
-    // 2. 根据文件扩展名选择解析器
-    let path = Path::new(file_path);
-    let parsed_file = if path.extension().and_then(|s| s.to_str()) == Some("md") {
-        MarkdownFileParser::parse_file(path)?
+    let mut builder = Client::builder()
+        .follow_redirects(max_redirects.is_some())
+        .insecure(insecure || http.insecure)
+        .ca_cert(ca_cert.map(std::path::PathBuf::from))
+        .proxy(proxy)
+        .no_proxy(no_proxy)
+        .resolve(resolve)
+        .retry(retry_policy);
+    if let Some(max_redirects) = max_redirects {
+        builder = builder.max_redirects(max_redirects);
+    }
+    if let Some(max_body_size) = http.max_body_size {
+        builder = builder.max_body_size(max_body_size);
+    }
+    builder.build()
+}
+
+/// 根据文件扩展名选择 .md 或 .http 解析器
+fn parse_request_file(path: &std::path::Path) -> Result<rupost::parser::ParsedFile> {
+    use rupost::parser::{HttpFileParser, MarkdownFileParser};
+
+    if path.extension().and_then(|s| s.to_str()) == Some("md") {
+        Ok(MarkdownFileParser::parse_file(path)?)
     } else {
-        HttpFileParser::parse_file(path)?
-    };
+        Ok(HttpFileParser::parse_file(path)?)
+    }
+}
 
-    let total = parsed_file.requests.len();
+/// 导出为 Postman Collection，并在同目录生成各环境的 environment 文件
+fn export_postman(file_path: &str, out: &str) -> Result<()> {
+    use rupost::generator::postman::PostmanGenerator;
+    use std::fs;
+    use std::path::Path;
 
-    // 4. 创建报告器并打印开始信息
-    let reporter = TestReporter::new(verbose);
-    reporter.print_header(file_path, total);
+    let path = Path::new(file_path);
+    let parsed_file = parse_request_file(path)?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("rupost");
 
-    // 5. 执行所有请求
-    let executor = TestExecutor::new();
-    let results = executor.execute_all(parsed_file, &mut var_context).await?;
+    let collection = PostmanGenerator::collection(name, &parsed_file);
+    fs::write(out, serde_json::to_string_pretty(&collection)?)?;
+    println!(
+        "Exported Postman collection: {} ({} requests)",
+        out,
+        collection.item.len()
+    );
 
-    // 6. 打印每个结果
-    for result in &results {
-        reporter.print_result(result);
+    let config = load_config()?.into_config();
+    let out_dir = Path::new(out).parent().unwrap_or(Path::new(""));
+    for environment in PostmanGenerator::environments(&config) {
+        let env_path = out_dir.join(format!("{}.postman_environment.json", environment.name));
+        fs::write(&env_path, serde_json::to_string_pretty(&environment)?)?;
+        println!("Exported Postman environment: {}", env_path.display());
     }
 
-    // 7. 打印摘要
-    let summary = TestSummary::from_results(&results);
-    reporter.print_summary(&summary);
+    Ok(())
+}
+
+/// 打印文件中请求对应的 curl 命令（变量已解析），多个命令以空行分隔
+fn export_curl(
+    file_path: &str,
+    name: Option<&str>,
+    index: Option<usize>,
+    env_name: Option<&str>,
+    var_overrides: &[String],
+    base_url: Option<&str>,
+) -> Result<()> {
+    use rupost::generator::curl::CurlGenerator;
+    use rupost::runner::TestExecutor;
+    use rupost::variable::ConfigLoader;
+    use std::path::Path;
 
-    // 8. 设置退出码
-    if summary.failed > 0 {
-        std::process::exit(1);
+    let mut parsed_file = parse_request_file(Path::new(file_path))?;
+    if let Some(base_url) = base_url {
+        parsed_file.apply_base_url(base_url);
     }
+    let context = build_var_context(env_name, None, var_overrides)?;
+    // 与 rupost test 相同：应用 [defaults] 中的 header 和 base_url
+    let defaults = ConfigLoader::resolve(&load_config()?.into_config(), env_name)?;
+
+    let requests: Vec<_> = parsed_file
+        .requests
+        .into_iter()
+        .enumerate()
+        .filter(|(i, request)| {
+            index.is_none_or(|n| n == i + 1) && name.is_none_or(|n| request.name() == Some(n))
+        })
+        .map(|(_, request)| request)
+        .collect();
+
+    if requests.is_empty() {
+        return Err(RupostError::InvalidCliArgs(format!(
+            "No matching request found in {}",
+            file_path
+        )));
+    }
+
+    let commands: Vec<String> = requests
+        .into_iter()
+        .map(|mut request| {
+            defaults.apply(&mut request);
+            TestExecutor::resolve_request(&mut request, &context);
+            CurlGenerator::from_request(&request)
+        })
+        .collect();
+    println!("{}", commands.join("\n\n"));
+
+    Ok(())
+}
+
+/// 将 curl 命令转换为 .http 请求块，写入/追加到文件或打印到 stdout
+fn convert_curl(command: Option<String>, out: Option<&str>, append: bool) -> Result<()> {
+    use rupost::generator::http::HttpGenerator;
+    use std::fs;
+    use std::io::Read;
+
+    let command = match command {
+        Some(command) => command,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    let request = cli::convert_curl(&command)?;
+    if !request.form.is_empty() {
+        return Err(RupostError::InvalidCliArgs(
+            "Multipart forms (-F) cannot be written to .http files".to_string(),
+        ));
+    }
+    let block = HttpGenerator::format_request(&request);
+
+    let Some(out) = out else {
+        print!("{}", block);
+        return Ok(());
+    };
+
+    let content = if append {
+        match fs::read_to_string(out) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        String::new()
+    };
+    fs::write(out, HttpGenerator::append_block(&content, &block))?;
 
+    println!(
+        "Converted {} {} -> {}",
+        request.method_or_default(),
+        request.url,
+        out
+    );
     Ok(())
 }
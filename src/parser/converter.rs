@@ -1,12 +1,23 @@
 use crate::http::Request;
 use crate::parser::ParsedRequest;
+use crate::parser::types::Auth;
 use crate::{Result, RupostError};
 
 /// 将 ParsedRequest 转换为可执行的 Request
 impl TryFrom<ParsedRequest> for Request {
     type Error = RupostError;
 
-    fn try_from(parsed: ParsedRequest) -> Result<Self> {
+    fn try_from(mut parsed: ParsedRequest) -> Result<Self> {
+        // @content-type 替换请求中已有的 Content-Type header
+        if let Some(content_type) = &parsed.metadata.content_type {
+            parsed
+                .headers
+                .retain(|(key, _)| !key.eq_ignore_ascii_case("content-type"));
+            parsed
+                .headers
+                .push(("Content-Type".to_string(), content_type.clone()));
+        }
+
         // 1. 获取方法（默认 GET）
         let method = parsed.method_or_default();
 
@@ -18,9 +29,64 @@ impl TryFrom<ParsedRequest> for Request {
             request = request.with_header(key, value);
         }
 
-        // 4. 添加 body（自动推断类型）
-        if let Some(body) = &parsed.body {
-            request = add_body(request, body, &parsed.headers)?;
+        // @auth / curl -u：显式的 Authorization header 优先
+        if let Some(auth) = &parsed.metadata.auth {
+            if parsed.has_authorization_header() {
+                tracing::warn!(
+                    "Request at line {} has an explicit Authorization header, ignoring @auth {}",
+                    parsed.line_number,
+                    match auth {
+                        Auth::Basic { .. } => "basic",
+                        Auth::Bearer(_) => "bearer",
+                    }
+                );
+            } else {
+                request = match auth {
+                    Auth::Basic { user, password } => request.with_auth_basic(user, password),
+                    Auth::Bearer(token) => request.with_auth_bearer(token),
+                };
+            }
+        }
+
+        // 添加 @query 参数（发送时由 reqwest 编码）
+        for (key, value) in &parsed.metadata.queries {
+            request = request.with_query(key, value);
+        }
+
+        // @timeout 覆盖客户端默认超时
+        if let Some(timeout) = parsed.metadata.timeout {
+            request = request.with_timeout(timeout);
+        }
+
+        // @stream 流式读取响应
+        if let Some(limit) = parsed.metadata.stream {
+            request = request.with_stream(limit);
+        }
+
+        // @insecure 不校验服务器证书
+        if parsed.metadata.insecure {
+            request = request.with_insecure(true);
+        }
+
+        // @no-redirect 不跟随重定向
+        if parsed.metadata.no_redirect {
+            request = request.with_no_redirect(true);
+        }
+
+        // @resolve 覆盖该请求的 DNS 解析
+        if !parsed.metadata.resolve.is_empty() {
+            request = request.with_resolve(parsed.metadata.resolve.clone());
+        }
+
+        // 4. 添加 body（自动推断类型），multipart 表单优先
+        if !parsed.form.is_empty() {
+            request = request.with_multipart(parsed.form);
+        } else if let Some(body) = &parsed.body {
+            request = match parsed.metadata.content_type.as_deref() {
+                // @content-type 指定非 JSON 类型：按原文发送，不做 JSON 检测
+                Some(content_type) if !content_type.contains("json") => request.with_text(body),
+                _ => add_body(request, body, &parsed.headers)?,
+            };
         }
 
         Ok(request)
@@ -69,7 +135,7 @@ fn add_body(mut request: Request, body: &str, headers: &[(String, String)]) -> R
 }
 
 /// 简单的 JSON 格式检测
-fn is_json_like(s: &str) -> bool {
+pub fn is_json_like(s: &str) -> bool {
     let trimmed = s.trim();
     (trimmed.starts_with('{') && trimmed.ends_with('}'))
         || (trimmed.starts_with('[') && trimmed.ends_with(']'))
@@ -83,6 +149,7 @@ pub fn to_request(parsed: ParsedRequest) -> Result<Request> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::RequestBody;
     use crate::parser::ParsedRequest;
 
     #[test]
@@ -92,7 +159,7 @@ mod tests {
 
         let request: Request = parsed.try_into().unwrap();
         assert_eq!(request.method.as_str(), "GET");
-        assert_eq!(request.url.to_string(), "http://example.com:80/");
+        assert_eq!(request.url.to_string(), "http://example.com/");
     }
 
     #[test]
@@ -105,6 +172,43 @@ mod tests {
         assert_eq!(request.method.as_str(), "POST");
     }
 
+    #[test]
+    fn test_convert_with_timeout() {
+        let mut parsed = ParsedRequest::new(1);
+        parsed.url = "http://example.com".to_string();
+        parsed.metadata.timeout = Some(std::time::Duration::from_millis(2500));
+
+        let request: Request = parsed.try_into().unwrap();
+        assert_eq!(
+            request.timeout,
+            Some(std::time::Duration::from_millis(2500))
+        );
+    }
+
+    #[test]
+    fn test_convert_with_auth() {
+        let mut parsed = ParsedRequest::new(1);
+        parsed.url = "http://example.com".to_string();
+        parsed.metadata.auth = Some(Auth::Basic {
+            user: "admin".to_string(),
+            password: "secret".to_string(),
+        });
+
+        let request: Request = parsed.clone().try_into().unwrap();
+        assert_eq!(request.headers["authorization"], "Basic YWRtaW46c2VjcmV0");
+
+        parsed.metadata.auth = Some(Auth::Bearer("abc".to_string()));
+        let request: Request = parsed.clone().try_into().unwrap();
+        assert_eq!(request.headers["authorization"], "Bearer abc");
+
+        // 显式 header 优先
+        parsed
+            .headers
+            .push(("authorization".to_string(), "Token xyz".to_string()));
+        let request: Request = parsed.try_into().unwrap();
+        assert_eq!(request.headers["authorization"], "Token xyz");
+    }
+
     #[test]
     fn test_convert_with_headers() {
         let mut parsed = ParsedRequest::new(1);
@@ -141,6 +245,26 @@ mod tests {
         assert!(request.body.is_some());
     }
 
+    #[test]
+    fn test_convert_with_content_type_directive() {
+        let mut parsed = ParsedRequest::new(1);
+        parsed.url = "http://example.com".to_string();
+        parsed
+            .headers
+            .push(("content-type".to_string(), "application/json".to_string()));
+        parsed.metadata.content_type = Some("text/plain".to_string());
+        // 看起来像 JSON 但不会被解析和重新序列化
+        parsed.body = Some(r#"{"a":  1}"#.to_string());
+
+        let request: Request = parsed.try_into().unwrap();
+        assert_eq!(request.headers.get_all("content-type").iter().count(), 1);
+        assert_eq!(request.headers["content-type"], "text/plain");
+        let Some(RequestBody::Raw(body)) = &request.body else {
+            panic!("Expected raw body");
+        };
+        assert_eq!(body.as_bytes(), Some(r#"{"a":  1}"#.as_bytes()));
+    }
+
     #[test]
     fn test_is_json_like() {
         assert!(is_json_like(r#"{"key": "value"}"#));
@@ -0,0 +1,372 @@
+//! .http 文件的规范格式（rupost fmt）
+//!
+//! 每个请求以 `### <name>` 开始，之后依次是注释、固定顺序的元数据、请求行、
+//! headers 和 body，块之间只空一行。JSON body 以两个空格缩进重新排版。
+
+use crate::parser::http_file::{HttpFileParser, ParsedBlock};
+use crate::parser::types::{ParseResult, ParsedRequest};
+use std::time::Duration;
+
+/// 将 .http 文件内容格式化为规范格式，文件无法解析时返回第一个错误
+///
+/// `< file` 引用的请求体不会被读取，格式化后仍保留引用。
+pub fn format_http(content: &str) -> ParseResult<String> {
+    let (blocks, errors) = HttpFileParser::parse_blocks(content, None);
+    if let Some(error) = errors.into_iter().next() {
+        return Err(error);
+    }
+
+    let sections: Vec<String> = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| match block {
+            ParsedBlock::Request(request) => format_request(request),
+            ParsedBlock::Comments { title, lines } => {
+                // 文件开头的注释不需要分隔符
+                let mut section = if index == 0 && title.is_none() {
+                    String::new()
+                } else {
+                    separator(title.as_deref())
+                };
+                for line in lines {
+                    push_line(&mut section, line);
+                }
+                section
+            }
+        })
+        .collect();
+
+    Ok(sections.join("\n"))
+}
+
+fn format_request(request: &ParsedRequest) -> String {
+    let metadata = &request.metadata;
+    let mut block = separator(metadata.name.as_deref().or(metadata.title.as_deref()));
+
+    // 分隔符显示名称后，与名称不同的原标题作为注释保留
+    if let (Some(name), Some(title)) = (&metadata.name, &metadata.title)
+        && name != title
+    {
+        push_line(&mut block, &format!("# {}", title));
+    }
+    for comment in &metadata.comments {
+        push_line(&mut block, comment);
+    }
+
+    if let Some(name) = &metadata.name {
+        push_line(&mut block, &format!("@name {}", name));
+    }
+    if let Some(timeout) = metadata.timeout {
+        push_line(
+            &mut block,
+            &format!("@timeout {}", format_duration(timeout)),
+        );
+    }
+    if metadata.skip {
+        push_line(&mut block, "@skip");
+    }
+    if let Some(condition) = &metadata.skip_if {
+        push_line(&mut block, &format!("@skip-if {}", condition));
+    }
+    if !metadata.env_only.is_empty() {
+        push_line(
+            &mut block,
+            &format!("@env-only {}", metadata.env_only.join(", ")),
+        );
+    }
+    if metadata.once {
+        push_line(&mut block, "@once");
+    }
+    if metadata.insecure {
+        push_line(&mut block, "@insecure");
+    }
+    if metadata.no_redirect {
+        push_line(&mut block, "@no-redirect");
+    }
+    for entry in &metadata.resolve {
+        push_line(&mut block, &format!("@resolve {}", entry));
+    }
+    if !metadata.groups.is_empty() {
+        push_line(
+            &mut block,
+            &format!("@group {}", metadata.groups.join(", ")),
+        );
+    }
+    if let Some(auth) = &metadata.auth {
+        push_line(&mut block, &format!("@auth {}", auth));
+    }
+    if let Some(content_type) = &metadata.content_type {
+        push_line(&mut block, &format!("@content-type {}", content_type));
+    }
+    if let Some(stream) = metadata.stream {
+        let mut limits = Vec::new();
+        if let Some(lines) = stream.lines {
+            limits.push(format!("lines={}", lines));
+        }
+        if let Some(duration) = stream.duration {
+            limits.push(format!("duration={}", format_duration(duration)));
+        }
+        push_line(&mut block, &format!("@stream {}", limits.join(" ")));
+    }
+    for (var_name, command) in &metadata.pre_run {
+        push_line(
+            &mut block,
+            &format!("@pre-run {} = $({})", var_name, command),
+        );
+    }
+    for (key, value) in &metadata.queries {
+        push_line(&mut block, &format!("@query {}={}", key, value));
+    }
+    for assertion in &metadata.assertions {
+        push_line(&mut block, &format!("@assert {}", assertion));
+    }
+    if let Some(schema) = &metadata.schema {
+        push_line(&mut block, &format!("@schema {}", schema));
+    }
+    for capture in &metadata.captures {
+        push_line(
+            &mut block,
+            &format!("@capture {} from {}", capture.name, capture.source),
+        );
+    }
+    if !metadata.persist.is_empty() {
+        push_line(
+            &mut block,
+            &format!("@persist {}", metadata.persist.join(", ")),
+        );
+    }
+
+    match &request.method {
+        Some(method) => push_line(&mut block, &format!("{} {}", method, request.url)),
+        None => push_line(&mut block, &request.url),
+    }
+    for (key, value) in &request.headers {
+        push_line(&mut block, &format!("{}: {}", key, value));
+    }
+
+    if let Some(path) = &metadata.body_file {
+        block.push('\n');
+        push_line(&mut block, &format!("< {}", path));
+    } else if let Some(body) = request.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        block.push('\n');
+        push_line(
+            &mut block,
+            &pretty_json(body).unwrap_or_else(|| body.to_string()),
+        );
+    }
+
+    block
+}
+
+fn separator(title: Option<&str>) -> String {
+    match title {
+        Some(title) => format!("### {}\n", title),
+        None => "###\n".to_string(),
+    }
+}
+
+fn push_line(block: &mut String, line: &str) {
+    block.push_str(line);
+    block.push('\n');
+}
+
+/// 以能整除的最大单位输出（与 @timeout 的解析格式一致）
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis > 0 && millis.is_multiple_of(60_000) {
+        format!("{}m", millis / 60_000)
+    } else if millis.is_multiple_of(1000) {
+        format!("{}s", millis / 1000)
+    } else {
+        format!("{}ms", millis)
+    }
+}
+
+/// 以两个空格缩进重新排版 JSON，保持键的顺序和数字的原文；不是合法 JSON 时返回 None
+fn pretty_json(body: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(body).ok()?;
+
+    let newline = |output: &mut String, indent: usize| {
+        output.push('\n');
+        output.push_str(&"  ".repeat(indent));
+    };
+
+    let mut output = String::with_capacity(body.len() * 2);
+    let mut indent = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = body.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            '{' | '[' => {
+                output.push(c);
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                // 空对象/数组保持 {} / []
+                if let Some(close) = chars.next_if(|&next| matches!(next, '}' | ']')) {
+                    output.push(close);
+                } else {
+                    indent += 1;
+                    newline(&mut output, indent);
+                }
+            }
+            '}' | ']' => {
+                indent -= 1;
+                newline(&mut output, indent);
+                output.push(c);
+            }
+            ',' => {
+                output.push(c);
+                newline(&mut output, indent);
+            }
+            ':' => output.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => output.push(c),
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_json() {
+        assert_eq!(
+            pretty_json(r#"{"b": 1.50, "a": [1,2 , {}], "s": "x, {y}: \"z\"", "e": [ ]}"#).unwrap(),
+            "{\n  \"b\": 1.50,\n  \"a\": [\n    1,\n    2,\n    {}\n  ],\n  \"s\": \"x, {y}: \\\"z\\\"\",\n  \"e\": []\n}"
+        );
+        assert_eq!(pretty_json("42").unwrap(), "42");
+        assert_eq!(pretty_json(r#"{"id": {{id}}}"#), None);
+        assert_eq!(pretty_json("name=foo"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(120)), "2m");
+        assert_eq!(format_duration(Duration::from_secs(5)), "5s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+    }
+
+    #[test]
+    fn test_format_http() {
+        let content = "\
+# Shared notes for the file
+// second line
+
+### Login flow
+# @assert status == 200
+@persist token
+@capture token from body.token
+// login with the test account
+@name login
+@timeout 2m
+POST {{base_url}}/login
+content-type: application/json
+# header comment
+
+{\"user\":\"admin\",\"tags\":[\"a\",\"b\"]}
+###
+
+
+@skip-if env == prod
+@custom thing
+@query verbose=true
+GET {{base_url}}/health?full=1
+
+
+
+### Upload
+@stream lines=5 duration=1500ms
+@pre-run sig = $(./sign.sh {{user}})
+@skip
+@once
+@resolve files.example.com:443:[::1]
+@auth   Bearer {{token}}
+@content-type text/plain
+PUT {{base_url}}/files
+X-Sig: {{sig}}
+
+< ./payload.txt
+### Trailing notes
+# nothing to run here
+";
+        let expected = "\
+# Shared notes for the file
+// second line
+
+### login
+# Login flow
+// login with the test account
+# header comment
+@name login
+@timeout 2m
+@assert status == 200
+@capture token from body.token
+@persist token
+POST {{base_url}}/login
+content-type: application/json
+
+{
+  \"user\": \"admin\",
+  \"tags\": [
+    \"a\",
+    \"b\"
+  ]
+}
+
+###
+@custom thing
+@skip-if env == prod
+@query verbose=true
+GET {{base_url}}/health?full=1
+
+### Upload
+@skip
+@once
+@resolve files.example.com:443:[::1]
+@auth bearer {{token}}
+@content-type text/plain
+@stream lines=5 duration=1500ms
+@pre-run sig = $(./sign.sh {{user}})
+PUT {{base_url}}/files
+X-Sig: {{sig}}
+
+< ./payload.txt
+
+### Trailing notes
+# nothing to run here
+";
+        let formatted = format_http(content).unwrap();
+        assert_eq!(formatted, expected);
+
+        // 格式化结果是稳定的，且解析出相同的请求
+        assert_eq!(format_http(&formatted).unwrap(), formatted);
+        let before = HttpFileParser::parse_blocks(content, None).0;
+        let after = HttpFileParser::parse_blocks(&formatted, None).0;
+        assert_eq!(before.len(), after.len());
+    }
+
+    #[test]
+    fn test_format_http_invalid() {
+        assert!(format_http("fetch http://example.com").is_err());
+    }
+}
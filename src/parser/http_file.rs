@@ -1,3 +1,4 @@
+use crate::http::types::Method;
 use crate::parser::metadata;
 use crate::parser::types::{ParseError, ParseResult, ParsedFile, ParsedRequest};
 use std::path::Path;
@@ -5,100 +6,175 @@ use std::path::Path;
 /// HTTP 文件解析器
 pub struct HttpFileParser;
 
+/// `###` 分隔的一个块
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParsedBlock {
+    Request(Box<ParsedRequest>),
+    /// 没有请求行的块（只有注释或元数据），保留非空行原文
+    Comments {
+        title: Option<String>,
+        lines: Vec<String>,
+    },
+}
+
 impl HttpFileParser {
     /// 从文件路径解析
     pub fn parse_file<P: AsRef<Path>>(path: P) -> ParseResult<ParsedFile> {
         let content = std::fs::read_to_string(path.as_ref())?;
-        let mut parsed = Self::parse_content(&content)?;
+        let base_dir = path.as_ref().parent().unwrap_or(Path::new(""));
+        let mut parsed = Self::parse_content_in(&content, base_dir)?;
         parsed.source_path = Some(path.as_ref().to_path_buf());
         Ok(parsed)
     }
 
-    /// 从字符串内容解析
+    /// 从字符串内容解析，`< file` 形式的请求体相对于当前目录读取
     pub fn parse_content(content: &str) -> ParseResult<ParsedFile> {
-        let mut file = ParsedFile::new();
+        Self::parse_content_in(content, Path::new(""))
+    }
 
-        // 按 ### 分割请求块
-        let blocks = Self::split_by_separator(content);
+    /// 从字符串内容解析，`< file` 形式的请求体相对于 base_dir 读取
+    pub(crate) fn parse_content_in(content: &str, base_dir: &Path) -> ParseResult<ParsedFile> {
+        let (file, errors) = Self::parse_content_lenient(content, base_dir);
 
-        if blocks.is_empty() {
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        if file.requests.is_empty() {
             return Err(ParseError::NoRequests);
         }
 
-        for (block, start_line) in blocks {
-            if let Some(request) = Self::parse_request_block(&block, start_line)? {
-                file.add_request(request);
+        Ok(file)
+    }
+
+    /// 逐块解析，出错的请求块被跳过并收集错误，用于一次报告所有问题（rupost validate）
+    pub(crate) fn parse_content_lenient(
+        content: &str,
+        base_dir: &Path,
+    ) -> (ParsedFile, Vec<ParseError>) {
+        let mut file = ParsedFile::new();
+        let (blocks, errors) = Self::parse_blocks(content, Some(base_dir));
+
+        for block in blocks {
+            if let ParsedBlock::Request(request) = block {
+                file.add_request(*request);
             }
         }
 
-        if file.requests.is_empty() {
-            return Err(ParseError::NoRequests);
+        (file, errors)
+    }
+
+    /// 按顺序解析所有块，保留只有注释的块（rupost fmt）
+    ///
+    /// base_dir 为 None 时不读取 `< file` 引用的请求体，只记录路径。
+    pub(crate) fn parse_blocks(
+        content: &str,
+        base_dir: Option<&Path>,
+    ) -> (Vec<ParsedBlock>, Vec<ParseError>) {
+        let mut blocks = Vec::new();
+        let mut errors = Vec::new();
+
+        // 按 ### 分割请求块
+        for (block, start_line, title) in Self::split_by_separator(content) {
+            match Self::parse_request_block(&block, start_line, title.clone(), base_dir) {
+                Ok(Some(request)) => blocks.push(ParsedBlock::Request(Box::new(request))),
+                Ok(None) => blocks.push(ParsedBlock::Comments {
+                    title,
+                    lines: block
+                        .lines()
+                        .map(str::trim)
+                        .filter(|l| !l.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                }),
+                Err(error) => errors.push(error),
+            }
         }
 
-        Ok(file)
+        (blocks, errors)
     }
 
-    /// 按 ### 分隔符分割内容
-    fn split_by_separator(content: &str) -> Vec<(String, usize)> {
+    /// 按 ### 分隔符分割内容，返回块内容、起始行号和分隔符后的标题
+    fn split_by_separator(content: &str) -> Vec<(String, usize, Option<String>)> {
         let mut blocks = Vec::new();
         let mut current_block = String::new();
+        let mut current_title = None;
         let mut block_start_line = 1;
-        let mut current_line = 1;
 
-        for line in content.lines() {
+        for (index, line) in content.lines().enumerate() {
+            let current_line = index + 1;
             if line.trim().starts_with("###") {
-                // 遇到分隔符，保存当前块
-                if !current_block.trim().is_empty() {
-                    blocks.push((current_block.clone(), block_start_line));
+                // 遇到分隔符，保存当前块（只有标题的块也保留）
+                if !current_block.trim().is_empty() || current_title.is_some() {
+                    blocks.push((
+                        current_block.clone(),
+                        block_start_line,
+                        current_title.take(),
+                    ));
                 }
                 current_block.clear();
+                current_title = Some(line.trim().trim_start_matches('#').trim())
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string);
                 block_start_line = current_line + 1;
             } else {
                 current_block.push_str(line);
                 current_block.push('\n');
             }
-            current_line += 1;
         }
 
         // 添加最后一个块
-        if !current_block.trim().is_empty() {
-            blocks.push((current_block, block_start_line));
+        if !current_block.trim().is_empty() || current_title.is_some() {
+            blocks.push((current_block, block_start_line, current_title));
         }
 
         // 如果没有找到分隔符，整个内容作为一个块
         if blocks.is_empty() && !content.trim().is_empty() {
-            blocks.push((content.to_string(), 1));
+            blocks.push((content.to_string(), 1, None));
         }
 
         blocks
     }
 
-    /// 解析单个请求块
-    fn parse_request_block(block: &str, start_line: usize) -> ParseResult<Option<ParsedRequest>> {
+    /// 解析单个请求块，没有请求行（只有注释或元数据）时返回 None
+    fn parse_request_block(
+        block: &str,
+        start_line: usize,
+        title: Option<String>,
+        base_dir: Option<&Path>,
+    ) -> ParseResult<Option<ParsedRequest>> {
         let lines: Vec<&str> = block.lines().collect();
 
-        if lines.is_empty() {
+        if lines.iter().all(|l| l.trim().is_empty()) {
             return Ok(None);
         }
 
-        let mut request = ParsedRequest::new(start_line);
+        // 请求的行号指向块内第一个非空行，而不是 ### 之后的空行
+        let first_line = start_line + lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(0);
+        let mut request = ParsedRequest::new(first_line);
+        request.metadata.title = title;
         let mut line_index = 0;
         let mut current_line = start_line;
 
         // 解析元数据和跳过空行/注释
         while line_index < lines.len() {
-            let line = lines[line_index].trim();
+            let raw_line = lines[line_index].trim();
+            // 注释形式的元数据，如 `# @name login`
+            let line = Self::comment_directive(raw_line).unwrap_or(raw_line);
 
             if line.is_empty() || Self::is_comment(line) {
+                if !line.is_empty() {
+                    request.metadata.comments.push(raw_line.to_string());
+                }
                 line_index += 1;
                 current_line += 1;
                 continue;
             }
 
-            // 解析元数据
+            // 解析元数据，未识别的指令作为注释保留
             if line.starts_with('@') {
-                if let Some(metadata) = metadata::parse_metadata(line)? {
-                    metadata::apply_metadata(&metadata, &mut request.metadata);
+                match metadata::parse_metadata(line).map_err(|e| e.at_line(current_line))? {
+                    Some(metadata) => metadata::apply_metadata(&metadata, &mut request.metadata),
+                    None => request.metadata.comments.push(raw_line.to_string()),
                 }
                 line_index += 1;
                 current_line += 1;
@@ -128,8 +204,9 @@ impl HttpFileParser {
                 break;
             }
 
-            // 跳过注释
+            // 注释保留在元数据中
             if Self::is_comment(line) {
+                request.metadata.comments.push(line.to_string());
                 line_index += 1;
                 continue;
             }
@@ -146,11 +223,34 @@ impl HttpFileParser {
         if line_index < lines.len() {
             let body = lines[line_index..].join("\n");
             let body = body.trim();
-            if !body.is_empty() {
+            if let Some(path) = Self::body_file_reference(body) {
+                // 引用外部文件作为请求体，如 `< ./payload.json`
+                request.metadata.body_file = Some(path.to_string());
+                if let Some(base_dir) = base_dir {
+                    let body_line = start_line
+                        + lines[line_index..]
+                            .iter()
+                            .position(|l| !l.trim().is_empty())
+                            .map_or(line_index, |offset| line_index + offset);
+                    let content = std::fs::read_to_string(base_dir.join(path)).map_err(|e| {
+                        ParseError::BodyFile {
+                            path: path.to_string(),
+                            line: body_line,
+                            message: e.to_string(),
+                        }
+                    })?;
+                    request.body = Some(content);
+                }
+            } else if !body.is_empty() {
                 request.body = Some(body.to_string());
             }
         }
 
+        // @schema 相对于文件所在目录，执行时再读取
+        if let (Some(schema), Some(base_dir)) = (&request.metadata.schema, base_dir) {
+            request.metadata.schema_path = Some(base_dir.join(schema));
+        }
+
         // 验证 URL
         if request.url.is_empty() {
             return Err(ParseError::MissingUrl { line: start_line });
@@ -180,13 +280,19 @@ impl HttpFileParser {
                 request.method = None;
             }
             2 => {
-                // 方法 + URL
+                // 方法 + URL，已知方法不区分大小写，扩展方法（如 PURGE）必须是大写字母
                 let method = parts[0].to_uppercase();
-                let valid_methods = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
-                if !valid_methods.contains(&method.as_str()) {
-                    return Err(ParseError::InvalidMethod {
+                if let Some(reason) = Method::unsupported_reason(&method) {
+                    return Err(ParseError::UnsupportedMethod {
                         method,
                         line: line_number,
+                        reason,
+                    });
+                }
+                if !Method::NAMES.contains(&method.as_str()) && !Method::is_extension(parts[0]) {
+                    return Err(ParseError::InvalidMethod {
+                        method: parts[0].to_string(),
+                        line: line_number,
                     });
                 }
                 request.method = Some(method);
@@ -215,10 +321,28 @@ impl HttpFileParser {
         None
     }
 
+    /// 单行 `< path` 形式的请求体引用（与 `<xml>` 请求体区分）
+    fn body_file_reference(body: &str) -> Option<&str> {
+        let path = body.strip_prefix('<')?;
+        if body.contains('\n') || !path.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(path.trim()).filter(|p| !p.is_empty())
+    }
+
     /// 判断是否为注释行
     fn is_comment(line: &str) -> bool {
         line.starts_with('#') || line.starts_with("//")
     }
+
+    /// 提取注释中的元数据指令（`# @assert ...` / `// @name ...`）
+    fn comment_directive(line: &str) -> Option<&str> {
+        let rest = line
+            .strip_prefix("//")
+            .or_else(|| line.strip_prefix('#'))?
+            .trim_start();
+        rest.starts_with('@').then_some(rest)
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +360,23 @@ mod tests {
         assert_eq!(result.requests[0].url, "http://example.com");
     }
 
+    #[test]
+    fn test_parse_trace_and_connect() {
+        let result = HttpFileParser::parse_content("TRACE http://example.com/x").unwrap();
+        assert_eq!(result.requests[0].method, Some("TRACE".to_string()));
+
+        let result = HttpFileParser::parse_content("PURGE http://example.com/x").unwrap();
+        assert_eq!(result.requests[0].method, Some("PURGE".to_string()));
+        assert!(HttpFileParser::parse_content("purge http://example.com/x").is_err());
+
+        let err = HttpFileParser::parse_content("CONNECT example.com:443").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnsupportedMethod { ref method, line: 1, .. } if method == "CONNECT"
+        ));
+        assert!(err.to_string().contains("--proxy"), "{}", err);
+    }
+
     #[test]
     fn test_parse_url_only() {
         let content = "http://example.com";
@@ -383,4 +524,69 @@ POST http://example.com
         );
         assert_eq!(result.requests[0].metadata.assertions.len(), 2);
     }
+
+    #[test]
+    fn test_parse_body_file_reference() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("payload.json"),
+            "{\"name\": \"{{user}}\"}\n",
+        )
+        .unwrap();
+        let http_file = dir.path().join("api.http");
+        std::fs::write(
+            &http_file,
+            "POST http://example.com/users\nContent-Type: application/json\n\n< ./payload.json\n\n###\nPOST http://example.com/xml\n\n<user/>\n",
+        )
+        .unwrap();
+
+        // 相对于 .http 文件所在目录读取
+        let result = HttpFileParser::parse_file(&http_file).unwrap();
+        assert_eq!(
+            result.requests[0].body.as_deref(),
+            Some("{\"name\": \"{{user}}\"}\n")
+        );
+        // 内联的 XML 请求体不受影响
+        assert_eq!(result.requests[1].body.as_deref(), Some("<user/>"));
+    }
+
+    #[test]
+    fn test_parse_schema_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let http_file = dir.path().join("api.http");
+        std::fs::write(
+            &http_file,
+            "@schema ./schemas/user.json\nGET http://example.com/users/1\n",
+        )
+        .unwrap();
+
+        // 相对于 .http 文件所在目录，原文保留给 fmt
+        let result = HttpFileParser::parse_file(&http_file).unwrap();
+        let metadata = &result.requests[0].metadata;
+        assert_eq!(metadata.schema.as_deref(), Some("./schemas/user.json"));
+        assert_eq!(
+            metadata.schema_path,
+            Some(dir.path().join("./schemas/user.json"))
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_body_file() {
+        let content = "### Upload\nPOST http://example.com\nContent-Type: application/json\n\n\n< ./missing.json";
+        let err = HttpFileParser::parse_content(content).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::BodyFile { ref path, line: 6, .. } if path == "./missing.json"
+        ));
+    }
+
+    #[test]
+    fn test_parse_comment_directives() {
+        let content = "### Login\n# @name login\n// @assert status == 200\n# plain comment\nPOST http://example.com/login";
+        let result = HttpFileParser::parse_content(content).unwrap();
+        let req = &result.requests[0];
+        assert_eq!(req.metadata.name, Some("login".to_string()));
+        assert_eq!(req.metadata.assertions, vec!["status == 200"]);
+        assert_eq!(req.url, "http://example.com/login");
+    }
 }
@@ -0,0 +1,458 @@
+//! 请求文件的静态检查（rupost validate）
+//!
+//! 不发送请求，只检查文件能否解析、断言和捕获语法是否正确、URL 在替换变量后
+//! 是否有效。指定环境的变量上下文时，还会报告无法解析的 `{{变量}}`。
+//! 所有问题一次性收集，而不是在第一个错误处停止。
+
+use crate::assertion::{parse_assertion, parse_condition};
+use crate::http::types::Url;
+use crate::parser::http_file::HttpFileParser;
+use crate::parser::markdown_file::MarkdownFileParser;
+use crate::parser::types::{ParseError, ParsedFile, ParsedRequest};
+use crate::utils::json_path;
+use crate::variable::VariableResolver;
+use crate::variable::capture::{CaptureSource, VariableCapture};
+use crate::variable::types::VariableContext;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    /// 不影响执行（如重复的 @name），--strict 时视为错误
+    Warning,
+}
+
+/// 一条检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 文件中的行号，0 表示与具体行无关
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 单个文件的检查报告
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    /// 成功解析的请求数
+    pub requests: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    fn error(&mut self, line: usize, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            severity: Severity::Error,
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, line: usize, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            severity: Severity::Warning,
+            message: message.into(),
+        });
+    }
+
+    fn parse_error(&mut self, error: ParseError, severity: Severity) {
+        self.diagnostics.push(Diagnostic {
+            line: error.line().unwrap_or(0),
+            severity,
+            message: error.to_string(),
+        });
+    }
+}
+
+/// 检查 .http 或 .md 文件（按扩展名选择解析器）
+///
+/// 传入变量上下文（--env）时，检查每个请求引用的变量能否解析。
+pub fn lint_file(path: &Path, vars: Option<&VariableContext>) -> LintReport {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            let markdown = path.extension().and_then(|s| s.to_str()) == Some("md");
+            let base_dir = path.parent().unwrap_or(Path::new(""));
+            lint_content(&content, base_dir, markdown, vars)
+        }
+        Err(e) => {
+            let mut report = LintReport::default();
+            report.parse_error(ParseError::Io(e), Severity::Error);
+            report
+        }
+    }
+}
+
+/// 检查文件内容，`< file` 形式的请求体相对于 base_dir 读取
+pub fn lint_content(
+    content: &str,
+    base_dir: &Path,
+    markdown: bool,
+    vars: Option<&VariableContext>,
+) -> LintReport {
+    let (file, errors) = if markdown {
+        MarkdownFileParser::parse_content_lenient(content, base_dir)
+    } else {
+        HttpFileParser::parse_content_lenient(content, base_dir)
+    };
+
+    let mut report = LintReport {
+        requests: file.requests.len(),
+        diagnostics: Vec::new(),
+    };
+    for error in errors {
+        report.parse_error(error, Severity::Error);
+    }
+    if file.requests.is_empty() && report.diagnostics.is_empty() && !markdown {
+        report.parse_error(ParseError::NoRequests, Severity::Error);
+    }
+
+    for request in &file.requests {
+        lint_request(request, &mut report);
+    }
+    lint_file_level(&file, &mut report);
+    if let Some(context) = vars {
+        lint_variables(&file, context, &mut report);
+    }
+
+    report.diagnostics.sort_by_key(|d| d.line);
+    report
+}
+
+fn lint_file_level(file: &ParsedFile, report: &mut LintReport) {
+    for error in file.duplicate_names() {
+        report.parse_error(error, Severity::Warning);
+    }
+}
+
+/// 报告无法从变量上下文解析的变量
+///
+/// 之前请求的 @capture 和本请求的 @pre-run 在运行时才有值，视为已定义；
+/// 路径包含 `[*]` 的 @capture 还定义按下标访问的 `name.0`、`name.1` ...
+fn lint_variables(file: &ParsedFile, context: &VariableContext, report: &mut LintReport) {
+    let mut runtime: HashSet<&str> = HashSet::new();
+    let mut lists: HashSet<&str> = HashSet::new();
+    let is_runtime = |runtime: &HashSet<&str>, lists: &HashSet<&str>, name: &str| {
+        runtime.contains(name)
+            || name
+                .rsplit_once('.')
+                .is_some_and(|(base, index)| lists.contains(base) && index.parse::<usize>().is_ok())
+    };
+
+    for request in &file.requests {
+        runtime.extend(
+            request
+                .metadata
+                .pre_run
+                .iter()
+                .map(|(name, _)| name.as_str()),
+        );
+
+        let mut missing: Vec<String> = Vec::new();
+        for text in request.templated_texts() {
+            let resolved = VariableResolver::resolve(text, context);
+            for name in VariableResolver::unresolved(&resolved) {
+                if !is_runtime(&runtime, &lists, &name) && !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+        }
+        if !missing.is_empty() {
+            report.error(
+                request.line_number,
+                format!("Unresolved variables: {}", missing.join(", ")),
+            );
+        }
+
+        for capture in &request.metadata.captures {
+            runtime.insert(&capture.name);
+            if let CaptureSource::Body(path) = &capture.source
+                && json_path::has_wildcard(&json_path::split_path(path))
+            {
+                lists.insert(&capture.name);
+            }
+        }
+    }
+}
+
+fn lint_request(request: &ParsedRequest, report: &mut LintReport) {
+    let line = request.line_number;
+
+    for assertion in &request.metadata.assertions {
+        if let Err(e) = parse_assertion(&with_placeholders(assertion, "1")) {
+            report.error(line, format!("Invalid @assert '{}': {}", assertion, e));
+        }
+    }
+
+    if let Some(condition) = &request.metadata.skip_if
+        && let Err(e) = parse_condition(&with_placeholders(condition, "1"))
+    {
+        report.error(line, format!("Invalid @skip-if '{}': {}", condition, e));
+    }
+
+    for capture in &request.metadata.captures {
+        if let Err(message) = check_capture(capture) {
+            report.error(
+                line,
+                format!("Invalid @capture '{}': {}", capture.name, message),
+            );
+        }
+    }
+
+    for name in &request.metadata.persist {
+        if !request.metadata.captures.iter().any(|c| &c.name == name) {
+            report.warning(
+                line,
+                format!("@persist '{}' is not captured by this request", name),
+            );
+        }
+    }
+
+    if let Err(message) = check_url(&request.url) {
+        report.error(line, format!("Invalid URL '{}': {}", request.url, message));
+    }
+
+    if let (Some(schema), Some(path)) = (&request.metadata.schema, &request.metadata.schema_path)
+        && !path.is_file()
+    {
+        report.error(line, format!("@schema file not found: {}", schema));
+    }
+}
+
+fn check_capture(capture: &VariableCapture) -> Result<(), String> {
+    if !capture
+        .name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err("variable names may only contain letters, digits, '_', '-' and '.'".into());
+    }
+
+    match &capture.source {
+        CaptureSource::Body(path) if path.is_empty() => Err("empty body path".into()),
+        CaptureSource::Header(name) if name.is_empty() => Err("empty header name".into()),
+        _ => Ok(()),
+    }
+}
+
+/// 用占位值替换变量后检查 URL，任一占位值能得到有效 URL 即通过
+///
+/// `{{base_url}}/users` 需要主机名形式的占位值，`:{{port}}` 需要数字形式的占位值。
+/// 以 `/` 开头的相对 URL 留给 --base-url 处理。
+fn check_url(url: &str) -> Result<(), String> {
+    if url.trim_start().starts_with('/') {
+        return Ok(());
+    }
+
+    let mut last_error = String::new();
+    for placeholder in ["localhost", "1"] {
+        match Url::parse(&with_placeholders(url, placeholder)) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+/// 将所有 `{{...}}` 替换为占位值，未闭合的 `{{` 原样保留
+fn with_placeholders(input: &str, placeholder: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(placeholder);
+        rest = &rest[start + 2 + end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(content: &str) -> LintReport {
+        lint_content(content, Path::new(""), false, None)
+    }
+
+    #[test]
+    fn test_with_placeholders() {
+        assert_eq!(
+            with_placeholders("{{base_url}}/users/{{id}}", "1"),
+            "1/users/1"
+        );
+        assert_eq!(with_placeholders("a {{ b", "x"), "a {{ b");
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("{{base_url}}/users").is_ok());
+        assert!(check_url("https://{{host}}:{{port}}/api").is_ok());
+        assert!(check_url("{{scheme}}://example.com").is_ok());
+        assert!(check_url("/api/users").is_ok());
+        assert!(check_url("http://exa mple.com").is_err());
+    }
+
+    #[test]
+    fn test_lint_valid_file() {
+        let report = lint(
+            "@name login\n@assert status == {{expected}}\n@capture token from body.token\nPOST {{base_url}}/login\n\n###\n\nGET {{base_url}}/me\nAuthorization: Bearer {{token}}\n",
+        );
+        assert_eq!(report.requests, 2);
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    }
+
+    #[test]
+    fn test_lint_reports_all_problems() {
+        let content = "\
+@assert status = 200
+@capture bad/name from body.x
+GET http://[::1/path
+
+###
+
+@timeout soon
+GET https://example.com
+
+###
+
+fetch https://example.com
+
+###
+
+@name dup
+@skip-if env ==
+GET https://example.com/a
+
+###
+
+@name dup
+@persist token
+GET https://example.com/b
+";
+        let report = lint(content);
+        let lines: Vec<(usize, Severity)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.severity))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (1, Severity::Error),
+                (1, Severity::Error),
+                (1, Severity::Error),
+                (7, Severity::Error),
+                (12, Severity::Error),
+                (16, Severity::Error),
+                (22, Severity::Warning),
+                (22, Severity::Warning),
+            ],
+            "{:?}",
+            report.diagnostics
+        );
+        assert_eq!(report.errors(), 6);
+        assert_eq!(report.warnings(), 2);
+        assert_eq!(report.requests, 3);
+    }
+
+    #[test]
+    fn test_lint_schema_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("user.schema.json"), "{}").unwrap();
+        let content = "@schema ./user.schema.json\nGET https://example.com/a\n\n###\n\n@schema ./missing.json\nGET https://example.com/b\n";
+
+        let report = lint_content(content, dir.path(), false, None);
+        assert_eq!(report.diagnostics.len(), 1, "{:?}", report.diagnostics);
+        assert_eq!(report.diagnostics[0].line, 6);
+        assert!(report.diagnostics[0].message.contains("./missing.json"));
+    }
+
+    #[test]
+    fn test_lint_markdown_lines() {
+        let content = "\
+# API
+
+## Login
+
+<!-- @capture token body.token -->
+```http
+@timeout soon
+POST https://example.com/login
+```
+
+## Me
+
+```http
+GET https://example.com/me
+```
+";
+        let report = lint_content(content, Path::new(""), true, None);
+        let lines: Vec<usize> = report.diagnostics.iter().map(|d| d.line).collect();
+        // @timeout 在第 7 行；注释指令报告在代码块围栏所在的第 6 行
+        assert_eq!(lines, vec![6, 7], "{:?}", report.diagnostics);
+        assert_eq!(report.requests, 1);
+
+        let (parsed, _) = MarkdownFileParser::parse_content_lenient(content, Path::new(""));
+        assert_eq!(parsed.requests[0].line_number, 14);
+    }
+
+    #[test]
+    fn test_lint_unresolved_variables() {
+        let content = "\
+@capture token from body.token
+@capture ids from body.users[*].id
+POST {{base_url}}/login
+
+###
+
+@pre-run sig = $(./sign.sh)
+GET {{base_url}}/me?trace={{$uuid}}&page={{page:-1}}
+Authorization: Bearer {{token}}
+X-Sig: {{sig}}
+X-Ids: {{ids}} {{ids.1}} {{token.1}}
+X-Tenant: {{tenant}}
+
+{\"region\": \"{{region}}\", \"tenant\": \"{{tenant}}\"}
+";
+        let mut context = VariableContext::new();
+        context.insert("base_url", "http://localhost");
+
+        let report = lint_content(content, Path::new(""), false, Some(&context));
+        let messages: Vec<(usize, &str)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![(7, "Unresolved variables: token.1, tenant, region")],
+            "{:?}",
+            report.diagnostics
+        );
+
+        // 不指定环境时不检查变量
+        assert!(lint(content).diagnostics.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::parser::http_file::HttpFileParser;
-use crate::parser::types::{ParseResult, ParsedFile};
+use crate::parser::metadata;
+use crate::parser::types::{ParseError, ParseResult, ParsedFile};
 use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
 use std::path::Path;
 
@@ -10,37 +11,78 @@ impl MarkdownFileParser {
     /// 从文件路径解析
     pub fn parse_file<P: AsRef<Path>>(path: P) -> ParseResult<ParsedFile> {
         let content = std::fs::read_to_string(&path)?;
-        let mut parsed = Self::parse_content(&content)?;
+        let base_dir = path.as_ref().parent().unwrap_or(Path::new(""));
+        let mut parsed = Self::parse_content_in(&content, base_dir)?;
         parsed.source_path = Some(path.as_ref().to_path_buf());
         Ok(parsed)
     }
 
     /// 从字符串内容解析
     pub fn parse_content(content: &str) -> ParseResult<ParsedFile> {
-        let code_blocks = Self::extract_code_blocks(content);
+        Self::parse_content_in(content, Path::new(""))
+    }
+
+    /// 从字符串内容解析，`< file` 形式的请求体相对于 base_dir 读取
+    fn parse_content_in(content: &str, base_dir: &Path) -> ParseResult<ParsedFile> {
+        let (parsed_file, errors) = Self::parse_content_lenient(content, base_dir);
+
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(parsed_file),
+        }
+    }
 
+    /// 逐个代码块解析并收集错误（rupost validate），行号为 Markdown 文件中的行号
+    pub(crate) fn parse_content_lenient(
+        content: &str,
+        base_dir: &Path,
+    ) -> (ParsedFile, Vec<ParseError>) {
         let mut parsed_file = ParsedFile::new();
+        let mut errors = Vec::new();
 
-        for block in code_blocks {
+        for block in Self::extract_code_blocks(content) {
             // 解析代码块内容为请求
-            let mut block_parsed = HttpFileParser::parse_content(&block.content)?;
+            let (mut block_parsed, block_errors) =
+                HttpFileParser::parse_content_lenient(&block.content, base_dir);
+            if block_parsed.requests.is_empty() && block_errors.is_empty() {
+                errors.push(ParseError::NoRequests);
+            }
+            errors.extend(
+                block_errors
+                    .into_iter()
+                    .map(|e| e.offset_lines(block.fence_line)),
+            );
 
-            // 为每个请求设置名称（如果没有明确的 @name）
             for req in &mut block_parsed.requests {
+                req.line_number += block.fence_line;
+                // 为每个请求设置名称（如果没有明确的 @name）
                 if req.metadata.name.is_none() {
                     req.metadata.name = block.preceding_header.clone();
                 }
             }
 
+            // 代码块前 HTML 注释中的 @assert / @capture 指令作用于块内所有请求
+            for directive in &block.directives {
+                match metadata::parse_metadata(directive) {
+                    Ok(Some(metadata)) => {
+                        for req in &mut block_parsed.requests {
+                            metadata::apply_metadata(&metadata, &mut req.metadata);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => errors.push(e.at_line(block.fence_line)),
+                }
+            }
+
             parsed_file.requests.extend(block_parsed.requests);
         }
 
-        Ok(parsed_file)
+        (parsed_file, errors)
     }
 
     /// 提取所有 http/rest 代码块（使用 pulldown-cmark）
     fn extract_code_blocks(content: &str) -> Vec<ExtractedCodeBlock> {
-        let parser = Parser::new(content);
+        let parser = Parser::new(content).into_offset_iter();
 
         let mut blocks = Vec::new();
         let mut current_header: Option<String> = None;
@@ -48,13 +90,40 @@ impl MarkdownFileParser {
         let mut current_code = String::new();
         let mut is_capturing_header = false;
         let mut header_text = String::new();
+        let mut in_html_block = false;
+        let mut html_text = String::new();
+        // 紧邻代码块之前的 HTML 注释指令
+        let mut pending_directives: Vec<String> = Vec::new();
+
+        let mut fence_line = 0;
 
-        for event in parser {
+        for (event, range) in parser {
             match event {
                 // 标题开始
                 Event::Start(Tag::Heading { .. }) => {
                     is_capturing_header = true;
                     header_text.clear();
+                    pending_directives.clear();
+                }
+
+                // HTML 块（收集其中的注释指令）
+                Event::Start(Tag::HtmlBlock) => {
+                    in_html_block = true;
+                    html_text.clear();
+                }
+                Event::Html(html) if in_html_block => {
+                    html_text.push_str(&html);
+                }
+                Event::End(TagEnd::HtmlBlock) => {
+                    in_html_block = false;
+                    pending_directives.extend(Self::comment_directives(&html_text));
+                }
+
+                // 其他块级内容使指令不再“紧邻”代码块
+                Event::Start(
+                    Tag::Paragraph | Tag::List(_) | Tag::BlockQuote(_) | Tag::Table(_),
+                ) => {
+                    pending_directives.clear();
                 }
 
                 // 标题结束
@@ -82,20 +151,23 @@ impl MarkdownFileParser {
                     if lang_str == "http" || lang_str == "rest" {
                         in_code_block = true;
                         current_code.clear();
+                        fence_line = content[..range.start].matches('\n').count() + 1;
+                    } else {
+                        pending_directives.clear();
                     }
                 }
 
                 // 代码块结束
-                Event::End(TagEnd::CodeBlock) => {
-                    if in_code_block {
-                        blocks.push(ExtractedCodeBlock {
-                            content: current_code.clone(),
-                            preceding_header: current_header.clone(),
-                        });
-
-                        in_code_block = false;
-                        current_code.clear();
-                    }
+                Event::End(TagEnd::CodeBlock) if in_code_block => {
+                    blocks.push(ExtractedCodeBlock {
+                        content: current_code.clone(),
+                        preceding_header: current_header.clone(),
+                        directives: std::mem::take(&mut pending_directives),
+                        fence_line,
+                    });
+
+                    in_code_block = false;
+                    current_code.clear();
                 }
 
                 _ => {}
@@ -104,12 +176,40 @@ impl MarkdownFileParser {
 
         blocks
     }
+
+    /// 提取 HTML 注释（`<!-- ... -->`）中的 @assert / @capture 指令，每行一条
+    fn comment_directives(html: &str) -> Vec<String> {
+        let mut directives = Vec::new();
+        let mut rest = html;
+
+        while let Some(start) = rest.find("<!--") {
+            let after_open = &rest[start + 4..];
+            let Some(end) = after_open.find("-->") else {
+                break;
+            };
+
+            directives.extend(
+                after_open[..end]
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| line.starts_with("@assert ") || line.starts_with("@capture "))
+                    .map(str::to_string),
+            );
+            rest = &after_open[end + 3..];
+        }
+
+        directives
+    }
 }
 
 #[derive(Debug)]
 struct ExtractedCodeBlock {
     content: String,
     preceding_header: Option<String>,
+    /// 代码块前 HTML 注释中的指令
+    directives: Vec<String>,
+    /// 开始围栏（```http）所在的行号，块内第 n 行对应文件第 fence_line + n 行
+    fence_line: usize,
 }
 
 #[cfg(test)]
@@ -226,4 +326,45 @@ GET https://api.example.com
         assert_eq!(req.metadata.assertions[0], "status == 200");
         assert!(req.metadata.skip);
     }
+
+    #[test]
+    fn test_html_comment_directives() {
+        let content = r#"
+## Get User
+
+<!-- @assert status == 200 -->
+<!--
+@assert body.id exists
+@capture user_id from body.id
+-->
+```http
+# @assert headers.content-type contains "json"
+GET https://api.example.com/users/1
+```
+
+<!-- @assert status == 404 -->
+
+Some prose breaks the association.
+
+```http
+GET https://api.example.com/users/2
+```
+"#;
+        let parsed = MarkdownFileParser::parse_content(content).unwrap();
+        assert_eq!(parsed.requests.len(), 2);
+
+        let first = &parsed.requests[0];
+        assert_eq!(
+            first.metadata.assertions,
+            vec![
+                r#"headers.content-type contains "json""#,
+                "status == 200",
+                "body.id exists"
+            ]
+        );
+        assert_eq!(first.metadata.captures.len(), 1);
+
+        let second = &parsed.requests[1];
+        assert!(second.metadata.assertions.is_empty());
+    }
 }
@@ -1,5 +1,6 @@
+use crate::http::types::{ResolveOverride, StreamLimit};
 use crate::parser::types::RequestMetadata;
-use crate::parser::types::{Metadata, ParseError, ParseResult};
+use crate::parser::types::{Auth, Metadata, ParseError, ParseResult};
 use crate::variable::capture::VariableCapture;
 use std::time::Duration;
 
@@ -19,9 +20,24 @@ pub fn parse_metadata(line: &str) -> ParseResult<Option<Metadata>> {
     match directive {
         "@name" => parse_name(content).map(Some),
         "@skip" => parse_skip(content).map(Some),
+        "@skip-if" => parse_skip_if(content).map(Some),
+        "@once" => parse_once(content).map(Some),
+        "@insecure" => parse_insecure(content).map(Some),
+        "@no-redirect" => parse_no_redirect(content).map(Some),
+        "@resolve" => parse_resolve(content).map(Some),
+        "@group" => parse_group(content).map(Some),
+        "@env-only" => parse_env_only(content).map(Some),
         "@timeout" => parse_timeout(content).map(Some),
         "@assert" => parse_assert(content).map(Some),
+        "@expect-header" => parse_expect_header(content).map(Some),
         "@capture" => parse_capture(content).map(Some),
+        "@persist" => parse_persist(content).map(Some),
+        "@query" => parse_query(content).map(Some),
+        "@stream" => parse_stream(content).map(Some),
+        "@content-type" => parse_content_type(content).map(Some),
+        "@pre-run" => parse_pre_run(content).map(Some),
+        "@auth" => parse_auth(content).map(Some),
+        "@schema" => parse_schema(content).map(Some),
         _ => Ok(None), // 未识别的元数据
     }
 }
@@ -36,6 +52,34 @@ pub fn apply_metadata(metadata: &Metadata, target: &mut RequestMetadata) {
         Metadata::Skip(skip) => {
             target.skip = *skip;
         }
+        Metadata::SkipIf(condition) => {
+            target.skip_if = Some(condition.clone());
+        }
+        Metadata::Once(once) => {
+            target.once = *once;
+        }
+        Metadata::Insecure(insecure) => {
+            target.insecure = *insecure;
+        }
+        Metadata::NoRedirect(no_redirect) => {
+            target.no_redirect = *no_redirect;
+        }
+        Metadata::Resolve(entry) => {
+            target
+                .resolve
+                .retain(|existing| existing.host != entry.host);
+            target.resolve.push(entry.clone());
+        }
+        Metadata::Group(groups) => {
+            for group in groups {
+                if !target.groups.contains(group) {
+                    target.groups.push(group.clone());
+                }
+            }
+        }
+        Metadata::EnvOnly(environments) => {
+            target.env_only = environments.clone();
+        }
         Metadata::Timeout(duration) => {
             target.timeout = Some(*duration);
         }
@@ -47,6 +91,31 @@ pub fn apply_metadata(metadata: &Metadata, target: &mut RequestMetadata) {
                 .captures
                 .push(VariableCapture::parse(var_name, source));
         }
+        Metadata::Persist(names) => {
+            for name in names {
+                if !target.persist.contains(name) {
+                    target.persist.push(name.clone());
+                }
+            }
+        }
+        Metadata::Query { key, value } => {
+            target.queries.push((key.clone(), value.clone()));
+        }
+        Metadata::Stream(limit) => {
+            target.stream = Some(*limit);
+        }
+        Metadata::ContentType(content_type) => {
+            target.content_type = Some(content_type.clone());
+        }
+        Metadata::PreRun { var_name, command } => {
+            target.pre_run.push((var_name.clone(), command.clone()));
+        }
+        Metadata::Auth(auth) => {
+            target.auth = Some(auth.clone());
+        }
+        Metadata::Schema(path) => {
+            target.schema = Some(path.clone());
+        }
     }
 }
 
@@ -65,6 +134,85 @@ fn parse_skip(content: &str) -> ParseResult<Metadata> {
     Ok(Metadata::Skip(value))
 }
 
+fn parse_once(content: &str) -> ParseResult<Metadata> {
+    let value = if content.is_empty() {
+        true
+    } else {
+        content.parse::<bool>().unwrap_or(true)
+    };
+    Ok(Metadata::Once(value))
+}
+
+fn parse_insecure(content: &str) -> ParseResult<Metadata> {
+    let value = if content.is_empty() {
+        true
+    } else {
+        content.parse::<bool>().unwrap_or(true)
+    };
+    Ok(Metadata::Insecure(value))
+}
+
+fn parse_no_redirect(content: &str) -> ParseResult<Metadata> {
+    let value = if content.is_empty() {
+        true
+    } else {
+        content.parse::<bool>().unwrap_or(true)
+    };
+    Ok(Metadata::NoRedirect(value))
+}
+
+fn parse_resolve(content: &str) -> ParseResult<Metadata> {
+    // 语法: @resolve <host>:<port>:<address>
+    content
+        .parse::<ResolveOverride>()
+        .map(Metadata::Resolve)
+        .map_err(|e| ParseError::InvalidMetadata {
+            line: 0,
+            message: format!("Invalid @resolve: {}", e),
+        })
+}
+
+fn parse_group(content: &str) -> ParseResult<Metadata> {
+    // 语法: @group <name>[, <name>...]，也可以写多行
+    parse_name_list(content, "@group <name>[, <name>...]").map(Metadata::Group)
+}
+
+fn parse_persist(content: &str) -> ParseResult<Metadata> {
+    // 语法: @persist <var>[, <var>...]，变量需由同一请求的 @capture 捕获
+    parse_name_list(content, "@persist <var>[, <var>...]").map(Metadata::Persist)
+}
+
+fn parse_env_only(content: &str) -> ParseResult<Metadata> {
+    // 语法: @env-only <env>[, <env>...]
+    parse_name_list(content, "@env-only <env>[, <env>...]").map(Metadata::EnvOnly)
+}
+
+/// 解析逗号或空白分隔的名称列表，至少包含一个名称
+fn parse_name_list(content: &str, usage: &str) -> ParseResult<Vec<String>> {
+    let names: Vec<String> = content
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+    if names.is_empty() {
+        return Err(ParseError::InvalidMetadata {
+            line: 0,
+            message: format!("Invalid syntax. Expected: {}", usage),
+        });
+    }
+    Ok(names)
+}
+
+fn parse_skip_if(content: &str) -> ParseResult<Metadata> {
+    if content.is_empty() {
+        return Err(ParseError::InvalidMetadata {
+            line: 0,
+            message: "Invalid @skip-if syntax. Expected: @skip-if <condition>".to_string(),
+        });
+    }
+    Ok(Metadata::SkipIf(content.to_string()))
+}
+
 fn parse_timeout(content: &str) -> ParseResult<Metadata> {
     let duration = parse_duration(content)?;
     Ok(Metadata::Timeout(duration))
@@ -74,6 +222,44 @@ fn parse_assert(content: &str) -> ParseResult<Metadata> {
     Ok(Metadata::Assert(content.to_string()))
 }
 
+fn parse_expect_header(content: &str) -> ParseResult<Metadata> {
+    // 语法: @expect-header <Name>: <value> 或 @expect-header <Name> exists，
+    // 分别等同于 @assert headers.<name> contains "<value>" 和 @assert headers.<name> exists
+    let invalid = || {
+        ParseError::InvalidMetadata {
+        line: 0,
+        message: "Invalid @expect-header syntax. Expected: @expect-header <Name>: <value> or @expect-header <Name> exists".to_string(),
+    }
+    };
+    let valid_name = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    };
+
+    let assertion = match content.split_once(':') {
+        Some((name, value)) => {
+            let (name, value) = (name.trim(), value.trim());
+            if !valid_name(name) || value.is_empty() {
+                return Err(invalid());
+            }
+            format!(
+                "headers.{} contains \"{}\"",
+                name.to_ascii_lowercase(),
+                value
+            )
+        }
+        None => match content.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [name, "exists"] if valid_name(name) => {
+                format!("headers.{} exists", name.to_ascii_lowercase())
+            }
+            _ => return Err(invalid()),
+        },
+    };
+    Ok(Metadata::Assert(assertion))
+}
+
 fn parse_capture(content: &str) -> ParseResult<Metadata> {
     let parts: Vec<&str> = content.split_whitespace().collect();
 
@@ -91,6 +277,121 @@ fn parse_capture(content: &str) -> ParseResult<Metadata> {
     })
 }
 
+fn parse_query(content: &str) -> ParseResult<Metadata> {
+    // 语法: <key>=<value>，value 可以为空或包含 '='
+    match content.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok(Metadata::Query {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        }),
+        _ => Err(ParseError::InvalidMetadata {
+            line: 0,
+            message: "Invalid @query syntax. Expected: @query <key>=<value>".to_string(),
+        }),
+    }
+}
+
+fn parse_stream(content: &str) -> ParseResult<Metadata> {
+    // 语法: lines=<N> 和/或 duration=<时间>，至少一个
+    let invalid = |message: String| ParseError::InvalidMetadata { line: 0, message };
+    let mut limit = StreamLimit::default();
+
+    for option in content.split_whitespace() {
+        match option.split_once('=') {
+            Some(("lines", value)) => {
+                let lines = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|lines| *lines > 0)
+                    .ok_or_else(|| invalid(format!("Invalid @stream lines: {}", value)))?;
+                limit.lines = Some(lines);
+            }
+            Some(("duration", value)) => limit.duration = Some(parse_duration(value)?),
+            _ => return Err(invalid(format!("Unknown @stream option: {}", option))),
+        }
+    }
+
+    if limit == StreamLimit::default() {
+        return Err(invalid(
+            "Invalid @stream syntax. Expected: @stream lines=<N> and/or duration=<time>"
+                .to_string(),
+        ));
+    }
+    Ok(Metadata::Stream(limit))
+}
+
+fn parse_content_type(content: &str) -> ParseResult<Metadata> {
+    if content.is_empty() {
+        return Err(ParseError::InvalidMetadata {
+            line: 0,
+            message: "Invalid @content-type syntax. Expected: @content-type <mime>".to_string(),
+        });
+    }
+    Ok(Metadata::ContentType(content.to_string()))
+}
+
+fn parse_schema(content: &str) -> ParseResult<Metadata> {
+    if content.is_empty() {
+        return Err(ParseError::InvalidMetadata {
+            line: 0,
+            message: "Invalid @schema syntax. Expected: @schema <path>".to_string(),
+        });
+    }
+    Ok(Metadata::Schema(content.to_string()))
+}
+
+fn parse_auth(content: &str) -> ParseResult<Metadata> {
+    // 语法: @auth basic <user> [<password>] 或 @auth bearer <token>
+    let (scheme, rest) = content
+        .split_once(char::is_whitespace)
+        .map(|(scheme, rest)| (scheme, rest.trim()))
+        .unwrap_or((content, ""));
+
+    let auth = match scheme.to_ascii_lowercase().as_str() {
+        "basic" if !rest.is_empty() => {
+            let (user, password) = rest
+                .split_once(char::is_whitespace)
+                .map(|(user, password)| (user, password.trim()))
+                .unwrap_or((rest, ""));
+            Some(Auth::Basic {
+                user: user.to_string(),
+                password: password.to_string(),
+            })
+        }
+        "bearer" if !rest.is_empty() && !rest.contains(char::is_whitespace) => {
+            Some(Auth::Bearer(rest.to_string()))
+        }
+        _ => None,
+    };
+
+    auth.map(Metadata::Auth)
+        .ok_or_else(|| ParseError::InvalidMetadata {
+            line: 0,
+            message: "Invalid @auth syntax. Expected: @auth basic <user> <password> or @auth bearer <token>".to_string(),
+        })
+}
+
+fn parse_pre_run(content: &str) -> ParseResult<Metadata> {
+    // 语法: <var> = $(<command>)
+    let parsed = content.split_once('=').and_then(|(var_name, command)| {
+        let var_name = var_name.trim();
+        let command = command.trim().strip_prefix("$(")?.strip_suffix(')')?.trim();
+        let valid_name = !var_name.is_empty()
+            && var_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        (valid_name && !command.is_empty()).then(|| Metadata::PreRun {
+            var_name: var_name.to_string(),
+            command: command.to_string(),
+        })
+    });
+
+    parsed.ok_or_else(|| ParseError::InvalidMetadata {
+        line: 0,
+        message: "Invalid @pre-run syntax. Expected: @pre-run <var> = $(<command>)".to_string(),
+    })
+}
+
 /// 解析时间字符串（支持 "5s", "1000ms", "2m"）
 pub fn parse_duration(s: &str) -> ParseResult<Duration> {
     let s = s.trim();
@@ -131,6 +432,128 @@ mod tests {
         assert!(matches!(result, Metadata::Name(ref s) if s == "Test Request"));
     }
 
+    #[test]
+    fn test_parse_once() {
+        let result = parse_metadata("@once").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Once(true)));
+
+        let result = parse_metadata("@once false").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Once(false)));
+    }
+
+    #[test]
+    fn test_parse_resolve() {
+        let result = parse_metadata("@resolve api.example.com:443:10.0.0.5")
+            .unwrap()
+            .unwrap();
+        let Metadata::Resolve(entry) = &result else {
+            panic!("expected @resolve, got {:?}", result);
+        };
+        assert_eq!(entry.to_string(), "api.example.com:443:10.0.0.5");
+
+        // 同一主机的 @resolve 以最后一条为准
+        let mut target = RequestMetadata::default();
+        apply_metadata(&result, &mut target);
+        apply_metadata(
+            &parse_metadata("@resolve api.example.com:443:10.0.0.6")
+                .unwrap()
+                .unwrap(),
+            &mut target,
+        );
+        assert_eq!(target.resolve.len(), 1);
+        assert_eq!(
+            target.resolve[0].to_string(),
+            "api.example.com:443:10.0.0.6"
+        );
+
+        let err = parse_metadata("@resolve api.example.com:10.0.0.5").unwrap_err();
+        assert!(
+            err.to_string().contains("<host>:<port>:<address>"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_insecure() {
+        let result = parse_metadata("@insecure").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Insecure(true)));
+
+        let result = parse_metadata("@insecure false").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Insecure(false)));
+    }
+
+    #[test]
+    fn test_parse_no_redirect() {
+        let result = parse_metadata("@no-redirect").unwrap().unwrap();
+        assert!(matches!(result, Metadata::NoRedirect(true)));
+
+        let result = parse_metadata("@no-redirect false").unwrap().unwrap();
+        assert!(matches!(result, Metadata::NoRedirect(false)));
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let result = parse_metadata("@group smoke, slow regression")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(result, Metadata::Group(ref g) if g == &["smoke", "slow", "regression"]));
+        assert!(parse_metadata("@group").is_err());
+
+        let mut metadata = RequestMetadata::default();
+        apply_metadata(
+            &parse_metadata("@group smoke").unwrap().unwrap(),
+            &mut metadata,
+        );
+        apply_metadata(&result, &mut metadata);
+        assert_eq!(metadata.groups, vec!["smoke", "slow", "regression"]);
+    }
+
+    #[test]
+    fn test_parse_auth() {
+        let auth = |line: &str| match parse_metadata(line).unwrap().unwrap() {
+            Metadata::Auth(auth) => auth,
+            other => panic!("Unexpected metadata: {:?}", other),
+        };
+
+        assert_eq!(
+            auth("@auth basic {{user}} {{pass}}"),
+            Auth::Basic {
+                user: "{{user}}".to_string(),
+                password: "{{pass}}".to_string()
+            }
+        );
+        assert_eq!(
+            auth("@auth Basic admin p@ss word"),
+            Auth::Basic {
+                user: "admin".to_string(),
+                password: "p@ss word".to_string()
+            }
+        );
+        assert_eq!(
+            auth("@auth bearer {{token}}"),
+            Auth::Bearer("{{token}}".to_string())
+        );
+        // Display 与指令语法一致
+        assert_eq!(auth("@auth basic admin").to_string(), "basic admin");
+        assert_eq!(
+            auth("@auth basic admin secret").to_string(),
+            "basic admin secret"
+        );
+
+        assert!(parse_metadata("@auth").is_err());
+        assert!(parse_metadata("@auth basic").is_err());
+        assert!(parse_metadata("@auth bearer a b").is_err());
+        assert!(parse_metadata("@auth digest admin secret").is_err());
+    }
+
+    #[test]
+    fn test_parse_env_only() {
+        let result = parse_metadata("@env-only dev,staging").unwrap().unwrap();
+        assert!(matches!(result, Metadata::EnvOnly(ref e) if e == &["dev", "staging"]));
+        assert!(parse_metadata("@env-only").is_err());
+    }
+
     #[test]
     fn test_parse_skip() {
         let result = parse_metadata("@skip").unwrap().unwrap();
@@ -140,6 +563,14 @@ mod tests {
         assert!(matches!(result, Metadata::Skip(false)));
     }
 
+    #[test]
+    fn test_parse_skip_if() {
+        let result = parse_metadata("@skip-if env == prod").unwrap().unwrap();
+        assert!(matches!(result, Metadata::SkipIf(ref s) if s == "env == prod"));
+
+        assert!(parse_metadata("@skip-if").is_err());
+    }
+
     #[test]
     fn test_parse_timeout() {
         let result = parse_metadata("@timeout 5s").unwrap().unwrap();
@@ -152,6 +583,33 @@ mod tests {
         assert!(matches!(result, Metadata::Assert(ref s) if s == "status == 200"));
     }
 
+    #[test]
+    fn test_parse_expect_header() {
+        let result = parse_metadata("@expect-header Content-Type: application/json")
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(result, Metadata::Assert(ref s) if s == "headers.content-type contains \"application/json\"")
+        );
+
+        let result = parse_metadata("@expect-header X-Request-Id exists")
+            .unwrap()
+            .unwrap();
+        assert!(matches!(result, Metadata::Assert(ref s) if s == "headers.x-request-id exists"));
+
+        // 值中可以包含冒号
+        let result = parse_metadata("@expect-header Location: http://localhost:8080/users/1")
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(result, Metadata::Assert(ref s) if s == "headers.location contains \"http://localhost:8080/users/1\"")
+        );
+
+        assert!(parse_metadata("@expect-header X-Request-Id").is_err());
+        assert!(parse_metadata("@expect-header Content-Type:").is_err());
+        assert!(parse_metadata("@expect-header Bad Name: value").is_err());
+    }
+
     #[test]
     fn test_parse_capture() {
         let result = parse_metadata("@capture token from body.token")
@@ -164,12 +622,121 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_persist() {
+        let result = parse_metadata("@persist token, user_id").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Persist(ref names) if names == &["token", "user_id"]));
+        assert!(parse_metadata("@persist").is_err());
+
+        let mut metadata = RequestMetadata::default();
+        apply_metadata(&result, &mut metadata);
+        apply_metadata(
+            &parse_metadata("@persist token").unwrap().unwrap(),
+            &mut metadata,
+        );
+        assert_eq!(metadata.persist, vec!["token", "user_id"]);
+    }
+
     #[test]
     fn test_parse_capture_invalid() {
         let result = parse_metadata("@capture invalid syntax");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_query() {
+        let result = parse_metadata("@query filter=a=b c").unwrap().unwrap();
+        assert!(matches!(
+            result,
+            Metadata::Query { ref key, ref value } if key == "filter" && value == "a=b c"
+        ));
+
+        let result = parse_metadata("@query empty=").unwrap().unwrap();
+        assert!(matches!(result, Metadata::Query { ref value, .. } if value.is_empty()));
+
+        assert!(parse_metadata("@query novalue").is_err());
+        assert!(parse_metadata("@query =x").is_err());
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        let result = parse_metadata("@stream lines=5").unwrap().unwrap();
+        assert_eq!(
+            result,
+            Metadata::Stream(StreamLimit {
+                lines: Some(5),
+                duration: None,
+            })
+        );
+
+        let result = parse_metadata("@stream duration=2s lines=3")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            Metadata::Stream(StreamLimit {
+                lines: Some(3),
+                duration: Some(Duration::from_secs(2)),
+            })
+        );
+
+        assert!(parse_metadata("@stream").is_err());
+        assert!(parse_metadata("@stream lines=0").is_err());
+        assert!(parse_metadata("@stream lines=many").is_err());
+        assert!(parse_metadata("@stream duration=2").is_err());
+        assert!(parse_metadata("@stream events=5").is_err());
+    }
+
+    #[test]
+    fn test_parse_pre_run() {
+        let result = parse_metadata("@pre-run token = $(./sign.sh {{user}} \"a b\")")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            Metadata::PreRun {
+                var_name: "token".to_string(),
+                command: "./sign.sh {{user}} \"a b\"".to_string(),
+            }
+        );
+
+        // 命令本身可以包含括号
+        let result = parse_metadata("@pre-run ts=$(date +%s $(echo x))")
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(result, Metadata::PreRun { ref command, .. } if command == "date +%s $(echo x)")
+        );
+
+        assert!(parse_metadata("@pre-run token = ./sign.sh").is_err());
+        assert!(parse_metadata("@pre-run = $(./sign.sh)").is_err());
+        assert!(parse_metadata("@pre-run token = $()").is_err());
+        assert!(parse_metadata("@pre-run bad name = $(echo)").is_err());
+    }
+
+    #[test]
+    fn test_parse_content_type() {
+        let result = parse_metadata("@content-type application/xml; charset=utf-8")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            Metadata::ContentType("application/xml; charset=utf-8".to_string())
+        );
+
+        assert!(parse_metadata("@content-type").is_err());
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        let result = parse_metadata("@schema ./user.schema.json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(result, Metadata::Schema("./user.schema.json".to_string()));
+
+        assert!(parse_metadata("@schema").is_err());
+    }
+
     #[test]
     fn test_parse_unrecognized() {
         let result = parse_metadata("@unknown directive").unwrap();
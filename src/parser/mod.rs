@@ -1,5 +1,7 @@
 pub mod converter;
+pub mod format;
 pub mod http_file;
+pub mod lint;
 pub mod markdown_file;
 pub mod metadata;
 pub mod types;
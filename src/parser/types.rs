@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -16,6 +17,9 @@ pub struct ParsedRequest {
     /// 请求体（可选）
     pub body: Option<String>,
 
+    /// multipart 表单字段（curl -F），非空时代替 body 发送
+    pub form: Vec<FormPart>,
+
     /// 请求元数据
     pub metadata: RequestMetadata,
 
@@ -31,6 +35,7 @@ impl ParsedRequest {
             url: String::new(),
             headers: Vec::new(),
             body: None,
+            form: Vec::new(),
             metadata: RequestMetadata::default(),
             line_number,
         }
@@ -46,12 +51,84 @@ impl ParsedRequest {
         self.metadata.skip
     }
 
+    /// 是否属于 --group 选择的分组；未指定分组时所有请求都参与，
+    /// 指定后只有带任一所选 @group 的请求参与
+    pub fn in_groups(&self, groups: &[String]) -> bool {
+        groups.is_empty() || self.metadata.groups.iter().any(|g| groups.contains(g))
+    }
+
+    /// URL、Headers、查询参数、Content-Type、Body 和 multipart 文本字段等可以引用变量的文本
+    pub fn templated_texts(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.url)
+            .chain(self.headers.iter().map(|(_, value)| value))
+            .chain(self.metadata.queries.iter().map(|(_, value)| value))
+            .chain(self.metadata.content_type.iter())
+            .chain(self.body.iter())
+            .chain(self.form.iter().filter_map(|part| match part {
+                FormPart::Text { value, .. } => Some(value),
+                FormPart::File { .. } => None,
+            }))
+            .chain(self.metadata.auth.iter().flat_map(Auth::values))
+    }
+
+    /// 是否有显式的 Authorization header
+    pub fn has_authorization_header(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("authorization"))
+    }
+
+    /// 是否在当前环境中执行：没有 @env-only 时总是执行，否则要求环境在列表中
+    pub fn runs_in_env(&self, env: Option<&str>) -> bool {
+        self.metadata.env_only.is_empty()
+            || env.is_some_and(|env| self.metadata.env_only.iter().any(|e| e == env))
+    }
+
     /// 获取请求名称（如果有）
     pub fn name(&self) -> Option<&str> {
         self.metadata.name.as_deref()
     }
+
+    /// 为相对 URL 加上 base（--base-url）
+    ///
+    /// 以 scheme、`:` 简写（`:3000`、`:/api`）或 `{{变量}}` 开头的 URL 保持不变。
+    pub fn apply_base_url(&mut self, base: &str) {
+        let url = self.url.trim();
+        let has_scheme = url.split_once("://").is_some_and(|(scheme, _)| {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+        if has_scheme || url.starts_with(':') || url.starts_with("{{") {
+            return;
+        }
+
+        let base = base.trim().trim_end_matches('/');
+        let path = url.trim_start_matches('/');
+        self.url = if path.is_empty() {
+            base.to_string()
+        } else {
+            format!("{}/{}", base, path)
+        };
+    }
+
+    /// 拼接 @query 参数后的完整 URL（参数经过 URL 编码）
+    pub fn url_with_query(&self) -> String {
+        if self.metadata.queries.is_empty() {
+            return self.url.clone();
+        }
+
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.metadata.queries)
+            .finish();
+        let separator = if self.url.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", self.url, separator, query)
+    }
 }
 
+use crate::http::request::basic_auth_value;
+use crate::http::types::{FormPart, ResolveOverride, StreamLimit};
 use crate::variable::capture::VariableCapture;
 
 /// 请求元数据
@@ -60,9 +137,39 @@ pub struct RequestMetadata {
     /// 请求名称（@name）
     pub name: Option<String>,
 
+    /// `###` 分隔符后的标题文本
+    pub title: Option<String>,
+
+    /// 块内的普通注释和未识别的指令（rupost fmt 时保留）
+    pub comments: Vec<String>,
+
+    /// 请求体引用的文件（`< ./payload.json`），body 为读取后的内容
+    pub body_file: Option<String>,
+
     /// 是否跳过该请求（@skip）
     pub skip: bool,
 
+    /// 条件跳过表达式（@skip-if，基于变量求值）
+    pub skip_if: Option<String>,
+
+    /// 重复执行（--repeat）时只在第一轮执行（@once）
+    pub once: bool,
+
+    /// 不校验服务器证书（@insecure）
+    pub insecure: bool,
+
+    /// 不跟随重定向，3xx 响应原样返回（@no-redirect）
+    pub no_redirect: bool,
+
+    /// 将主机名解析到指定地址（@resolve host:port:addr，可重复）
+    pub resolve: Vec<ResolveOverride>,
+
+    /// 所属分组（@group，可重复），用于 --group 过滤
+    pub groups: Vec<String>,
+
+    /// 只在这些环境（--env）中执行（@env-only），为空时在所有环境执行
+    pub env_only: Vec<String>,
+
     /// 请求超时时间（@timeout，可选）
     pub timeout: Option<Duration>,
 
@@ -71,6 +178,80 @@ pub struct RequestMetadata {
 
     /// 变量捕获列表（@capture）
     pub captures: Vec<VariableCapture>,
+
+    /// 运行结束后写入 rupost.captured.toml 的捕获变量（@persist，可重复）
+    pub persist: Vec<String>,
+
+    /// Query 参数列表（@query key=value，可重复）
+    pub queries: Vec<(String, String)>,
+
+    /// 流式读取响应（@stream lines=N duration=2s）
+    pub stream: Option<StreamLimit>,
+
+    /// 强制指定 Content-Type（@content-type，非 JSON 类型按原文发送 body）
+    pub content_type: Option<String>,
+
+    /// 发送前执行的 shell 命令，stdout 写入变量（@pre-run var = $(cmd)，需 --allow-exec）
+    pub pre_run: Vec<(String, String)>,
+
+    /// 认证信息（@auth basic/bearer，curl -u），显式的 Authorization header 优先
+    pub auth: Option<Auth>,
+
+    /// 响应体需要符合的 JSON Schema 文件（@schema ./user.schema.json），按原文保存
+    pub schema: Option<String>,
+
+    /// 相对于 .http 文件所在目录解析后的 schema 路径，执行时读取
+    pub schema_path: Option<PathBuf>,
+}
+
+/// 请求认证方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Auth {
+    Basic { user: String, password: String },
+    Bearer(String),
+}
+
+impl Auth {
+    /// 可以引用变量的字段
+    pub fn values(&self) -> Vec<&String> {
+        match self {
+            Auth::Basic { user, password } => vec![user, password],
+            Auth::Bearer(token) => vec![token],
+        }
+    }
+
+    fn values_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Auth::Basic { user, password } => vec![user, password],
+            Auth::Bearer(token) => vec![token],
+        }
+    }
+
+    /// 对每个字段应用变量替换
+    pub fn map_values(&mut self, mut f: impl FnMut(&str) -> String) {
+        for value in self.values_mut() {
+            *value = f(value);
+        }
+    }
+
+    /// Authorization header 的值
+    pub fn header_value(&self) -> String {
+        match self {
+            Auth::Basic { user, password } => basic_auth_value(user, password),
+            Auth::Bearer(token) => format!("Bearer {}", token),
+        }
+    }
+}
+
+/// `@auth` 指令的内容形式：`basic <user> <password>` 或 `bearer <token>`
+impl std::fmt::Display for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::Basic { user, password } if password.is_empty() => write!(f, "basic {}", user),
+            Auth::Basic { user, password } => write!(f, "basic {} {}", user, password),
+            Auth::Bearer(token) => write!(f, "bearer {}", token),
+        }
+    }
 }
 
 /// 解析出的元数据指令（中间状态）
@@ -78,9 +259,23 @@ pub struct RequestMetadata {
 pub enum Metadata {
     Name(String),
     Skip(bool),
+    SkipIf(String),
+    Once(bool),
+    Insecure(bool),
+    NoRedirect(bool),
+    Resolve(ResolveOverride),
+    Group(Vec<String>),
+    EnvOnly(Vec<String>),
     Timeout(Duration),
     Assert(String),
     Capture { var_name: String, source: String },
+    Persist(Vec<String>),
+    Query { key: String, value: String },
+    Stream(StreamLimit),
+    ContentType(String),
+    PreRun { var_name: String, command: String },
+    Auth(Auth),
+    Schema(String),
 }
 
 /// 整个文件的解析结果
@@ -117,6 +312,49 @@ impl ParsedFile {
     pub fn active_requests(&self) -> impl Iterator<Item = &ParsedRequest> {
         self.requests.iter().filter(|r| !r.should_skip())
     }
+
+    /// 为所有相对 URL 加上 base（见 [`ParsedRequest::apply_base_url`]）
+    pub fn apply_base_url(&mut self, base: &str) {
+        for request in &mut self.requests {
+            request.apply_base_url(base);
+        }
+    }
+
+    /// 校验解析结果：重复的 @name 记录警告，严格模式下返回错误
+    pub fn validate(&self, strict: bool) -> ParseResult<()> {
+        for error in self.duplicate_names() {
+            if strict {
+                return Err(error);
+            }
+            tracing::warn!("{}", error);
+        }
+
+        Ok(())
+    }
+
+    /// 收集重复的 @name（不影响执行，默认只作为警告）
+    pub fn duplicate_names(&self) -> Vec<ParseError> {
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let mut duplicates = Vec::new();
+
+        for request in &self.requests {
+            let Some(name) = request.name().filter(|n| !n.trim().is_empty()) else {
+                continue;
+            };
+
+            if let Some(&first_line) = seen.get(name) {
+                duplicates.push(ParseError::DuplicateName {
+                    name: name.to_string(),
+                    line: request.line_number,
+                    first_line,
+                });
+            } else {
+                seen.insert(name, request.line_number);
+            }
+        }
+
+        duplicates
+    }
 }
 
 impl Default for ParsedFile {
@@ -144,10 +382,34 @@ pub enum ParseError {
     #[error("Invalid HTTP method '{method}' at line {line}")]
     InvalidMethod { method: String, line: usize },
 
+    /// 有意不支持的 HTTP 方法（如 CONNECT），附带原因
+    #[error("Unsupported HTTP method '{method}' at line {line}: {reason}")]
+    UnsupportedMethod {
+        method: String,
+        line: usize,
+        reason: &'static str,
+    },
+
     /// 无效的 Header 格式
     #[error("Invalid header format at line {line}: expected 'Key: Value'")]
     InvalidHeader { line: usize },
 
+    /// 重复的请求名称
+    #[error("Duplicate request name '{name}' at line {line} (first defined at line {first_line})")]
+    DuplicateName {
+        name: String,
+        line: usize,
+        first_line: usize,
+    },
+
+    /// 无法读取请求体引用的文件（`< ./payload.json`）
+    #[error("Cannot read body file '{path}' at line {line}: {message}")]
+    BodyFile {
+        path: String,
+        line: usize,
+        message: String,
+    },
+
     /// IO 错误
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -157,6 +419,54 @@ pub enum ParseError {
     NoRequests,
 }
 
+impl ParseError {
+    /// 错误所在的行号（IO 错误和空文件没有行号）
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseError::InvalidFormat { line, .. }
+            | ParseError::MissingUrl { line }
+            | ParseError::InvalidMetadata { line, .. }
+            | ParseError::InvalidMethod { line, .. }
+            | ParseError::UnsupportedMethod { line, .. }
+            | ParseError::InvalidHeader { line }
+            | ParseError::DuplicateName { line, .. }
+            | ParseError::BodyFile { line, .. } => Some(*line),
+            ParseError::Io(_) | ParseError::NoRequests => None,
+        }
+    }
+
+    /// 元数据解析时不知道行号（为 0），由调用方补上
+    pub(crate) fn at_line(mut self, line_number: usize) -> Self {
+        if let ParseError::InvalidMetadata { line, .. } = &mut self
+            && *line == 0
+        {
+            *line = line_number;
+        }
+        self
+    }
+
+    /// 行号整体平移（Markdown 代码块内的行号换算为文件中的行号）
+    pub(crate) fn offset_lines(mut self, offset: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidFormat { line, .. }
+            | ParseError::MissingUrl { line }
+            | ParseError::InvalidMetadata { line, .. }
+            | ParseError::InvalidMethod { line, .. }
+            | ParseError::UnsupportedMethod { line, .. }
+            | ParseError::InvalidHeader { line }
+            | ParseError::BodyFile { line, .. } => *line += offset,
+            ParseError::DuplicateName {
+                line, first_line, ..
+            } => {
+                *line += offset;
+                *first_line += offset;
+            }
+            ParseError::Io(_) | ParseError::NoRequests => {}
+        }
+        self
+    }
+}
+
 /// 解析结果类型别名
 pub type ParseResult<T> = Result<T, ParseError>;
 
@@ -164,6 +474,67 @@ pub type ParseResult<T> = Result<T, ParseError>;
 mod tests {
     use super::*;
 
+    fn named_request(line: usize, name: Option<&str>) -> ParsedRequest {
+        let mut request = ParsedRequest::new(line);
+        request.url = "http://example.com".to_string();
+        request.metadata.name = name.map(|n| n.to_string());
+        request
+    }
+
+    #[test]
+    fn test_validate_duplicate_names() {
+        let mut file = ParsedFile::new();
+        file.add_request(named_request(1, Some("login")));
+        file.add_request(named_request(5, None));
+        file.add_request(named_request(9, None));
+        file.add_request(named_request(13, Some("login")));
+
+        // 非严格模式只记录警告
+        assert!(file.validate(false).is_ok());
+
+        let err = file.validate(true).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::DuplicateName { ref name, line: 13, first_line: 1 } if name == "login"
+        ));
+    }
+
+    #[test]
+    fn test_validate_unique_names() {
+        let mut file = ParsedFile::new();
+        file.add_request(named_request(1, Some("a")));
+        file.add_request(named_request(5, Some("b")));
+        assert!(file.validate(true).is_ok());
+    }
+
+    #[test]
+    fn test_apply_base_url() {
+        let cases = [
+            ("/api/users", "http://localhost:3000/api/users"),
+            ("api/users?page=2", "http://localhost:3000/api/users?page=2"),
+            ("/", "http://localhost:3000"),
+            ("https://example.com/a", "https://example.com/a"),
+            (":3000/api", ":3000/api"),
+            (":/api", ":/api"),
+            ("{{base_url}}/api", "{{base_url}}/api"),
+        ];
+        for (url, expected) in cases {
+            let mut request = ParsedRequest::new(1);
+            request.url = url.to_string();
+            request.apply_base_url("http://localhost:3000/");
+            assert_eq!(request.url, expected, "url: {}", url);
+        }
+
+        let mut file = ParsedFile::new();
+        file.add_request(named_request(1, None));
+        let mut relative = ParsedRequest::new(3);
+        relative.url = "//health".to_string();
+        file.add_request(relative);
+        file.apply_base_url("https://api.example.com/v1");
+        assert_eq!(file.requests[0].url, "http://example.com");
+        assert_eq!(file.requests[1].url, "https://api.example.com/v1/health");
+    }
+
     #[test]
     fn test_parsed_request_new() {
         let req = ParsedRequest::new(1);
@@ -215,4 +586,30 @@ mod tests {
         assert_eq!(active.len(), 1);
         assert_eq!(active[0].url, "http://example.com");
     }
+
+    #[test]
+    fn test_in_groups() {
+        let mut req = ParsedRequest::new(1);
+        let groups = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(req.in_groups(&[]));
+        assert!(!req.in_groups(&groups(&["smoke"])));
+
+        req.metadata.groups = groups(&["smoke", "auth"]);
+        assert!(req.in_groups(&[]));
+        assert!(req.in_groups(&groups(&["slow", "auth"])));
+        assert!(!req.in_groups(&groups(&["slow"])));
+    }
+
+    #[test]
+    fn test_runs_in_env() {
+        let mut req = ParsedRequest::new(1);
+        assert!(req.runs_in_env(None));
+        assert!(req.runs_in_env(Some("prod")));
+
+        req.metadata.env_only = vec!["dev".to_string(), "staging".to_string()];
+        assert!(req.runs_in_env(Some("staging")));
+        assert!(!req.runs_in_env(Some("prod")));
+        assert!(!req.runs_in_env(None));
+    }
 }
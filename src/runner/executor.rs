@@ -1,23 +1,232 @@
 use crate::Result;
-use crate::assertion::{AssertionResult, evaluate_assertion, parse_assertion};
+use crate::assertion::{
+    AssertionResult, evaluate_assertion, evaluate_condition, parse_assertion, parse_condition,
+    validate_schema,
+};
 use crate::history::model::RequestSnapshot;
-use crate::http::Client;
+use crate::http::types::FormPart;
+use crate::http::{Client, DownloadTarget, Request};
 use crate::parser::{ParsedFile, ParsedRequest};
 use crate::runner::types::TestResult;
-use crate::variable::{VariableContext, VariableResolver, capture_from_response};
+use crate::variable::{ResolvedConfig, VariableContext, VariableResolver, capture_from_response};
 use reqwest::header::{HeaderName, HeaderValue};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tracing::{error, info};
 
+/// 每得到一个执行结果时调用的回调
+pub type ResultCallback = Arc<dyn Fn(&TestResult) + Send + Sync>;
+
+/// 请求执行选项，由 [`TestExecutor::builder`] 设置
+#[derive(Clone)]
+pub struct ExecutionOptions {
+    /// 变量解析后仍残留 {{...}} 时直接判定请求失败
+    pub strict_vars: bool,
+    /// 是否为每个请求写入历史记录（默认开启）
+    pub record_history: bool,
+    /// 第一个失败的请求之后不再执行，其余请求报告为跳过
+    pub bail_on_failure: bool,
+    /// 是否允许 @pre-run 执行 shell 命令（--allow-exec）
+    pub allow_exec: bool,
+    /// --group 选择的分组，为空时执行所有请求
+    pub groups: Vec<String>,
+    /// 当前环境名称（--env），用于 @env-only
+    pub environment: Option<String>,
+    /// 持久化所有捕获的变量，而不只是 @persist 指定的（--persist-captures）
+    pub persist_captures: bool,
+    /// execute_all 每得到一个结果（包括跳过的请求）时调用
+    pub on_result: Option<ResultCallback>,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self {
+            strict_vars: false,
+            record_history: true,
+            bail_on_failure: false,
+            allow_exec: false,
+            groups: Vec::new(),
+            environment: None,
+            persist_captures: false,
+            on_result: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExecutionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionOptions")
+            .field("strict_vars", &self.strict_vars)
+            .field("record_history", &self.record_history)
+            .field("bail_on_failure", &self.bail_on_failure)
+            .field("allow_exec", &self.allow_exec)
+            .field("groups", &self.groups)
+            .field("environment", &self.environment)
+            .field("persist_captures", &self.persist_captures)
+            .field("on_result", &self.on_result.is_some())
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct TestExecutor {
     client: Client,
+    /// 配置文件中的请求默认值（header、超时和 base URL）
+    defaults: ResolvedConfig,
+    /// 已执行过的 @once 请求，多次调用 execute_all 时不再执行
+    once_done: Arc<Mutex<HashSet<String>>>,
+    /// 将响应 body 写入文件（-o / --download）
+    download: Option<DownloadTarget>,
+    options: ExecutionOptions,
+}
+
+/// [`TestExecutor`] 的构建器
+#[derive(Clone, Default)]
+pub struct TestExecutorBuilder {
+    client: Option<Client>,
+    defaults: ResolvedConfig,
+    download: Option<DownloadTarget>,
+    options: ExecutionOptions,
+}
+
+impl TestExecutorBuilder {
+    /// 使用自定义的客户端（代理、TLS、重试等），默认为 [`Client::new`]
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// 合并请求默认值（见 [`ConfigLoader::resolve`](crate::variable::ConfigLoader::resolve)），
+    /// 与之前设置的默认值合并而不是替换
+    pub fn config(mut self, config: ResolvedConfig) -> Self {
+        self.defaults.merge(config);
+        self
+    }
+
+    /// 添加默认请求头，请求中未定义的 header 会被补上；同名 header 以后设置的为准
+    pub fn default_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.defaults.merge(ResolvedConfig {
+            headers: headers.into_iter().collect(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// 整体替换执行选项
+    pub fn options(mut self, options: ExecutionOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 是否记录请求历史（默认开启）
+    pub fn record_history(mut self, record_history: bool) -> Self {
+        self.options.record_history = record_history;
+        self
+    }
+
+    /// 存在未解析的变量时不发送请求，直接判定失败
+    pub fn strict_vars(mut self, strict_vars: bool) -> Self {
+        self.options.strict_vars = strict_vars;
+        self
+    }
+
+    /// 第一个失败的请求之后不再执行，其余请求报告为跳过
+    pub fn bail_on_failure(mut self, bail_on_failure: bool) -> Self {
+        self.options.bail_on_failure = bail_on_failure;
+        self
+    }
+
+    /// 允许 @pre-run 执行 shell 命令（--allow-exec）
+    pub fn allow_exec(mut self, allow_exec: bool) -> Self {
+        self.options.allow_exec = allow_exec;
+        self
+    }
+
+    /// 只执行带有任一指定 @group 的请求（--group），其余报告为跳过
+    pub fn groups(mut self, groups: Vec<String>) -> Self {
+        self.options.groups = groups;
+        self
+    }
+
+    /// 当前环境名称（--env），带 @env-only 且不包含该环境的请求报告为跳过
+    pub fn environment(mut self, environment: Option<String>) -> Self {
+        self.options.environment = environment;
+        self
+    }
+
+    /// 将所有捕获的变量标记为需要持久化（--persist-captures），默认只有 @persist 指定的
+    pub fn persist_captures(mut self, persist_captures: bool) -> Self {
+        self.options.persist_captures = persist_captures;
+        self
+    }
+
+    /// 将响应 body 直接写入文件而不读入内存（-o / --download）
+    pub fn download(mut self, download: Option<DownloadTarget>) -> Self {
+        self.download = download;
+        self
+    }
+
+    /// execute_all 每得到一个结果（包括跳过的请求）时调用
+    pub fn on_result(mut self, callback: impl Fn(&TestResult) + Send + Sync + 'static) -> Self {
+        self.options.on_result = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn build(self) -> TestExecutor {
+        TestExecutor {
+            client: self.client.unwrap_or_default(),
+            defaults: self.defaults,
+            once_done: Arc::default(),
+            download: self.download,
+            options: self.options,
+        }
+    }
 }
 
 impl TestExecutor {
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+        Self::builder().build()
+    }
+
+    /// 创建构建器
+    ///
+    /// ```no_run
+    /// use rupost::http::Client;
+    /// use rupost::runner::TestExecutor;
+    ///
+    /// let executor = TestExecutor::builder()
+    ///     .client(Client::builder().insecure(true).build()?)
+    ///     .record_history(false)
+    ///     .strict_vars(true)
+    ///     .bail_on_failure(true)
+    ///     .on_result(|result| println!("#{} {}", result.request_number, result.success))
+    ///     .build();
+    /// assert!(executor.options().bail_on_failure);
+    /// # Ok::<(), rupost::RupostError>(())
+    /// ```
+    pub fn builder() -> TestExecutorBuilder {
+        TestExecutorBuilder::default()
+    }
+
+    /// 当前的执行选项
+    pub fn options(&self) -> &ExecutionOptions {
+        &self.options
+    }
+
+    /// 是否对所有请求关闭证书校验（--insecure）
+    pub fn is_insecure(&self) -> bool {
+        self.client.is_insecure()
+    }
+
+    /// 清除已执行的 @once 请求记录，运行下一个文件前调用，使其 @once 请求重新执行
+    pub fn reset_once(&self) {
+        self.once_done.lock().unwrap().clear();
+    }
+
+    /// 当前环境名称（--env）
+    pub fn environment(&self) -> Option<&str> {
+        self.options.environment.as_deref()
     }
 
     /// 批量执行所有请求
@@ -25,30 +234,92 @@ impl TestExecutor {
         &self,
         parsed_file: ParsedFile,
         context: &mut VariableContext,
+    ) -> Result<Vec<TestResult>> {
+        self.execute_all_with_progress(parsed_file, context, |_| {})
+            .await
+    }
+
+    /// 批量执行所有请求，每得到一个结果（包括跳过的请求）调用一次 on_result
+    /// （在选项中的回调之后调用）
+    ///
+    /// 设置了 bail_on_failure 时，第一个失败的请求之后的请求都报告为跳过。
+    pub async fn execute_all_with_progress(
+        &self,
+        parsed_file: ParsedFile,
+        context: &mut VariableContext,
+        mut on_result: impl FnMut(&TestResult),
     ) -> Result<Vec<TestResult>> {
         let mut results = Vec::new();
+        let mut report = |result: &TestResult| {
+            if let Some(callback) = &self.options.on_result {
+                callback(result);
+            }
+            on_result(result);
+        };
+        let mut bailed = false;
 
-        // Determine source once for the file
-        let source = parsed_file
-            .source_path
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| "file".to_string());
+        // 历史记录来源：文件路径，execute_one 再附加请求名称
+        let source = match &parsed_file.source_path {
+            Some(path) => format!("file:{}", path.to_string_lossy()),
+            None => "file".to_string(),
+        };
 
         for (index, parsed_request) in parsed_file.requests.into_iter().enumerate() {
             let request_number = index + 1;
 
-            // 检查是否跳过
-            if parsed_request.should_skip() {
-                results.push(TestResult::skipped(
+            // @once 请求以名称（无名称时以序号）标识，已执行过则跳过，捕获的变量保留在上下文中
+            let once_identity = parsed_request.metadata.once.then(|| {
+                parsed_request
+                    .name()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("#{}", request_number))
+            });
+            let already_run = once_identity
+                .as_ref()
+                .is_some_and(|identity| self.once_done.lock().unwrap().contains(identity));
+
+            // 检查是否跳过（之前的请求失败且 bail_on_failure、不在 --group 中、不在 @env-only
+            // 环境中、@skip、@skip-if 条件成立或 @once 已执行）
+            let skip = bailed
+                || already_run
+                || !parsed_request.in_groups(&self.options.groups)
+                || !parsed_request.runs_in_env(self.options.environment.as_deref())
+                || parsed_request.should_skip();
+            let skip = skip
+                || match Self::skip_condition_met(&parsed_request, context) {
+                    Ok(met) => met,
+                    // 条件无效时不发送请求，作为错误报告
+                    Err(message) => {
+                        let result = TestResult::error(
+                            request_number,
+                            parsed_request.name().map(|s| s.to_string()),
+                            parsed_request.method_or_default().to_string(),
+                            parsed_request.url.clone(),
+                            message,
+                            std::time::Duration::ZERO,
+                        );
+                        report(&result);
+                        bailed = self.options.bail_on_failure;
+                        results.push(result);
+                        continue;
+                    }
+                };
+            if skip {
+                let result = TestResult::skipped(
                     request_number,
                     parsed_request.name().map(|s| s.to_string()),
                     parsed_request.method_or_default().to_string(),
                     parsed_request.url.clone(),
-                ));
+                );
+                report(&result);
+                results.push(result);
                 continue;
             }
 
+            if let Some(identity) = once_identity {
+                self.once_done.lock().unwrap().insert(identity);
+            }
+
             let result = self
                 .execute_one(
                     parsed_request,
@@ -57,21 +328,73 @@ impl TestExecutor {
                     Some(source.clone()),
                 )
                 .await;
+            report(&result);
+            bailed = self.options.bail_on_failure && !result.success;
             results.push(result);
         }
 
         Ok(results)
     }
 
-    /// 执行单个请求
-    pub async fn execute_one(
+    /// 求值 @skip-if 条件，条件无效时返回错误信息
+    fn skip_condition_met(
+        parsed: &ParsedRequest,
+        context: &VariableContext,
+    ) -> std::result::Result<bool, String> {
+        let Some(condition) = &parsed.metadata.skip_if else {
+            return Ok(false);
+        };
+
+        let resolved = VariableResolver::resolve(condition, context);
+        parse_condition(&resolved)
+            .and_then(|expr| evaluate_condition(&expr, context))
+            .map_err(|e| format!("Invalid @skip-if condition '{}': {}", condition, e))
+    }
+
+    /// 依次执行 @pre-run 命令（在当前目录下通过 shell 执行），stdout 去除首尾空白后写入变量
+    fn run_pre_commands(
         &self,
-        mut parsed: ParsedRequest,
-        request_number: usize,
+        parsed: &ParsedRequest,
         context: &mut VariableContext,
-        source: Option<String>,
-    ) -> TestResult {
-        // 1. 变量替换
+    ) -> std::result::Result<(), String> {
+        for (var_name, command) in &parsed.metadata.pre_run {
+            if !self.options.allow_exec {
+                return Err(format!(
+                    "@pre-run {} requires --allow-exec to run shell commands",
+                    var_name
+                ));
+            }
+
+            let command = VariableResolver::resolve(command, context);
+            info!(variable = %var_name, command = %command, "Running @pre-run command");
+
+            let output = shell_command(&command).output().map_err(|e| {
+                format!("@pre-run {}: failed to run '{}': {}", var_name, command, e)
+            })?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!(
+                    "@pre-run {}: '{}' failed ({}){}",
+                    var_name,
+                    command,
+                    output.status,
+                    if stderr.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!(": {}", stderr.trim())
+                    }
+                ));
+            }
+
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            context.set(var_name.clone(), value);
+        }
+
+        Ok(())
+    }
+
+    /// 替换请求 URL、Headers 和 Body 中的变量
+    pub fn resolve_request(parsed: &mut ParsedRequest, context: &VariableContext) {
         // 替换 URL
         parsed.url = VariableResolver::resolve(&parsed.url, context);
 
@@ -86,8 +409,140 @@ impl TestExecutor {
             *body = VariableResolver::resolve(body, context);
         }
 
+        // 替换 @query 参数值
+        for (_key, value) in &mut parsed.metadata.queries {
+            *value = VariableResolver::resolve(value, context);
+        }
+
+        // 替换 @content-type
+        if let Some(content_type) = &mut parsed.metadata.content_type {
+            *content_type = VariableResolver::resolve(content_type, context);
+        }
+
+        // 替换 multipart 文本字段
+        for part in &mut parsed.form {
+            if let FormPart::Text { value, .. } = part {
+                *value = VariableResolver::resolve(value, context);
+            }
+        }
+
+        // 替换 @auth 的用户名、密码和 token
+        if let Some(auth) = &mut parsed.metadata.auth {
+            auth.map_values(|value| VariableResolver::resolve(value, context));
+        }
+    }
+
+    /// 收集 URL、Headers 和 Body 中残留的变量名（去重）
+    fn unresolved_variables(parsed: &ParsedRequest) -> Vec<String> {
+        let mut missing: Vec<String> = Vec::new();
+        for text in parsed.templated_texts() {
+            for name in VariableResolver::unresolved(text) {
+                if !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+        }
+        missing
+    }
+
+    /// 将同一请求执行 `times` 次，最多 `concurrency` 个同时进行（--repeat）
+    ///
+    /// 每次执行使用独立的变量上下文副本。为避免历史文件被大量重复记录淹没，
+    /// 只记录第一个收到响应的请求。结果按请求序号排序。
+    /// 上下文设置了种子时，第 N 次执行使用由种子和 N 得到的种子，动态变量与并发调度无关。
+    pub async fn execute_repeated(
+        &self,
+        parsed: ParsedRequest,
+        context: &VariableContext,
+        times: usize,
+        concurrency: usize,
+        source: Option<String>,
+    ) -> Vec<TestResult> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut executor = self.clone();
+        executor.options.record_history = false;
+        let seed = context.seed();
+        let next = Arc::new(AtomicUsize::new(0));
+        let mut workers = tokio::task::JoinSet::new();
+
+        for _ in 0..concurrency.clamp(1, times.max(1)) {
+            let executor = executor.clone();
+            let parsed = parsed.clone();
+            let mut context = context.clone();
+            let next = Arc::clone(&next);
+            workers.spawn(async move {
+                let mut results = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= times {
+                        break;
+                    }
+                    if let Some(seed) = seed {
+                        context.set_seed(seed.wrapping_add(index as u64));
+                    }
+                    let result = executor
+                        .execute_one(parsed.clone(), index + 1, &mut context, None)
+                        .await;
+                    results.push(result);
+                }
+                results
+            });
+        }
+
+        let mut results = Vec::with_capacity(times);
+        while let Some(worker) = workers.join_next().await {
+            match worker {
+                Ok(worker_results) => results.extend(worker_results),
+                Err(e) => error!("Repeat worker failed: {}", e),
+            }
+        }
+        results.sort_by_key(|r| r.request_number);
+
+        if self.options.record_history
+            && let Some((snapshot, response)) = results
+                .iter()
+                .find_map(|r| Some((r.request_snapshot.clone()?, r.response.as_ref()?)))
+        {
+            use crate::history::recorder::record_history;
+            let name = parsed.name().map(|s| s.to_string());
+            record_history(
+                snapshot,
+                response,
+                history_source(source, name.as_deref()),
+                name,
+            );
+        }
+
+        results
+    }
+
+    /// 执行单个请求
+    pub async fn execute_one(
+        &self,
+        mut parsed: ParsedRequest,
+        request_number: usize,
+        context: &mut VariableContext,
+        source: Option<String>,
+    ) -> TestResult {
+        // 0. 执行 @pre-run 命令，输出写入变量后才能参与替换
+        if let Err(e) = self.run_pre_commands(&parsed, context) {
+            return TestResult::error(
+                request_number,
+                parsed.name().map(|s| s.to_string()),
+                parsed.method_or_default().to_string(),
+                parsed.url_with_query(),
+                e,
+                std::time::Duration::ZERO,
+            );
+        }
+
+        // 1. 应用请求默认值并替换变量
+        self.defaults.apply(&mut parsed);
+        Self::resolve_request(&mut parsed, context);
+
         let method = parsed.method_or_default().to_string();
-        let url = parsed.url.clone();
+        let url = parsed.url_with_query();
         let name = parsed.name().map(|s| s.to_string());
 
         // 开始计时
@@ -96,8 +551,17 @@ impl TestExecutor {
         // 提前保存断言列表和捕获配置（在 parsed 被移动前）
         let assertions_to_eval = parsed.metadata.assertions.clone();
         let captures_to_eval = parsed.metadata.captures.clone();
+        let persist_names = parsed.metadata.persist.clone();
+        let schema = parsed.metadata.schema.clone().map(|schema| {
+            let path = parsed
+                .metadata
+                .schema_path
+                .clone()
+                .unwrap_or_else(|| schema.clone().into());
+            (format!("schema {}", schema), path)
+        });
 
-        // [History] 创建请求快照 (在 parsed 被 move 之前)
+        // [History] 创建请求快照 (在 parsed 被 move 之前)，请求未发送时也用于 -vv 显示替换结果
         let request_snapshot = {
             let mut headers = reqwest::header::HeaderMap::new();
             for (k, v) in &parsed.headers {
@@ -105,7 +569,7 @@ impl TestExecutor {
                     HeaderName::from_bytes(k.as_bytes()),
                     HeaderValue::from_str(v),
                 ) {
-                    headers.insert(n, v);
+                    headers.append(n, v);
                 }
             }
 
@@ -117,11 +581,28 @@ impl TestExecutor {
             }
         };
 
+        // 严格模式：存在未解析的变量时不发送请求
+        if self.options.strict_vars {
+            let missing = Self::unresolved_variables(&parsed);
+            if !missing.is_empty() {
+                let mut test_result = TestResult::error(
+                    request_number,
+                    name,
+                    method,
+                    url,
+                    format!("Unresolved variables: {}", missing.join(", ")),
+                    start.elapsed(),
+                );
+                test_result.request_snapshot = Some(request_snapshot);
+                return test_result;
+            }
+        }
+
         // 转换为 Request
-        let request = match parsed.try_into() {
+        let mut request: Request = match parsed.try_into() {
             Ok(req) => req,
             Err(e) => {
-                return TestResult::error(
+                let mut test_result = TestResult::error(
                     request_number,
                     name,
                     method,
@@ -129,28 +610,40 @@ impl TestExecutor {
                     format!("Failed to build request: {}", e),
                     start.elapsed(),
                 );
+                test_result.request_snapshot = Some(request_snapshot);
+                return test_result;
             }
         };
 
+        request.download = self.download.clone();
+
         // 执行请求
         match self.client.execute(request).await {
             Ok(response) => {
                 // 计算耗时
                 // [History] 异步保存历史记录 (Best Effort)
-                use crate::history::recorder::record_history;
-                record_history(request_snapshot, &response, source);
+                if self.options.record_history {
+                    use crate::history::recorder::record_history;
+                    record_history(
+                        request_snapshot.clone(),
+                        &response,
+                        history_source(source, name.as_deref()),
+                        name.clone(),
+                    );
+                }
 
                 // 2. 变量捕获
+                let mut persisted = Vec::new();
                 if !captures_to_eval.is_empty() {
-                    match capture_from_response(
-                        &response.body,
-                        &response.headers,
-                        &captures_to_eval,
-                    ) {
+                    match capture_from_response(&response, &captures_to_eval) {
                         Ok(captured_vars) => {
                             for (key, value) in &captured_vars {
                                 info!("Captured variable: {} = '{}'", key, value);
+                                if self.options.persist_captures || persist_names.contains(key) {
+                                    persisted.push((key.clone(), value.clone()));
+                                }
                             }
+                            persisted.sort();
                             context.extend(captured_vars);
                         }
                         Err(e) => {
@@ -180,10 +673,17 @@ impl TestExecutor {
                     }
                 }
 
+                // 4. JSON Schema 校验（@schema）
+                if let Some((raw, path)) = schema {
+                    assertion_results.push(validate_schema(raw, &path, &response));
+                }
+
                 // 创建成功的测试结果
                 let mut test_result =
                     TestResult::success(request_number, name, method, url, response);
                 test_result.assertions = assertion_results;
+                test_result.request_snapshot = Some(request_snapshot);
+                test_result.persisted = persisted;
 
                 // 如果有断言失败，标记测试为失败
                 if test_result.assertions.iter().any(|a| !a.passed) {
@@ -192,14 +692,18 @@ impl TestExecutor {
 
                 test_result
             }
-            Err(e) => TestResult::error(
-                request_number,
-                name,
-                method,
-                url,
-                format!("Request failed: {}", e),
-                start.elapsed(),
-            ),
+            Err(e) => {
+                let mut test_result = TestResult::error(
+                    request_number,
+                    name,
+                    method,
+                    url,
+                    format!("Request failed: {}", e.full_message()),
+                    start.elapsed(),
+                );
+                test_result.request_snapshot = Some(request_snapshot);
+                test_result
+            }
         }
     }
 }
@@ -209,3 +713,25 @@ impl Default for TestExecutor {
         Self::new()
     }
 }
+
+/// 历史记录的来源，来自文件的请求附加名称，如 `file:tests/auth.http#login`
+fn history_source(source: Option<String>, name: Option<&str>) -> Option<String> {
+    match (source, name) {
+        (Some(source), Some(name)) if source.starts_with("file") => {
+            Some(format!("{}#{}", source, name))
+        }
+        (source, _) => source,
+    }
+}
+
+/// 通过系统 shell 执行命令字符串
+fn shell_command(command: &str) -> std::process::Command {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut cmd = std::process::Command::new(shell);
+    cmd.arg(flag).arg(command);
+    cmd
+}
@@ -2,6 +2,6 @@ pub mod executor;
 pub mod reporter;
 pub mod types;
 
-pub use executor::TestExecutor;
-pub use reporter::TestReporter;
-pub use types::{TestResult, TestSummary};
+pub use executor::{ExecutionOptions, ResultCallback, TestExecutor, TestExecutorBuilder};
+pub use reporter::{Progress, TestReporter};
+pub use types::{RepeatSummary, TestOutcome, TestResult, TestSummary};
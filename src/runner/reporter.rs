@@ -1,26 +1,48 @@
-use crate::runner::types::{TestResult, TestSummary};
+use crate::assertion::FailureContext;
+use crate::history::model::RequestSnapshot;
+use crate::parser::ParsedFile;
+use crate::runner::types::{RepeatSummary, TestResult, TestSummary};
 use crate::utils::{ResponseFormat, ResponseFormatter};
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::time::Duration;
 
 pub struct TestReporter {
-    verbose: bool,
+    /// 输出详细级别：0 默认，1 (-v) 显示响应，2 (-vv) 额外显示发送的请求
+    verbosity: u8,
     formatter: ResponseFormatter,
+    /// 非 verbose 时也显示断言失败处的响应片段（--show-context）
+    show_context: bool,
 }
 
 impl TestReporter {
-    pub fn new(verbose: bool) -> Self {
-        let format = if verbose {
+    pub fn new(verbosity: u8) -> Self {
+        let format = if verbosity > 0 {
             ResponseFormat::Verbose
         } else {
             ResponseFormat::Compact
         };
 
         Self {
-            verbose,
+            verbosity,
             formatter: ResponseFormatter::new(format),
+            show_context: false,
         }
     }
 
+    /// 非 verbose 时也显示断言失败处的响应片段
+    pub fn with_show_context(mut self, show_context: bool) -> Self {
+        self.show_context = show_context;
+        self
+    }
+
+    /// 响应 body 最多显示的字节数（--max-body-bytes），0 表示不限制
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.formatter = self.formatter.with_max_body_bytes(max_body_bytes);
+        self
+    }
+
     /// 打印单个测试结果
     pub fn print_result(&self, result: &TestResult) {
         // 跳过的测试
@@ -52,18 +74,49 @@ impl TestReporter {
             String::new()
         };
 
+        // 启用重试且不止一次尝试时注明
+        let attempts_part = match result.response.as_ref().map(|r| r.attempts) {
+            Some(attempts) if attempts > 1 && result.success => {
+                format!(" {}", format!("succeeded on attempt {}", attempts).yellow())
+            }
+            Some(attempts) if attempts > 1 => {
+                format!(" {}", format!("after {} attempts", attempts).yellow())
+            }
+            _ => String::new(),
+        };
+
+        // 跟随了重定向时注明次数
+        let redirects_part = result
+            .response
+            .as_ref()
+            .and_then(|r| format_redirects(r.redirects))
+            .map(|note| format!(" {}", note.dimmed()))
+            .unwrap_or_default();
+
         println!(
-            " {} [{}]{} {} {} ({}ms)",
+            " {} [{}]{} {} {} ({}ms){}{}",
             symbol.color(color),
             result.request_number,
             name_part,
             result.method.cyan(),
             result.url,
-            result.duration.as_millis()
+            result.duration.as_millis(),
+            redirects_part,
+            attempts_part
         );
 
+        // -vv：在响应之前显示实际发送的请求
+        if self.verbosity >= 2
+            && let Some(request) = &result.request_snapshot
+        {
+            for line in format_request(request).lines() {
+                println!("   {}", line.dimmed());
+            }
+            println!();
+        }
+
         // 如果是 verbose 模式，或者失败了，显示详细信息
-        if (self.verbose || !result.success)
+        if (self.verbosity > 0 || !result.success)
             && let Some(response) = &result.response
         {
             // 复用 ResponseFormatter 格式化响应
@@ -102,12 +155,41 @@ impl TestReporter {
                     if let Some(msg) = &assertion.message {
                         println!("       {}", msg.red());
                     }
+                    if (self.verbosity > 0 || self.show_context)
+                        && let Some(context) = &assertion.context
+                    {
+                        for line in format_failure_context(context).lines() {
+                            println!("         {}", line);
+                        }
+                    }
                 }
             }
             println!();
         }
     }
 
+    /// 创建进度条，共 total 个请求
+    ///
+    /// 只在 stdout 是终端、非 verbose 且允许彩色输出（未设置 --no-color / NO_COLOR）时显示，
+    /// 否则返回不显示任何内容的进度条。
+    pub fn progress(&self, total: usize) -> Progress {
+        let enabled = self.verbosity == 0
+            && std::io::stdout().is_terminal()
+            && colored::control::SHOULD_COLORIZE.should_colorize();
+        if !enabled {
+            return Progress(None);
+        }
+
+        let bar = ProgressBar::new(total as u64);
+        bar.set_style(
+            ProgressStyle::with_template("{spinner:.cyan} [{bar:30.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        bar.enable_steady_tick(Duration::from_millis(100));
+        Progress(Some(bar))
+    }
+
     /// 打印测试开始
     pub fn print_header(&self, file_path: &str, total: usize) {
         println!(
@@ -117,37 +199,97 @@ impl TestReporter {
         );
     }
 
+    /// 关闭证书校验时的警告，requests 为空表示所有请求（--insecure），否则为 @insecure 的请求
+    pub fn print_tls_warning(&self, requests: &[String]) {
+        let scope = if requests.is_empty() {
+            "all requests".to_string()
+        } else {
+            requests.join(", ")
+        };
+        println!(
+            "{} TLS certificate verification is disabled for {}\n",
+            "WARNING:".yellow().bold(),
+            scope.bold()
+        );
+    }
+
+    /// 打印写入 rupost.captured.toml 的变量
+    pub fn print_persisted(&self, path: &std::path::Path, names: &[&str]) {
+        println!(
+            "  {} {} to {}",
+            "Persisted:".dimmed(),
+            names.join(", "),
+            path.display()
+        );
+    }
+
+    /// 打印 `rupost test --list` 的请求列表（URL 按调用方替换后的原样显示）
+    pub fn print_request_list(&self, file_path: &str, file: &ParsedFile) {
+        println!(
+            "\n{} ({} requests)\n",
+            file_path.bold(),
+            file.requests.len()
+        );
+        for line in format_request_list(file).lines() {
+            println!("  {}", line);
+        }
+    }
+
+    /// 打印 `rupost test --repeat` 每一轮的标题
+    pub fn print_iteration(&self, iteration: usize, total: usize) {
+        println!("{}", format!("Iteration {}/{}", iteration, total).bold());
+    }
+
+    /// 打印 --repeat 的紧凑统计：各状态码次数和耗时分布
+    pub fn print_repeat_summary(&self, summary: &RepeatSummary) {
+        let mut counts: Vec<String> = summary
+            .statuses
+            .iter()
+            .map(|(status, count)| {
+                let label = format!("{}: {}", status, count);
+                match status {
+                    200..=399 => label.green().to_string(),
+                    400..=499 => label.yellow().to_string(),
+                    _ => label.red().to_string(),
+                }
+            })
+            .collect();
+        if summary.errors > 0 {
+            counts.push(format!("error: {}", summary.errors).red().to_string());
+        }
+
+        let ms = |duration: Duration| format!("{:.1}ms", duration.as_secs_f64() * 1000.0);
+        println!(
+            "  {}: {} total, {}",
+            "Requests".bold(),
+            summary.total,
+            counts.join(", ")
+        );
+        println!(
+            "  {}: min {}, avg {}, p95 {}, max {}",
+            "Latency".bold(),
+            ms(summary.min).cyan(),
+            ms(summary.avg).cyan(),
+            ms(summary.p95).cyan(),
+            ms(summary.max).cyan()
+        );
+    }
+
+    /// 运行多个文件时打印各文件的结果，之后由 [`Self::print_summary`] 打印合计
+    pub fn print_file_summaries(&self, files: &[(&str, TestSummary)]) {
+        println!("\n{}", "Files".bold());
+        for (path, summary) in files {
+            println!("{}", format_file_summary(path, summary));
+        }
+    }
+
     /// 打印测试摘要
     pub fn print_summary(&self, summary: &TestSummary) {
         println!("\n{}", "━".repeat(50));
         println!("{}", "Summary".bold());
         println!("{}", "━".repeat(50));
 
-        if summary.skipped > 0 {
-            println!(
-                "  {}: {} passed, {} failed, {} skipped, {} total",
-                "Tests".bold(),
-                summary.passed.to_string().green(),
-                summary.failed.to_string().red(),
-                summary.skipped.to_string().dimmed(),
-                summary.total
-            );
-        } else if summary.failed == 0 {
-            println!(
-                "  {}: {} passed, {} total",
-                "Tests".bold(),
-                summary.passed.to_string().green(),
-                summary.total
-            );
-        } else {
-            println!(
-                "  {}: {} passed, {} failed, {} total",
-                "Tests".bold(),
-                summary.passed.to_string().green(),
-                summary.failed.to_string().red(),
-                summary.total
-            );
-        }
+        println!("  {}: {}", "Tests".bold(), format_counts(summary));
 
         // 显示断言统计
         if summary.total_assertions > 0 {
@@ -178,8 +320,389 @@ impl TestReporter {
     }
 }
 
+/// 执行过程中的进度条（见 [`TestReporter::progress`]），不显示时所有方法都不做任何事
+pub struct Progress(Option<ProgressBar>);
+
+impl Progress {
+    /// 打印一个结果并推进进度条，打印期间隐藏进度条以免输出交错
+    pub fn report(&self, reporter: &TestReporter, result: &TestResult) {
+        match &self.0 {
+            Some(bar) => {
+                bar.suspend(|| reporter.print_result(result));
+                bar.set_message(format!("{} {}", result.method, result.url));
+                bar.inc(1);
+            }
+            None => reporter.print_result(result),
+        }
+    }
+
+    /// 隐藏进度条执行 f，用于在执行过程中打印其他内容
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        match &self.0 {
+            Some(bar) => bar.suspend(f),
+            None => f(),
+        }
+    }
+
+    /// 清除进度条
+    pub fn finish(&self) {
+        if let Some(bar) = &self.0 {
+            bar.finish_and_clear();
+        }
+    }
+}
+
 impl Default for TestReporter {
     fn default() -> Self {
-        Self::new(false)
+        Self::new(0)
+    }
+}
+
+/// 按列对齐的请求表格：序号、名称（@name 或 Markdown 标题）、方法、URL、分组、跳过标记
+/// 摘要中的请求计数，如 `2 passed, 1 failed, 3 total`
+fn format_counts(summary: &TestSummary) -> String {
+    let mut counts = vec![format!("{} passed", summary.passed.to_string().green())];
+    if summary.failed > 0 || summary.skipped > 0 {
+        counts.push(format!("{} failed", summary.failed.to_string().red()));
+    }
+    if summary.errored > 0 {
+        counts.push(format!("{} errored", summary.errored.to_string().red()));
+    }
+    if summary.skipped > 0 {
+        counts.push(format!("{} skipped", summary.skipped.to_string().dimmed()));
+    }
+    format!("{}, {} total", counts.join(", "), summary.total)
+}
+
+/// 多文件运行时单个文件的结果行
+fn format_file_summary(path: &str, summary: &TestSummary) -> String {
+    let mark = if summary.passed + summary.skipped == summary.total {
+        "✓".green()
+    } else {
+        "✗".red()
+    };
+    format!("  {} {}: {}", mark, path, format_counts(summary))
+}
+
+fn format_request_list(file: &ParsedFile) -> String {
+    let rows: Vec<[String; 6]> = file
+        .requests
+        .iter()
+        .enumerate()
+        .map(|(index, request)| {
+            let skip = if request.metadata.skip {
+                "@skip"
+            } else if request.metadata.skip_if.is_some() {
+                "@skip-if"
+            } else if !request.metadata.env_only.is_empty() {
+                "@env-only"
+            } else {
+                ""
+            };
+            [
+                (index + 1).to_string(),
+                request.metadata.name.clone().unwrap_or_else(|| "-".into()),
+                request.method.clone().unwrap_or_else(|| "GET".into()),
+                request.url.clone(),
+                request.metadata.groups.join(","),
+                skip.to_string(),
+            ]
+        })
+        .collect();
+
+    let header = ["#", "NAME", "METHOD", "URL", "GROUPS", "SKIP"].map(String::from);
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell))
+                .collect();
+            line.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// JSON 片段中失败的键前后各显示的行数
+const CONTEXT_LINES: usize = 3;
+/// 字符串片段中匹配位置之前 / 之后显示的字符数
+const TEXT_BEFORE: usize = 30;
+const TEXT_AFTER: usize = 50;
+
+/// 格式化断言失败处的响应片段，失败的键或字符串中最接近的匹配以红色高亮
+fn format_failure_context(context: &FailureContext) -> String {
+    match context {
+        FailureContext::Json { parent, key } => format_json_context(parent, key),
+        FailureContext::Text { actual, expected } => format_text_context(actual, expected),
+    }
+}
+
+/// 按行格式化父节点，高亮 key 对应的条目，只保留其前后 CONTEXT_LINES 行
+///
+/// 父节点中没有 key（例如 exists 失败）时显示父节点开头的几行。
+fn format_json_context(parent: &serde_json::Value, key: &str) -> String {
+    // 每个条目格式化为若干行，记录 key 对应条目的行范围
+    let (open, close, entries): (&str, &str, Vec<(bool, String)>) = match parent {
+        serde_json::Value::Object(map) => (
+            "{",
+            "}",
+            map.iter()
+                .map(|(k, v)| (k == key, format!("{}: {}", json_string(k), pretty(v))))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => (
+            "[",
+            "]",
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string() == key, pretty(v)))
+                .collect(),
+        ),
+        other => return pretty(other),
+    };
+
+    let mut lines = Vec::new();
+    let mut highlighted = None;
+    let count = entries.len();
+    for (i, (is_key, entry)) in entries.into_iter().enumerate() {
+        let start = lines.len();
+        let comma = if i + 1 < count { "," } else { "" };
+        let entry = format!("{}{}", entry, comma);
+        lines.extend(entry.lines().map(|line| format!("  {}", line)));
+        if is_key {
+            highlighted = Some((start, lines.len()));
+        }
+    }
+
+    let (from, to) = match highlighted {
+        Some((start, end)) => (
+            start.saturating_sub(CONTEXT_LINES),
+            (end + CONTEXT_LINES).min(lines.len()),
+        ),
+        None => (0, (CONTEXT_LINES * 2).min(lines.len())),
+    };
+
+    let mut out = vec![open.to_string()];
+    if from > 0 {
+        out.push("  …".to_string());
+    }
+    for (i, line) in lines.iter().enumerate().take(to).skip(from) {
+        match highlighted {
+            Some((start, end)) if (start..end).contains(&i) => out.push(line.red().to_string()),
+            _ => out.push(line.clone()),
+        }
+    }
+    if to < lines.len() {
+        out.push("  …".to_string());
+    }
+    out.push(close.to_string());
+    out.join("\n")
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn json_string(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
+
+/// 找出 expected 在 actual 中能匹配上的最长前缀，显示其前后的一段文字并高亮匹配部分
+///
+/// 连第一个字符都匹配不上时显示 actual 的开头。
+fn format_text_context(actual: &str, expected: &str) -> String {
+    let chars: Vec<char> = actual.chars().collect();
+    let (start, len) = longest_prefix_match(actual, expected);
+
+    let from = start.saturating_sub(TEXT_BEFORE);
+    let to = (start + len + TEXT_AFTER).min(chars.len());
+    let slice = |a: usize, b: usize| chars[a..b].iter().collect::<String>();
+
+    format!(
+        "{}{}{}{}{}",
+        if from > 0 { "…" } else { "" },
+        slice(from, start),
+        slice(start, start + len).red(),
+        slice(start + len, to),
+        if to < chars.len() { "…" } else { "" },
+    )
+}
+
+/// expected 的最长前缀在 actual 中首次出现的位置和长度（按字符计）
+fn longest_prefix_match(actual: &str, expected: &str) -> (usize, usize) {
+    let prefix_ends: Vec<usize> = expected
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .collect();
+    for (n, &end) in prefix_ends.iter().enumerate().rev() {
+        if let Some(byte_pos) = actual.find(&expected[..end]) {
+            return (actual[..byte_pos].chars().count(), n + 1);
+        }
+    }
+    (0, 0)
+}
+
+/// 跟随的重定向次数说明，如 `via 2 redirects`，没有重定向时为 None
+fn format_redirects(redirects: usize) -> Option<String> {
+    match redirects {
+        0 => None,
+        1 => Some("via 1 redirect".to_string()),
+        n => Some(format!("via {} redirects", n)),
+    }
+}
+
+/// 按 `> ` 前缀格式化已发送的请求（方法、最终 URL、请求头、请求体）
+fn format_request(request: &RequestSnapshot) -> String {
+    let mut output = vec![format!("> {} {}", request.method, request.url)];
+    for (key, value) in request.headers.iter() {
+        output.push(format!(
+            "> {}: {}",
+            key,
+            value.to_str().unwrap_or("<invalid utf-8>")
+        ));
+    }
+
+    if let Some(body) = request.body.as_deref().filter(|b| !b.is_empty()) {
+        output.push(">".to_string());
+        for line in body.lines() {
+            output.push(format!("> {}", line));
+        }
+    }
+
+    output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_format_redirects() {
+        assert_eq!(format_redirects(0), None);
+        assert_eq!(format_redirects(1).as_deref(), Some("via 1 redirect"));
+        assert_eq!(format_redirects(2).as_deref(), Some("via 2 redirects"));
+    }
+
+    #[test]
+    fn test_format_request() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        let request = RequestSnapshot {
+            method: "POST".to_string(),
+            url: "https://example.com/users?page=1".to_string(),
+            headers,
+            body: Some("{\n  \"name\": \"alice\"\n}".to_string()),
+        };
+
+        assert_eq!(
+            format_request(&request),
+            "> POST https://example.com/users?page=1\n\
+             > content-type: application/json\n\
+             >\n\
+             > {\n\
+             >   \"name\": \"alice\"\n\
+             > }"
+        );
+    }
+
+    #[test]
+    fn test_format_request_without_body() {
+        let request = RequestSnapshot {
+            method: "GET".to_string(),
+            url: "https://example.com/health".to_string(),
+            headers: HeaderMap::new(),
+            body: Some(String::new()),
+        };
+        assert_eq!(format_request(&request), "> GET https://example.com/health");
+    }
+
+    #[test]
+    fn test_format_json_context() {
+        colored::control::set_override(false);
+        let parent = serde_json::json!({
+            "a": 1, "b": 2, "c": 3, "d": 4, "e": "alice", "f": 6, "g": 7, "h": 8, "i": 9
+        });
+        assert_eq!(
+            format_json_context(&parent, "e"),
+            "{\n  …\n  \"b\": 2,\n  \"c\": 3,\n  \"d\": 4,\n  \"e\": \"alice\",\n  \"f\": 6,\n  \"g\": 7,\n  \"h\": 8,\n  …\n}"
+        );
+
+        let parent = serde_json::json!([{"id": 1}, {"id": 2}]);
+        assert_eq!(
+            format_json_context(&parent, "1"),
+            "[\n  {\n    \"id\": 1\n  },\n  {\n    \"id\": 2\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn test_format_text_context() {
+        colored::control::set_override(false);
+        assert_eq!(longest_prefix_match("user created", "used"), (0, 3));
+        assert_eq!(longest_prefix_match("user created", "created!"), (5, 7));
+        assert_eq!(longest_prefix_match("用户已创建", "已删除"), (2, 1));
+        assert_eq!(longest_prefix_match("abc", "xyz"), (0, 0));
+
+        let actual = format!("{}user created{}", "x".repeat(40), "y".repeat(60));
+        assert_eq!(
+            format_text_context(&actual, "created!"),
+            format!("…{}user created{}…", "x".repeat(25), "y".repeat(50))
+        );
+        assert_eq!(format_text_context("short", "zzz"), "short");
+    }
+
+    #[test]
+    fn test_format_request_list() {
+        let file = crate::parser::HttpFileParser::parse_content(
+            "@name login\n@group smoke, auth\nPOST {{base_url}}/login\n\n###\n\n@skip\nhttps://example.com/health\n\n###\n\n@skip-if env == prod\nDELETE {{base_url}}/users/1\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            format_request_list(&file),
+            "#  NAME   METHOD  URL                         GROUPS      SKIP\n\
+             1  login  POST    {{base_url}}/login          smoke,auth\n\
+             2  -      GET     https://example.com/health              @skip\n\
+             3  -      DELETE  {{base_url}}/users/1                    @skip-if"
+        );
+    }
+
+    #[test]
+    fn test_format_file_summary() {
+        colored::control::set_override(false);
+        let summary = |passed, failed, errored, skipped| TestSummary {
+            total: passed + failed + errored + skipped,
+            passed,
+            failed,
+            errored,
+            skipped,
+            total_duration: Duration::ZERO,
+            total_assertions: 0,
+            passed_assertions: 0,
+            failed_assertions: 0,
+        };
+
+        assert_eq!(
+            format_file_summary("smoke.http", &summary(3, 0, 0, 0)),
+            "  ✓ smoke.http: 3 passed, 3 total"
+        );
+        assert_eq!(
+            format_file_summary("users.http", &summary(1, 1, 1, 0)),
+            "  ✗ users.http: 1 passed, 1 failed, 1 errored, 3 total"
+        );
+        assert_eq!(
+            format_file_summary("auth.http", &summary(1, 0, 0, 1)),
+            "  ✓ auth.http: 1 passed, 0 failed, 1 skipped, 2 total"
+        );
     }
 }
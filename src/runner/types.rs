@@ -1,5 +1,7 @@
 use crate::assertion::AssertionResult;
+use crate::history::model::RequestSnapshot;
 use crate::http::Response;
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 /// 单个请求的执行结果
@@ -32,11 +34,17 @@ pub struct TestResult {
     /// 完整的 HTTP 响应（用于详细输出）
     pub response: Option<Response>,
 
+    /// 实际发送的请求（变量已替换，用于 -vv 输出）
+    pub request_snapshot: Option<RequestSnapshot>,
+
     /// 是否被跳过
     pub skipped: bool,
 
     /// 断言结果列表
     pub assertions: Vec<AssertionResult>,
+
+    /// 需要持久化的捕获变量（@persist 或 --persist-captures），按名称排序
+    pub persisted: Vec<(String, String)>,
 }
 
 impl TestResult {
@@ -49,7 +57,8 @@ impl TestResult {
     ) -> Self {
         let status = response.status.code();
         let duration = response.duration;
-        let success = response.is_success();
+        // --no-redirect 时 3xx 是正常结果
+        let success = response.is_success() || response.is_redirect();
 
         Self {
             request_number,
@@ -61,8 +70,10 @@ impl TestResult {
             success,
             error: None,
             response: Some(response),
+            request_snapshot: None,
             skipped: false,
             assertions: Vec::new(),
+            persisted: Vec::new(),
         }
     }
 
@@ -84,8 +95,10 @@ impl TestResult {
             success: false,
             error: Some(error),
             response: None,
+            request_snapshot: None,
             skipped: false,
             assertions: Vec::new(),
+            persisted: Vec::new(),
         }
     }
 
@@ -105,8 +118,10 @@ impl TestResult {
             success: true, // 跳过的测试算作成功
             error: None,
             response: None,
+            request_snapshot: None,
             skipped: true,
             assertions: Vec::new(),
+            persisted: Vec::new(),
         }
     }
 }
@@ -116,7 +131,10 @@ impl TestResult {
 pub struct TestSummary {
     pub total: usize,
     pub passed: usize,
+    /// 收到响应但断言或状态码未通过
     pub failed: usize,
+    /// 没有收到响应（网络错误、超时、请求无法构建等）
+    pub errored: usize,
     pub skipped: usize,
     pub total_duration: Duration,
     pub total_assertions: usize,
@@ -128,6 +146,10 @@ impl TestSummary {
     pub fn from_results(results: &[TestResult]) -> Self {
         let passed = results.iter().filter(|r| r.success && !r.skipped).count();
         let skipped = results.iter().filter(|r| r.skipped).count();
+        let errored = results
+            .iter()
+            .filter(|r| r.error.is_some() && r.response.is_none())
+            .count();
         let total_duration = results.iter().map(|r| r.duration).sum();
 
         // 统计断言
@@ -146,7 +168,8 @@ impl TestSummary {
         Self {
             total: results.len(),
             passed,
-            failed: results.len() - passed - skipped,
+            failed: results.len() - passed - skipped - errored,
+            errored,
             skipped,
             total_duration,
             total_assertions,
@@ -156,6 +179,79 @@ impl TestSummary {
     }
 }
 
+/// 一次测试运行的结果，由调用方映射为退出码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    /// 所有执行的请求都通过（或全部跳过）
+    Passed,
+    /// 有请求的断言或状态码未通过，或部分请求没有收到响应
+    Failed,
+    /// 所有执行的请求都没有收到响应（网络错误、超时等）
+    AllErrored,
+}
+
+impl TestOutcome {
+    pub fn from_summary(summary: &TestSummary) -> Self {
+        let executed = summary.total - summary.skipped;
+        if summary.errored > 0 && summary.errored == executed {
+            TestOutcome::AllErrored
+        } else if summary.failed > 0 || summary.errored > 0 {
+            TestOutcome::Failed
+        } else {
+            TestOutcome::Passed
+        }
+    }
+}
+
+/// 重复执行同一请求（--repeat）的统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatSummary {
+    pub total: usize,
+    /// 各状态码出现的次数
+    pub statuses: BTreeMap<u16, usize>,
+    /// 没有响应的请求数（网络错误等）
+    pub errors: usize,
+    pub min: Duration,
+    pub avg: Duration,
+    /// 95 分位耗时（nearest-rank）
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl RepeatSummary {
+    pub fn from_results(results: &[TestResult]) -> Self {
+        let mut statuses = BTreeMap::new();
+        for status in results.iter().filter_map(|r| r.status) {
+            *statuses.entry(status).or_insert(0) += 1;
+        }
+
+        let mut durations: Vec<Duration> = results.iter().map(|r| r.duration).collect();
+        durations.sort();
+        let percentile = |p: f64| {
+            let rank = (p * durations.len() as f64).ceil() as usize;
+            durations
+                .get(rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        let avg = if durations.is_empty() {
+            Duration::ZERO
+        } else {
+            durations.iter().sum::<Duration>() / durations.len() as u32
+        };
+
+        Self {
+            total: results.len(),
+            errors: results.iter().filter(|r| r.status.is_none()).count(),
+            statuses,
+            min: durations.first().copied().unwrap_or_default(),
+            avg,
+            p95: percentile(0.95),
+            max: durations.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,7 +280,98 @@ mod tests {
         let summary = TestSummary::from_results(&results);
         assert_eq!(summary.total, 2);
         assert_eq!(summary.passed, 0);
-        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.failed, 0);
+        assert_eq!(summary.errored, 2);
         assert_eq!(summary.total_duration, Duration::from_millis(300));
+        assert_eq!(TestOutcome::from_summary(&summary), TestOutcome::AllErrored);
+    }
+
+    #[test]
+    fn test_summary_failed_vs_errored() {
+        let response = |status: u16| {
+            Response::new(
+                status,
+                reqwest::header::HeaderMap::new(),
+                String::new(),
+                Duration::from_millis(10),
+            )
+            .unwrap()
+        };
+        let result = |status: u16| {
+            TestResult::success(
+                1,
+                None,
+                "GET".to_string(),
+                "http://example.com".to_string(),
+                response(status),
+            )
+        };
+
+        let mut assertion_failed = result(200);
+        assertion_failed.success = false;
+        assertion_failed.error = Some("1 assertion(s) failed".to_string());
+        let results = vec![
+            result(200),
+            result(500),
+            assertion_failed,
+            TestResult::error(
+                4,
+                None,
+                "GET".to_string(),
+                "http://example.com".to_string(),
+                "Request failed: connection refused".to_string(),
+                Duration::ZERO,
+            ),
+            TestResult::skipped(5, None, "GET".to_string(), "http://example.com".to_string()),
+        ];
+
+        let summary = TestSummary::from_results(&results);
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.errored, 1);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(TestOutcome::from_summary(&summary), TestOutcome::Failed);
+
+        // 跳过的请求不计入：其余全部通过时为 Passed
+        let summary = TestSummary::from_results(&[
+            result(200),
+            TestResult::skipped(2, None, "GET".to_string(), "http://example.com".to_string()),
+        ]);
+        assert_eq!(TestOutcome::from_summary(&summary), TestOutcome::Passed);
+    }
+
+    #[test]
+    fn test_repeat_summary() {
+        let result = |status: Option<u16>, ms: u64| {
+            let mut result = TestResult::error(
+                1,
+                None,
+                "GET".to_string(),
+                "http://example.com".to_string(),
+                "failed".to_string(),
+                Duration::from_millis(ms),
+            );
+            result.status = status;
+            result
+        };
+        let mut results: Vec<TestResult> = (1..=18).map(|ms| result(Some(200), ms)).collect();
+        results.push(result(Some(500), 40));
+        results.push(result(None, 100));
+
+        let summary = RepeatSummary::from_results(&results);
+        assert_eq!(summary.total, 20);
+        assert_eq!(summary.statuses, BTreeMap::from([(200, 18), (500, 1)]));
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.max, Duration::from_millis(100));
+        // (1 + ... + 18 + 40 + 100) / 20
+        assert_eq!(summary.avg, Duration::from_micros(15_550));
+        // 第 19 个（ceil(0.95 * 20)）
+        assert_eq!(summary.p95, Duration::from_millis(40));
+
+        let empty = RepeatSummary::from_results(&[]);
+        assert_eq!(empty.total, 0);
+        assert_eq!(empty.p95, Duration::ZERO);
     }
 }
@@ -0,0 +1,285 @@
+use crate::Result;
+use crate::http::types::Url;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Netscape cookie 文件中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    /// 域名（不含开头的 `.`）
+    pub domain: String,
+    /// 是否同时匹配子域名
+    pub include_subdomains: bool,
+    pub path: String,
+    /// 是否只通过 https 发送
+    pub secure: bool,
+    pub http_only: bool,
+    /// 过期时间（Unix 秒），0 表示会话 cookie
+    pub expires: i64,
+    pub name: String,
+    pub value: String,
+}
+
+impl Cookie {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires != 0 && self.expires <= now
+    }
+
+    /// 是否应随该 URL 的请求发送
+    fn matches(&self, url: &Url, now: i64) -> bool {
+        let domain_matches = url.host == self.domain
+            || (self.include_subdomains && url.host.ends_with(&format!(".{}", self.domain)));
+        let path_matches = url.path == self.path
+            || (url.path.starts_with(&self.path)
+                && (self.path.ends_with('/') || url.path[self.path.len()..].starts_with('/')));
+        let secure_ok = !self.secure || matches!(url.scheme.as_str(), "https" | "wss");
+
+        domain_matches && path_matches && secure_ok && !self.is_expired(now)
+    }
+
+    /// 解析 Netscape 格式的一行，注释和格式不正确的行返回 None
+    fn from_netscape_line(line: &str) -> Option<Self> {
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None if line.starts_with('#') => return None,
+            None => (line, false),
+        };
+
+        let fields: Vec<&str> = line.splitn(7, '\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return None;
+        };
+
+        Some(Self {
+            domain: domain.trim_start_matches('.').to_ascii_lowercase(),
+            include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+            path: path.to_string(),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+            expires: expires.parse().unwrap_or(0),
+            name: name.to_string(),
+            value: value.trim_end_matches('\r').to_string(),
+        })
+    }
+
+    fn to_netscape_line(&self) -> String {
+        let flag = |b: bool| if b { "TRUE" } else { "FALSE" };
+        format!(
+            "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.http_only { "#HttpOnly_" } else { "" },
+            if self.include_subdomains { "." } else { "" },
+            self.domain,
+            flag(self.include_subdomains),
+            self.path,
+            flag(self.secure),
+            self.expires,
+            self.name,
+            self.value
+        )
+    }
+}
+
+/// curl 兼容的 cookie 文件（`-b file` 读取，`-c file` 写入）
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// 解析 Netscape 格式的 cookie 文件内容
+    pub fn parse(content: &str) -> Self {
+        Self {
+            cookies: content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(Cookie::from_netscape_line)
+                .collect(),
+        }
+    }
+
+    /// 读取 cookie 文件，文件不存在时返回空 jar（与 curl 一致）
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_netscape())?;
+        Ok(())
+    }
+
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    /// 生成发送到该 URL 的 Cookie header 值，没有匹配的 cookie 时返回 None
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        let now = Utc::now().timestamp();
+        let pairs: Vec<String> = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(url, now))
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect();
+        (!pairs.is_empty()).then(|| pairs.join("; "))
+    }
+
+    /// 记录一条 Set-Cookie 响应头，同名 cookie 被替换，已过期的被删除
+    pub fn store(&mut self, set_cookie: &str, url: &Url) {
+        let mut parts = set_cookie.split(';');
+        let Some((name, value)) = parts.next().and_then(|pair| pair.split_once('=')) else {
+            return;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let mut cookie = Cookie {
+            domain: url.host.clone(),
+            include_subdomains: false,
+            path: default_path(&url.path),
+            secure: false,
+            http_only: false,
+            expires: 0,
+            name: name.to_string(),
+            value: value.trim().to_string(),
+        };
+        let mut max_age = None;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    // 不接受为其他域名设置的 cookie
+                    if url.host != domain && !url.host.ends_with(&format!(".{}", domain)) {
+                        return;
+                    }
+                    cookie.domain = domain;
+                    cookie.include_subdomains = true;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "expires" => {
+                    if let Ok(expires) = DateTime::parse_from_rfc2822(value) {
+                        // 0 表示会话 cookie，更早的时间一律视为已过期
+                        cookie.expires = expires.timestamp().max(1);
+                    }
+                }
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+
+        let now = Utc::now().timestamp();
+        // Max-Age 优先于 Expires，<= 0 表示立即删除
+        if let Some(max_age) = max_age {
+            cookie.expires = if max_age > 0 { now + max_age } else { -1 };
+        }
+
+        self.cookies.retain(|c| {
+            !(c.domain == cookie.domain && c.path == cookie.path && c.name == cookie.name)
+        });
+        if !cookie.is_expired(now) {
+            self.cookies.push(cookie);
+        }
+    }
+
+    /// 序列化为 Netscape 格式
+    pub fn to_netscape(&self) -> String {
+        let mut output = String::from("# Netscape HTTP Cookie File\n# Written by rupost\n\n");
+        for cookie in &self.cookies {
+            output.push_str(&cookie.to_netscape_line());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Set-Cookie 未指定 Path 时的默认值（请求路径的目录部分）
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => path[..index].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_netscape() {
+        let content = "# Netscape HTTP Cookie File\n\
+                       .example.com\tTRUE\t/\tFALSE\t0\tsession\tabc\n\
+                       #HttpOnly_api.example.com\tFALSE\t/v1\tTRUE\t0\ttoken\tx=y\n\
+                       # a comment\n\
+                       broken line\n";
+        let jar = CookieJar::parse(content);
+        assert_eq!(jar.cookies().len(), 2);
+        assert_eq!(jar.cookies()[0].domain, "example.com");
+        assert!(jar.cookies()[0].include_subdomains);
+        assert!(jar.cookies()[1].http_only);
+        assert_eq!(jar.cookies()[1].value, "x=y");
+
+        assert_eq!(
+            jar.cookie_header(&url("https://api.example.com/v1/users")),
+            Some("session=abc; token=x=y".to_string())
+        );
+        // secure cookie 不通过 http 发送，路径需按段匹配
+        assert_eq!(
+            jar.cookie_header(&url("http://api.example.com/v1")),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(
+            jar.cookie_header(&url("https://api.example.com/v10")),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(jar.cookie_header(&url("https://other.com/")), None);
+    }
+
+    #[test]
+    fn test_store_set_cookie() {
+        let mut jar = CookieJar::default();
+        let origin = url("https://api.example.com/auth/login");
+
+        jar.store("sid=123; Path=/; HttpOnly; Secure", &origin);
+        jar.store("theme=dark; Domain=.example.com; Max-Age=3600", &origin);
+        jar.store("tmp=1", &origin);
+        jar.store("foreign=1; Domain=evil.com", &origin);
+        assert_eq!(jar.cookies().len(), 3);
+        assert_eq!(jar.cookies()[0].path, "/");
+        assert!(jar.cookies()[1].include_subdomains);
+        assert!(jar.cookies()[1].expires > Utc::now().timestamp());
+        assert_eq!(jar.cookies()[2].path, "/auth");
+
+        // 同名 cookie 被替换，Max-Age=0 删除
+        jar.store("sid=456; Path=/", &origin);
+        jar.store("theme=; Domain=example.com; Max-Age=0", &origin);
+        jar.store("tmp=2; Expires=Thu, 01 Jan 1970 00:00:00 GMT", &origin);
+        assert_eq!(jar.cookies().len(), 1);
+        assert_eq!(jar.cookies()[0].value, "456");
+
+        let restored = CookieJar::parse(&jar.to_netscape());
+        assert_eq!(restored.cookies(), jar.cookies());
+    }
+}
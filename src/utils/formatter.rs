@@ -1,6 +1,13 @@
-use crate::http::Response;
+use crate::http::{Response, Timing};
 use anyhow::Result;
 use colored::*;
+use serde_json::Value;
+
+/// 详细模式下默认最多显示的 body 字节数
+pub const DEFAULT_MAX_BODY_BYTES: usize = 10 * 1024;
+/// 紧凑模式下默认最多显示的 body 字节数
+pub const COMPACT_MAX_BODY_BYTES: usize = 200;
+
 pub enum ResponseFormat {
     Compact,
     Verbose,
@@ -12,12 +19,19 @@ pub struct ResponseFormatter {
     show_body: bool,
     show_headers: bool,
     show_timing: bool,
+    /// 超过该字节数的 body 被截断，0 表示不限制
+    max_body_bytes: usize,
 }
 
 impl ResponseFormatter {
     pub fn new(format: ResponseFormat) -> Self {
+        let max_body_bytes = match format {
+            ResponseFormat::Compact => COMPACT_MAX_BODY_BYTES,
+            ResponseFormat::Verbose => DEFAULT_MAX_BODY_BYTES,
+        };
         Self {
             format,
+            max_body_bytes,
             color: true,
             show_body: true,
             show_headers: true,
@@ -25,6 +39,18 @@ impl ResponseFormatter {
         }
     }
 
+    /// 是否输出颜色（默认开启）
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// body 最多显示的字节数（--max-body-bytes），0 表示不限制
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
     pub fn format(&self, response: &Response) -> Result<String> {
         match self.format {
             ResponseFormat::Compact => self.format_compact(response),
@@ -61,17 +87,8 @@ impl ResponseFormatter {
             }
         }
 
-        if self.show_body {
-            let body = &response.body;
-            if !body.is_empty() && body.len() < 200 {
-                // 尝试格式化 JSON，失败则显示原始内容
-                let formatted_body = self
-                    .try_format_json(body)
-                    .unwrap_or_else(|_| body.to_string());
-                output.push(formatted_body);
-            } else if !body.is_empty() {
-                output.push(format!("Body: {} bytes", body.len()));
-            }
+        if self.show_body && !response.body.is_empty() {
+            output.push(self.format_body(response));
         }
 
         Ok(output.join("\n"))
@@ -105,6 +122,14 @@ impl ResponseFormatter {
             } else {
                 output.push(timeing);
             }
+            if let Some(timing) = response.timing {
+                let breakdown = format_timing(timing);
+                if self.color {
+                    output.push(breakdown.dimmed().to_string());
+                } else {
+                    output.push(breakdown);
+                }
+            }
         }
         if self.show_headers {
             output.push("".to_string());
@@ -123,30 +148,234 @@ impl ResponseFormatter {
             }
         }
 
-        if self.show_body {
-            let body = &response.body;
-            if !body.is_empty() {
-                output.push("".to_string());
-                if self.color {
-                    output.push("Body:".blue().bold().to_string());
+        if self.show_body && !response.body.is_empty() {
+            output.push("".to_string());
+            if self.color {
+                output.push("Body:".blue().bold().to_string());
+            } else {
+                output.push("Body:".to_string());
+            }
+            output.push(self.format_body(response));
+        }
+
+        Ok(output.join("\n"))
+    }
+
+    /// 未超出长度限制时尝试格式化 JSON（复用响应缓存的解析结果），失败则显示原始内容；
+    /// 超出时按原文截断并标注剩余字节数。客户端读取时已截断的 body 另起一行说明
+    fn format_body(&self, response: &Response) -> String {
+        let body = self.format_body_text(response);
+        match response.truncation_error() {
+            Some(note) => {
+                let note = format!("({})", note);
+                let note = if self.color {
+                    note.dimmed().to_string()
                 } else {
-                    output.push("Body:".to_string());
+                    note
+                };
+                format!("{}\n{}", body, note)
+            }
+            None => body,
+        }
+    }
+
+    fn format_body_text(&self, response: &Response) -> String {
+        let body = &response.body;
+        if self.max_body_bytes == 0 || body.len() <= self.max_body_bytes {
+            return match response.json_value() {
+                Some(value) => self.format_json(value),
+                None => body.to_string(),
+            };
+        }
+
+        // 在字符边界处截断
+        let mut end = self.max_body_bytes;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        let marker = format!("... ({} more bytes)", body.len() - end);
+        let marker = if self.color {
+            marker.dimmed().to_string()
+        } else {
+            marker
+        };
+        format!("{}{}", &body[..end], marker)
+    }
+
+    /// 将 JSON 格式化为漂亮的多行形式，开启颜色时按 token 着色
+    fn format_json(&self, value: &Value) -> String {
+        if self.color {
+            let mut output = String::new();
+            highlight_json(value, 0, &mut output);
+            output
+        } else {
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        }
+    }
+}
+
+/// 输出带颜色的 JSON，缩进和换行与 `serde_json::to_string_pretty` 一致
+///
+/// key 为蓝色，字符串为绿色，数字为黄色，布尔值和 null 为紫色。
+fn highlight_json(value: &Value, indent: usize, output: &mut String) {
+    let pad = |output: &mut String, level: usize| output.push_str(&"  ".repeat(level));
+
+    match value {
+        Value::Null => output.push_str(&"null".magenta().to_string()),
+        Value::Bool(b) => output.push_str(&b.to_string().magenta().to_string()),
+        Value::Number(n) => output.push_str(&n.to_string().yellow().to_string()),
+        Value::String(s) => output.push_str(&json_string(s).green().to_string()),
+        Value::Array(items) if items.is_empty() => output.push_str("[]"),
+        Value::Object(map) if map.is_empty() => output.push_str("{}"),
+        Value::Array(items) => {
+            output.push_str("[\n");
+            for (index, item) in items.iter().enumerate() {
+                pad(output, indent + 1);
+                highlight_json(item, indent + 1, output);
+                if index + 1 < items.len() {
+                    output.push(',');
+                }
+                output.push('\n');
+            }
+            pad(output, indent);
+            output.push(']');
+        }
+        Value::Object(map) => {
+            output.push_str("{\n");
+            for (index, (key, item)) in map.iter().enumerate() {
+                pad(output, indent + 1);
+                output.push_str(&json_string(key).blue().to_string());
+                output.push_str(": ");
+                highlight_json(item, indent + 1, output);
+                if index + 1 < map.len() {
+                    output.push(',');
                 }
-                // 尝试格式化 JSON，失败则显示原始内容
-                let formatted_body = self
-                    .try_format_json(body)
-                    .unwrap_or_else(|_| body.to_string());
-                output.push(formatted_body);
+                output.push('\n');
             }
+            pad(output, indent);
+            output.push('}');
         }
+    }
+}
 
-        Ok(output.join("\n"))
+/// 带引号和转义的 JSON 字符串
+fn json_string(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+/// 耗时拆分：收到响应头和读取 body 各自的耗时
+fn format_timing(timing: Timing) -> String {
+    let ms = |duration: std::time::Duration| format!("{:.1}ms", duration.as_secs_f64() * 1000.0);
+    format!("  TTFB: {}, Body: {}", ms(timing.ttfb), ms(timing.body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> Response {
+        Response::new(
+            200,
+            reqwest::header::HeaderMap::new(),
+            body.to_string(),
+            std::time::Duration::ZERO,
+        )
+        .unwrap()
+    }
+
+    /// 去掉 ANSI 颜色转义序列
+    fn strip_ansi(s: &str) -> String {
+        let mut output = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                output.push(c);
+            }
+        }
+        output
     }
 
-    /// 尝试将 body 格式化为漂亮的 JSON
-    /// 如果不是有效的 JSON，返回错误
-    fn try_format_json(&self, body: &str) -> Result<String> {
-        let value: serde_json::Value = serde_json::from_str(body)?;
-        serde_json::to_string_pretty(&value).map_err(Into::into)
+    #[test]
+    fn test_highlight_json_layout() {
+        let body = r#"{"name":"a \"b\"\n","n":[1,2.5,-3],"ok":true,"none":null,"empty":{},"list":[],"nested":{"k":[{"x":1}]}}"#;
+        let value: Value = serde_json::from_str(body).unwrap();
+
+        let mut highlighted = String::new();
+        highlight_json(&value, 0, &mut highlighted);
+        assert_eq!(
+            strip_ansi(&highlighted),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+        if colored::control::SHOULD_COLORIZE.should_colorize() {
+            assert!(highlighted.contains(&"\"name\"".blue().to_string()));
+            assert!(highlighted.contains(&"true".magenta().to_string()));
+        }
+    }
+
+    #[test]
+    fn test_format_json_without_color() {
+        let formatter = ResponseFormatter::new(ResponseFormat::Verbose).with_color(false);
+        assert_eq!(
+            formatter.format_body(&response(r#"{"a":[1]}"#)),
+            "{\n  \"a\": [\n    1\n  ]\n}"
+        );
+        assert_eq!(formatter.format_body(&response("not json")), "not json");
+    }
+
+    #[test]
+    fn test_format_body_truncates() {
+        let formatter = ResponseFormatter::new(ResponseFormat::Verbose)
+            .with_color(false)
+            .with_max_body_bytes(5);
+        assert_eq!(formatter.format_body(&response("[1,2]")), "[\n  1,\n  2\n]");
+        assert_eq!(
+            formatter.format_body(&response("[1,2,3]")),
+            "[1,2,... (2 more bytes)"
+        );
+        // 不在字符中间截断
+        assert_eq!(
+            formatter.format_body(&response("abcdé!")),
+            "abcd... (3 more bytes)"
+        );
+
+        let unlimited = formatter.with_max_body_bytes(0);
+        assert_eq!(
+            unlimited.format_body(&response(&"x".repeat(100_000))).len(),
+            100_000
+        );
+
+        let compact = ResponseFormatter::new(ResponseFormat::Compact).with_color(false);
+        let body = "y".repeat(COMPACT_MAX_BODY_BYTES + 10);
+        assert!(
+            compact
+                .format_body(&response(&body))
+                .ends_with("y... (10 more bytes)")
+        );
+    }
+
+    #[test]
+    fn test_format_verbose_timing() {
+        let mut response = response("ok");
+        response.duration = std::time::Duration::from_millis(125);
+        let formatter = ResponseFormatter::new(ResponseFormat::Verbose).with_color(false);
+        let output = formatter.format(&response).unwrap();
+        assert!(output.contains("Time: 125ms\n\nHeaders:"));
+
+        response.timing = Some(Timing {
+            ttfb: std::time::Duration::from_micros(40_250),
+            body: std::time::Duration::from_millis(85),
+        });
+        let output = formatter.format(&response).unwrap();
+        assert!(output.contains("Time: 125ms\n  TTFB: 40.2ms, Body: 85.0ms\n"));
+
+        // 紧凑模式只显示总耗时
+        let compact = ResponseFormatter::new(ResponseFormat::Compact).with_color(false);
+        assert!(!compact.format(&response).unwrap().contains("TTFB"));
     }
 }
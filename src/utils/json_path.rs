@@ -0,0 +1,230 @@
+//! 点号分隔的 JSON 路径（@assert body.x 与 @capture 共用）
+//!
+//! `user.id` 访问对象的键，`items.0.id` 或 `items[0].id` 访问数组下标。
+//! `items[*].id` 中的 `*` 匹配数组的所有元素（对象的所有值），见 [`lookup_all`]。
+
+use serde_json::Value;
+
+/// 路径无法在 JSON 值上解析
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JsonPathError {
+    #[error("Key '{key}' not found in path '{path}'")]
+    KeyNotFound { path: String, key: String },
+
+    #[error("Index {index} out of bounds in path '{path}'")]
+    IndexOutOfBounds { path: String, index: usize },
+
+    #[error("Cannot read '{segment}' of a {kind} value in path '{path}'")]
+    NotNavigable {
+        path: String,
+        segment: String,
+        kind: &'static str,
+    },
+}
+
+/// 将路径拆分为段，`items[0][1]` 拆为 `items`、`0`、`1`
+pub fn split_path(path: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut indices) = match part.find('[') {
+            Some(start) if part.ends_with(']') => part.split_at(start),
+            _ => (part, ""),
+        };
+        if !key.is_empty() || indices.is_empty() {
+            segments.push(key.to_string());
+        }
+        while let Some(rest) = indices.strip_prefix('[') {
+            let Some(end) = rest.find(']') else {
+                break;
+            };
+            segments.push(rest[..end].to_string());
+            indices = &rest[end + 1..];
+        }
+    }
+    segments
+}
+
+/// 按路径段查找值
+///
+/// 对象按键查找（数字形式的键也按键处理），数组按下标查找。
+pub fn lookup<'a, S: AsRef<str>>(
+    json: &'a Value,
+    segments: &[S],
+) -> Result<&'a Value, JsonPathError> {
+    let path = || {
+        segments
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    let mut current = json;
+    for segment in segments {
+        let segment = segment.as_ref();
+        current = match current {
+            Value::Object(map) => map.get(segment).ok_or_else(|| JsonPathError::KeyNotFound {
+                path: path(),
+                key: segment.to_string(),
+            })?,
+            Value::Array(items) => match segment.parse::<usize>() {
+                Ok(index) => items
+                    .get(index)
+                    .ok_or_else(|| JsonPathError::IndexOutOfBounds {
+                        path: path(),
+                        index,
+                    })?,
+                Err(_) => {
+                    return Err(JsonPathError::NotNavigable {
+                        path: path(),
+                        segment: segment.to_string(),
+                        kind: type_name(current),
+                    });
+                }
+            },
+            other => {
+                return Err(JsonPathError::NotNavigable {
+                    path: path(),
+                    segment: segment.to_string(),
+                    kind: type_name(other),
+                });
+            }
+        };
+    }
+    Ok(current)
+}
+
+/// 匹配所有数组元素或对象值的路径段
+pub const WILDCARD: &str = "*";
+
+/// 路径中是否包含 `*`
+pub fn has_wildcard<S: AsRef<str>>(segments: &[S]) -> bool {
+    segments.iter().any(|segment| segment.as_ref() == WILDCARD)
+}
+
+/// 按可能包含 `*` 的路径查找所有匹配的值，按文档顺序返回
+///
+/// `*` 之前的部分按 [`lookup`] 查找，找不到时返回错误；`*` 之后的部分在各元素上查找，
+/// 找不到的元素被跳过（与 JSONPath 一致），因此结果可能为空。
+pub fn lookup_all<'a, S: AsRef<str>>(
+    json: &'a Value,
+    segments: &[S],
+) -> Result<Vec<&'a Value>, JsonPathError> {
+    let Some(position) = segments.iter().position(|s| s.as_ref() == WILDCARD) else {
+        return lookup(json, segments).map(|value| vec![value]);
+    };
+
+    let children: Vec<&Value> = match lookup(json, &segments[..position])? {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        other => {
+            return Err(JsonPathError::NotNavigable {
+                path: segments
+                    .iter()
+                    .map(AsRef::as_ref)
+                    .collect::<Vec<_>>()
+                    .join("."),
+                segment: WILDCARD.to_string(),
+                kind: type_name(other),
+            });
+        }
+    };
+
+    let rest = &segments[position + 1..];
+    Ok(children
+        .into_iter()
+        .flat_map(|child| lookup_all(child, rest).unwrap_or_default())
+        .collect())
+}
+
+/// JSON 值的类型名称，用于错误信息
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(split_path("user.id"), vec!["user", "id"]);
+        assert_eq!(split_path("items[0].id"), vec!["items", "0", "id"]);
+        assert_eq!(split_path("matrix[1][2]"), vec!["matrix", "1", "2"]);
+        assert_eq!(split_path("items.0.id"), vec!["items", "0", "id"]);
+        assert_eq!(split_path("a[b"), vec!["a[b"]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let json = json!({"items": [{"id": 1}, {"id": 2}], "map": {"0": "zero"}, "n": 5});
+
+        assert_eq!(lookup(&json, &["items", "1", "id"]).unwrap(), &json!(2));
+        assert_eq!(lookup(&json, &["map", "0"]).unwrap(), &json!("zero"));
+        assert_eq!(lookup::<&str>(&json, &[]).unwrap(), &json);
+
+        assert_eq!(
+            lookup(&json, &["missing"]).unwrap_err().to_string(),
+            "Key 'missing' not found in path 'missing'"
+        );
+        assert!(matches!(
+            lookup(&json, &["items", "5"]),
+            Err(JsonPathError::IndexOutOfBounds { index: 5, .. })
+        ));
+        assert_eq!(
+            lookup(&json, &["n", "x"]).unwrap_err().to_string(),
+            "Cannot read 'x' of a number value in path 'n.x'"
+        );
+        assert!(matches!(
+            lookup(&json, &["items", "id"]),
+            Err(JsonPathError::NotNavigable { kind: "array", .. })
+        ));
+    }
+
+    #[test]
+    fn test_lookup_all() {
+        let json = json!({
+            "users": [
+                {"id": 1, "tags": ["a", "b"]},
+                {"name": "no id"},
+                {"id": 3, "tags": ["c"]}
+            ],
+            "n": 5
+        });
+
+        let ids = lookup_all(&json, &split_path("users[*].id")).unwrap();
+        assert_eq!(ids, vec![&json!(1), &json!(3)]);
+        let tags = lookup_all(&json, &split_path("users.*.tags[*]")).unwrap();
+        assert_eq!(tags, vec![&json!("a"), &json!("b"), &json!("c")]);
+        assert_eq!(
+            lookup_all(&json, &["users", "0", "id"]).unwrap(),
+            vec![&json!(1)]
+        );
+        assert!(
+            lookup_all(&json, &split_path("users[*].missing"))
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(matches!(
+            lookup_all(&json, &split_path("missing[*].id")),
+            Err(JsonPathError::KeyNotFound { .. })
+        ));
+        assert_eq!(
+            lookup_all(&json, &split_path("n[*]"))
+                .unwrap_err()
+                .to_string(),
+            "Cannot read '*' of a number value in path 'n.*'"
+        );
+        assert!(has_wildcard(&split_path("users[*].id")));
+        assert!(!has_wildcard(&split_path("users[0].id")));
+    }
+}
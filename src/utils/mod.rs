@@ -1,3 +1,6 @@
+pub mod cookie;
 mod formatter;
+pub mod json_path;
+pub mod shell;
 
 pub use formatter::{ResponseFormat, ResponseFormatter};
@@ -0,0 +1,146 @@
+use crate::{Result, RupostError};
+
+/// 按 POSIX shell 规则将命令字符串拆分为参数
+///
+/// - 空白分隔参数，单引号内原样保留
+/// - 双引号内支持 `\"`、`\\`、`\$`、`` \` `` 转义
+/// - 引号外的反斜杠转义下一个字符，行尾的 `\` 视为续行
+///
+/// 不做变量展开或通配符展开，引号未闭合时返回错误。
+pub fn split_words(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    // 区分 "" 这样的空参数和没有参数
+    let mut in_word = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated('\'')),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err(unterminated('"')),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated('"')),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // 续行
+                Some('\n') => {}
+                Some(c) => {
+                    in_word = true;
+                    current.push(c);
+                }
+                None => {
+                    in_word = true;
+                    current.push('\\');
+                }
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+fn unterminated(quote: char) -> RupostError {
+    RupostError::ParseError(format!("Unterminated {} quote in command", quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_plain() {
+        assert_eq!(
+            split_words("curl  -X POST\thttps://a.com").unwrap(),
+            vec!["curl", "-X", "POST", "https://a.com"]
+        );
+        assert!(split_words("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_quotes() {
+        assert_eq!(
+            split_words(r#"-H 'Content-Type: application/json' -d '{"a": "b c"}'"#).unwrap(),
+            vec![
+                "-H",
+                "Content-Type: application/json",
+                "-d",
+                r#"{"a": "b c"}"#
+            ]
+        );
+        assert_eq!(
+            split_words(r#"-d "say \"hi\" \$HOME \n""#).unwrap(),
+            vec!["-d", r#"say "hi" $HOME \n"#]
+        );
+        // 引号拼接与空参数
+        assert_eq!(
+            split_words(r#"a'b c'"d" '' """#).unwrap(),
+            vec!["ab cd", "", ""]
+        );
+        // 单引号内的转义写法 'it'\''s'
+        assert_eq!(split_words(r"'it'\''s'").unwrap(), vec!["it's"]);
+    }
+
+    #[test]
+    fn test_split_line_continuation() {
+        let input = "curl -X POST https://a.com \\\n  -H 'A: 1' \\\n  -d 'x'";
+        assert_eq!(
+            split_words(input).unwrap(),
+            vec![
+                "curl",
+                "-X",
+                "POST",
+                "https://a.com",
+                "-H",
+                "A: 1",
+                "-d",
+                "x"
+            ]
+        );
+        // 单引号内的换行原样保留
+        assert_eq!(split_words("'a\nb'").unwrap(), vec!["a\nb"]);
+    }
+
+    #[test]
+    fn test_split_unterminated() {
+        assert!(split_words("-d 'oops").is_err());
+        assert!(split_words(r#"-d "oops"#).is_err());
+    }
+}
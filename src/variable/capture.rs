@@ -1,6 +1,7 @@
 use crate::Result;
 use crate::error::RupostError;
-use reqwest::header::HeaderMap;
+use crate::http::Response;
+use crate::utils::json_path;
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -30,6 +31,19 @@ pub enum CaptureSource {
     Regex(String),
 }
 
+impl std::fmt::Display for CaptureSource {
+    /// @capture 中的来源写法，如 `body.token`、`header.X-Token`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureSource::Body(path) => write!(f, "body.{}", path),
+            CaptureSource::Header(name) => write!(f, "header.{}", name),
+            CaptureSource::TraceHeader => write!(f, "trace"),
+            CaptureSource::Cookie(name) => write!(f, "cookie.{}", name),
+            CaptureSource::Regex(pattern) => write!(f, "regex.{}", pattern),
+        }
+    }
+}
+
 /// 变量捕获配置
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct VariableCapture {
@@ -63,6 +77,7 @@ impl VariableCapture {
     /// - `body.token` → CaptureSource::Body("token")
     /// - `body.user.id` → CaptureSource::Body("user.id")
     /// - `header.X-Token` → CaptureSource::Header("X-Token")
+    /// - `header.Set-Cookie[1]` → CaptureSource::Header("Set-Cookie[1]")（第二个 Set-Cookie）
     pub fn parse(var_name: &str, source_str: &str) -> Self {
         let source = if let Some(path) = source_str.strip_prefix("body.") {
             CaptureSource::Body(path.to_string())
@@ -90,96 +105,114 @@ impl VariableCapture {
 }
 
 /// 从响应中提取变量
+///
+/// 从 Body 提取时复用响应缓存的 JSON 解析结果。
 pub fn capture_from_response(
-    response_body: &str,
-    response_headers: &HeaderMap,
+    response: &Response,
     captures: &[VariableCapture],
 ) -> Result<HashMap<String, String>> {
     let mut vars = HashMap::new();
 
-    if captures.is_empty() {
-        return Ok(vars);
-    }
-
-    // 只有当需要从 Body 提取时才解析 JSON
-    let body_value: Option<Value> = if captures
-        .iter()
-        .any(|c| matches!(c.source, CaptureSource::Body(_)))
-    {
-        serde_json::from_str(response_body).ok()
-    } else {
-        None
-    };
-
     for capture in captures {
-        let value = match &capture.source {
-            CaptureSource::Body(path) => {
-                if let Some(json) = &body_value {
-                    extract_from_json_path(json, path)?
-                } else {
-                    return Err(RupostError::ParseError(format!(
-                        "Response body is not valid JSON, cannot capture '{}'",
-                        capture.name
-                    )));
-                }
+        let value = capture_value(response, &capture.source).map_err(|reason| {
+            RupostError::CaptureError {
+                variable: capture.name.clone(),
+                reason,
             }
-            CaptureSource::Header(name) => response_headers
-                .get(name)
-                .and_then(|v| v.to_str().ok())
-                .map(String::from)
-                .ok_or_else(|| RupostError::Other(format!("Header '{}' not found", name)))?,
-            _ => {
-                return Err(RupostError::Other(format!(
-                    "Unsupported capture source for '{}'",
-                    capture.name
-                )));
+        })?;
+        match value {
+            Captured::Single(value) => {
+                vars.insert(capture.name.clone(), value);
             }
-        };
-
-        vars.insert(capture.name.clone(), value);
+            // `[*]` 匹配的多个值以逗号连接，同时按下标保存为 name.0、name.1 ...
+            Captured::List(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    vars.insert(format!("{}.{}", capture.name, index), value.clone());
+                }
+                vars.insert(capture.name.clone(), values.join(","));
+            }
+        }
     }
 
     Ok(vars)
 }
 
-/// 简单的 JSON Path 提取 (支持 . 符号)
-fn extract_from_json_path(json: &Value, path: &str) -> Result<String> {
-    let parts: Vec<&str> = path.split('.').collect();
-    let mut current = json;
-
-    for part in parts {
-        match current {
-            Value::Object(map) => {
-                if let Some(val) = map.get(part) {
-                    current = val;
-                } else {
-                    return Err(RupostError::Other(format!(
-                        "Key '{}' not found in path '{}'",
-                        part, path
-                    )));
-                }
+/// 一个 @capture 提取到的值
+enum Captured {
+    Single(String),
+    /// 路径中包含 `[*]` 时所有匹配的值
+    List(Vec<String>),
+}
+
+/// 提取单个变量的值，失败时返回原因
+fn capture_value(
+    response: &Response,
+    source: &CaptureSource,
+) -> std::result::Result<Captured, String> {
+    match source {
+        CaptureSource::Body(path) => {
+            if let Some(message) = response.truncation_error() {
+                return Err(message);
             }
-            _ => {
-                return Err(RupostError::Other(format!(
-                    "Cannot navigate path '{}' on non-object value",
-                    path
-                )));
+            match response.json_value() {
+                Some(json) => extract_from_json_path(json, path),
+                None => Err("response body is not valid JSON".to_string()),
             }
         }
+        CaptureSource::Header(name) => {
+            // `Set-Cookie[1]` 取同名 Header 中的第 n 个（从 0 开始）
+            let (name, index) = match name.strip_suffix(']').and_then(|r| r.split_once('[')) {
+                Some((name, index)) => match index.trim().parse::<usize>() {
+                    Ok(index) => (name, index),
+                    Err(_) => return Err(format!("invalid header index in '{}'", source)),
+                },
+                None => (name.as_str(), 0),
+            };
+            response
+                .headers
+                .get_all(name)
+                .iter()
+                .nth(index)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| Captured::Single(v.to_string()))
+                .ok_or_else(|| match index {
+                    0 => format!("header '{}' not found", name),
+                    _ => format!("header '{}' has no value at index {}", name, index),
+                })
+        }
+        other => Err(format!("capture source '{}' is not supported", other)),
     }
+}
 
-    match current {
-        Value::String(s) => Ok(s.clone()),
-        Value::Number(n) => Ok(n.to_string()),
-        Value::Bool(b) => Ok(b.to_string()),
-        Value::Null => Ok("null".to_string()),
-        _ => Ok(current.to_string()), // Object/Array 转为 JSON 字符串
+/// 按 JSON 路径提取值，路径包含 `[*]` 时提取所有匹配的值
+fn extract_from_json_path(json: &Value, path: &str) -> std::result::Result<Captured, String> {
+    let segments = json_path::split_path(path);
+    if json_path::has_wildcard(&segments) {
+        let values = json_path::lookup_all(json, &segments).map_err(|e| e.to_string())?;
+        return Ok(Captured::List(values.into_iter().map(value_text).collect()));
+    }
+
+    let value = json_path::lookup(json, &segments).map_err(|e| e.to_string())?;
+    Ok(Captured::Single(value_text(value)))
+}
+
+/// 字符串取原文，其他值（包括对象和数组）转为 JSON 文本
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reqwest::header::HeaderMap;
+    use std::time::Duration;
+
+    fn response(body: &str, headers: HeaderMap) -> Response {
+        Response::new(200, headers, body.to_string(), Duration::ZERO).unwrap()
+    }
 
     #[test]
     fn test_from_body() {
@@ -234,7 +267,7 @@ mod tests {
             VariableCapture::from_body("token", "token"),
         ];
 
-        let vars = capture_from_response(body, &headers, &captures).unwrap();
+        let vars = capture_from_response(&response(body, headers), &captures).unwrap();
         assert_eq!(vars.get("user_id").unwrap(), "123");
         assert_eq!(vars.get("token").unwrap(), "abc-123");
     }
@@ -247,27 +280,96 @@ mod tests {
 
         let captures = vec![VariableCapture::from_header("auth_token", "X-Token")];
 
-        let vars = capture_from_response(body, &headers, &captures).unwrap();
+        let vars = capture_from_response(&response(body, headers), &captures).unwrap();
         assert_eq!(vars.get("auth_token").unwrap(), "header-token-123");
     }
 
+    #[test]
+    fn test_capture_duplicate_header() {
+        let mut headers = HeaderMap::new();
+        headers.append("Set-Cookie", "session=abc".parse().unwrap());
+        headers.append("Set-Cookie", "theme=dark".parse().unwrap());
+        let captures = vec![
+            VariableCapture::parse("first", "header.Set-Cookie"),
+            VariableCapture::parse("second", "header.Set-Cookie[1]"),
+        ];
+
+        let vars = capture_from_response(&response("{}", headers.clone()), &captures).unwrap();
+        assert_eq!(vars.get("first").unwrap(), "session=abc");
+        assert_eq!(vars.get("second").unwrap(), "theme=dark");
+
+        let captures = vec![VariableCapture::parse("third", "header.Set-Cookie[2]")];
+        let err = capture_from_response(&response("{}", headers), &captures).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "无法捕获变量 'third': header 'Set-Cookie' has no value at index 2"
+        );
+    }
+
     #[test]
     fn test_capture_nested_json() {
         let body = r#"{"data": {"items": {"first": "item1"}}}"#;
         let headers = HeaderMap::new();
         let captures = vec![VariableCapture::from_body("item", "data.items.first")];
 
-        let vars = capture_from_response(body, &headers, &captures).unwrap();
+        let vars = capture_from_response(&response(body, headers), &captures).unwrap();
         assert_eq!(vars.get("item").unwrap(), "item1");
     }
 
+    #[test]
+    fn test_capture_array_index() {
+        let body = r#"{"items": [{"id": "a"}, {"id": "b"}], "tags": ["x"]}"#;
+        let captures = vec![
+            VariableCapture::from_body("second", "items.1.id"),
+            VariableCapture::from_body("first", "items[0].id"),
+            VariableCapture::from_body("tags", "tags"),
+        ];
+
+        let vars = capture_from_response(&response(body, HeaderMap::new()), &captures).unwrap();
+        assert_eq!(vars.get("second").unwrap(), "b");
+        assert_eq!(vars.get("first").unwrap(), "a");
+        assert_eq!(vars.get("tags").unwrap(), r#"["x"]"#);
+    }
+
+    #[test]
+    fn test_capture_wildcard() {
+        let body = r#"{"users": [{"id": 5, "name": "a"}, {"id": 7, "name": "b"}], "empty": []}"#;
+        let captures = vec![
+            VariableCapture::from_body("ids", "users[*].id"),
+            VariableCapture::from_body("names", "users.*.name"),
+            VariableCapture::from_body("none", "empty[*].id"),
+        ];
+
+        let vars = capture_from_response(&response(body, HeaderMap::new()), &captures).unwrap();
+        assert_eq!(vars.get("ids").unwrap(), "5,7");
+        assert_eq!(vars.get("ids.0").unwrap(), "5");
+        assert_eq!(vars.get("ids.1").unwrap(), "7");
+        assert_eq!(vars.get("names").unwrap(), "a,b");
+        assert_eq!(vars.get("none").unwrap(), "");
+        assert!(!vars.contains_key("none.0"));
+
+        let captures = vec![VariableCapture::from_body("ids", "missing[*].id")];
+        assert!(capture_from_response(&response(body, HeaderMap::new()), &captures).is_err());
+    }
+
     #[test]
     fn test_capture_missing_key() {
         let body = r#"{"data": {}}"#;
         let headers = HeaderMap::new();
         let captures = vec![VariableCapture::from_body("item", "data.missing")];
 
-        let result = capture_from_response(body, &headers, &captures);
-        assert!(result.is_err());
+        let err = capture_from_response(&response(body, headers), &captures).unwrap_err();
+        assert!(
+            matches!(err, RupostError::CaptureError { ref variable, .. } if variable == "item"),
+            "{:?}",
+            err
+        );
+
+        let captures = vec![VariableCapture::from_header("token", "X-Token")];
+        let err = capture_from_response(&response(body, HeaderMap::new()), &captures).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "无法捕获变量 'token': header 'X-Token' not found"
+        );
     }
 }
@@ -1,7 +1,135 @@
+use crate::parser::ParseError;
+use crate::parser::metadata::parse_duration;
 use crate::variable::resolver::VariableResolver;
-use crate::variable::types::{VariableConfig, VariableContext};
+use crate::variable::types::{RequestDefaults, ResolvedConfig, VariableConfig, VariableContext};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// 配置文件错误
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// 无法读取配置文件或变量文件
+    #[error("Failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// TOML 语法错误或字段类型不匹配，位置从 1 开始
+    #[error("Failed to parse {}{}: {message}", path.display(), location(*line, *column))]
+    TomlParse {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    /// 请求的环境不在 `[environments]` 中
+    #[error(
+        "Environment '{name}' is not defined in rupost.toml (available: {})",
+        available_list(available)
+    )]
+    UnknownEnvironment {
+        name: String,
+        available: Vec<String>,
+    },
+
+    /// `default_environment` 指向不存在的环境
+    #[error(
+        "default_environment '{name}' is not defined in rupost.toml (expected [environments.{name}])"
+    )]
+    UnknownDefaultEnvironment { name: String },
+
+    /// 指定了环境，但没有找到任何配置文件
+    #[error(
+        "Environment '{env}' was requested but no rupost.toml was found; searched:{}",
+        searched_list(searched)
+    )]
+    NotFound { env: String, searched: Vec<PathBuf> },
+
+    /// `[defaults]` 中的值无效
+    #[error("Invalid [defaults] {key}: {message}")]
+    InvalidDefault { key: &'static str, message: String },
+
+    /// 变量文件（--var-file）的行不是 key=value
+    #[error("Invalid var file line {line}: expected key=value, got: {content}")]
+    InvalidVarFile { line: usize, content: String },
+}
+
+impl ConfigError {
+    /// 由 TOML 错误构建，按错误位置计算行列号
+    fn toml(path: &Path, content: &str, err: toml::de::Error) -> Self {
+        let (line, column) = match err.span() {
+            Some(span) => {
+                let before = &content[..span.start.min(content.len())];
+                let line = before.matches('\n').count() + 1;
+                let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+                (Some(line), Some(column))
+            }
+            None => (None, None),
+        };
+        ConfigError::TomlParse {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message: err.message().trim_end().to_string(),
+        }
+    }
+}
+
+fn location(line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!(" at line {}, column {}", line, column),
+        (Some(line), None) => format!(" at line {}", line),
+        _ => String::new(),
+    }
+}
+
+fn searched_list(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("\n  {}", path.display()))
+        .collect()
+}
+
+fn available_list(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// 配置文件查找结果（见 [`ConfigLoader::find_and_load`]）
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLookup {
+    /// 合并后的配置，没有找到配置文件时为 None
+    pub config: Option<VariableConfig>,
+
+    /// 按查找顺序检查过的路径
+    pub searched: Vec<PathBuf>,
+}
+
+impl ConfigLookup {
+    /// 取出配置，没有找到配置文件时为空配置
+    pub fn into_config(self) -> VariableConfig {
+        self.config.unwrap_or_default()
+    }
+
+    /// 取出配置；指定了环境但没有找到配置文件时返回 [`ConfigError::NotFound`]，
+    /// 而不是继续使用空的变量上下文
+    pub fn require_env(self, env_name: Option<&str>) -> Result<VariableConfig, ConfigError> {
+        match (self.config, env_name) {
+            (Some(config), _) => Ok(config),
+            (None, Some(env)) => Err(ConfigError::NotFound {
+                env: env.to_string(),
+                searched: self.searched,
+            }),
+            (None, None) => Ok(VariableConfig::default()),
+        }
+    }
+}
 
 /// 配置文件加载器
 pub struct ConfigLoader;
@@ -10,76 +138,248 @@ impl ConfigLoader {
     /// 配置文件名
     const CONFIG_FILE: &'static str = "rupost.toml";
 
-    /// 从指定路径加载配置文件
-    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<VariableConfig, String> {
-        let content = fs::read_to_string(path.as_ref())
-            .map_err(|e| format!("Failed to read config file: {}", e))?;
+    /// 未指定 --env 时读取环境名称的环境变量
+    pub const ENV_NAME_VAR: &'static str = "RUPOST_ENV";
+
+    /// 显式不使用环境的名称（`--env none`）
+    pub const NO_ENV: &'static str = "none";
+
+    /// 持久化的捕获变量文件名（@persist / --persist-captures）
+    pub const CAPTURED_FILE: &'static str = "rupost.captured.toml";
 
-        toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))
+    /// 选择环境名称
+    ///
+    /// 优先级：--env、RUPOST_ENV 环境变量（为空时忽略）、配置的 `default_environment`，
+    /// 都没有时不使用环境。任一来源为 `none` 时不使用环境。
+    /// `default_environment` 不在 `[environments]` 中时报错。
+    pub fn env_name(
+        cli_env: Option<&str>,
+        config: &VariableConfig,
+    ) -> Result<Option<String>, ConfigError> {
+        let explicit = cli_env.map(String::from).or_else(|| {
+            std::env::var(Self::ENV_NAME_VAR)
+                .ok()
+                .filter(|name| !name.trim().is_empty())
+        });
+        let name = match (explicit, &config.default_environment) {
+            (Some(name), _) => name,
+            (None, Some(default)) if default != Self::NO_ENV => {
+                if config.get_environment(default).is_none() {
+                    return Err(ConfigError::UnknownDefaultEnvironment {
+                        name: default.clone(),
+                    });
+                }
+                default.clone()
+            }
+            (None, _) => return Ok(None),
+        };
+        Ok(Some(name).filter(|name| name != Self::NO_ENV))
     }
 
-    /// 查找并加载配置文件
-    /// 查找顺序：
-    /// 1. 当前目录
-    /// 2. 父目录递归查找
-    /// 3. 用户配置目录 ~/.config/rupost/
-    pub fn find_and_load() -> Option<VariableConfig> {
-        // 1. 当前目录
-        if let Some(config) = Self::try_load_from_current_dir() {
-            return Some(config);
-        }
+    /// 从指定路径加载配置文件
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<VariableConfig, ConfigError> {
+        Self::load_and_merge(&[path])
+    }
 
-        // 2. 用户配置目录
-        if let Some(config) = Self::try_load_from_user_dir() {
-            return Some(config);
+    /// 依次加载多个配置文件并深度合并，后面的文件优先
+    ///
+    /// 表（如 `[environments.dev]`、`[http]`）按键合并，只存在于一个文件中的键和环境都会保留；
+    /// 数组和其他值整体替换。
+    pub fn load_and_merge<P: AsRef<Path>>(paths: &[P]) -> Result<VariableConfig, ConfigError> {
+        let mut merged = toml::Table::new();
+        let mut last_path = PathBuf::new();
+        for path in paths {
+            let path = path.as_ref();
+            let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            // 先按配置结构解析，字段类型错误也能报告位置
+            toml::from_str::<VariableConfig>(&content)
+                .map_err(|e| ConfigError::toml(path, &content, e))?;
+            let table: toml::Table =
+                toml::from_str(&content).map_err(|e| ConfigError::toml(path, &content, e))?;
+            merge_tables(&mut merged, table);
+            last_path = path.to_path_buf();
         }
 
-        None
+        toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::TomlParse {
+                path: last_path,
+                line: None,
+                column: None,
+                message: e.message().trim_end().to_string(),
+            })
+    }
+
+    /// 查找并加载配置文件
+    ///
+    /// 用户配置 `~/.config/rupost/rupost.toml` 与项目配置（当前目录及其父目录中最近的
+    /// rupost.toml）都存在时合并两者，项目配置优先。
+    /// 没有找到配置文件时结果的 `config` 为 None，找到但无法读取或解析时返回错误。
+    pub fn find_and_load() -> Result<ConfigLookup, ConfigError> {
+        let mut searched = Vec::new();
+        let paths: Vec<PathBuf> = Self::user_config_path(&mut searched)
+            .into_iter()
+            .chain(Self::project_config_path(&mut searched))
+            .collect();
+        let config = if paths.is_empty() {
+            None
+        } else {
+            Some(Self::load_and_merge(&paths)?)
+        };
+        Ok(ConfigLookup { config, searched })
     }
 
-    /// 尝试从当前目录及其父目录加载
-    fn try_load_from_current_dir() -> Option<VariableConfig> {
+    /// 当前目录及其父目录中最近的配置文件
+    fn project_config_path(searched: &mut Vec<PathBuf>) -> Option<PathBuf> {
         let mut current = std::env::current_dir().ok()?;
 
         loop {
             let config_path = current.join(Self::CONFIG_FILE);
             if config_path.exists() {
-                return Self::load_from_path(&config_path).ok();
+                return Some(config_path);
             }
+            searched.push(config_path);
 
             // 尝试父目录
             if !current.pop() {
-                break;
+                return None;
             }
         }
-
-        None
     }
 
-    /// 尝试从用户配置目录加载
-    fn try_load_from_user_dir() -> Option<VariableConfig> {
+    /// 用户配置目录中的配置文件
+    fn user_config_path(searched: &mut Vec<PathBuf>) -> Option<PathBuf> {
         let home = dirs::home_dir()?;
         let config_path = home.join(".config").join("rupost").join(Self::CONFIG_FILE);
-
         if config_path.exists() {
-            Self::load_from_path(&config_path).ok()
-        } else {
-            None
+            return Some(config_path);
+        }
+        searched.push(config_path);
+        None
+    }
+
+    /// 持久化捕获变量的文件路径
+    ///
+    /// 位于 config_path（--config）或最近的 rupost.toml 所在目录，都没有时为当前目录。
+    pub fn captured_path(config_path: Option<&Path>) -> PathBuf {
+        let config_path = match config_path {
+            Some(path) => Some(path.to_path_buf()),
+            None => Self::project_config_path(&mut Vec::new()),
+        };
+        config_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""))
+            .join(Self::CAPTURED_FILE)
+    }
+
+    /// 读取指定环境持久化的捕获变量，文件不存在时为空
+    ///
+    /// 文件中每个环境一个表，没有环境时使用 `[none]`。
+    pub fn load_captured(
+        path: &Path,
+        env_name: Option<&str>,
+    ) -> Result<Vec<(String, String)>, ConfigError> {
+        let table = Self::read_captured(path)?;
+        let Some(toml::Value::Table(vars)) = table.get(env_name.unwrap_or(Self::NO_ENV)) else {
+            return Ok(Vec::new());
+        };
+        Ok(vars
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect())
+    }
+
+    /// 将捕获的变量写入指定环境的表，覆盖同名变量，其他环境和变量保持不变
+    pub fn save_captured(
+        path: &Path,
+        env_name: Option<&str>,
+        vars: &[(String, String)],
+    ) -> Result<(), ConfigError> {
+        if vars.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Self::read_captured(path)?;
+        let section = table
+            .entry(env_name.unwrap_or(Self::NO_ENV))
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if !section.is_table() {
+            *section = toml::Value::Table(toml::Table::new());
+        }
+        if let toml::Value::Table(section) = section {
+            for (key, value) in vars {
+                section.insert(key.clone(), toml::Value::String(value.clone()));
+            }
+        }
+
+        let content = format!(
+            "# Captured by rupost (@persist / --persist-captures), one table per environment.\n\
+             # Loaded with the lowest priority; safe to delete.\n\n{}",
+            toml::to_string(&table).unwrap_or_default()
+        );
+        fs::write(path, content).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn read_captured(path: &Path) -> Result<toml::Table, ConfigError> {
+        if !path.exists() {
+            return Ok(toml::Table::new());
+        }
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|e| ConfigError::toml(path, &content, e))
+    }
+
+    /// 以最低优先级并入持久化的捕获变量：上下文中已有的变量（环境、--var 等）不被覆盖
+    pub fn merge_captured(context: &mut VariableContext, captured: Vec<(String, String)>) {
+        for (key, value) in captured {
+            if context.get(&key).is_none() {
+                context.insert(key, value);
+            }
         }
     }
 
     /// 构建变量上下文
-    /// env_name: 环境名称（如 "dev", "prod"）
+    /// env_name: 环境名称（如 "dev", "prod"），同时以 `env` 变量注入上下文
     /// cli_vars: CLI 传入的变量覆盖（--var key=value）
+    ///
+    /// 指定的环境不在配置中时返回 [`ConfigError::UnknownEnvironment`]。
     pub fn build_context(
         config: &VariableConfig,
         env_name: Option<&str>,
         cli_vars: &[(String, String)],
-    ) -> VariableContext {
+    ) -> Result<VariableContext, ConfigError> {
         let mut context = VariableContext::new();
 
+        // 0. 注入当前环境名称，供 @skip-if 等条件引用
+        if let Some(name) = env_name {
+            context.insert("env", name);
+        }
+
         // 1. 从配置文件加载环境变量
-        if let Some(env) = env_name.and_then(|name| config.get_environment(name)) {
+        if let Some(name) = env_name {
+            let env = config.get_environment(name).ok_or_else(|| {
+                let mut available: Vec<String> = config.environments.keys().cloned().collect();
+                available.sort();
+                ConfigError::UnknownEnvironment {
+                    name: name.to_string(),
+                    available,
+                }
+            })?;
             for (key, value) in &env.variables {
                 // 解析系统环境变量 ${VAR}
                 let resolved_value = VariableResolver::resolve_env_vars(value);
@@ -92,7 +392,48 @@ impl ConfigLoader {
             context.insert(key.clone(), value.clone());
         }
 
-        context
+        Ok(context)
+    }
+
+    /// 合并指定环境的请求默认值
+    ///
+    /// 优先级从低到高：`[headers]`、`[defaults]`、`[environments.<name>.defaults]`，
+    /// header 名称不区分大小写。
+    pub fn resolve(
+        config: &VariableConfig,
+        env_name: Option<&str>,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        let mut resolved = ResolvedConfig::default();
+        merge_headers(&mut resolved.headers, &config.headers);
+
+        let env_defaults = env_name
+            .and_then(|name| config.get_environment(name))
+            .map(|env| &env.defaults);
+        for defaults in std::iter::once(&config.defaults).chain(env_defaults) {
+            let RequestDefaults {
+                headers,
+                timeout,
+                base_url,
+            } = defaults;
+            merge_headers(&mut resolved.headers, headers);
+            if let Some(timeout) = timeout {
+                let timeout = parse_duration(timeout).map_err(|e| ConfigError::InvalidDefault {
+                    key: "timeout",
+                    // parse_duration 不知道行号，只保留说明
+                    message: match e {
+                        ParseError::InvalidMetadata { message, .. } => message,
+                        other => other.to_string(),
+                    },
+                })?;
+                resolved.timeout = Some(timeout);
+            }
+            if let Some(base_url) = base_url {
+                resolved.base_url = Some(base_url.clone());
+            }
+        }
+
+        resolved.headers.sort();
+        Ok(resolved)
     }
 
     /// 解析 CLI 变量参数 "key=value"
@@ -100,6 +441,59 @@ impl ConfigLoader {
         s.split_once('=')
             .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
     }
+
+    /// 从文件加载变量覆盖（--var-file）
+    pub fn load_var_file<P: AsRef<Path>>(path: P) -> Result<Vec<(String, String)>, ConfigError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_var_file(&content)
+    }
+
+    /// 解析变量文件：每行一个 key=value，跳过空行和 # 注释
+    pub fn parse_var_file(content: &str) -> Result<Vec<(String, String)>, ConfigError> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(index, line)| {
+                Self::parse_cli_var(line).ok_or_else(|| ConfigError::InvalidVarFile {
+                    line: index + 1,
+                    content: line.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 将 overlay 深度合并到 base：两边都是表时递归合并，否则 overlay 的值覆盖
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(table)) => {
+                merge_tables(existing, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// 合并 header，同名（不区分大小写）时后者覆盖前者
+fn merge_headers(
+    target: &mut Vec<(String, String)>,
+    headers: &std::collections::HashMap<String, String>,
+) {
+    for (key, value) in headers {
+        target.retain(|(existing, _)| !existing.eq_ignore_ascii_case(key));
+        target.push((key.clone(), value.clone()));
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +502,43 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_parse_var_file() {
+        let content = "# 注释\n\nbase_url = http://localhost:8080/api\ngreeting=hello world\nquery = a=1&b=2\n  token=  abc  \n";
+        let vars = ConfigLoader::parse_var_file(content).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "base_url".to_string(),
+                    "http://localhost:8080/api".to_string()
+                ),
+                ("greeting".to_string(), "hello world".to_string()),
+                ("query".to_string(), "a=1&b=2".to_string()),
+                ("token".to_string(), "abc".to_string()),
+            ]
+        );
+
+        let err = ConfigLoader::parse_var_file("a=1\nbroken\n").unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidVarFile { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_var_file_precedence() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "token = from file\nuser=admin").unwrap();
+
+        // 与 main 中 --var-file 的合并顺序一致：文件在前，--var 在后
+        let mut cli_vars = ConfigLoader::load_var_file(temp_file.path()).unwrap();
+        cli_vars.extend(ConfigLoader::parse_cli_var("token=from cli"));
+        let context =
+            ConfigLoader::build_context(&VariableConfig::default(), None, &cli_vars).unwrap();
+
+        assert_eq!(context.get("token"), Some("from cli"));
+        assert_eq!(context.get("user"), Some("admin"));
+        assert!(ConfigLoader::load_var_file("/nonexistent/vars.env").is_err());
+    }
+
     #[test]
     fn test_load_from_path() {
         let config_content = r#"
@@ -125,6 +556,123 @@ api_key = "dev-key"
         assert!(config.get_environment("dev").is_some());
     }
 
+    #[test]
+    fn test_load_reports_parse_location() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            "[environments.dev]\nbase_url = \"http://localhost\"\ntoken = dev-token\n"
+        )
+        .unwrap();
+        let err = ConfigLoader::load_from_path(temp_file.path()).unwrap_err();
+        match &err {
+            ConfigError::TomlParse { line, column, .. } => {
+                assert_eq!(*line, Some(3));
+                assert_eq!(*column, Some(9));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(err.to_string().contains("at line 3, column 9"), "{}", err);
+
+        // 字段类型错误同样带位置
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "[http]\nproxy = 8080\n").unwrap();
+        let err = ConfigLoader::load_from_path(temp_file.path()).unwrap_err();
+        assert!(
+            matches!(err, ConfigError::TomlParse { line: Some(2), .. }),
+            "{:?}",
+            err
+        );
+
+        let err = ConfigLoader::load_from_path("/nonexistent/rupost.toml").unwrap_err();
+        assert!(matches!(err, ConfigError::Io { .. }));
+    }
+
+    #[test]
+    fn test_require_env_without_config() {
+        let lookup = ConfigLookup {
+            config: None,
+            searched: vec![
+                PathBuf::from("/work/api/rupost.toml"),
+                PathBuf::from("/work/rupost.toml"),
+            ],
+        };
+        assert!(lookup.clone().require_env(None).is_ok());
+
+        let err = lookup.require_env(Some("staging")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment 'staging' was requested but no rupost.toml was found; searched:\n  /work/api/rupost.toml\n  /work/rupost.toml"
+        );
+
+        let lookup = ConfigLookup {
+            config: Some(VariableConfig::default()),
+            searched: Vec::new(),
+        };
+        assert!(lookup.require_env(Some("staging")).is_ok());
+    }
+
+    #[test]
+    fn test_persisted_captures() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ConfigLoader::CAPTURED_FILE);
+        let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        assert!(
+            ConfigLoader::load_captured(&path, Some("dev"))
+                .unwrap()
+                .is_empty()
+        );
+
+        ConfigLoader::save_captured(&path, Some("dev"), &vars(&[("token", "t1"), ("id", "7")]))
+            .unwrap();
+        ConfigLoader::save_captured(&path, None, &vars(&[("token", "anon")])).unwrap();
+        // 覆盖同名变量，保留其他变量
+        ConfigLoader::save_captured(&path, Some("dev"), &vars(&[("token", "t2")])).unwrap();
+
+        assert_eq!(
+            ConfigLoader::load_captured(&path, Some("dev")).unwrap(),
+            vars(&[("id", "7"), ("token", "t2")])
+        );
+        assert_eq!(
+            ConfigLoader::load_captured(&path, None).unwrap(),
+            vars(&[("token", "anon")])
+        );
+        assert!(
+            ConfigLoader::load_captured(&path, Some("prod"))
+                .unwrap()
+                .is_empty()
+        );
+
+        // 优先级最低：环境变量和 --var 不被覆盖
+        let config: VariableConfig =
+            toml::from_str("[environments.dev]\ntoken = \"from-env\"\n").unwrap();
+        let mut context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
+        ConfigLoader::merge_captured(
+            &mut context,
+            ConfigLoader::load_captured(&path, Some("dev")).unwrap(),
+        );
+        assert_eq!(context.get("token"), Some("from-env"));
+        assert_eq!(context.get("id"), Some("7"));
+    }
+
+    #[test]
+    fn test_captured_path() {
+        assert_eq!(
+            ConfigLoader::captured_path(Some(Path::new("/work/api/rupost.toml"))),
+            PathBuf::from("/work/api/rupost.captured.toml")
+        );
+        assert_eq!(
+            ConfigLoader::captured_path(Some(Path::new("custom.toml"))),
+            PathBuf::from("rupost.captured.toml")
+        );
+    }
+
     #[test]
     fn test_build_context() {
         let config_content = r#"
@@ -140,14 +688,84 @@ token = "${PROD_TOKEN}"
         let config: VariableConfig = toml::from_str(config_content).unwrap();
 
         // 测试 dev 环境
-        let context = ConfigLoader::build_context(&config, Some("dev"), &[]);
+        let context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
         assert_eq!(context.get("base_url"), Some("http://localhost:8080"));
         assert_eq!(context.get("token"), Some("dev-token"));
+        assert_eq!(context.get("env"), Some("dev"));
 
         // 测试 CLI 覆盖
         let cli_vars = vec![("token".to_string(), "custom-token".to_string())];
-        let context = ConfigLoader::build_context(&config, Some("dev"), &cli_vars);
+        let context = ConfigLoader::build_context(&config, Some("dev"), &cli_vars).unwrap();
         assert_eq!(context.get("token"), Some("custom-token"));
+
+        // 不存在的环境报错并列出可用的环境
+        let err = ConfigLoader::build_context(&config, Some("qa"), &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment 'qa' is not defined in rupost.toml (available: dev, prod)"
+        );
+    }
+
+    #[test]
+    fn test_resolve_defaults() {
+        let config: VariableConfig = toml::from_str(
+            r#"
+[headers]
+Accept = "text/plain"
+X-Trace = "legacy"
+
+[defaults]
+timeout = "10s"
+base_url = "http://localhost:8080"
+
+[defaults.headers]
+accept = "application/json"
+
+[environments.dev]
+token = "dev-token"
+
+[environments.prod]
+base_url = "https://api.example.com"
+
+[environments.prod.defaults]
+base_url = "https://api.example.com/v2"
+headers = { X-Trace = "prod" }
+"#,
+        )
+        .unwrap();
+
+        let dev = ConfigLoader::resolve(&config, Some("dev")).unwrap();
+        assert_eq!(
+            dev.headers,
+            vec![
+                ("X-Trace".to_string(), "legacy".to_string()),
+                ("accept".to_string(), "application/json".to_string()),
+            ]
+        );
+        assert_eq!(dev.timeout, Some(std::time::Duration::from_secs(10)));
+        assert_eq!(dev.base_url.as_deref(), Some("http://localhost:8080"));
+
+        // 环境的 defaults 覆盖全局值，同名的环境变量不受影响
+        let prod = ConfigLoader::resolve(&config, Some("prod")).unwrap();
+        assert_eq!(
+            prod.headers,
+            vec![
+                ("X-Trace".to_string(), "prod".to_string()),
+                ("accept".to_string(), "application/json".to_string()),
+            ]
+        );
+        assert_eq!(prod.base_url.as_deref(), Some("https://api.example.com/v2"));
+        let context = ConfigLoader::build_context(&config, Some("prod"), &[]).unwrap();
+        assert_eq!(context.get("base_url"), Some("https://api.example.com"));
+        assert_eq!(context.get("defaults"), None);
+
+        let invalid: VariableConfig = toml::from_str("[defaults]\ntimeout = \"soon\"\n").unwrap();
+        assert!(
+            ConfigLoader::resolve(&invalid, None)
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid [defaults] timeout: Duration must end with")
+        );
     }
 
     #[test]
@@ -4,6 +4,8 @@ pub mod resolver;
 pub mod types;
 
 pub use capture::{CaptureSource, VariableCapture, capture_from_response};
-pub use config::ConfigLoader;
+pub use config::{ConfigError, ConfigLoader, ConfigLookup};
 pub use resolver::VariableResolver;
-pub use types::{Environment, VariableConfig, VariableContext};
+pub use types::{
+    Environment, HttpConfig, RequestDefaults, ResolvedConfig, VariableConfig, VariableContext,
+};
@@ -1,21 +1,86 @@
 use crate::variable::types::VariableContext;
+use chrono::Utc;
+use rand::Rng;
 use regex::{Captures, Regex};
 use std::sync::OnceLock;
 
+/// 嵌套变量展开的最大轮数，防止循环引用导致死循环
+const MAX_SUBSTITUTE_PASSES: usize = 10;
+
+/// 匹配 {{variable}} 占位符，允许花括号内两侧有空白（如 `{{ name }}`）
+///
+/// 分组 1 为变量名，分组 2 为 `:-` 之后的默认值。替换与未解析检测共用此模式。
+fn placeholder_regex() -> &'static Regex {
+    static VAR_REGEX: OnceLock<Regex> = OnceLock::new();
+    VAR_REGEX.get_or_init(|| {
+        Regex::new(r"\{\{\s*(\$?[a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z0-9_]+)*)(?::-(.*?))?\s*\}\}")
+            .unwrap()
+    })
+}
+
 /// 变量替换器
 pub struct VariableResolver;
 
 impl VariableResolver {
-    /// 替换文本中的所有 {{variable}} 占位符
+    /// 替换文本中的所有 {{variable}} 占位符（花括号内两侧的空白会被忽略）
+    ///
+    /// 以 `$` 开头的名称为内置动态变量（如 `{{$uuid}}`），每次替换时重新生成。
+    /// 名称可以包含 `.` 分隔的段，如配置数组展开后的 `{{hosts.0}}`。
+    /// 支持 `{{var:-default}}` 语法，变量未定义时使用默认值（默认值可包含 `:`、`/` 等字符）。
+    /// 变量值中引用的其他变量会被递归展开，直到结果不再变化或达到最大轮数。
     pub fn substitute(text: &str, context: &VariableContext) -> String {
-        static VAR_REGEX: OnceLock<Regex> = OnceLock::new();
-        let re = VAR_REGEX.get_or_init(|| Regex::new(r"\{\{([a-zA-Z_][a-zA-Z0-9_]*)\}\}").unwrap());
+        let mut current = text.to_string();
+        for _ in 0..MAX_SUBSTITUTE_PASSES {
+            let next = Self::substitute_once(&current, context);
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        current
+    }
 
-        re.replace_all(text, |caps: &Captures| {
-            let var_name = &caps[1];
-            context.get(var_name).unwrap_or(&caps[0]).to_string()
-        })
-        .to_string()
+    /// 单轮替换
+    fn substitute_once(text: &str, context: &VariableContext) -> String {
+        placeholder_regex()
+            .replace_all(text, |caps: &Captures| {
+                let var_name = &caps[1];
+                let default = caps.get(2).map(|m| m.as_str().to_string());
+
+                let value = match var_name.strip_prefix('$') {
+                    Some(builtin) => Self::dynamic_value(builtin, context),
+                    None => context.get(var_name).map(|v| v.to_string()),
+                };
+
+                value.or(default).unwrap_or_else(|| caps[0].to_string())
+            })
+            .to_string()
+    }
+
+    /// 生成内置动态变量的值，未知名称返回 None
+    ///
+    /// - `$uuid`: 随机 UUID v4
+    /// - `$timestamp`: Unix 时间戳（秒）
+    /// - `$isoTimestamp`: ISO 8601 格式的当前 UTC 时间
+    /// - `$randomInt`: 0..10000 之间的随机整数
+    ///
+    /// 上下文设置了种子（--seed）时 `$uuid` 和 `$randomInt` 可复现。
+    fn dynamic_value(name: &str, context: &VariableContext) -> Option<String> {
+        match name {
+            "uuid" => Some(
+                context
+                    .with_rng(|rng| uuid::Builder::from_random_bytes(rng.random()).into_uuid())
+                    .to_string(),
+            ),
+            "timestamp" => Some(Utc::now().timestamp().to_string()),
+            "isoTimestamp" => Some(Utc::now().to_rfc3339()),
+            "randomInt" => Some(
+                context
+                    .with_rng(|rng| rng.random_range(0..10000))
+                    .to_string(),
+            ),
+            _ => None,
+        }
     }
 
     /// 解析并替换系统环境变量 ${VAR}
@@ -35,11 +100,26 @@ impl VariableResolver {
         let with_env = Self::resolve_env_vars(text);
         Self::substitute(&with_env, context)
     }
+
+    /// 找出文本中残留的 {{variable}} 占位符名称（按出现顺序去重）
+    ///
+    /// 通常在 `resolve` 之后调用，用于检测未定义的变量。
+    pub fn unresolved(text: &str) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for caps in placeholder_regex().captures_iter(text) {
+            let name = &caps[1];
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use uuid::Uuid;
 
     #[test]
     fn test_substitute_simple() {
@@ -52,6 +132,21 @@ mod tests {
         assert_eq!(output, "http://localhost:8080/api/users");
     }
 
+    #[test]
+    fn test_substitute_dotted_name() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("hosts", "a.example.com,b.example.com");
+        ctx.insert("hosts.1", "b.example.com");
+
+        let output = VariableResolver::substitute("https://{{hosts.1}}/{{hosts.5:-none}}", &ctx);
+        assert_eq!(output, "https://b.example.com/none");
+        assert_eq!(
+            VariableResolver::substitute("{{hosts}}", &ctx),
+            "a.example.com,b.example.com"
+        );
+        assert_eq!(VariableResolver::unresolved("{{hosts.2}}"), vec!["hosts.2"]);
+    }
+
     #[test]
     fn test_substitute_multiple() {
         let mut ctx = VariableContext::new();
@@ -74,6 +169,142 @@ mod tests {
         assert_eq!(output, "{{missing}}/path");
     }
 
+    #[test]
+    fn test_substitute_nested() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("a", "{{b}}");
+        ctx.insert("b", "{{c}}");
+        ctx.insert("c", "done");
+        assert_eq!(VariableResolver::substitute("{{a}}", &ctx), "done");
+
+        ctx.insert("host", "example.com");
+        ctx.insert("base", "http://{{host}}");
+        assert_eq!(
+            VariableResolver::substitute("{{base}}/api", &ctx),
+            "http://example.com/api"
+        );
+    }
+
+    #[test]
+    fn test_substitute_cycle_terminates() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("a", "{{b}}");
+        ctx.insert("b", "{{a}}");
+
+        // 循环引用在达到最大轮数后停止，保留未展开的占位符
+        assert_eq!(VariableResolver::substitute("{{a}}", &ctx), "{{a}}");
+    }
+
+    #[test]
+    fn test_substitute_default_value() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("token", "secret");
+
+        // 未定义时使用默认值，默认值可包含 : 和 /
+        assert_eq!(
+            VariableResolver::substitute("{{base_url:-http://localhost:8080}}/users", &ctx),
+            "http://localhost:8080/users"
+        );
+        // 已定义时忽略默认值
+        assert_eq!(
+            VariableResolver::substitute("Bearer {{token:-anonymous}}", &ctx),
+            "Bearer secret"
+        );
+        // 空默认值
+        assert_eq!(VariableResolver::substitute("[{{missing:-}}]", &ctx), "[]");
+        // 同一行多个带默认值的变量
+        assert_eq!(
+            VariableResolver::substitute("{{host:-a.com}}:{{port:-80}}", &ctx),
+            "a.com:80"
+        );
+    }
+
+    #[test]
+    fn test_unresolved() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("base_url", "http://localhost");
+
+        let resolved = VariableResolver::resolve(
+            "{{base_url}}/users/{{user_id}}?t={{token}}&again={{user_id}}",
+            &ctx,
+        );
+        assert_eq!(
+            VariableResolver::unresolved(&resolved),
+            vec!["user_id".to_string(), "token".to_string()]
+        );
+        assert!(VariableResolver::unresolved("http://localhost/users").is_empty());
+        // 未知的内置变量同样视为未解析
+        assert_eq!(VariableResolver::unresolved("{{$nope}}"), vec!["$nope"]);
+    }
+
+    #[test]
+    fn test_placeholder_whitespace() {
+        let mut ctx = VariableContext::new();
+        ctx.insert("host", "example.com");
+
+        assert_eq!(
+            VariableResolver::substitute("https://{{ host }}/{{ path:-users }}", &ctx),
+            "https://example.com/users"
+        );
+        // 替换与未解析检测对空白的处理一致
+        let resolved = VariableResolver::resolve("{{ host }}/{{ token }}", &ctx);
+        assert_eq!(resolved, "example.com/{{ token }}");
+        assert_eq!(VariableResolver::unresolved(&resolved), vec!["token"]);
+    }
+
+    #[test]
+    fn test_substitute_dynamic_variables() {
+        let ctx = VariableContext::new();
+
+        let uuid = VariableResolver::substitute("{{$uuid}}", &ctx);
+        assert!(Uuid::parse_str(&uuid).is_ok());
+        // 每次替换都生成新值
+        let output = VariableResolver::substitute("{{$uuid}} {{$uuid}}", &ctx);
+        let (first, second) = output.split_once(' ').unwrap();
+        assert_ne!(first, second);
+
+        let timestamp = VariableResolver::substitute("{{$timestamp}}", &ctx);
+        assert!(timestamp.parse::<i64>().unwrap() > 0);
+
+        let iso = VariableResolver::substitute("{{$isoTimestamp}}", &ctx);
+        assert!(chrono::DateTime::parse_from_rfc3339(&iso).is_ok());
+
+        let random: u32 = VariableResolver::substitute("{{$randomInt}}", &ctx)
+            .parse()
+            .unwrap();
+        assert!(random < 10000);
+
+        // 未知的内置变量保持原样
+        assert_eq!(
+            VariableResolver::substitute("{{$unknown}}", &ctx),
+            "{{$unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_substitute_seeded_dynamic_variables() {
+        let template = "{{$uuid}} {{$randomInt}} {{$uuid}}";
+        let run = |seed: u64| {
+            let mut ctx = VariableContext::new();
+            ctx.set_seed(seed);
+            let first = VariableResolver::substitute(template, &ctx);
+            // 克隆的上下文继续同一序列
+            let second = VariableResolver::substitute(template, &ctx.clone());
+            (first, second)
+        };
+
+        let (first, second) = run(42);
+        assert_eq!(run(42), (first.clone(), second.clone()));
+        assert_ne!(first, second);
+        assert_ne!(run(43).0, first);
+
+        let values: Vec<&str> = first.split(' ').collect();
+        let uuid = Uuid::parse_str(values[0]).unwrap();
+        assert_eq!(uuid.get_version_num(), 4);
+        assert_ne!(values[0], values[2]);
+        assert!(values[1].parse::<u32>().unwrap() < 10000);
+    }
+
     #[test]
     fn test_resolve_env_vars() {
         // 设置测试环境变量
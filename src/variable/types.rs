@@ -1,11 +1,24 @@
+use crate::parser::types::ParsedRequest;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// 变量上下文，存储所有可用变量
 #[derive(Debug, Clone, Default)]
 pub struct VariableContext {
     /// 变量映射表
     variables: HashMap<String, String>,
+    /// 设置种子（--seed）后动态变量使用的随机数生成器，克隆的上下文共享同一序列
+    rng: Option<SeededRng>,
+}
+
+#[derive(Debug, Clone)]
+struct SeededRng {
+    seed: u64,
+    rng: Arc<Mutex<StdRng>>,
 }
 
 impl VariableContext {
@@ -43,22 +56,201 @@ impl VariableContext {
     pub fn is_empty(&self) -> bool {
         self.variables.is_empty()
     }
+
+    /// 设置随机种子（--seed），之后 `{{$uuid}}`、`{{$randomInt}}` 按固定序列生成
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(SeededRng {
+            seed,
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        });
+    }
+
+    /// 当前的随机种子，未设置时为 None
+    pub fn seed(&self) -> Option<u64> {
+        self.rng.as_ref().map(|rng| rng.seed)
+    }
+
+    /// 使用上下文的随机数生成器，未设置种子时使用线程本地的随机数生成器
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        match &self.rng {
+            Some(seeded) => f(&mut *seeded.rng.lock().unwrap()),
+            None => f(&mut rand::rng()),
+        }
+    }
 }
 
 /// 环境配置
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct Environment {
-    /// 变量映射
-    #[serde(flatten)]
+    /// 该环境的请求默认值（`[environments.<name>.defaults]`），覆盖全局的 `[defaults]`
+    #[serde(default)]
+    pub defaults: RequestDefaults,
+
+    /// 变量映射，数组值展开为多个变量（见 [`flatten_variables`]）
+    #[serde(flatten, deserialize_with = "flatten_variables")]
     pub variables: HashMap<String, String>,
 }
 
+/// 环境变量的值：字符串或字符串数组
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VariableValue {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+/// 将数组值展开为变量
+///
+/// `hosts = ["a", "b"]` 得到 `hosts.0 = "a"`、`hosts.1 = "b"`，
+/// `hosts` 本身为逗号连接的 `"a,b"`；字符串值保持不变。
+fn flatten_variables<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = HashMap::<String, VariableValue>::deserialize(deserializer)?;
+    let mut variables = HashMap::with_capacity(raw.len());
+    for (key, value) in raw {
+        match value {
+            VariableValue::Scalar(value) => {
+                variables.insert(key, value);
+            }
+            VariableValue::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    variables.insert(format!("{}.{}", key, index), item.clone());
+                }
+                variables.insert(key, items.join(","));
+            }
+        }
+    }
+    Ok(variables)
+}
+
+/// 请求默认值（`[defaults]`），由 TestExecutor 应用到每个请求
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestDefaults {
+    /// 默认请求头，请求自身的同名 header 优先
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// 请求未指定 @timeout 时的超时，格式同 @timeout（如 "10s"）
+    pub timeout: Option<String>,
+
+    /// 相对 URL（`GET /api/users`）拼接到的 base URL，--base-url 优先
+    pub base_url: Option<String>,
+}
+
+/// 按当前环境合并后的配置（见 [`ConfigLoader::resolve`](crate::variable::ConfigLoader::resolve)）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedConfig {
+    /// 默认请求头（按名称排序）
+    pub headers: Vec<(String, String)>,
+
+    /// 默认超时
+    pub timeout: Option<Duration>,
+
+    /// 相对 URL 的 base URL
+    pub base_url: Option<String>,
+}
+
 /// 完整的变量配置文件
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct VariableConfig {
+    /// 未指定 --env 和 RUPOST_ENV 时使用的环境
+    pub default_environment: Option<String>,
+
     /// 所有环境配置
     #[serde(default)]
     pub environments: HashMap<String, Environment>,
+
+    /// 默认请求头（[headers]），合并到每个请求中，请求自身的同名 header 优先
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// 全局请求默认值（[defaults]），其中的 headers 覆盖 [headers] 的同名 header
+    #[serde(default)]
+    pub defaults: RequestDefaults,
+
+    /// HTTP 客户端选项（[http]）
+    #[serde(default)]
+    pub http: HttpConfig,
+}
+
+/// `[http]` 配置，命令行选项优先
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HttpConfig {
+    /// 不校验服务器证书（同 --insecure）
+    #[serde(default)]
+    pub insecure: bool,
+
+    /// 额外信任的 PEM 格式 CA 证书路径（同 --cacert）
+    pub ca_cert: Option<String>,
+
+    /// 代理地址（同 --proxy），未设置时使用 HTTP_PROXY / HTTPS_PROXY 环境变量
+    pub proxy: Option<String>,
+
+    /// DNS 覆盖（同 --resolve），如 ["api.example.com:443:10.0.0.5"]
+    #[serde(default)]
+    pub resolve: Vec<String>,
+
+    /// 在内存中保留的最大响应 body 字节数，默认 10 MB，0 表示不限制
+    pub max_body_size: Option<usize>,
+
+    /// 连接级别的自动重试（`[http.retry]`）
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+/// `[http.retry]` 配置，--retry 覆盖 max_attempts
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RetryConfig {
+    /// 最多尝试次数（包括第一次请求），默认 1 即不重试
+    pub max_attempts: Option<u32>,
+
+    /// 第一次重试前的等待毫秒数，之后每次翻倍
+    pub base_delay_ms: Option<u64>,
+
+    /// 需要重试的响应状态码，如 [429, 502, 503]
+    #[serde(default)]
+    pub retry_on_status: Vec<u16>,
+}
+
+impl ResolvedConfig {
+    /// 合并另一份默认值：同名 header（不区分大小写）、超时和 base URL 以 other 中设置的为准
+    pub fn merge(&mut self, other: ResolvedConfig) {
+        for (name, value) in other.headers {
+            self.headers
+                .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+            self.headers.push((name, value));
+        }
+        self.headers.sort();
+        if other.timeout.is_some() {
+            self.timeout = other.timeout;
+        }
+        if other.base_url.is_some() {
+            self.base_url = other.base_url;
+        }
+    }
+
+    /// 应用到请求：补充缺失的 header（名称不区分大小写），未指定 @timeout 时使用默认超时，
+    /// 相对 URL 拼接到默认 base URL
+    pub fn apply(&self, parsed: &mut ParsedRequest) {
+        if parsed.metadata.timeout.is_none() {
+            parsed.metadata.timeout = self.timeout;
+        }
+        if let Some(base_url) = &self.base_url {
+            parsed.apply_base_url(base_url);
+        }
+
+        for (key, value) in &self.headers {
+            if !parsed
+                .headers
+                .iter()
+                .any(|(existing, _)| existing.eq_ignore_ascii_case(key))
+            {
+                parsed.headers.push((key.clone(), value.clone()));
+            }
+        }
+    }
 }
 
 impl VariableConfig {
@@ -110,6 +302,7 @@ api_key = "${PROD_KEY}"
 
         let config: VariableConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.environments.len(), 2);
+        assert!(!config.http.insecure);
 
         let dev = config.get_environment("dev").unwrap();
         assert_eq!(
@@ -117,4 +310,72 @@ api_key = "${PROD_KEY}"
             Some(&"http://localhost:8080".to_string())
         );
     }
+
+    #[test]
+    fn test_array_variables_flattened() {
+        let toml_str = r#"
+[environments.dev]
+base_url = "http://localhost:8080"
+hosts = ["a.example.com", "b.example.com"]
+"#;
+        let config: VariableConfig = toml::from_str(toml_str).unwrap();
+        let dev = config.get_environment("dev").unwrap();
+        let get = |key: &str| dev.variables.get(key).map(String::as_str);
+        assert_eq!(get("base_url"), Some("http://localhost:8080"));
+        assert_eq!(get("hosts"), Some("a.example.com,b.example.com"));
+        assert_eq!(get("hosts.0"), Some("a.example.com"));
+        assert_eq!(get("hosts.1"), Some("b.example.com"));
+        assert_eq!(get("hosts.2"), None);
+    }
+
+    #[test]
+    fn test_resolved_config_merge() {
+        let mut config = ResolvedConfig {
+            headers: vec![
+                ("Accept".to_string(), "text/plain".to_string()),
+                ("X-Trace".to_string(), "global".to_string()),
+            ],
+            timeout: Some(Duration::from_secs(5)),
+            base_url: None,
+        };
+        config.merge(ResolvedConfig {
+            headers: vec![("accept".to_string(), "application/json".to_string())],
+            timeout: None,
+            base_url: Some("https://api.example.com".to_string()),
+        });
+
+        assert_eq!(
+            config.headers,
+            vec![
+                ("X-Trace".to_string(), "global".to_string()),
+                ("accept".to_string(), "application/json".to_string()),
+            ]
+        );
+        assert_eq!(config.timeout, Some(Duration::from_secs(5)));
+        assert_eq!(config.base_url.as_deref(), Some("https://api.example.com"));
+    }
+
+    #[test]
+    fn test_http_config_parse() {
+        let toml_str = r#"
+[http]
+insecure = true
+ca_cert = "certs/staging.pem"
+proxy = "socks5://127.0.0.1:1080"
+
+[http.retry]
+max_attempts = 3
+retry_on_status = [429, 503]
+"#;
+        let config: VariableConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.http.insecure);
+        assert_eq!(config.http.retry.max_attempts, Some(3));
+        assert_eq!(config.http.retry.base_delay_ms, None);
+        assert_eq!(config.http.retry.retry_on_status, vec![429, 503]);
+        assert_eq!(config.http.ca_cert.as_deref(), Some("certs/staging.pem"));
+        assert_eq!(
+            config.http.proxy.as_deref(),
+            Some("socks5://127.0.0.1:1080")
+        );
+    }
 }
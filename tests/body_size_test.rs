@@ -0,0 +1,98 @@
+use rupost::http::{Client, DownloadTarget, Request};
+use rupost::parser::HttpFileParser;
+use rupost::runner::TestExecutor;
+use rupost::variable::VariableContext;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const EXPORT: &str = r#"{"id": 1, "rows": ["aaaaaaaa", "bbbbbbbb", "cccccccc", "dddddddd"]}"#;
+
+async fn start_server() -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/export"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/json")
+                .set_body_string(EXPORT),
+        )
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+/// 测试超过 max_body_size 的 body 被截断，基于 body 的断言给出明确的错误
+#[tokio::test]
+async fn test_body_truncated_at_limit() {
+    let mock_server = start_server().await;
+
+    let content = format!(
+        "@assert status == 200\n@assert body.id == 1\nGET {}/export\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let client = Client::builder().max_body_size(16).build().unwrap();
+    let mut context = VariableContext::new();
+    let result = TestExecutor::builder()
+        .record_history(false)
+        .client(client)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap()
+        .remove(0);
+
+    let response = result.response.unwrap();
+    assert_eq!(response.body, &EXPORT[..16]);
+    assert_eq!(response.truncated_at, Some(16));
+
+    assert!(result.assertions[0].passed);
+    assert!(!result.assertions[1].passed);
+    assert_eq!(
+        result.assertions[1].message.as_deref(),
+        Some("Value extraction failed: body truncated at 16 bytes, increase [http] max_body_size")
+    );
+}
+
+/// 测试未超过限制（或不限制）时完整读取 body
+#[tokio::test]
+async fn test_body_within_limit() {
+    let mock_server = start_server().await;
+    let url = format!("{}/export", mock_server.uri());
+
+    for max_body_size in [EXPORT.len(), 0] {
+        let client = Client::builder()
+            .max_body_size(max_body_size)
+            .build()
+            .unwrap();
+        let response = client
+            .execute(Request::new("GET", &url).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.body, EXPORT);
+        assert_eq!(response.truncated_at, None);
+    }
+}
+
+/// 测试指定输出文件时 body 按块写入文件，不受 max_body_size 限制
+#[tokio::test]
+async fn test_download_streams_to_file() {
+    let mock_server = start_server().await;
+    let dir = tempfile::TempDir::new().unwrap();
+    let output = dir.path().join("export.json");
+
+    let mut request = Request::new("GET", &format!("{}/export", mock_server.uri())).unwrap();
+    request.download = Some(DownloadTarget {
+        path: Some(output.clone()),
+        force: false,
+    });
+    let client = Client::builder().max_body_size(16).build().unwrap();
+    let response = client.execute(request).await.unwrap();
+
+    let saved = response.saved_to.unwrap();
+    assert_eq!(saved.path, output);
+    assert_eq!(saved.size, EXPORT.len() as u64);
+    assert_eq!(response.body, "");
+    assert_eq!(response.truncated_at, None);
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), EXPORT);
+}
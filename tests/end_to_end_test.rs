@@ -1,9 +1,10 @@
+use rupost::http::Client;
 use rupost::parser::{HttpFileParser, MarkdownFileParser};
-use rupost::runner::TestExecutor;
+use rupost::runner::{RepeatSummary, TestExecutor};
 use rupost::variable::{ConfigLoader, VariableContext};
 use std::fs;
 use tempfile::TempDir;
-use wiremock::matchers::{header, method, path};
+use wiremock::matchers::{header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 /// 测试完整 HTTP 文件解析和执行流程
@@ -45,7 +46,7 @@ Accept: application/json
     assert_eq!(parsed.requests.len(), 1);
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let mut context = VariableContext::new(); // 新增空上下文
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
@@ -105,7 +106,7 @@ Content-Type: application/json
     assert_eq!(parsed.requests.len(), 1);
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let mut context = VariableContext::new();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
@@ -162,7 +163,7 @@ Authorization: Bearer {{api_key}}
 
     // 加载配置并构建变量上下文
     let config = ConfigLoader::load_from_path(&config_file).unwrap();
-    let mut context = ConfigLoader::build_context(&config, Some("test"), &[]);
+    let mut context = ConfigLoader::build_context(&config, Some("test"), &[]).unwrap();
 
     // 解析文件
     let parsed = HttpFileParser::parse_file(&http_file).unwrap();
@@ -170,7 +171,7 @@ Authorization: Bearer {{api_key}}
     // 手动替换部分已移除，由 executor 处理
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
     // 验证结果
@@ -207,7 +208,7 @@ async fn test_assertion_integration() {
 ### Check Status
 # @assert status == 200
 # @assert body.status == "healthy"
-# @assert header.X-Request-ID exists
+# @assert headers.X-Request-ID exists
 # @assert body.uptime > 10000
 GET {}/api/status
 
@@ -229,7 +230,7 @@ GET {}/api/status
     }
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let mut context = VariableContext::new();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
@@ -237,10 +238,9 @@ GET {}/api/status
     assert_eq!(results.len(), 1);
     assert!(results[0].success);
 
-    // 如果有断言，验证它们都通过
-    if !results[0].assertions.is_empty() {
-        assert!(results[0].assertions.iter().all(|a| a.passed));
-    }
+    // 注释形式的断言同样生效，且全部通过
+    assert_eq!(results[0].assertions.len(), 4);
+    assert!(results[0].assertions.iter().all(|a| a.passed));
 }
 
 /// 测试多请求链式执行
@@ -301,7 +301,7 @@ Authorization: Bearer auth-token-456
     assert_eq!(parsed.requests.len(), 2);
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let mut context = VariableContext::new();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
@@ -358,7 +358,7 @@ X-API-Key: {{api_key}}
 
     // 加载配置并构建变量上下文
     let config = ConfigLoader::load_from_path(&config_file).unwrap();
-    let mut context = ConfigLoader::build_context(&config, Some("test"), &[]);
+    let mut context = ConfigLoader::build_context(&config, Some("test"), &[]).unwrap();
 
     // 解析文件
     let parsed = HttpFileParser::parse_file(&http_file).unwrap();
@@ -366,7 +366,7 @@ X-API-Key: {{api_key}}
     // 移除手动替换
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
     // 验证结果
@@ -426,7 +426,7 @@ X-Custom-Header: {{custom_header}}
         "cli-override-value".to_string(),
     )];
     let config = ConfigLoader::load_from_path(&config_file).unwrap();
-    let mut context = ConfigLoader::build_context(&config, Some("test"), &cli_vars);
+    let mut context = ConfigLoader::build_context(&config, Some("test"), &cli_vars).unwrap();
 
     // 解析文件
     let parsed = HttpFileParser::parse_file(&http_file).unwrap();
@@ -434,7 +434,7 @@ X-Custom-Header: {{custom_header}}
     // 移除手动替换
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
     // 验证结果
@@ -481,6 +481,7 @@ async fn test_response_variable_capture() {
 @name login
 @capture token from body.data.token
 @capture uid from body.data.user_id
+@persist token
 POST {}/auth/login
 Content-Type: application/json
 
@@ -504,7 +505,7 @@ Authorization: Bearer {{{{token}}}}
     assert_eq!(parsed.requests.len(), 2);
 
     // 执行请求
-    let executor = TestExecutor::new();
+    let executor = TestExecutor::builder().record_history(false).build();
     let mut context = VariableContext::new();
     let results = executor.execute_all(parsed, &mut context).await.unwrap();
 
@@ -516,4 +517,1258 @@ Authorization: Bearer {{{{token}}}}
     // 验证变量上下文是否已更新
     assert_eq!(context.get("token"), Some("secret-access-token-123"));
     assert_eq!(context.get("uid"), Some("42"));
+
+    // 只有 @persist 的变量标记为需要持久化，--persist-captures 时为全部
+    assert_eq!(
+        results[0].persisted,
+        vec![("token".to_string(), "secret-access-token-123".to_string())]
+    );
+    let parsed = HttpFileParser::parse_file(&http_file).unwrap();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .persist_captures(true)
+        .build()
+        .execute_all(parsed, &mut VariableContext::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        results[0].persisted,
+        vec![
+            ("token".to_string(), "secret-access-token-123".to_string()),
+            ("uid".to_string(), "42".to_string()),
+        ]
+    );
+}
+
+/// 测试 @skip-if 基于环境变量的条件跳过
+#[tokio::test]
+async fn test_skip_if_condition() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Debug Only
+@skip-if env == prod
+GET {0}/api/debug
+
+### Health
+@skip-if env != prod
+GET {0}/api/health
+
+###
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let config: rupost::variable::VariableConfig = toml::from_str("[environments.prod]\n").unwrap();
+    let mut context = ConfigLoader::build_context(&config, Some("prod"), &[]).unwrap();
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].skipped);
+    assert!(!results[1].skipped);
+    assert!(results[1].success);
+}
+
+/// 测试 @skip-if 条件无效时请求报错且不发送
+#[tokio::test]
+async fn test_skip_if_invalid_condition_is_error() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "@skip-if env ==\nGET {0}/a\n\n###\n\nGET {0}/b\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(!results[0].success);
+    assert!(!results[0].skipped);
+    assert!(
+        results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Invalid @skip-if condition 'env =='"),
+        "{:?}",
+        results[0].error
+    );
+    assert!(results[1].success);
+}
+
+/// 测试 execute_all_with_progress 按顺序为每个结果（包括跳过的请求）调用回调
+#[tokio::test]
+async fn test_execute_all_with_progress() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "GET {0}/a\n\n###\n\n@skip\nGET {0}/b\n\n###\n\nGET {0}/c\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+
+    let mut reported = Vec::new();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all_with_progress(parsed, &mut context, |result| {
+            reported.push((result.request_number, result.skipped))
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(reported, vec![(1, false), (2, true), (3, false)]);
+}
+
+/// 测试构建器的选项：bail_on_failure 之后的请求报告为跳过，回调收到每个结果，strict_vars 生效
+#[tokio::test]
+async fn test_executor_builder_options() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fail"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+    // 构建时的 bail 之后不应再有请求到达
+    Mock::given(method("GET"))
+        .and(path("/after"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "GET {0}/ok\n\n###\n\nGET {0}/fail\n\n###\n\nGET {0}/after\n",
+        mock_server.uri()
+    );
+    let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let callback_reported = reported.clone();
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .client(Client::builder().build().unwrap())
+        .record_history(false)
+        .bail_on_failure(true)
+        .on_result(move |result| {
+            callback_reported.lock().unwrap().push((
+                result.request_number,
+                result.success,
+                result.skipped,
+            ))
+        })
+        .build();
+    assert!(!executor.options().record_history);
+
+    let results = executor
+        .execute_all(
+            HttpFileParser::parse_content(&content).unwrap(),
+            &mut VariableContext::new(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[2].skipped);
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![(1, true, false), (2, false, false), (3, true, true)]
+    );
+
+    // strict_vars：未解析的变量不发送请求
+    let content = format!("GET {}/ok?id={{{{missing}}}}\n", mock_server.uri());
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .strict_vars(true)
+        .build()
+        .execute_all(
+            HttpFileParser::parse_content(&content).unwrap(),
+            &mut VariableContext::new(),
+        )
+        .await
+        .unwrap();
+    assert!(results[0].response.is_none());
+    assert!(results[0].error.as_deref().unwrap().contains("missing"));
+}
+
+/// 测试 --strict-vars 模式下未解析的变量导致请求失败
+#[tokio::test]
+async fn test_strict_vars_reports_unresolved() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/users"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Missing Token
+GET {0}/api/users
+Authorization: Bearer {{{{token}}}}
+
+### Resolved
+GET {{{{base_url}}}}/api/users
+
+###
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    context.insert("base_url", mock_server.uri());
+
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .strict_vars(true)
+        .build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(!results[0].success);
+    assert!(results[0].error.as_ref().unwrap().contains("token"));
+    assert!(results[1].success);
+
+    // 未发送的请求同样记录替换后的内容，-vv 可以看到哪些变量没有替换
+    let snapshot = results[0].request_snapshot.as_ref().unwrap();
+    assert_eq!(snapshot.url, format!("{}/api/users", mock_server.uri()));
+    assert_eq!(snapshot.headers["authorization"], "Bearer {{token}}");
+    let snapshot = results[1].request_snapshot.as_ref().unwrap();
+    assert_eq!(snapshot.url, format!("{}/api/users", mock_server.uri()));
+}
+
+/// 测试 @query 参数的变量替换与 URL 编码
+#[tokio::test]
+async fn test_query_directive() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/search"))
+        .and(query_param("q", "rust & tokio"))
+        .and(query_param("token", "secret"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Search
+@query q=rust & tokio
+@query token={{{{token}}}}
+GET {0}/api/search?page=2
+
+###
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    context.insert("token", "secret");
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success);
+    assert_eq!(
+        results[0].url,
+        format!(
+            "{}/api/search?page=2&q=rust+%26+tokio&token=secret",
+            mock_server.uri()
+        )
+    );
+}
+
+/// 测试重复的 @query 键按顺序全部发送
+#[tokio::test]
+async fn test_query_directive_repeated_key() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .and(|request: &wiremock::Request| request.url.query() == Some("tag=a&tag=b&page=1"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "@query tag=a\n@query tag=b\n@query page=1\nGET {}/api/items\n",
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success, "{:?}", results[0].error);
+}
+
+/// 测试 rupost.toml [headers] 默认请求头的合并
+#[tokio::test]
+async fn test_config_default_headers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/me"))
+        .and(header("accept", "application/json"))
+        .and(header("user-agent", "rupost/v2"))
+        .and(header("x-client", "explicit"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config: rupost::variable::VariableConfig = toml::from_str(
+        r#"
+[headers]
+Accept = "application/json"
+User-Agent = "rupost/{{version}}"
+X-Client = "default"
+
+[environments.dev]
+version = "v2"
+"#,
+    )
+    .unwrap();
+
+    let content = format!(
+        r#"
+### Me
+GET {}/api/me
+x-client: explicit
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
+
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .default_headers(config.headers.clone())
+        .build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(results[0].success);
+
+    // 请求自身的 header 优先，且不会重复发送
+    let received = mock_server.received_requests().await.unwrap();
+    let values: Vec<_> = received[0].headers.get_all("x-client").iter().collect();
+    assert_eq!(values, vec!["explicit"]);
+}
+
+/// 测试 [defaults] 的 header 优先级：请求自身 > 环境 defaults > 全局 defaults > [headers]
+#[tokio::test]
+async fn test_config_defaults_header_precedence() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/me"))
+        .and(header("accept", "application/json"))
+        .and(header("x-trace", "dev"))
+        .and(header("x-client", "explicit"))
+        .and(header("x-legacy", "kept"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config: rupost::variable::VariableConfig = toml::from_str(
+        r#"
+[headers]
+Accept = "text/plain"
+X-Legacy = "kept"
+
+[defaults.headers]
+Accept = "application/json"
+X-Trace = "global"
+X-Client = "default"
+
+[environments.dev.defaults.headers]
+x-trace = "dev"
+"#,
+    )
+    .unwrap();
+
+    let content = "GET /api/me\nX-Client: explicit\n";
+    let parsed = HttpFileParser::parse_content(content).unwrap();
+    let mut defaults = ConfigLoader::resolve(&config, Some("dev")).unwrap();
+    defaults.base_url = Some(mock_server.uri());
+    let mut context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
+
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .config(defaults)
+        .build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    assert!(results[0].success, "{:?}", results[0].error);
+
+    let received = mock_server.received_requests().await.unwrap();
+    for name in ["accept", "x-trace", "x-client"] {
+        assert_eq!(
+            received[0].headers.get_all(name).iter().count(),
+            1,
+            "{}",
+            name
+        );
+    }
+}
+
+/// 测试 default_headers 与 config 合并请求默认值，结果与调用顺序无关
+#[tokio::test]
+async fn test_builder_defaults_merge() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/me"))
+        .and(header("accept", "application/json"))
+        .and(header("x-extra", "1"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config: rupost::variable::VariableConfig = toml::from_str(&format!(
+        "[defaults]\nbase_url = \"{}\"\n\n[defaults.headers]\nAccept = \"application/json\"\n",
+        mock_server.uri()
+    ))
+    .unwrap();
+    let defaults = ConfigLoader::resolve(&config, None).unwrap();
+    let extra = || vec![("X-Extra".to_string(), "1".to_string())];
+
+    let executors = [
+        TestExecutor::builder()
+            .record_history(false)
+            .default_headers(extra())
+            .config(defaults.clone())
+            .build(),
+        TestExecutor::builder()
+            .record_history(false)
+            .config(defaults.clone())
+            .default_headers(extra())
+            .build(),
+    ];
+    for executor in executors {
+        let parsed = HttpFileParser::parse_content("GET /api/me\n").unwrap();
+        let results = executor
+            .execute_all(parsed, &mut VariableContext::new())
+            .await
+            .unwrap();
+        assert!(results[0].success, "{:?}", results[0].error);
+    }
+}
+
+/// 测试相对 URL 拼接到 [defaults] base_url（base 有无结尾斜杠、路径有无开头斜杠）
+#[tokio::test]
+async fn test_config_defaults_base_url() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/users"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let content = "GET /users\n\n###\n\nGET users\n";
+    for base_url in [
+        format!("{}/v1", mock_server.uri()),
+        format!("{}/v1/", mock_server.uri()),
+    ] {
+        let config: rupost::variable::VariableConfig =
+            toml::from_str(&format!("[defaults]\nbase_url = \"{}\"\n", base_url)).unwrap();
+        let executor = TestExecutor::builder()
+            .record_history(false)
+            .config(ConfigLoader::resolve(&config, None).unwrap())
+            .build();
+        let parsed = HttpFileParser::parse_content(content).unwrap();
+        let results = executor
+            .execute_all(parsed, &mut VariableContext::new())
+            .await
+            .unwrap();
+
+        for result in &results {
+            assert!(result.success, "{}: {:?}", base_url, result.error);
+            assert_eq!(result.url, format!("{}/v1/users", mock_server.uri()));
+        }
+    }
+}
+
+/// 测试 [defaults] timeout 只在请求没有 @timeout 时生效
+#[tokio::test]
+async fn test_config_defaults_timeout() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500)))
+        .mount(&mock_server)
+        .await;
+
+    let config: rupost::variable::VariableConfig =
+        toml::from_str("[defaults]\ntimeout = \"100ms\"\n").unwrap();
+    let content = format!(
+        "GET {0}/slow\n\n###\n\n@timeout 5s\nGET {0}/slow\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .config(ConfigLoader::resolve(&config, None).unwrap())
+        .build();
+    let results = executor
+        .execute_all(parsed, &mut VariableContext::new())
+        .await
+        .unwrap();
+
+    assert!(!results[0].success);
+    assert!(results[0].response.is_none());
+    assert!(results[1].success, "{:?}", results[1].error);
+}
+
+/// 测试响应记录收到响应头和读取 body 的耗时拆分
+#[tokio::test]
+async fn test_response_timing() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("done")
+                .set_delay(std::time::Duration::from_millis(100)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let request =
+        rupost::http::Request::new("GET", &format!("{}/slow", mock_server.uri())).unwrap();
+    let response = Client::new().execute(request).await.unwrap();
+
+    let timing = response.timing.unwrap();
+    assert!(timing.ttfb >= std::time::Duration::from_millis(100));
+    assert_eq!(timing.ttfb + timing.body, response.duration);
+}
+
+/// 测试 @content-type 覆盖 Content-Type 并按原文发送类 JSON 的 body
+#[tokio::test]
+async fn test_content_type_directive() {
+    let mock_server = MockServer::start().await;
+
+    let raw_body = "[<item>{\"a\": 1}</item>]";
+    Mock::given(method("POST"))
+        .and(path("/api/xml"))
+        .and(header("content-type", "application/xml"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Upload XML
+@content-type application/{{{{format}}}}
+POST {0}/api/xml
+Content-Type: application/json
+
+{1}
+"#,
+        mock_server.uri(),
+        raw_body
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    context.insert("format", "xml");
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    assert!(results[0].success, "{:?}", results[0].error);
+
+    let received = mock_server.received_requests().await.unwrap();
+    assert_eq!(
+        received[0].headers.get_all("content-type").iter().count(),
+        1
+    );
+    assert_eq!(String::from_utf8_lossy(&received[0].body), raw_body);
+}
+
+/// 测试 DELETE / PUT / PATCH 请求同样发送 JSON body
+#[tokio::test]
+async fn test_json_body_for_non_post_methods() {
+    use wiremock::matchers::body_json;
+
+    let mock_server = MockServer::start().await;
+
+    for verb in ["DELETE", "PUT", "PATCH"] {
+        Mock::given(method(verb))
+            .and(path("/api/items/1"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(
+                serde_json::json!({"reason": verb, "force": true}),
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let content = ["DELETE", "PUT", "PATCH"]
+        .iter()
+        .map(|verb| {
+            format!(
+                "### {verb}\n{verb} {uri}/api/items/1\n\n{{\"reason\": \"{verb}\", \"force\": true}}\n",
+                uri = mock_server.uri()
+            )
+        })
+        .collect::<String>();
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    for result in &results {
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.response.as_ref().unwrap().status.code(), 204);
+    }
+}
+
+/// 测试 execute_repeated 按次数执行并按请求序号返回结果
+#[tokio::test]
+async fn test_execute_repeated() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(10)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!("GET {}/health\n", mock_server.uri());
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let context = VariableContext::new();
+    let results = executor
+        .execute_repeated(parsed.requests[0].clone(), &context, 10, 4, None)
+        .await;
+
+    let numbers: Vec<usize> = results.iter().map(|r| r.request_number).collect();
+    assert_eq!(numbers, (1..=10).collect::<Vec<_>>());
+    assert!(results.iter().all(|r| r.success && r.status == Some(200)));
+
+    let summary = RepeatSummary::from_results(&results);
+    assert_eq!(summary.total, 10);
+    assert_eq!(summary.statuses.get(&200), Some(&10));
+    assert!(summary.min <= summary.avg && summary.p95 <= summary.max);
+}
+
+/// 测试设置种子后并发重复执行的动态变量与调度顺序无关
+#[tokio::test]
+async fn test_execute_repeated_with_seed() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "GET {}/items?id={{{{$uuid}}}}&n={{{{$randomInt}}}}\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    context.set_seed(2024);
+
+    let mut runs = Vec::new();
+    for concurrency in [1, 4] {
+        let results = executor
+            .execute_repeated(parsed.requests[0].clone(), &context, 6, concurrency, None)
+            .await;
+        let urls: Vec<String> = results.into_iter().map(|r| r.url).collect();
+        runs.push(urls);
+    }
+
+    assert_eq!(runs[0], runs[1]);
+    let mut unique = runs[0].clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 6);
+}
+
+/// 测试 @once 请求在多次 execute_all 中只执行一次，捕获的变量继续可用
+#[tokio::test]
+async fn test_once_across_iterations() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"token": "abc"})))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/profile"))
+        .and(header("authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Login
+@name login
+@once
+@capture token from body.token
+POST {base}/login
+
+### Profile
+@assert status == 200
+GET {base}/profile
+Authorization: Bearer {{{{token}}}}
+"#,
+        base = mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+
+    for iteration in 0..3 {
+        let results = executor
+            .execute_all(parsed.clone(), &mut context)
+            .await
+            .unwrap();
+        assert_eq!(results[0].skipped, iteration > 0);
+        assert!(results[1].success, "{:?}", results[1].error);
+        assert!(results[1].assertions.iter().all(|a| a.passed));
+    }
+
+    // 新的执行器不共享已执行记录
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+    assert!(!results[0].skipped);
+}
+
+/// 测试 @pre-run 将命令输出写入变量，未启用 --allow-exec 或命令失败时请求失败
+#[cfg(unix)]
+#[tokio::test]
+async fn test_pre_run_command() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/signed"))
+        .and(header("x-signature", "alice-signed"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Signed
+@pre-run signature = $(printf '  %s-signed\n' {{{{user}}}})
+GET {}/signed
+X-Signature: {{{{signature}}}}
+
+### Failing
+@pre-run token = $(echo oops >&2; exit 3)
+GET {}/never
+"#,
+        mock_server.uri(),
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+
+    let mut context = VariableContext::new();
+    context.set("user", "alice");
+    let executor = TestExecutor::builder()
+        .record_history(false)
+        .allow_exec(true)
+        .build();
+    let results = executor
+        .execute_all(parsed.clone(), &mut context)
+        .await
+        .unwrap();
+
+    assert!(results[0].success, "{:?}", results[0].error);
+    assert_eq!(context.get("signature"), Some("alice-signed"));
+    assert!(!results[1].success);
+    let error = results[1].error.as_deref().unwrap();
+    assert!(
+        error.contains("exit status: 3") && error.ends_with(": oops"),
+        "{}",
+        error
+    );
+
+    // 默认不允许执行命令
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut VariableContext::new())
+        .await
+        .unwrap();
+    assert!(
+        results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("--allow-exec")
+    );
+}
+
+/// 测试 response.url 断言跟随重定向后的最终 URL
+#[tokio::test]
+async fn test_final_url_after_redirect() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(
+            ResponseTemplate::new(302)
+                .insert_header("location", format!("{}/login", mock_server.uri()).as_str()),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("login page"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Redirected
+@assert status == 200
+@assert response.url == "{uri}/login"
+@assert response.url contains "/login"
+@assert response.redirects == 1
+GET {uri}/old
+"#,
+        uri = mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    assert!(results[0].success, "{:?}", results[0].assertions);
+    assert!(results[0].assertions.iter().all(|a| a.passed));
+    assert_eq!(
+        results[0].response.as_ref().unwrap().final_url,
+        Some(format!("{}/login", mock_server.uri()))
+    );
+}
+
+/// 测试 302 链：默认跟随并记录次数，@no-redirect 只对该请求生效，超过 max_redirects 时请求失败
+#[tokio::test]
+async fn test_redirect_chain() {
+    let mock_server = MockServer::start().await;
+
+    for (from, to) in [("/a", "/b"), ("/b", "/c")] {
+        Mock::given(method("GET"))
+            .and(path(from))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", to))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/c"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Followed
+@assert status == 200
+@assert response.redirects == 2
+GET {uri}/a
+
+### Not followed
+@no-redirect
+@assert status == 302
+@assert headers.location == "/b"
+@assert response.redirects == 0
+GET {uri}/a
+"#,
+        uri = mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed.clone(), &mut context)
+        .await
+        .unwrap();
+    assert!(results[0].success, "{:?}", results[0].assertions);
+    assert_eq!(
+        results[0].response.as_ref().unwrap().final_url,
+        Some(format!("{}/c", mock_server.uri()))
+    );
+    assert!(results[1].success, "{:?}", results[1].assertions);
+
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .client(Client::builder().max_redirects(1).build().unwrap())
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+    assert!(!results[0].success);
+    assert!(
+        results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("too many redirects (max 1)"),
+        "{:?}",
+        results[0].error
+    );
+    assert!(results[1].success, "{:?}", results[1].assertions);
+}
+
+/// 测试 --no-redirect 时 3xx 作为正常结果返回
+#[tokio::test]
+async fn test_no_redirect() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", "/login"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Not followed
+@assert status == 302
+@assert headers.location == "/login"
+@assert response.redirects == 0
+GET {}/old
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .client(Client::builder().follow_redirects(false).build().unwrap())
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    assert!(results[0].success, "{:?}", results[0].assertions);
+    assert_eq!(results[0].status, Some(302));
+}
+
+/// 测试 --group 只执行带所选分组的请求，多个分组取并集，无分组的请求被跳过
+#[tokio::test]
+async fn test_group_filter() {
+    let mock_server = MockServer::start().await;
+
+    for (route, expected) in [
+        ("/smoke", 1),
+        ("/slow", 1),
+        ("/regression", 0),
+        ("/plain", 0),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(expected)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let content = format!(
+        r#"
+### Smoke
+@group smoke
+GET {uri}/smoke
+
+### Slow
+@group regression
+@group slow
+GET {uri}/slow
+
+### Regression
+@group regression
+GET {uri}/regression
+
+### Plain
+GET {uri}/plain
+"#,
+        uri = mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let mut context = VariableContext::new();
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .groups(vec!["smoke".to_string(), "slow".to_string()])
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    let skipped: Vec<bool> = results.iter().map(|r| r.skipped).collect();
+    assert_eq!(skipped, vec![false, false, true, true]);
+    assert!(results.iter().all(|r| r.success || r.skipped));
+}
+
+/// 测试 @env-only 只在列出的环境中执行，没有该指令的请求在所有环境执行
+#[tokio::test]
+async fn test_env_only() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/debug"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Debug
+@env-only dev, staging
+GET {uri}/debug
+
+### Health
+GET {uri}/health
+"#,
+        uri = mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+
+    for (env, debug_skipped) in [(Some("dev"), false), (Some("prod"), true), (None, true)] {
+        let mut context = VariableContext::new();
+        let results = TestExecutor::builder()
+            .record_history(false)
+            .environment(env.map(String::from))
+            .build()
+            .execute_all(parsed.clone(), &mut context)
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].skipped, debug_skipped, "env: {:?}", env);
+        assert!(!results[1].skipped);
+    }
+}
+
+/// 测试 @auth 在变量替换后生成 Authorization header，显式 header 优先
+#[tokio::test]
+async fn test_auth_directive() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/basic"))
+        .and(header("authorization", "Basic YWRtaW46c2VjcmV0"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/bearer"))
+        .and(header("authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/explicit"))
+        .and(header("authorization", "Token xyz"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Basic
+@auth basic {{{{user}}}} {{{{pass}}}}
+GET {uri}/basic
+
+### Bearer
+@auth bearer {{{{token}}}}
+GET {uri}/bearer
+
+### Explicit
+@auth bearer {{{{token}}}}
+GET {uri}/explicit
+Authorization: Token xyz
+"#,
+        uri = mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+
+    let mut context = VariableContext::new();
+    context.insert("user", "admin");
+    context.insert("pass", "secret");
+    context.insert("token", "abc");
+    let results = TestExecutor::builder()
+        .record_history(false)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap();
+
+    for result in &results {
+        assert!(result.success, "{:?}: {:?}", result.name, result.error);
+    }
+}
+
+/// 扩展方法（PURGE、WebDAV 的 PROPFIND）原样发送
+#[tokio::test]
+async fn test_extension_method() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("PURGE"))
+        .and(path("/cache/users"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("PROPFIND"))
+        .and(path("/dav/docs/"))
+        .and(header("depth", "1"))
+        .respond_with(
+            ResponseTemplate::new(207)
+                .set_body_raw("<d:multistatus xmlns:d=\"DAV:\"/>", "application/xml"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "@assert status == 204\nPURGE {0}/cache/users\n\n###\n\n@assert status == 207\n@assert body contains \"multistatus\"\nPROPFIND {0}/dav/docs/\nDepth: 1\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    assert_eq!(parsed.requests[0].method.as_deref(), Some("PURGE"));
+    assert_eq!(parsed.requests[1].method.as_deref(), Some("PROPFIND"));
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    for result in &results {
+        assert!(result.success, "{} {:?}", result.method, result.error);
+    }
+    assert_eq!(results[0].method, "PURGE");
+    assert_eq!(results[1].method, "PROPFIND");
+}
+
+/// 多个同名响应 header（Set-Cookie）按下标断言、计数和捕获
+#[tokio::test]
+async fn test_duplicate_response_headers() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .append_header("set-cookie", "session=abc; HttpOnly")
+                .append_header("set-cookie", "theme=dark"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        "@assert headers.set-cookie.count == 2\n@assert headers.set-cookie contains \"session=abc\"\n@assert headers.set-cookie[1] == \"theme=dark\"\n@assert headers.set-cookie[2] not exists\n@capture theme from header.set-cookie[1]\nPOST {}/login\n",
+        mock_server.uri()
+    );
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    assert!(results[0].success, "{:?}", results[0].assertions);
+    assert_eq!(results[0].assertions.len(), 4);
+    assert_eq!(context.get("theme"), Some("theme=dark"));
 }
@@ -32,3 +32,61 @@ fn test_result_type() {
         _ => panic!("Expected ParseError"),
     }
 }
+
+#[test]
+fn test_url_error_keeps_input_and_source() {
+    let source = url::Url::parse("http://[::1").unwrap_err();
+    let err = RupostError::url("http://[::1", source);
+    assert_eq!(
+        err.to_string(),
+        "无效的 URL 'http://[::1': invalid IPv6 address"
+    );
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "invalid IPv6 address");
+}
+
+#[test]
+fn test_capture_error() {
+    let err = RupostError::CaptureError {
+        variable: "token".to_string(),
+        reason: "header 'X-Token' not found".to_string(),
+    };
+    assert_eq!(
+        err.to_string(),
+        "无法捕获变量 'token': header 'X-Token' not found"
+    );
+}
+
+/// reqwest 错误的 URL 放入 url 字段，full_message 附带底层原因（如连接被拒绝）
+#[tokio::test]
+async fn test_http_error_with_url_and_cause() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/health", listener.local_addr().unwrap());
+    drop(listener);
+
+    let err: RupostError = reqwest::get(&url).await.unwrap_err().into();
+    match &err {
+        RupostError::HttpError {
+            url: Some(target), ..
+        } => assert_eq!(target, &url),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.to_string().contains(&url), "{}", err);
+    assert!(
+        err.full_message().len() > err.to_string().len(),
+        "{}",
+        err.full_message()
+    );
+}
+
+#[test]
+fn test_config_error_source() {
+    let err: RupostError =
+        rupost::variable::ConfigLoader::load_from_path("/nonexistent/rupost.toml")
+            .unwrap_err()
+            .into();
+    assert!(matches!(err, RupostError::ConfigError(_)));
+    // ConfigError -> io::Error
+    let config_error = std::error::Error::source(&err).unwrap();
+    assert!(config_error.source().is_some());
+}
@@ -0,0 +1,176 @@
+//! `rupost test` 的退出码：0 通过，1 测试失败，2 解析/配置错误，3 所有请求都没有收到响应
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 在临时目录中运行 `rupost test`，避免读到仓库或用户目录下的配置、写入历史记录
+fn rupost_test(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rupost")
+        .unwrap()
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env_remove("RUPOST_ENV")
+        .arg("test")
+        .args(args)
+        .assert()
+}
+
+async fn mock_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// 一个没有监听的本地地址，连接会被拒绝
+async fn closed_address() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("http://{}", addr)
+}
+
+fn write_file(dir: &TempDir, name: &str, content: &str) -> String {
+    fs::write(dir.path().join(name), content).unwrap();
+    name.to_string()
+}
+
+#[tokio::test]
+async fn test_exit_code_passed() {
+    let server = mock_server().await;
+    let dir = TempDir::new().unwrap();
+    let file = write_file(
+        &dir,
+        "ok.http",
+        &format!("@assert status == 200\nGET {}/ok\n", server.uri()),
+    );
+
+    rupost_test(dir.path(), &[&file]).code(0);
+}
+
+#[tokio::test]
+async fn test_exit_code_assertion_failed() {
+    let server = mock_server().await;
+    let dir = TempDir::new().unwrap();
+    let file = write_file(
+        &dir,
+        "fail.http",
+        &format!("@assert status == 201\nGET {}/ok\n", server.uri()),
+    );
+
+    rupost_test(dir.path(), &[&file]).code(1);
+    rupost_test(dir.path(), &[&file, "--exit-zero"]).code(0);
+}
+
+#[tokio::test]
+async fn test_exit_code_partially_errored_is_failure() {
+    let server = mock_server().await;
+    let closed = closed_address().await;
+    let dir = TempDir::new().unwrap();
+    let file = write_file(
+        &dir,
+        "mixed.http",
+        &format!("GET {}/ok\n\n###\n\nGET {}/down\n", server.uri(), closed),
+    );
+
+    rupost_test(dir.path(), &[&file]).code(1);
+}
+
+#[tokio::test]
+async fn test_exit_code_all_errored() {
+    let closed = closed_address().await;
+    let dir = TempDir::new().unwrap();
+    let file = write_file(
+        &dir,
+        "down.http",
+        &format!("GET {}/a\n\n###\n\nGET {}/b\n", closed, closed),
+    );
+
+    rupost_test(dir.path(), &[&file]).code(3);
+    rupost_test(dir.path(), &[&file, "--exit-zero"]).code(0);
+}
+
+#[test]
+fn test_exit_code_parse_and_config_errors() {
+    let dir = TempDir::new().unwrap();
+
+    // 文件不存在
+    rupost_test(dir.path(), &["missing.http"]).code(2);
+
+    // 无效的请求行，--exit-zero 不影响
+    let file = write_file(&dir, "bad.http", "NOT A REQUEST LINE WITH SPACES\n");
+    rupost_test(dir.path(), &[&file]).code(2);
+    rupost_test(dir.path(), &[&file, "--exit-zero"]).code(2);
+
+    // 配置中没有指定的环境
+    write_file(&dir, "rupost.toml", "[environments.dev]\nhost = \"x\"\n");
+    let file = write_file(&dir, "ok.http", "GET http://127.0.0.1:1/\n");
+    rupost_test(dir.path(), &[&file, "--env", "prod"]).code(2);
+
+    // 无效的命令行参数（clap）
+    rupost_test(dir.path(), &[&file, "--repeat", "abc"]).code(2);
+}
+
+#[tokio::test]
+async fn test_exit_code_multiple_files() {
+    let server = mock_server().await;
+    let dir = TempDir::new().unwrap();
+    let ok = write_file(
+        &dir,
+        "ok.http",
+        &format!("@assert status == 200\nGET {}/ok\n", server.uri()),
+    );
+    let fail = write_file(
+        &dir,
+        "fail.http",
+        &format!("@assert status == 201\nGET {}/ok\n", server.uri()),
+    );
+    let bad = write_file(&dir, "bad.http", "fetch http://127.0.0.1:1/\n");
+
+    let output = rupost_test(dir.path(), &[&ok, &ok]).code(0);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(stdout.contains("2 passed, 2 total"), "{}", stdout);
+
+    // 任一文件失败时整体失败，合计摘要包含所有文件的请求
+    let output = rupost_test(dir.path(), &[&ok, &fail]).code(1);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(
+        stdout.contains("fail.http: 0 passed, 1 failed, 1 total"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("1 passed, 1 failed, 2 total"), "{}", stdout);
+
+    // 有文件无法解析时不发送任何请求
+    let output = rupost_test(dir.path(), &[&ok, &bad]).code(2);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(!stdout.contains("Running"), "{}", stdout);
+}
+
+#[test]
+fn test_exit_code_list_reads_config_only_for_variables() {
+    let dir = TempDir::new().unwrap();
+    write_file(&dir, "rupost.toml", "not = [valid\n");
+    let file = write_file(&dir, "list.http", "GET {{base_url}}/users\n");
+
+    // 普通 --list 不加载配置
+    let output = rupost_test(dir.path(), &[&file, "--list"]).code(0);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(stdout.contains("{{base_url}}/users"), "{}", stdout);
+
+    // 指定变量时才解析 URL，此时配置错误照常报告
+    rupost_test(dir.path(), &[&file, "--list", "--var", "base_url=http://x"]).code(2);
+
+    write_file(&dir, "rupost.toml", "");
+    let output = rupost_test(dir.path(), &[&file, "--list", "--var", "base_url=http://x"]).code(0);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(stdout.contains("http://x/users"), "{}", stdout);
+}
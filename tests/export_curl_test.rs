@@ -0,0 +1,68 @@
+//! `rupost export curl`：生成的命令与 `rupost test` 发送的请求一致
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// 在临时目录中运行 `rupost export curl`，避免读到仓库或用户目录下的配置
+fn export_curl(dir: &Path, args: &[&str]) -> String {
+    let output = Command::cargo_bin("rupost")
+        .unwrap()
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env_remove("RUPOST_ENV")
+        .args(["export", "curl"])
+        .args(args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_export_curl_applies_defaults_to_relative_url() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("rupost.toml"),
+        r#"
+[defaults]
+base_url = "https://api.example.com"
+
+[defaults.headers]
+X-Client = "rupost"
+Accept = "text/plain"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("api.http"),
+        "GET /users\nAccept: application/json\n",
+    )
+    .unwrap();
+
+    let command = export_curl(dir.path(), &["api.http"]);
+    assert!(
+        command.contains("https://api.example.com/users"),
+        "{}",
+        command
+    );
+    assert!(command.contains("X-Client: rupost"), "{}", command);
+    // 请求自身的 header 优先
+    assert!(command.contains("Accept: application/json"), "{}", command);
+    assert!(!command.contains("text/plain"), "{}", command);
+
+    // --base-url 先于 [defaults] 应用
+    let command = export_curl(
+        dir.path(),
+        &["api.http", "--base-url", "http://localhost:8080"],
+    );
+    assert!(
+        command.contains("http://localhost:8080/users"),
+        "{}",
+        command
+    );
+}
@@ -0,0 +1,47 @@
+use rupost::generator::har::HarGenerator;
+use rupost::generator::http::GenerateOptions;
+use rupost::history::serialization::har::Har;
+use rupost::parser::HttpFileParser;
+use std::fs;
+
+fn load_fixture() -> Har {
+    let content = fs::read_to_string("tests/fixtures/flow.har").unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+#[test]
+fn test_generate_from_har_filters_assets() {
+    let (content, count) =
+        HarGenerator::generate(load_fixture(), false, &GenerateOptions::default()).unwrap();
+
+    // logo.png (image/png) 和 app.css (text/css) 被过滤
+    assert_eq!(count, 3);
+    assert!(!content.contains("logo.png"));
+    assert!(!content.contains("app.css"));
+
+    // 最常见的 origin 被替换为 {{base_url}}，并注明原始 host
+    assert!(content.contains("https://api.example.com"));
+    assert!(content.contains("POST {{base_url}}/auth/login"));
+    assert!(content.contains("GET {{base_url}}/users?page=2"));
+    // 其他 origin 保持原样
+    assert!(content.contains("GET https://metrics.example.org/collect"));
+
+    // 状态断言
+    assert!(content.contains("@assert status == 204"));
+
+    // 生成的内容可以被重新解析
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    assert_eq!(parsed.requests.len(), 3);
+    assert_eq!(parsed.requests[0].method.as_deref(), Some("POST"));
+    assert!(parsed.requests[0].body.as_ref().unwrap().contains("admin"));
+}
+
+#[test]
+fn test_generate_from_har_include_assets() {
+    let (content, count) =
+        HarGenerator::generate(load_fixture(), true, &GenerateOptions::default()).unwrap();
+
+    assert_eq!(count, 5);
+    assert!(content.contains("https://cdn.example.com/static/logo.png"));
+    assert!(content.contains("GET {{base_url}}/assets/app.css"));
+}
@@ -18,9 +18,11 @@ fn create_dummy_entry(id: String) -> HistoryEntry {
             body: None,
         },
         source: None,
+        name: None,
         response: ResponseMeta {
             status: 200,
             headers: HeaderMap::new(),
+            body: None,
         },
     }
 }
@@ -33,6 +33,7 @@ async fn test_history_recording() {
         method: Some("POST".to_string()),
         headers: vec![("Content-Type".to_string(), "application/json".to_string())],
         body: Some(r#"{"test": "data"}"#.to_string()),
+        form: Vec::new(),
         metadata: RequestMetadata::default(),
         line_number: 1,
     };
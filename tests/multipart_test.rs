@@ -0,0 +1,94 @@
+use rupost::http::types::FormPart;
+use rupost::parser::{ParsedFile, ParsedRequest};
+use rupost::runner::TestExecutor;
+use rupost::variable::VariableContext;
+use std::fs;
+use tempfile::TempDir;
+use wiremock::matchers::{header_regex, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn form_request(url: String, form: Vec<FormPart>) -> ParsedFile {
+    let mut request = ParsedRequest::new(1);
+    request.method = Some("POST".to_string());
+    request.url = url;
+    request.form = form;
+    ParsedFile {
+        requests: vec![request],
+        source_path: None,
+    }
+}
+
+/// 测试 multipart 表单发送文本字段和文件
+#[tokio::test]
+async fn test_multipart_upload() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header_regex(
+            "content-type",
+            "^multipart/form-data; boundary=",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("note.txt");
+    fs::write(&file, "file content").unwrap();
+
+    let parsed = form_request(
+        format!("{}/upload", mock_server.uri()),
+        vec![
+            FormPart::Text {
+                name: "title".to_string(),
+                value: "hello".to_string(),
+            },
+            FormPart::File {
+                name: "file".to_string(),
+                path: file,
+                content_type: None,
+            },
+        ],
+    );
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    assert!(results[0].success, "{:?}", results[0].error);
+
+    let received = mock_server.received_requests().await.unwrap();
+    let body = String::from_utf8_lossy(&received[0].body);
+    assert!(body.contains("name=\"title\"\r\n\r\nhello\r\n"));
+    assert!(body.contains("name=\"file\"; filename=\"note.txt\"\r\n"));
+    assert!(body.contains("Content-Type: text/plain\r\n\r\nfile content\r\n"));
+}
+
+/// 测试文件不存在时不发送请求，错误中包含路径
+#[tokio::test]
+async fn test_multipart_missing_file() {
+    let mock_server = MockServer::start().await;
+
+    let parsed = form_request(
+        format!("{}/upload", mock_server.uri()),
+        vec![FormPart::File {
+            name: "file".to_string(),
+            path: "./does-not-exist.png".into(),
+            content_type: None,
+        }],
+    );
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(!results[0].success);
+    assert!(
+        results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("./does-not-exist.png")
+    );
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,82 @@
+use rupost::generator::openapi::OpenApiGenerator;
+use rupost::parser::HttpFileParser;
+use std::fs;
+
+fn generate_fixture() -> (String, usize) {
+    let content = fs::read_to_string("tests/fixtures/petstore.yaml").unwrap();
+    let spec = OpenApiGenerator::parse_spec(&content).unwrap();
+    OpenApiGenerator::generate(&spec).unwrap()
+}
+
+#[test]
+fn test_generate_from_openapi_yaml() {
+    let (content, count) = generate_fixture();
+    assert_eq!(count, 4);
+    assert!(content.contains("# base_url: https://petstore.example.com/v1"));
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    assert_eq!(parsed.requests.len(), 4);
+
+    // 仅必填的 query 参数被加入 URL
+    let list = &parsed.requests[0];
+    assert_eq!(list.name(), Some("listPets"));
+    assert_eq!(list.url, "{{base_url}}/pets?limit={{limit}}");
+    assert_eq!(list.metadata.assertions, vec!["status == 200"]);
+
+    // $ref 引用的 header 参数和请求体
+    let create = &parsed.requests[1];
+    assert_eq!(create.method.as_deref(), Some("POST"));
+    assert!(
+        create
+            .headers
+            .contains(&("X-API-Key".to_string(), "{{X_API_Key}}".to_string()))
+    );
+    assert_eq!(create.metadata.assertions, vec!["status == 201"]);
+    let body: serde_json::Value = serde_json::from_str(create.body.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "name": "Rex",
+            "tag": "string",
+            "birthday": "2024-01-01",
+            "owner": {"email": "user@example.com"}
+        })
+    );
+
+    // 路径参数转换为变量
+    let get = &parsed.requests[2];
+    assert_eq!(get.url, "{{base_url}}/pets/{{petId}}");
+    assert!(content.contains("### GET /pets/{petId}"));
+
+    let delete = &parsed.requests[3];
+    assert_eq!(delete.method.as_deref(), Some("DELETE"));
+    assert_eq!(delete.metadata.assertions, vec!["status == 204"]);
+}
+
+#[test]
+fn test_generate_from_openapi_json() {
+    let yaml = fs::read_to_string("tests/fixtures/petstore.yaml").unwrap();
+    let spec = OpenApiGenerator::parse_spec(&yaml).unwrap();
+    let json = serde_json::to_string(&spec).unwrap();
+
+    let (from_json, count) =
+        OpenApiGenerator::generate(&OpenApiGenerator::parse_spec(&json).unwrap()).unwrap();
+    assert_eq!(count, 4);
+    assert_eq!(from_json, generate_fixture().0);
+}
+
+#[test]
+fn test_generate_from_openapi_remote_ref() {
+    let spec = serde_json::json!({
+        "openapi": "3.0.0",
+        "paths": {
+            "/users": {
+                "post": {
+                    "requestBody": {"$ref": "https://example.com/common.yaml#/User"},
+                    "responses": {"201": {"description": "ok"}}
+                }
+            }
+        }
+    });
+    assert!(OpenApiGenerator::generate(&spec).is_err());
+}
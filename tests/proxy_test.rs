@@ -0,0 +1,124 @@
+use rupost::http::Client;
+use rupost::parser::HttpFileParser;
+use rupost::runner::{TestExecutor, TestResult};
+use rupost::variable::VariableContext;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 启动一个 HTTP 代理桩：记录收到的请求行，并对所有请求返回 "via proxy"
+async fn start_proxy_stub() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let request_lines = Arc::new(Mutex::new(Vec::new()));
+
+    let lines = request_lines.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let lines = lines.clone();
+            tokio::spawn(async move {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 1024];
+                while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&buffer);
+                let line = request.lines().next().unwrap_or_default().to_string();
+                lines.lock().unwrap().push(line);
+
+                let body = "via proxy";
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (format!("http://{}", addr), request_lines)
+}
+
+async fn run(client: Client, url: &str) -> TestResult {
+    let parsed = HttpFileParser::parse_content(&format!("GET {}\n", url)).unwrap();
+    let mut context = VariableContext::new();
+    TestExecutor::builder()
+        .record_history(false)
+        .client(client)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap()
+        .remove(0)
+}
+
+/// 测试 --proxy 时请求经过代理发送
+#[tokio::test]
+async fn test_requests_go_through_proxy() {
+    let (proxy, request_lines) = start_proxy_stub().await;
+
+    let client = Client::builder()
+        .proxy(Some(proxy))
+        .no_proxy_hosts(Some(String::new()))
+        .build()
+        .unwrap();
+    let result = run(client, "http://api.example.test/users?page=1").await;
+
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.response.unwrap().body, "via proxy");
+    assert_eq!(
+        *request_lines.lock().unwrap(),
+        vec!["GET http://api.example.test/users?page=1 HTTP/1.1"]
+    );
+}
+
+/// 测试 NO_PROXY 列表中的主机直接连接，--no-proxy 时不使用任何代理
+#[tokio::test]
+async fn test_proxy_exclusions() {
+    let (proxy, request_lines) = start_proxy_stub().await;
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/direct"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("direct"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/direct", mock_server.uri());
+
+    let excluded = Client::builder()
+        .proxy(Some(proxy.clone()))
+        .no_proxy_hosts(Some("localhost,127.0.0.1".to_string()))
+        .build()
+        .unwrap();
+    let result = run(excluded, &url).await;
+    assert_eq!(result.response.unwrap().body, "direct");
+
+    let disabled = Client::builder()
+        .proxy(Some(proxy))
+        .no_proxy(true)
+        .build()
+        .unwrap();
+    let result = run(disabled, &url).await;
+    assert_eq!(result.response.unwrap().body, "direct");
+
+    assert!(request_lines.lock().unwrap().is_empty());
+}
+
+/// 测试无效的代理地址在创建客户端时报错
+#[test]
+fn test_invalid_proxy() {
+    assert!(
+        Client::builder()
+            .proxy(Some("not a url".to_string()))
+            .build()
+            .is_err()
+    );
+}
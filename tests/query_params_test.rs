@@ -1,5 +1,12 @@
 use rupost::http::Request;
 
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
 #[test]
 fn test_query_params_storage() {
     let request = Request::new("GET", "http://example.com")
@@ -8,21 +15,23 @@ fn test_query_params_storage() {
         .with_query("page", "1")
         .with_query("limit", "10");
 
-    assert_eq!(request.query_params.len(), 3);
-    assert_eq!(request.query_params.get("q"), Some(&"search".to_string()));
-    assert_eq!(request.query_params.get("page"), Some(&"1".to_string()));
-    assert_eq!(request.query_params.get("limit"), Some(&"10".to_string()));
+    assert_eq!(
+        request.query_params,
+        pairs(&[("q", "search"), ("page", "1"), ("limit", "10")])
+    );
 }
 
 #[test]
-fn test_query_params_override() {
+fn test_query_params_repeated_key() {
     let request = Request::new("GET", "http://example.com")
         .unwrap()
         .with_query("q", "first")
-        .with_query("q", "second"); // 应该覆盖第一个值
+        .with_query("q", "second"); // 同名参数按顺序保留两个值
 
-    assert_eq!(request.query_params.len(), 1);
-    assert_eq!(request.query_params.get("q"), Some(&"second".to_string()));
+    assert_eq!(
+        request.query_params,
+        pairs(&[("q", "first"), ("q", "second")])
+    );
 }
 
 #[test]
@@ -0,0 +1,70 @@
+use rupost::http::Client;
+use rupost::parser::HttpFileParser;
+use rupost::runner::{TestExecutor, TestResult};
+use rupost::variable::VariableContext;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn run(client: Client, content: &str) -> Vec<TestResult> {
+    let parsed = HttpFileParser::parse_content(content).unwrap();
+    let mut context = VariableContext::new();
+    TestExecutor::builder()
+        .record_history(false)
+        .client(client)
+        .build()
+        .execute_all(parsed, &mut context)
+        .await
+        .unwrap()
+}
+
+async fn start_backend(host: &str) -> MockServer {
+    let mock_server = MockServer::start().await;
+    let port = mock_server.address().port();
+    Mock::given(method("GET"))
+        .and(path("/ping"))
+        .and(header("host", format!("{}:{}", host, port).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_string("pong"))
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+/// 测试 --resolve 将主机名连接到指定地址，Host header 保持不变
+#[tokio::test]
+async fn test_resolve_override() {
+    let backend = start_backend("api.example.test").await;
+    let port = backend.address().port();
+
+    let client = Client::builder()
+        .resolve(vec![
+            format!("api.example.test:{}:127.0.0.1", port)
+                .parse()
+                .unwrap(),
+        ])
+        .build()
+        .unwrap();
+    let results = run(
+        client,
+        &format!("GET http://api.example.test:{}/ping\n", port),
+    )
+    .await;
+
+    assert!(results[0].success, "{:?}", results[0].error);
+    assert_eq!(results[0].response.as_ref().unwrap().body, "pong");
+}
+
+/// 测试 @resolve 只对所在的请求生效
+#[tokio::test]
+async fn test_resolve_directive() {
+    let backend = start_backend("internal.example.test").await;
+    let port = backend.address().port();
+
+    let content = format!(
+        "@resolve internal.example.test:{port}:127.0.0.1\nGET http://internal.example.test:{port}/ping\n\n###\n\nGET http://internal.example.test:{port}/ping\n"
+    );
+    let results = run(Client::new(), &content).await;
+
+    assert!(results[0].success, "{:?}", results[0].error);
+    assert!(!results[1].success);
+    assert!(results[1].response.is_none());
+}
@@ -0,0 +1,92 @@
+use rupost::http::{Client, Request, RetryPolicy};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use wiremock::matchers::{body_string, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(max_attempts: u32, retry_on_status: Vec<u16>) -> Client {
+    Client::builder()
+        .retry(RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(20),
+            retry_on_status,
+        })
+        .build()
+        .unwrap()
+}
+
+/// 测试指定的状态码被重试，请求体在重试时重新发送，响应记录尝试次数
+#[tokio::test]
+async fn test_retry_on_status() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/orders"))
+        .and(body_string("payload"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/orders"))
+        .and(body_string("payload"))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/orders", mock_server.uri());
+    let request = Request::new("POST", &url).unwrap().with_text("payload");
+    let response = client(3, vec![503]).execute(request).await.unwrap();
+
+    assert_eq!(response.status.code(), 201);
+    assert_eq!(response.attempts, 2);
+}
+
+/// 测试默认不重试，未列出的状态码原样返回
+#[tokio::test]
+async fn test_no_retry_by_default() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/busy"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/busy", mock_server.uri());
+
+    let response = Client::new()
+        .execute(Request::new("GET", &url).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status.code(), 503);
+    assert_eq!(response.attempts, 1);
+
+    let response = client(3, vec![429])
+        .execute(Request::new("GET", &url).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.attempts, 1);
+}
+
+/// 测试连接失败按指数退避重试，用完次数后返回错误
+#[tokio::test]
+async fn test_retry_connection_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let start = Instant::now();
+    let result = client(3, Vec::new())
+        .execute(Request::new("GET", &format!("http://{}/", addr)).unwrap())
+        .await;
+
+    assert!(result.is_err());
+    // 等待 20ms + 40ms
+    assert!(start.elapsed() >= Duration::from_millis(60));
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "schema")]
+
+use rupost::parser::HttpFileParser;
+use rupost::runner::TestExecutor;
+use rupost::variable::VariableContext;
+use std::fs;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const USER_SCHEMA: &str = r#"{
+    "type": "object",
+    "required": ["id", "name"],
+    "properties": {
+        "id": {"type": "integer"},
+        "name": {"type": "string"}
+    }
+}"#;
+
+/// @schema 相对于 .http 文件所在目录，校验结果作为一条断言
+#[tokio::test]
+async fn test_schema_validation() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"id": 1, "name": "Ada"}"#))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/2"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"id": "2"}"#))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("schemas")).unwrap();
+    fs::write(dir.path().join("schemas/user.json"), USER_SCHEMA).unwrap();
+    let http_file = dir.path().join("users.http");
+    fs::write(
+        &http_file,
+        format!(
+            "@assert status == 200\n@schema ./schemas/user.json\nGET {uri}/users/1\n\n###\n\n@schema ./schemas/user.json\nGET {uri}/users/2\n\n###\n\n@schema ./schemas/user.json\nGET {uri}/health\n",
+            uri = mock_server.uri()
+        ),
+    )
+    .unwrap();
+    let parsed = HttpFileParser::parse_file(&http_file).unwrap();
+
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(results[0].success, "{:?}", results[0].assertions);
+    assert_eq!(results[0].assertions.len(), 2);
+    assert_eq!(results[0].assertions[1].raw, "schema ./schemas/user.json");
+
+    assert!(!results[1].success);
+    let message = results[1].assertions[0].message.as_deref().unwrap();
+    assert!(
+        message.contains("\"name\" is a required property"),
+        "{}",
+        message
+    );
+
+    assert!(!results[2].success);
+    let message = results[2].assertions[0].message.as_deref().unwrap();
+    assert!(message.contains("not valid JSON"), "{}", message);
+}
@@ -0,0 +1,93 @@
+use rupost::parser::HttpFileParser;
+use rupost::runner::TestExecutor;
+use rupost::variable::VariableContext;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 测试 @stream lines=N 只保留前 N 行
+#[tokio::test]
+async fn test_stream_lines_limit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/events"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string("data: 1\n\ndata: 2\n\ndata: 3\n\n"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let content = format!(
+        r#"
+### Events
+@stream lines=3
+@assert body contains "data: 2"
+GET {}/events
+"#,
+        mock_server.uri()
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(results[0].success);
+    assert!(results[0].assertions.iter().all(|a| a.passed));
+    let body = &results[0].response.as_ref().unwrap().body;
+    assert_eq!(body, "data: 1\n\ndata: 2\n");
+}
+
+/// 测试 @stream duration 在连接保持打开时按时长返回已读取的内容
+#[tokio::test]
+async fn test_stream_duration_limit() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // 发送一个事件后保持连接不结束
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = socket.read(&mut buf).await;
+
+        let event = "data: hello\n\n";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+            event.len(),
+            event
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    });
+
+    let content = format!(
+        r#"
+### Events
+@stream duration=300ms
+@assert body contains "hello"
+GET http://{}/events
+"#,
+        addr
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+
+    let start = Instant::now();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    assert!(results[0].success);
+    assert!(results[0].assertions.iter().all(|a| a.passed));
+    assert_eq!(
+        results[0].response.as_ref().unwrap().body,
+        "data: hello\n\n"
+    );
+}
@@ -57,12 +57,12 @@ timeout = "60"
     let config = ConfigLoader::load_from_path(&config_path).unwrap();
 
     // 构建 dev 环境上下文
-    let context = ConfigLoader::build_context(&config, Some("dev"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
     assert_eq!(context.get("base_url"), Some("http://localhost:3000"));
     assert_eq!(context.get("timeout"), Some("30"));
 
     // 构建 staging 环境上下文
-    let context = ConfigLoader::build_context(&config, Some("staging"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("staging"), &[]).unwrap();
     assert_eq!(context.get("base_url"), Some("http://staging.example.com"));
     assert_eq!(context.get("timeout"), Some("60"));
 }
@@ -86,7 +86,7 @@ api_key = "config-key"
 
     // 使用 CLI 变量覆盖
     let cli_vars = vec![("api_key".to_string(), "cli-override-key".to_string())];
-    let context = ConfigLoader::build_context(&config, Some("dev"), &cli_vars);
+    let context = ConfigLoader::build_context(&config, Some("dev"), &cli_vars).unwrap();
 
     // CLI 变量应该覆盖配置文件中的值
     assert_eq!(context.get("api_key"), Some("cli-override-key"));
@@ -113,7 +113,7 @@ api_key = "${TEST_ENV_VAR}"
 
     // 加载配置
     let config = ConfigLoader::load_from_path(&config_path).unwrap();
-    let context = ConfigLoader::build_context(&config, Some("dev"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
 
     let result = VariableResolver::substitute("{{api_key}}", &context);
     assert_eq!(result, "environment-value");
@@ -123,6 +123,85 @@ api_key = "${TEST_ENV_VAR}"
     }
 }
 
+/// 测试环境选择的优先级：--env > RUPOST_ENV > default_environment，`none` 不使用环境
+#[test]
+fn test_env_name_from_rupost_env() {
+    unsafe {
+        std::env::set_var("RUPOST_ENV", "staging");
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("rupost.toml");
+    fs::write(
+        &config_path,
+        r#"
+[environments.dev]
+base_url = "http://localhost:3000"
+
+[environments.staging]
+base_url = "https://staging.example.com"
+"#,
+    )
+    .unwrap();
+    let config = ConfigLoader::load_from_path(&config_path).unwrap();
+
+    let env = ConfigLoader::env_name(None, &config).unwrap();
+    assert_eq!(env.as_deref(), Some("staging"));
+    let context = ConfigLoader::build_context(&config, env.as_deref(), &[]).unwrap();
+    assert_eq!(context.get("base_url"), Some("https://staging.example.com"));
+    assert_eq!(context.get("env"), Some("staging"));
+
+    assert_eq!(
+        ConfigLoader::env_name(Some("dev"), &config)
+            .unwrap()
+            .as_deref(),
+        Some("dev")
+    );
+    assert_eq!(ConfigLoader::env_name(Some("none"), &config).unwrap(), None);
+
+    unsafe {
+        std::env::set_var("RUPOST_ENV", "");
+    }
+    assert_eq!(ConfigLoader::env_name(None, &config).unwrap(), None);
+
+    // 配置的默认环境优先级最低
+    let mut config = config;
+    config.default_environment = Some("dev".to_string());
+    assert_eq!(
+        ConfigLoader::env_name(None, &config).unwrap().as_deref(),
+        Some("dev")
+    );
+    assert_eq!(
+        ConfigLoader::env_name(Some("staging"), &config)
+            .unwrap()
+            .as_deref(),
+        Some("staging")
+    );
+    assert_eq!(ConfigLoader::env_name(Some("none"), &config).unwrap(), None);
+    unsafe {
+        std::env::set_var("RUPOST_ENV", "none");
+    }
+    assert_eq!(ConfigLoader::env_name(None, &config).unwrap(), None);
+    unsafe {
+        std::env::remove_var("RUPOST_ENV");
+    }
+
+    // 默认环境不存在时报错，而不是使用空的上下文
+    config.default_environment = Some("qa".to_string());
+    let err = ConfigLoader::env_name(None, &config).unwrap_err();
+    assert!(
+        err.to_string().contains("default_environment 'qa'"),
+        "{}",
+        err
+    );
+    assert_eq!(
+        ConfigLoader::env_name(Some("dev"), &config)
+            .unwrap()
+            .as_deref(),
+        Some("dev")
+    );
+}
+
 /// 测试复杂变量替换场景
 #[test]
 fn test_complex_variable_substitution() {
@@ -176,7 +255,7 @@ db_name = "prod_db"
     let config = ConfigLoader::load_from_path(&config_path).unwrap();
 
     // 测试 dev 环境
-    let context = ConfigLoader::build_context(&config, Some("dev"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
     assert_eq!(
         VariableResolver::substitute("{{base_url}}", &context),
         "http://localhost:3000"
@@ -187,7 +266,7 @@ db_name = "prod_db"
     );
 
     // 测试 test 环境
-    let context = ConfigLoader::build_context(&config, Some("test"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("test"), &[]).unwrap();
     assert_eq!(
         VariableResolver::substitute("{{base_url}}", &context),
         "http://test-server:3000"
@@ -198,7 +277,7 @@ db_name = "prod_db"
     );
 
     // 测试 prod 环境
-    let context = ConfigLoader::build_context(&config, Some("prod"), &[]);
+    let context = ConfigLoader::build_context(&config, Some("prod"), &[]).unwrap();
     assert_eq!(
         VariableResolver::substitute("{{base_url}}", &context),
         "https://api.example.com"
@@ -226,7 +305,7 @@ base_url = "http://localhost:3000"
     let config = ConfigLoader::load_from_path(&config_path).unwrap();
 
     // 不指定环境时，上下文为空
-    let context = ConfigLoader::build_context(&config, None, &[]);
+    let context = ConfigLoader::build_context(&config, None, &[]).unwrap();
     assert!(context.is_empty());
 }
 
@@ -247,6 +326,76 @@ fn test_missing_variable_handling() {
     assert_eq!(result, "value and {{undefined}}");
 }
 
+/// 测试用户配置与项目配置合并：项目配置按环境、按键覆盖，只存在于一边的环境保留
+#[test]
+fn test_merge_user_and_project_config() {
+    let user_dir = TempDir::new().unwrap();
+    let user_path = user_dir.path().join("rupost.toml");
+    fs::write(
+        &user_path,
+        r#"
+[headers]
+X-Team = "platform"
+
+[http]
+proxy = "http://proxy.corp.example:8080"
+resolve = ["api.example.com:443:10.0.0.5"]
+
+[environments.dev]
+api_key = "shared-dev-key"
+base_url = "http://dev.example.com"
+
+[environments.sandbox]
+api_key = "sandbox-key"
+"#,
+    )
+    .unwrap();
+
+    let project_dir = TempDir::new().unwrap();
+    let project_path = project_dir.path().join("rupost.toml");
+    fs::write(
+        &project_path,
+        r#"
+[http]
+resolve = ["api.example.com:443:127.0.0.1"]
+
+[environments.dev]
+base_url = "http://localhost:3000"
+
+[environments.test]
+base_url = "http://test-server:3000"
+"#,
+    )
+    .unwrap();
+
+    let config = ConfigLoader::load_and_merge(&[&user_path, &project_path]).unwrap();
+    assert_eq!(
+        config.http.proxy.as_deref(),
+        Some("http://proxy.corp.example:8080")
+    );
+    // 数组整体替换
+    assert_eq!(config.http.resolve, vec!["api.example.com:443:127.0.0.1"]);
+    assert_eq!(
+        config.headers.get("X-Team").map(String::as_str),
+        Some("platform")
+    );
+
+    let context = ConfigLoader::build_context(&config, Some("dev"), &[]).unwrap();
+    assert_eq!(context.get("base_url"), Some("http://localhost:3000"));
+    assert_eq!(context.get("api_key"), Some("shared-dev-key"));
+
+    let context = ConfigLoader::build_context(&config, Some("sandbox"), &[]).unwrap();
+    assert_eq!(context.get("api_key"), Some("sandbox-key"));
+    let context = ConfigLoader::build_context(&config, Some("test"), &[]).unwrap();
+    assert_eq!(context.get("base_url"), Some("http://test-server:3000"));
+
+    // 任一文件无效时报告该文件
+    let broken_path = project_dir.path().join("broken.toml");
+    fs::write(&broken_path, "[environments.dev\n").unwrap();
+    let err = ConfigLoader::load_and_merge(&[&user_path, &broken_path]).unwrap_err();
+    assert!(err.to_string().contains("broken.toml"), "{}", err);
+}
+
 /// 测试空配置文件
 #[test]
 fn test_empty_config() {
@@ -0,0 +1,71 @@
+#![cfg(feature = "websocket")]
+
+use futures_util::{SinkExt, StreamExt};
+use rupost::parser::HttpFileParser;
+use rupost::runner::TestExecutor;
+use rupost::variable::VariableContext;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// 启动一个回显服务器，收到的每条文本消息加上 "echo: " 前缀后返回
+async fn start_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    ws.send(Message::text(format!("echo: {}", text)))
+                        .await
+                        .unwrap();
+                }
+            });
+        }
+    });
+
+    format!("ws://{}", addr)
+}
+
+/// 测试 WebSocket 请求发送 body 后读取一条消息并对其断言
+#[tokio::test]
+async fn test_websocket_smoke() {
+    let url = start_echo_server().await;
+
+    let content = format!(
+        r#"
+### Connect
+@assert status == 101
+@assert body == "echo: hello"
+GET {url}/chat
+
+hello
+"#
+    );
+
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(results[0].success, "{:?}", results[0].error);
+    assert!(results[0].assertions.iter().all(|a| a.passed));
+}
+
+/// 测试连接被拒绝时请求失败
+#[tokio::test]
+async fn test_websocket_connection_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let content = format!("GET ws://{}/chat\n", addr);
+    let parsed = HttpFileParser::parse_content(&content).unwrap();
+    let executor = TestExecutor::builder().record_history(false).build();
+    let mut context = VariableContext::new();
+    let results = executor.execute_all(parsed, &mut context).await.unwrap();
+
+    assert!(!results[0].success);
+    assert!(results[0].error.as_deref().unwrap().contains("WebSocket"));
+}